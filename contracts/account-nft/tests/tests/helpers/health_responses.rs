@@ -22,6 +22,8 @@ pub fn generate_health_response(
         perps_pnl_profit: Uint128::zero(),
         perps_pnl_loss: Uint128::zero(),
         has_perps,
+        perps_liq_ltv_numerator: Uint128::zero(),
+        perps_liq_ltv_denominator: Uint128::zero(),
     }
 }
 
@@ -38,5 +40,7 @@ pub fn below_max_for_burn() -> HealthValuesResponse {
         perps_pnl_profit: Uint128::zero(),
         perps_pnl_loss: Uint128::zero(),
         has_perps: false,
+        perps_liq_ltv_numerator: Uint128::zero(),
+        perps_liq_ltv_denominator: Uint128::zero(),
     }
 }
@@ -161,6 +161,7 @@ impl MockEnvBuilder {
                         perps: "n/a".to_string(),
                         keeper_fee_config: Default::default(),
                         perps_liquidation_bonus_ratio: Decimal::percent(60),
+                        perps_liquidation_target_health_factor: None,
                     },
                 },
                 &[],
@@ -311,6 +312,7 @@ impl MockEnvBuilder {
                     cooldown_period: 360,
                     max_positions: 4,
                     protocol_fee_rate: Decimal::percent(0),
+                    insurance_fund_fee_rate: Decimal::zero(),
                     target_vault_collateralization_ratio: Decimal::from_ratio(12u128, 10u128),
                     deleverage_enabled: true,
                     vault_withdraw_enabled: true,
@@ -1,8 +1,9 @@
-use cosmwasm_std::{Coin, CosmosMsg, DepsMut, Response, Uint128};
+use cosmwasm_std::{Coin, CosmosMsg, Deps, DepsMut, Response, Uint128};
+use mars_types::params::TotalBorrowResponse;
 
 use crate::{
     error::{ContractError, ContractResult},
-    state::{DEBT_SHARES, RED_BANK, TOTAL_DEBT_SHARES},
+    state::{DEBT_SHARES, PARAMS, RED_BANK, TOTAL_DEBT_SHARES},
     utils::{assert_coin_is_whitelisted, increment_coin_balance},
 };
 
@@ -20,6 +21,8 @@ pub fn borrow(mut deps: DepsMut, account_id: &str, coin: Coin) -> ContractResult
 
     let (debt_shares_to_add, borrow_msg) = update_debt(&mut deps, account_id, &coin)?;
 
+    assert_below_borrow_cap(deps.as_ref(), &coin)?;
+
     increment_coin_balance(deps.storage, account_id, &coin)?;
 
     Ok(Response::new()
@@ -70,3 +73,27 @@ pub fn update_debt(
 
     Ok((debt_shares_to_add, red_bank.borrow_msg(coin)?))
 }
+
+/// Assert that borrowing the coin doesn't push the total debt of the asset in the Red Bank above
+/// its borrow cap
+fn assert_below_borrow_cap(deps: Deps, coin: &Coin) -> ContractResult<()> {
+    let params = PARAMS.load(deps.storage)?;
+    let TotalBorrowResponse {
+        amount,
+        cap,
+        ..
+    } = params.query_total_borrow(&deps.querier, &coin.denom)?;
+
+    let new_amount = amount.checked_add(coin.amount)?;
+    if new_amount > cap {
+        return Err(ContractError::AboveAssetBorrowCap {
+            new_value: Coin {
+                denom: coin.denom.clone(),
+                amount: new_amount,
+            },
+            maximum: cap,
+        });
+    }
+
+    Ok(())
+}
@@ -13,18 +13,22 @@ use crate::{
     execute::{create_credit_account, dispatch_actions, execute_callback},
     instantiate::store_config,
     migrations,
-    perp::update_balance_after_deleverage,
+    perp::{
+        fill_perp_limit_order, refund_perp_limit_order_fee, return_isolated_margin,
+        update_balance_after_deleverage,
+    },
     query::{
         query_account_tier_and_discount, query_accounts, query_all_coin_balances,
-        query_all_debt_shares, query_all_total_debt_shares, query_all_trigger_orders,
-        query_all_trigger_orders_for_account, query_all_vault_positions,
-        query_all_vault_utilizations, query_config, query_fee_tier_config, query_positions,
-        query_swap_fee, query_total_debt_shares, query_trading_fee, query_vault_bindings,
-        query_vault_position_value, query_vault_utilization,
+        query_all_debt_shares, query_all_total_debt_shares, query_all_trailing_marks_for_account,
+        query_all_trigger_orders, query_all_trigger_orders_for_account, query_all_vault_positions,
+        query_all_vault_utilizations, query_config, query_fee_tier_config, query_isolated_debts,
+        query_positions, query_swap_fee, query_total_debt_shares, query_trading_fee,
+        query_trailing_mark, query_vault_bindings, query_vault_position_value,
+        query_vault_utilization,
     },
-    repay::repay_from_wallet,
+    repay::{repay_from_wallet, write_off_bad_debt},
     state::NEXT_TRIGGER_ID,
-    trigger::execute_trigger_order,
+    trigger::{execute_trigger_order, prune_expired_trigger_order, refresh_trailing_mark},
     update_config::{update_config, update_nft_config, update_owner},
     utils::get_account_kind,
     vault::handle_unlock_request_reply,
@@ -71,7 +75,7 @@ pub fn execute(
             account_id,
             account_kind,
             actions,
-        } => dispatch_actions(deps, env, info, account_id, account_kind, actions, true),
+        } => dispatch_actions(deps, env, info, account_id, account_kind, actions, true, false),
         ExecuteMsg::RepayFromWallet {
             account_id,
         } => repay_from_wallet(deps, env, info, account_id),
@@ -86,10 +90,52 @@ pub fn execute(
             pnl,
             ActionKind::Liquidation,
         ),
+        ExecuteMsg::ReturnIsolatedMargin {
+            account_id,
+        } => return_isolated_margin(deps, info, account_id),
+        ExecuteMsg::FillPerpLimitOrder {
+            account_id,
+            denom,
+            size,
+            limit_price,
+            reduce_only,
+            keeper,
+            keeper_fee,
+        } => fill_perp_limit_order(
+            deps,
+            env,
+            info,
+            account_id,
+            denom,
+            size,
+            limit_price,
+            reduce_only,
+            keeper,
+            keeper_fee,
+        ),
+        ExecuteMsg::RefundPerpLimitOrderFee {
+            account_id,
+            keeper_fee,
+        } => refund_perp_limit_order_fee(deps, info, account_id, keeper_fee),
         ExecuteMsg::ExecuteTriggerOrder {
             account_id,
             trigger_order_id,
         } => execute_trigger_order(deps, env, info, &account_id, &trigger_order_id),
+        ExecuteMsg::RefreshTrailingMark {
+            account_id,
+            trigger_order_id,
+        } => refresh_trailing_mark(deps, &account_id, &trigger_order_id),
+        ExecuteMsg::PruneExpiredTriggerOrder {
+            account_id,
+            trigger_order_id,
+        } => prune_expired_trigger_order(deps, env, info, &account_id, &trigger_order_id),
+        ExecuteMsg::WriteOffBadDebt {
+            account_id,
+            denom,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            write_off_bad_debt(deps, env, &account_id, &denom)
+        }
     }
 }
 
@@ -169,6 +215,20 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             start_after,
             limit,
         } => to_json_binary(&query_all_trigger_orders(deps, start_after, limit)?),
+        QueryMsg::TrailingMark {
+            account_id,
+            trigger_order_id,
+        } => to_json_binary(&query_trailing_mark(deps, &account_id, &trigger_order_id)?),
+        QueryMsg::AllAccountTrailingMarks {
+            account_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_trailing_marks_for_account(
+            deps,
+            account_id,
+            start_after,
+            limit,
+        )?),
         QueryMsg::VaultBindings {
             start_after,
             limit,
@@ -182,6 +242,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
         } => to_json_binary(&query_trading_fee(deps, &account_id, &market_type)?),
         QueryMsg::SwapFeeRate {} => to_json_binary(&query_swap_fee(deps)?),
         QueryMsg::FeeTierConfig {} => to_json_binary(&query_fee_tier_config(deps)?),
+        QueryMsg::IsolatedDebts {
+            denom,
+        } => to_json_binary(&query_isolated_debts(deps, denom)?),
     };
     res.map_err(Into::into)
 }
@@ -1,6 +1,7 @@
 use cosmwasm_std::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError, Coin, Coins,
-    CoinsError, Decimal, DecimalRangeExceeded, OverflowError, StdError, Uint128,
+    CoinsError, ConversionOverflowError, Decimal, DecimalRangeExceeded, OverflowError, StdError,
+    Uint128,
 };
 use cw2::VersionError;
 use cw_utils::PaymentError;
@@ -29,6 +30,19 @@ pub enum ContractError {
         maximum: Uint128,
     },
 
+    #[error("Asset borrow would result in exceeding limit. With borrow: {new_value:?}, maximum: {maximum}")]
+    AboveAssetBorrowCap {
+        new_value: Coin,
+        maximum: Uint128,
+    },
+
+    #[error("Debt ceiling of isolated collateral {denom:?} exceeded: ceiling {ceiling}, new value {new_value}")]
+    IsolatedDebtCeilingExceeded {
+        denom: String,
+        ceiling: Uint128,
+        new_value: Uint128,
+    },
+
     #[error("Vault deposit would result in exceeding limit. With deposit: {new_value:?}, Maximum: {maximum:?}")]
     AboveVaultDepositCap {
         new_value: String,
@@ -65,6 +79,9 @@ pub enum ContractError {
     #[error("{0}")]
     CheckedMultiplyFraction(#[from] CheckedMultiplyFractionError),
 
+    #[error("{0}")]
+    ConversionOverflowError(#[from] ConversionOverflowError),
+
     #[error("{0}")]
     DecimalRangeExceeded(#[from] DecimalRangeExceeded),
 
@@ -281,6 +298,35 @@ pub enum ContractError {
         account_id: String,
     },
 
+    #[error("Trigger order with id {order_id:?} for account id {account_id:?} has no TrailingOraclePrice condition")]
+    NoTrailingCondition {
+        order_id: String,
+        account_id: String,
+    },
+
+    #[error(
+        "Trigger order with id {order_id:?} for account id {account_id:?} expired at {expires_at}"
+    )]
+    TriggerOrderExpired {
+        order_id: String,
+        account_id: String,
+        expires_at: u64,
+    },
+
+    #[error("Trigger order with id {order_id:?} for account id {account_id:?} has not expired")]
+    TriggerOrderNotExpired {
+        order_id: String,
+        account_id: String,
+    },
+
+    #[error(
+        "Trigger order expiry {expires_at} must be after the current block time {current_time}"
+    )]
+    InvalidTriggerOrderExpiry {
+        expires_at: u64,
+        current_time: u64,
+    },
+
     #[error("Received keeper fee is less than the min required fee. Expected: {expected_min_amount:?}, received: {received_amount:?}")]
     KeeperFeeTooSmall {
         expected_min_amount: Uint128,
@@ -293,9 +339,7 @@ pub enum ContractError {
         received_denom: String,
     },
 
-    #[error(
-        "Illegal trigger action. Trigger actions may only contain execute_perp_order and lend"
-    )]
+    #[error("Illegal trigger action. Trigger actions may only contain execute_perp_order, close_perp_position, lend, reclaim, repay and swap_exact_in")]
     IllegalTriggerAction,
 
     #[error("Trigger conditions may only have one OrderExecuted")]
@@ -337,4 +381,7 @@ pub enum ContractError {
         max_tiers: usize,
         provided_tiers: usize,
     },
+
+    #[error("Cannot write off debt of an account with collateral")]
+    CannotWriteOffDebtWithCollateral {},
 }
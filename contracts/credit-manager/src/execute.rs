@@ -20,12 +20,19 @@ use crate::{
     error::{ContractError, ContractResult},
     health::{assert_max_ltv, query_health_state},
     hls::assert_hls_rules,
+    isolation::update_isolated_debts,
     lend::lend,
-    liquidate::{assert_not_self_liquidation, check_health},
+    liquidate::{
+        assert_not_self_liquidation, calculate_perps_liquidation, check_health,
+        spot_liquidation_health,
+    },
     liquidate_astro_lp::liquidate_astro_lp,
     liquidate_deposit::liquidate_deposit,
     liquidate_lend::liquidate_lend,
-    perp::{close_all_perps, close_perp_position, execute_perp_order},
+    perp::{
+        add_perp_isolated_margin, cancel_perp_limit_order, close_all_perps, close_perp_position,
+        execute_perp_order, liquidate_perps, open_isolated_perp_position, place_perp_limit_order,
+    },
     perp_vault::{deposit_to_perp_vault, unlock_from_perp_vault, withdraw_from_perp_vault},
     reclaim::reclaim,
     refund::refund_coin_balances,
@@ -111,6 +118,7 @@ pub fn dispatch_actions(
     account_kind: Option<AccountKind>,
     mut actions: Vec<Action>,
     enforce_ownership: bool,
+    is_trigger_order: bool,
 ) -> ContractResult<Response> {
     let mut response = Response::new();
 
@@ -266,12 +274,14 @@ pub fn dispatch_actions(
                 denom,
                 order_size: size,
                 reduce_only,
+                limit_price,
                 ..
             } => callbacks.push(CallbackMsg::ExecutePerpOrder {
                 account_id: account_id.to_string(),
                 denom,
                 size,
                 reduce_only,
+                limit_price,
             }),
             Action::ClosePerpPosition {
                 denom,
@@ -279,16 +289,58 @@ pub fn dispatch_actions(
                 denom,
                 account_id: account_id.to_string(),
             }),
+            Action::OpenIsolatedPerpPosition {
+                denom,
+                order_size: size,
+                margin,
+                limit_price,
+            } => callbacks.push(CallbackMsg::OpenIsolatedPerpPosition {
+                account_id: account_id.to_string(),
+                denom,
+                size,
+                margin,
+                limit_price,
+            }),
+            Action::AddPerpIsolatedMargin {
+                denom,
+                amount,
+            } => callbacks.push(CallbackMsg::AddPerpIsolatedMargin {
+                account_id: account_id.to_string(),
+                denom,
+                amount,
+            }),
+            Action::PlacePerpLimitOrder {
+                denom,
+                order_size: size,
+                limit_price,
+                reduce_only,
+                keeper_fee,
+            } => callbacks.push(CallbackMsg::PlacePerpLimitOrder {
+                account_id: account_id.to_string(),
+                denom,
+                size,
+                limit_price,
+                reduce_only,
+                keeper_fee,
+            }),
+            Action::CancelPerpLimitOrder {
+                order_id,
+            } => callbacks.push(CallbackMsg::CancelPerpLimitOrder {
+                account_id: account_id.to_string(),
+                order_id,
+            }),
             Action::CreateTriggerOrder {
                 actions,
                 conditions,
                 keeper_fee,
+                expires_at,
                 ..
             } => callbacks.push(CallbackMsg::CreateTriggerOrder {
                 account_id: account_id.to_string(),
                 actions,
                 conditions,
                 keeper_fee,
+                expires_at,
             }),
             Action::DeleteTriggerOrder {
                 trigger_order_id,
@@ -312,12 +364,30 @@ pub fn dispatch_actions(
                 assert_not_self_liquidation(account_id, &liquidatee_account_id)?;
 
                 let health = check_health(deps.as_ref(), env.clone(), &liquidatee_account_id)?;
+                let mut prev_health = Some(health.clone());
                 if health.has_perps {
-                    // Close all perp positions before liquidating.
-                    // This creates the state of the account with only spot positions.
-                    callbacks.push(CallbackMsg::CloseAllPerps {
-                        account_id: liquidatee_account_id.to_string(),
-                    });
+                    // If closing a share of the perp positions is enough to bring the account
+                    // to the target health factor, only that share is closed. The health is then
+                    // queried again before liquidating the spot positions, which are left
+                    // untouched if the account is no longer liquidatable.
+                    if let Some(perps_liquidation) =
+                        calculate_perps_liquidation(deps.as_ref(), &health)?
+                    {
+                        callbacks.push(CallbackMsg::LiquidatePerps {
+                            liquidator_account_id: account_id.to_string(),
+                            liquidatee_account_id: liquidatee_account_id.to_string(),
+                            close_ratio: perps_liquidation.close_ratio,
+                            liquidator_bonus: perps_liquidation.liquidator_bonus,
+                            protocol_fee: perps_liquidation.protocol_fee,
+                        });
+                        prev_health = None;
+                    } else {
+                        // Close all perp positions before liquidating.
+                        // This creates the state of the account with only spot positions.
+                        callbacks.push(CallbackMsg::CloseAllPerps {
+                            account_id: liquidatee_account_id.to_string(),
+                        });
+                    }
                 }
 
                 match request {
@@ -326,14 +396,14 @@ pub fn dispatch_actions(
                         liquidatee_account_id: liquidatee_account_id.to_string(),
                         debt_coin,
                         request: LiquidateRequest::Deposit(denom),
-                        prev_health: health,
+                        prev_health,
                     }),
                     LiquidateRequest::Lend(denom) => callbacks.push(CallbackMsg::Liquidate {
                         liquidator_account_id: account_id.to_string(),
                         liquidatee_account_id: liquidatee_account_id.to_string(),
                         debt_coin,
                         request: LiquidateRequest::Lend(denom),
-                        prev_health: health,
+                        prev_health,
                     }),
                     LiquidateRequest::Vault {
                         request_vault,
@@ -346,7 +416,7 @@ pub fn dispatch_actions(
                             request_vault: request_vault.check(deps.api)?,
                             position_type,
                         },
-                        prev_health: health,
+                        prev_health,
                     }),
                     LiquidateRequest::StakedAstroLp(lp_denom) => {
                         callbacks.push(CallbackMsg::Liquidate {
@@ -354,10 +424,17 @@ pub fn dispatch_actions(
                             liquidatee_account_id: liquidatee_account_id.to_string(),
                             debt_coin,
                             request: LiquidateRequest::StakedAstroLp(lp_denom),
-                            prev_health: health,
+                            prev_health,
                         })
                     }
                 }
+
+                // Re-record the isolated debts of the liquidatee without enforcing the ceilings,
+                // so that a liquidation is never blocked by them
+                callbacks.push(CallbackMsg::UpdateIsolatedDebts {
+                    account_id: liquidatee_account_id.to_string(),
+                    enforce_ceiling: false,
+                });
             }
             Action::SwapExactIn {
                 coin_in,
@@ -371,6 +448,7 @@ pub fn dispatch_actions(
                     denom_out: denom_out.clone(),
                     min_receive,
                     route,
+                    enforce_oracle_min_receive: is_trigger_order,
                 });
                 // add the output denom to the map to check the deposit cap in the end of the TX
                 update_or_reset_denom_deposits(
@@ -474,6 +552,13 @@ pub fn dispatch_actions(
         });
     }
 
+    // Assert that the debts backed by the account's isolated collaterals, as they are after the
+    // user selected actions, stay within their debt ceilings
+    callbacks.push(CallbackMsg::UpdateIsolatedDebts {
+        account_id: account_id.to_string(),
+        enforce_ceiling: true,
+    });
+
     if let Some(phs) = prev_health_state {
         // After user selected actions, we assert LTV is either:
         // - Healthy, if prior to actions MaxLTV health factor >= 1 or None
@@ -620,6 +705,10 @@ pub fn execute_callback(
             account_id,
             prev_health_state,
         } => assert_max_ltv(deps.as_ref(), env, &account_id, prev_health_state),
+        CallbackMsg::UpdateIsolatedDebts {
+            account_id,
+            enforce_ceiling,
+        } => update_isolated_debts(deps, env, &account_id, enforce_ceiling),
         CallbackMsg::AssertDepositCaps {
             denoms,
         } => assert_deposit_caps(deps.as_ref(), denoms),
@@ -641,7 +730,16 @@ pub fn execute_callback(
             actions,
             conditions,
             keeper_fee,
-        } => create_trigger_order(deps, &account_id, actions, conditions, keeper_fee),
+            expires_at,
+        } => create_trigger_order(
+            deps,
+            env,
+            &account_id,
+            actions,
+            conditions,
+            keeper_fee,
+            expires_at,
+        ),
         CallbackMsg::DeleteTriggerOrder {
             account_id,
             trigger_order_id,
@@ -650,9 +748,55 @@ pub fn execute_callback(
             denom,
             account_id,
         } => close_perp_position(deps, &account_id, &denom),
+        CallbackMsg::OpenIsolatedPerpPosition {
+            account_id,
+            denom,
+            size,
+            margin,
+            limit_price,
+        } => open_isolated_perp_position(deps, &account_id, &denom, size, margin, limit_price),
+        CallbackMsg::AddPerpIsolatedMargin {
+            account_id,
+            denom,
+            amount,
+        } => add_perp_isolated_margin(deps, &account_id, &denom, amount),
+        CallbackMsg::PlacePerpLimitOrder {
+            account_id,
+            denom,
+            size,
+            limit_price,
+            reduce_only,
+            keeper_fee,
+        } => place_perp_limit_order(
+            deps,
+            &account_id,
+            &denom,
+            size,
+            limit_price,
+            reduce_only,
+            keeper_fee,
+        ),
+        CallbackMsg::CancelPerpLimitOrder {
+            account_id,
+            order_id,
+        } => cancel_perp_limit_order(deps, &account_id, order_id),
         CallbackMsg::CloseAllPerps {
             account_id,
-        } => close_all_perps(deps, &account_id, ActionKind::Liquidation),
+        } => close_all_perps(deps, env, &account_id, ActionKind::Liquidation),
+        CallbackMsg::LiquidatePerps {
+            liquidator_account_id,
+            liquidatee_account_id,
+            close_ratio,
+            liquidator_bonus,
+            protocol_fee,
+        } => liquidate_perps(
+            deps,
+            &liquidator_account_id,
+            &liquidatee_account_id,
+            close_ratio,
+            liquidator_bonus,
+            protocol_fee,
+        ),
         CallbackMsg::EnterVault {
             account_id,
             vault,
@@ -675,55 +819,80 @@ pub fn execute_callback(
             debt_coin,
             request,
             prev_health,
-        } => match request {
-            LiquidateRequest::Deposit(request_coin_denom) => liquidate_deposit(
-                deps,
-                env,
-                &liquidator_account_id,
-                &liquidatee_account_id,
-                debt_coin,
-                &request_coin_denom,
-                prev_health,
-            ),
-            LiquidateRequest::Lend(request_coin_denom) => liquidate_lend(
-                deps,
-                env,
-                &liquidator_account_id,
-                &liquidatee_account_id,
-                debt_coin,
-                &request_coin_denom,
-                prev_health,
-            ),
-            LiquidateRequest::Vault {
-                request_vault,
-                position_type,
-            } => liquidate_vault(
-                deps,
-                env,
-                &liquidator_account_id,
-                &liquidatee_account_id,
-                debt_coin,
-                request_vault,
-                position_type,
-                prev_health,
-            ),
-            LiquidateRequest::StakedAstroLp(request_coin_denom) => liquidate_astro_lp(
-                deps,
-                env,
-                &liquidator_account_id,
+        } => {
+            let Some(prev_health) = spot_liquidation_health(
+                deps.as_ref(),
+                env.clone(),
                 &liquidatee_account_id,
-                debt_coin,
-                &request_coin_denom,
                 prev_health,
-            ),
-        },
+            )?
+            else {
+                return Ok(Response::new()
+                    .add_attribute("action", "liquidate")
+                    .add_attribute("liquidatee_account_id", liquidatee_account_id)
+                    .add_attribute("skipped", "true"));
+            };
+
+            match request {
+                LiquidateRequest::Deposit(request_coin_denom) => liquidate_deposit(
+                    deps,
+                    env,
+                    &liquidator_account_id,
+                    &liquidatee_account_id,
+                    debt_coin,
+                    &request_coin_denom,
+                    prev_health,
+                ),
+                LiquidateRequest::Lend(request_coin_denom) => liquidate_lend(
+                    deps,
+                    env,
+                    &liquidator_account_id,
+                    &liquidatee_account_id,
+                    debt_coin,
+                    &request_coin_denom,
+                    prev_health,
+                ),
+                LiquidateRequest::Vault {
+                    request_vault,
+                    position_type,
+                } => liquidate_vault(
+                    deps,
+                    env,
+                    &liquidator_account_id,
+                    &liquidatee_account_id,
+                    debt_coin,
+                    request_vault,
+                    position_type,
+                    prev_health,
+                ),
+                LiquidateRequest::StakedAstroLp(request_coin_denom) => liquidate_astro_lp(
+                    deps,
+                    env,
+                    &liquidator_account_id,
+                    &liquidatee_account_id,
+                    debt_coin,
+                    &request_coin_denom,
+                    prev_health,
+                ),
+            }
+        }
         CallbackMsg::SwapExactIn {
             account_id,
             coin_in,
             denom_out,
             min_receive,
             route,
-        } => swap_exact_in(deps, env, &account_id, &coin_in, &denom_out, min_receive, route),
+            enforce_oracle_min_receive,
+        } => swap_exact_in(
+            deps,
+            env,
+            &account_id,
+            &coin_in,
+            &denom_out,
+            min_receive,
+            route,
+            enforce_oracle_min_receive,
+        ),
         CallbackMsg::UpdateCoinBalance {
             account_id,
             previous_balance,
@@ -793,6 +962,14 @@ pub fn execute_callback(
             denom,
             size,
             reduce_only,
-        } => execute_perp_order(deps, account_id.as_str(), denom.as_str(), size, reduce_only),
+            limit_price,
+        } => execute_perp_order(
+            deps,
+            account_id.as_str(),
+            denom.as_str(),
+            size,
+            reduce_only,
+            limit_price,
+        ),
     }
 }
@@ -7,9 +7,13 @@ use crate::{
     state::{
         DUALITY_SWAPPER, FEE_TIER_CONFIG, GOVERNANCE, HEALTH_CONTRACT, INCENTIVES,
         KEEPER_FEE_CONFIG, MAX_SLIPPAGE, MAX_TRIGGER_ORDERS, MAX_UNLOCKING_POSITIONS, ORACLE,
-        OWNER, PARAMS, PERPS_LB_RATIO, RED_BANK, SWAPPER, SWAP_FEE, ZAPPER,
+        OWNER, PARAMS, PERPS_LB_RATIO, PERPS_LIQUIDATION_TARGET_HF, RED_BANK, SWAPPER, SWAP_FEE,
+        ZAPPER,
+    },
+    utils::{
+        assert_max_slippage, assert_perps_lb_ratio, assert_perps_liquidation_target_hf,
+        assert_pruner_fee_pct,
     },
-    utils::{assert_max_slippage, assert_perps_lb_ratio},
 };
 
 pub fn store_config(deps: DepsMut, env: Env, msg: &InstantiateMsg) -> ContractResult<()> {
@@ -35,9 +39,15 @@ pub fn store_config(deps: DepsMut, env: Env, msg: &InstantiateMsg) -> ContractRe
     assert_perps_lb_ratio(msg.perps_liquidation_bonus_ratio)?;
     PERPS_LB_RATIO.save(deps.storage, &msg.perps_liquidation_bonus_ratio)?;
 
+    if let Some(target_hf) = msg.perps_liquidation_target_health_factor {
+        assert_perps_liquidation_target_hf(target_hf)?;
+        PERPS_LIQUIDATION_TARGET_HF.save(deps.storage, &target_hf)?;
+    }
+
     HEALTH_CONTRACT.save(deps.storage, &msg.health_contract.check(deps.api)?)?;
     PARAMS.save(deps.storage, &msg.params.check(deps.api)?)?;
     INCENTIVES.save(deps.storage, &msg.incentives.check(deps.api, env.contract.address)?)?;
+    assert_pruner_fee_pct(msg.keeper_fee_config.pruner_fee_pct)?;
     KEEPER_FEE_CONFIG.save(deps.storage, &msg.keeper_fee_config)?;
     SWAP_FEE.save(deps.storage, &msg.swap_fee)?;
     FEE_TIER_CONFIG.save(deps.storage, &msg.fee_tier_config)?;
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{
+    Deps, DepsMut, Env, Order, OverflowError, Response, StdResult, Storage, Uint128,
+};
+use mars_rover_health::{compute::compute_isolated_debt_portions, querier::HealthQuerier};
+use mars_types::{credit_manager::Positions, oracle::ActionKind};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    query::{query_config, query_positions},
+    state::{ACCOUNT_ISOLATED_DEBT_SHARES, DEBT_SHARES, ISOLATED_DEBT_SHARES, ORACLE, PARAMS},
+    utils::debt_shares_to_amount,
+};
+
+/// Re-record the debts of the account backed by each of its collaterals in isolation mode.
+///
+/// Each isolated collateral backs the portion of every debt that its max-LTV adjusted value makes
+/// up of the account's total. The portions are recorded as debt shares, so the interest accrued
+/// on them counts towards the debt ceilings.
+///
+/// With `enforce_ceiling`, assert that the total debt backed by any isolated collateral whose
+/// portion of the account's debts went up stays within its debt ceiling.
+pub fn update_isolated_debts(
+    deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    enforce_ceiling: bool,
+) -> ContractResult<Response> {
+    let old_records = ACCOUNT_ISOLATED_DEBT_SHARES
+        .sub_prefix(account_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Nothing to record for an account without debts
+    let has_debts = DEBT_SHARES
+        .prefix(account_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if old_records.is_empty() && !has_debts {
+        return Ok(Response::new());
+    }
+
+    // Liquidation pricing when the ceilings aren't enforced, so that liquidations and repayments
+    // go through when the default prices are unavailable
+    let action = if enforce_ceiling {
+        ActionKind::Default
+    } else {
+        ActionKind::Liquidation
+    };
+
+    let positions = query_positions(deps.as_ref(), account_id, action.clone())?;
+    if old_records.is_empty() && !has_isolated_collateral(deps.as_ref(), &positions)? {
+        return Ok(Response::new());
+    }
+
+    let debts = positions.debts.clone();
+    let config = query_config(deps.as_ref())?;
+    let querier = HealthQuerier::new_with_config(&deps.as_ref(), env.contract.address, config)?;
+    let portions = compute_isolated_debt_portions(deps.as_ref(), querier, positions, action)?;
+
+    let mut new_records = vec![];
+    for (collateral_denom, portion) in &portions {
+        for debt in &debts {
+            let shares = debt.shares.checked_mul_floor(*portion)?;
+            if !shares.is_zero() {
+                new_records.push(((collateral_denom.clone(), debt.denom.clone()), shares));
+            }
+        }
+    }
+
+    if enforce_ceiling {
+        let oracle = ORACLE.load(deps.storage)?;
+        let params = PARAMS.load(deps.storage)?;
+
+        let mut old_values: BTreeMap<&str, Uint128> = BTreeMap::new();
+        for ((collateral_denom, debt_denom), shares) in &old_records {
+            let coin = debt_shares_to_amount(deps.as_ref(), debt_denom, *shares)?;
+            let price = oracle.query_price(&deps.querier, debt_denom, ActionKind::Default)?;
+            let value = coin.amount.checked_mul_ceil(price.price)?;
+            let old_value = old_values.entry(collateral_denom.as_str()).or_default();
+            *old_value = old_value.checked_add(value)?;
+        }
+
+        for (collateral_denom, portion) in &portions {
+            let mut new_value = Uint128::zero();
+            for debt in &debts {
+                let price = oracle.query_price(&deps.querier, &debt.denom, ActionKind::Default)?;
+                let value = debt.amount.checked_mul_ceil(price.price * *portion)?;
+                new_value = new_value.checked_add(value)?;
+            }
+
+            let old_value = old_values.get(collateral_denom.as_str()).copied().unwrap_or_default();
+            if new_value <= old_value {
+                continue;
+            }
+
+            let isolation = params
+                .query_asset_params(&deps.querier, collateral_denom)?
+                .and_then(|p| p.isolation)
+                .ok_or_else(|| ContractError::AssetParamsNotFound(collateral_denom.clone()))?;
+            let total = params.query_total_isolated_debt(&deps.querier, collateral_denom)?;
+            let total_value = total.value.saturating_sub(old_value).checked_add(new_value)?;
+            if total_value > isolation.debt_ceiling {
+                return Err(ContractError::IsolatedDebtCeilingExceeded {
+                    denom: collateral_denom.clone(),
+                    ceiling: isolation.debt_ceiling,
+                    new_value: total_value,
+                });
+            }
+        }
+    }
+
+    for ((collateral_denom, debt_denom), shares) in &old_records {
+        ACCOUNT_ISOLATED_DEBT_SHARES
+            .remove(deps.storage, (account_id, collateral_denom, debt_denom));
+        update_total_shares(deps.storage, collateral_denom, debt_denom, |total| {
+            total.checked_sub(*shares)
+        })?;
+    }
+    for ((collateral_denom, debt_denom), shares) in &new_records {
+        ACCOUNT_ISOLATED_DEBT_SHARES.save(
+            deps.storage,
+            (account_id, collateral_denom, debt_denom),
+            shares,
+        )?;
+        update_total_shares(deps.storage, collateral_denom, debt_denom, |total| {
+            total.checked_add(*shares)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/update_isolated_debts")
+        .add_attribute("account_id", account_id))
+}
+
+/// Whether any of the deposits, lends or staked Astroport LPs of the account is in isolation mode
+fn has_isolated_collateral(deps: Deps, positions: &Positions) -> ContractResult<bool> {
+    let params = PARAMS.load(deps.storage)?;
+    for coin in positions
+        .deposits
+        .iter()
+        .chain(positions.lends.iter())
+        .chain(positions.staked_astro_lps.iter())
+    {
+        let isolation =
+            params.query_asset_params(&deps.querier, &coin.denom)?.and_then(|p| p.isolation);
+        if isolation.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn update_total_shares(
+    storage: &mut dyn Storage,
+    collateral_denom: &str,
+    debt_denom: &str,
+    f: impl FnOnce(Uint128) -> Result<Uint128, OverflowError>,
+) -> ContractResult<()> {
+    let key = (collateral_denom, debt_denom);
+    let total = f(ISOLATED_DEBT_SHARES.may_load(storage, key)?.unwrap_or_default())?;
+    if total.is_zero() {
+        ISOLATED_DEBT_SHARES.remove(storage, key);
+    } else {
+        ISOLATED_DEBT_SHARES.save(storage, key, &total)?;
+    }
+    Ok(())
+}
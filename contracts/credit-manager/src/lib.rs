@@ -8,6 +8,7 @@ pub mod execute;
 pub mod health;
 pub mod hls;
 pub mod instantiate;
+pub mod isolation;
 pub mod lend;
 pub mod liquidate;
 pub mod liquidate_astro_lp;
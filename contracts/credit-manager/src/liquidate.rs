@@ -1,7 +1,9 @@
 use cosmwasm_std::{
     Attribute, Coin, Decimal, Deps, DepsMut, Env, Int128, QuerierWrapper, Response, Uint128,
 };
-use mars_liquidation::liquidation::{calculate_liquidation_amounts, HealthData};
+use mars_liquidation::liquidation::{
+    calculate_liquidation_amounts, calculate_perps_close_ratio, HealthData,
+};
 use mars_types::{
     adapters::oracle::Oracle, health::HealthValuesResponse, oracle::ActionKind, traits::Stringify,
 };
@@ -10,7 +12,9 @@ use crate::{
     error::{ContractError, ContractResult},
     health::query_health_values,
     repay::current_debt_for_denom,
-    state::{ORACLE, PARAMS, PERPS_LB_RATIO, REWARDS_COLLECTOR},
+    state::{
+        ORACLE, PARAMS, PERPS, PERPS_LB_RATIO, PERPS_LIQUIDATION_TARGET_HF, REWARDS_COLLECTOR,
+    },
     utils::increment_coin_balance,
 };
 
@@ -34,6 +38,25 @@ pub fn check_health(
     Ok(health)
 }
 
+/// Returns the health values to liquidate the spot positions of the liquidatee with.
+///
+/// If the perp positions were partially liquidated beforehand, no previous health is given and it
+/// is queried again. `None` is returned when that was enough to make the account healthy, in
+/// which case the spot positions are left untouched.
+pub fn spot_liquidation_health(
+    deps: Deps,
+    env: Env,
+    liquidatee_account_id: &str,
+    prev_health: Option<HealthValuesResponse>,
+) -> ContractResult<Option<HealthValuesResponse>> {
+    if let Some(health) = prev_health {
+        return Ok(Some(health));
+    }
+
+    let health = query_health_values(deps, env, liquidatee_account_id, ActionKind::Liquidation)?;
+    Ok(health.liquidatable.then_some(health))
+}
+
 /// Result of a liquidation calculation.
 pub struct LiquidationResult {
     pub debt: Coin,
@@ -69,6 +92,64 @@ impl LiquidationResult {
     }
 }
 
+/// Result of a partial perps liquidation calculation.
+pub struct PerpsLiquidationResult {
+    pub close_ratio: Decimal,
+    pub liquidator_bonus: Coin,
+    pub protocol_fee: Coin,
+}
+
+/// Calculates the share of the liquidatee's cross-margin perp positions to close in order to
+/// bring the account to the perps liquidation target health factor, and the bonus paid for it.
+///
+/// Returns `None` if the target health factor is not configured or if it can't be reached by
+/// closing perps alone. In that case all perps are closed before liquidating the spot positions.
+pub fn calculate_perps_liquidation(
+    deps: Deps,
+    prev_health: &HealthValuesResponse,
+) -> ContractResult<Option<PerpsLiquidationResult>> {
+    let Some(target_health_factor) = PERPS_LIQUIDATION_TARGET_HF.may_load(deps.storage)? else {
+        return Ok(None);
+    };
+    let perps_lb_ratio = PERPS_LB_RATIO.load(deps.storage)?;
+
+    let base_denom = PERPS.load(deps.storage)?.query_config(&deps.querier)?.base_denom;
+    let base_denom_params = PARAMS
+        .load(deps.storage)?
+        .query_asset_params(&deps.querier, &base_denom)?
+        .ok_or(ContractError::AssetParamsNotFound(base_denom.clone()))?;
+
+    let (close_ratio, perps_lb_value, protocol_fee_value) = calculate_perps_close_ratio(
+        prev_health,
+        target_health_factor,
+        &base_denom_params,
+        perps_lb_ratio,
+    )?;
+    if close_ratio >= Decimal::one() {
+        return Ok(None);
+    }
+
+    let base_denom_price = ORACLE
+        .load(deps.storage)?
+        .query_price(&deps.querier, &base_denom, ActionKind::Liquidation)?
+        .price;
+    let protocol_fee_amount = protocol_fee_value.checked_div_floor(base_denom_price)?;
+    let liquidator_bonus_amount =
+        perps_lb_value.checked_div_floor(base_denom_price)?.saturating_sub(protocol_fee_amount);
+
+    Ok(Some(PerpsLiquidationResult {
+        close_ratio,
+        liquidator_bonus: Coin {
+            denom: base_denom.clone(),
+            amount: liquidator_bonus_amount,
+        },
+        protocol_fee: Coin {
+            denom: base_denom,
+            amount: protocol_fee_amount,
+        },
+    }))
+}
+
 /// Calculates precise debt, request coin amounts to liquidate, request coin transfered to liquidator and rewards-collector.
 /// The debt amount will be adjusted down if:
 /// - Exceeds liquidatee's total debt for denom
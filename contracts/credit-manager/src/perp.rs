@@ -1,11 +1,13 @@
 use std::cmp::min;
 
 use cosmwasm_std::{
-    coin, ensure_eq, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, Int128, MessageInfo,
+    coin, ensure_eq, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, Int128, MessageInfo,
     Response, Uint128,
 };
+use cw_utils::one_coin;
 use mars_types::{
     adapters::perps::Perps,
+    credit_manager::Action,
     oracle::ActionKind,
     perps::{PerpPosition, PnL, PnlAmounts},
 };
@@ -13,10 +15,12 @@ use mars_types::{
 use crate::{
     borrow,
     error::{ContractError, ContractResult},
+    execute::dispatch_actions,
+    health::query_health_values,
     staking::get_account_tier_and_discount,
-    state::{COIN_BALANCES, PERPS, RED_BANK},
+    state::{COIN_BALANCES, ORACLE, PARAMS, PERPS, RED_BANK, REWARDS_COLLECTOR},
     trigger::remove_invalid_trigger_orders,
-    utils::{decrement_coin_balance, increment_coin_balance},
+    utils::{assert_keeper_fee, decrement_coin_balance, increment_coin_balance},
 };
 
 /// Deducts a specified payment from the user's account. If the user's balance in the
@@ -85,12 +89,54 @@ fn deduct_payment(
     Ok(res.add_message(borrow_msg))
 }
 
+/// Returns the part of a perp loss that the account can't back with its collateral, valued with
+/// liquidation pricing. The account's balance and lends of the base denom pay for the loss first,
+/// so the bad debt is capped at what would otherwise be borrowed from the Red Bank.
+///
+/// `loss_in_health` tells whether the loss is still part of the account's health, i.e. whether
+/// the positions haven't been closed by the perps contract yet.
+fn query_perps_bad_debt(
+    deps: Deps,
+    env: Env,
+    account_id: &str,
+    loss: &Coin,
+    loss_in_health: bool,
+) -> ContractResult<Uint128> {
+    let balance =
+        COIN_BALANCES.may_load(deps.storage, (account_id, &loss.denom))?.unwrap_or(Uint128::zero());
+    let red_bank = RED_BANK.load(deps.storage)?;
+    let lent = red_bank.query_lent(&deps.querier, account_id, &loss.denom)?;
+    let to_borrow = loss.amount.saturating_sub(balance.checked_add(lent)?);
+    if to_borrow.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let health = query_health_values(deps, env, account_id, ActionKind::Liquidation)?;
+    let oracle = ORACLE.load(deps.storage)?;
+    let price = oracle.query_price(&deps.querier, &loss.denom, ActionKind::Liquidation)?.price;
+
+    let unsettled_loss_value = if loss_in_health {
+        Uint128::zero()
+    } else {
+        loss.amount.checked_mul_ceil(price)?
+    };
+    let assets = health.total_collateral_value.checked_add(health.perps_pnl_profit)?;
+    let liabilities = health
+        .total_debt_value
+        .checked_add(health.perps_pnl_loss)?
+        .checked_add(unsettled_loss_value)?;
+    let shortfall = liabilities.saturating_sub(assets).checked_div_floor(price)?;
+
+    Ok(min(shortfall, to_borrow))
+}
+
 pub fn execute_perp_order(
     mut deps: DepsMut,
     account_id: &str,
     denom: &str,
     order_size: Int128,
     reduce_only: Option<bool>,
+    limit_price: Option<Decimal>,
 ) -> ContractResult<Response> {
     let perps = PERPS.load(deps.storage)?;
 
@@ -107,8 +153,14 @@ pub fn execute_perp_order(
     // the position PnL first here in the credit manager (so that it knows how
     // much funds to send to the perps contract), then in the perps contract it
     // computes the PnL **again** to assert the amount is correct.
-    let position =
-        perps.query_position(&deps.querier, account_id, denom, Some(order_size), reduce_only)?;
+    let position = perps.query_position(
+        &deps.querier,
+        account_id,
+        denom,
+        Some(order_size),
+        reduce_only,
+        ActionKind::Default,
+    )?;
 
     Ok(match position {
         Some(position) => modify_existing_position(
@@ -120,8 +172,10 @@ pub fn execute_perp_order(
             position,
             order_size,
             reduce_only,
+            limit_price,
             discount_pct,
             &tier.id,
+            None,
         )?,
         None => {
             // Open new position
@@ -143,6 +197,9 @@ pub fn execute_perp_order(
                 reduce_only,
                 funds,
                 Some(discount_pct),
+                limit_price,
+                None,
+                None,
             )?;
 
             response
@@ -160,6 +217,242 @@ pub fn execute_perp_order(
     })
 }
 
+/// Opens an isolated-margin perp position. The margin is taken from the account's deposits and,
+/// together with the opening fee, sent to the perps contract where it backs the position.
+pub fn open_isolated_perp_position(
+    mut deps: DepsMut,
+    account_id: &str,
+    denom: &str,
+    order_size: Int128,
+    margin: Uint128,
+    limit_price: Option<Decimal>,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    let mut response = Response::new();
+
+    // Get staking tier discount for this account
+    let (tier, discount_pct, voting_power) =
+        get_account_tier_and_discount(deps.as_ref(), account_id)?;
+
+    let opening_fee =
+        perps.query_opening_fee(&deps.querier, denom, order_size, Some(discount_pct))?;
+    let fee = opening_fee.fee;
+
+    // The margin has to be deposited in the account, it is never borrowed
+    let margin_coin = coin(margin.u128(), &fee.denom);
+    decrement_coin_balance(deps.storage, account_id, &margin_coin)?;
+
+    if !fee.amount.is_zero() {
+        response = deduct_payment(&mut deps, account_id, &fee, None, response)?;
+    }
+
+    let funds = vec![coin(margin.checked_add(fee.amount)?.u128(), &fee.denom)];
+    let msg = perps.execute_perp_order(
+        account_id,
+        denom,
+        order_size,
+        None,
+        funds,
+        Some(discount_pct),
+        limit_price,
+        Some(margin),
+        None,
+    )?;
+
+    Ok(response
+        .add_message(msg)
+        .add_attribute("action", "open_isolated_perp_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("new_size", order_size.to_string())
+        .add_attribute("isolated_margin", margin.to_string())
+        .add_attribute("opening_fee", fee.to_string())
+        .add_attribute("voting_power", voting_power.to_string())
+        .add_attribute("tier_id", tier.id)
+        .add_attribute("discount_pct", discount_pct.to_string()))
+}
+
+/// Moves coins from the account's deposits to the margin of an isolated-margin perp position.
+pub fn add_perp_isolated_margin(
+    deps: DepsMut,
+    account_id: &str,
+    denom: &str,
+    amount: Uint128,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    let base_denom = perps.query_config(&deps.querier)?.base_denom;
+    let margin_coin = coin(amount.u128(), base_denom);
+    decrement_coin_balance(deps.storage, account_id, &margin_coin)?;
+
+    let msg = perps.add_isolated_margin_msg(account_id, denom, &margin_coin)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "add_perp_isolated_margin")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", margin_coin.to_string()))
+}
+
+/// Credits the account with the margin left of an isolated-margin perp position closed by the
+/// perps contract.
+pub fn return_isolated_margin(
+    deps: DepsMut,
+    info: MessageInfo,
+    account_id: String,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    // Only the perps contract can return isolated margin
+    ensure_eq!(
+        &info.sender,
+        perps.address(),
+        ContractError::Unauthorized {
+            user: info.sender.to_string(),
+            action: "return isolated margin".to_string()
+        }
+    );
+
+    let margin = one_coin(&info)?;
+    increment_coin_balance(deps.storage, &account_id, &margin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "return_isolated_margin")
+        .add_attribute("account_id", account_id)
+        .add_attribute("isolated_margin", margin.to_string()))
+}
+
+/// Places a limit order in the perps contract. The keeper fee is deducted from the account and
+/// held by the credit manager until the order is filled or removed.
+pub fn place_perp_limit_order(
+    deps: DepsMut,
+    account_id: &str,
+    denom: &str,
+    size: Int128,
+    limit_price: Decimal,
+    reduce_only: Option<bool>,
+    keeper_fee: Coin,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    assert_keeper_fee(deps.storage, &keeper_fee)?;
+    decrement_coin_balance(deps.storage, account_id, &keeper_fee)?;
+
+    let msg = perps.place_limit_order_msg(
+        account_id,
+        denom,
+        size,
+        limit_price,
+        reduce_only,
+        keeper_fee.clone(),
+    )?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "place_perp_limit_order")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("order_size", size.to_string())
+        .add_attribute("limit_price", limit_price.to_string())
+        .add_attribute("keeper_fee", keeper_fee.to_string()))
+}
+
+pub fn cancel_perp_limit_order(
+    deps: DepsMut,
+    account_id: &str,
+    order_id: u64,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    let msg = perps.cancel_limit_order_msg(account_id, order_id)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "cancel_perp_limit_order")
+        .add_attribute("account_id", account_id)
+        .add_attribute("order_id", order_id.to_string()))
+}
+
+/// Executes a limit order filled by the perps contract as a regular perp order of the account.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_perp_limit_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    denom: String,
+    size: Int128,
+    limit_price: Decimal,
+    reduce_only: Option<bool>,
+    keeper: String,
+    keeper_fee: Coin,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    // Only the perps contract can fill limit orders
+    ensure_eq!(
+        &info.sender,
+        perps.address(),
+        ContractError::Unauthorized {
+            user: info.sender.to_string(),
+            action: "fill perp limit order".to_string()
+        }
+    );
+
+    let keeper = deps.api.addr_validate(&keeper)?;
+
+    let actions = vec![Action::ExecutePerpOrder {
+        denom,
+        order_size: size,
+        reduce_only,
+        order_type: None,
+        limit_price: Some(limit_price),
+    }];
+
+    let res = dispatch_actions(deps, env, info, Some(account_id), None, actions, false, false)?;
+
+    // Pay the escrowed keeper fee to the keeper
+    let transfer_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: keeper.to_string(),
+        amount: vec![keeper_fee.clone()],
+    });
+
+    Ok(res
+        .add_message(transfer_msg)
+        .add_attribute("keeper", keeper)
+        .add_attribute("keeper_fee", keeper_fee.to_string()))
+}
+
+/// Returns the escrowed keeper fee of a limit order removed by the perps contract without being
+/// filled to the account.
+pub fn refund_perp_limit_order_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    account_id: String,
+    keeper_fee: Coin,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+
+    // Only the perps contract can refund keeper fees
+    ensure_eq!(
+        &info.sender,
+        perps.address(),
+        ContractError::Unauthorized {
+            user: info.sender.to_string(),
+            action: "refund perp limit order fee".to_string()
+        }
+    );
+
+    increment_coin_balance(deps.storage, &account_id, &keeper_fee)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "refund_perp_limit_order_fee")
+        .add_attribute("account_id", account_id)
+        .add_attribute("keeper_fee", keeper_fee.to_string()))
+}
+
 pub fn close_perp_position(
     deps: DepsMut,
     account_id: &str,
@@ -177,7 +470,8 @@ pub fn close_perp_position(
     // the position PnL first here in the credit manager (so that it knows how
     // much funds to send to the perps contract), then in the perps contract it
     // computes the PnL **again** to assert the amount is correct.
-    let position = perps.query_position(&deps.querier, account_id, denom, None, None)?;
+    let position =
+        perps.query_position(&deps.querier, account_id, denom, None, None, ActionKind::Default)?;
 
     let response = Response::new();
 
@@ -195,8 +489,10 @@ pub fn close_perp_position(
                 position,
                 order_size,
                 Some(true),
+                None,
                 discount_pct,
                 &tier.id,
+                None,
             )?)
         }
         None => Err(ContractError::NoPerpPosition {
@@ -205,16 +501,20 @@ pub fn close_perp_position(
     }
 }
 
-/// Check if liquidatee has any perp positions.
+/// Check if liquidatee has any cross-margin perp positions.
 /// If so, close them before liquidating.
+///
+/// Isolated-margin positions are backed by their own margin and stay open.
 pub fn close_all_perps(
     mut deps: DepsMut,
+    env: Env,
     account_id: &str,
     action: ActionKind,
 ) -> ContractResult<Response> {
     let perps = PERPS.load(deps.storage)?;
-    let perp_positions =
+    let mut perp_positions =
         perps.query_positions_by_account(&deps.querier, account_id, action.clone())?;
+    perp_positions.retain(|position| position.isolated_margin.is_none());
     if perp_positions.is_empty() {
         return Ok(Response::new()
             .add_attribute("action", "close_all_perps")
@@ -234,27 +534,157 @@ pub fn close_all_perps(
     let response = Response::new();
 
     let pnl = pnl_amounts_accumulator.to_coins(&base_denom).pnl;
+
+    // When liquidating, the loss the account can't back is covered by the perps insurance fund
+    // instead of becoming Red Bank debt
+    let (pnl, bad_debt) = match pnl {
+        PnL::Loss(loss) if action == ActionKind::Liquidation => {
+            let bad_debt = query_perps_bad_debt(deps.as_ref(), env, account_id, &loss, true)?;
+            (PnL::Loss(coin(loss.amount.checked_sub(bad_debt)?.u128(), loss.denom)), bad_debt)
+        }
+        pnl => (pnl, Uint128::zero()),
+    };
+
     let (funds, response) =
         update_state_based_on_pnl(&mut deps, account_id, pnl, Some(action.clone()), response)?;
-    let funds = funds.map_or_else(Vec::new, |c| vec![c]);
+    let funds = funds.filter(|c| !c.amount.is_zero()).map_or_else(Vec::new, |c| vec![c]);
 
     // Get staking tier discount for this account
     let (tier, discount_pct, voting_power) =
         get_account_tier_and_discount(deps.as_ref(), account_id)?;
 
     // Close all perp positions at once
-    let close_msg = perps.close_all_msg(account_id, funds, action, Some(discount_pct))?;
+    let close_msg =
+        perps.close_all_msg(account_id, funds, action, Some(discount_pct), Some(bad_debt))?;
 
     Ok(response
         .add_message(close_msg)
         .add_attribute("action", "close_all_perps")
         .add_attribute("account_id", account_id)
         .add_attribute("number_of_positions", perp_positions.len().to_string())
+        .add_attribute("bad_debt", bad_debt.to_string())
         .add_attribute("voting_power", voting_power.to_string())
         .add_attribute("tier_id", tier.id)
         .add_attribute("discount_pct", discount_pct.to_string()))
 }
 
+/// Closes the given share of every cross-margin perp position of a liquidatable account.
+///
+/// Positions are reduced with reduce-only orders, settling the PnL the same way as when the user
+/// modifies them, but priced with `ActionKind::Liquidation` as when closing all positions. A
+/// position is closed entirely if what is left would be below the market's minimum position
+/// value, or if the market is disabled.
+///
+/// The liquidator's bonus and the protocol fee are then paid from the liquidatee's base denom
+/// balance, after the PnL has been settled.
+pub fn liquidate_perps(
+    mut deps: DepsMut,
+    liquidator_account_id: &str,
+    liquidatee_account_id: &str,
+    close_ratio: Decimal,
+    liquidator_bonus: Coin,
+    protocol_fee: Coin,
+) -> ContractResult<Response> {
+    let perps = PERPS.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
+
+    let mut perp_positions = perps.query_positions_by_account(
+        &deps.querier,
+        liquidatee_account_id,
+        ActionKind::Liquidation,
+    )?;
+    perp_positions.retain(|position| position.isolated_margin.is_none());
+
+    // Get staking tier discount for this account
+    let (tier, discount_pct, _) =
+        get_account_tier_and_discount(deps.as_ref(), liquidatee_account_id)?;
+
+    let mut response = Response::new();
+
+    for position in perp_positions {
+        let perp_params = params.query_perp_params(&deps.querier, &position.denom)?;
+
+        let entry_size = position.size.unsigned_abs();
+        let close_size = min(entry_size.checked_mul_ceil(close_ratio)?, entry_size);
+        let remaining_value =
+            (entry_size - close_size).checked_mul_floor(position.current_price)?;
+        let close_size = if !perp_params.enabled || remaining_value < perp_params.min_position_value
+        {
+            entry_size
+        } else {
+            close_size
+        };
+        if close_size.is_zero() {
+            continue;
+        }
+
+        let close_size = Int128::try_from(close_size)?;
+        let order_size = if position.size.is_negative() {
+            close_size
+        } else {
+            close_size.checked_neg()?
+        };
+
+        // Query the PnL of this exact modification so that the payment matches
+        let position = perps
+            .query_position(
+                &deps.querier,
+                liquidatee_account_id,
+                &position.denom,
+                Some(order_size),
+                Some(true),
+                ActionKind::Liquidation,
+            )?
+            .ok_or_else(|| ContractError::NoPerpPosition {
+                denom: position.denom.clone(),
+            })?;
+
+        let denom = position.denom.clone();
+        response = modify_existing_position(
+            deps.branch(),
+            response,
+            &perps,
+            liquidatee_account_id,
+            &denom,
+            position,
+            order_size,
+            Some(true),
+            None,
+            discount_pct,
+            &tier.id,
+            Some(ActionKind::Liquidation),
+        )?;
+    }
+
+    // Pay the bonus and the fee from what is left in the liquidatee's account
+    let mut available = COIN_BALANCES
+        .may_load(deps.storage, (liquidatee_account_id, &liquidator_bonus.denom))?
+        .unwrap_or_default();
+
+    let bonus = coin(min(liquidator_bonus.amount, available).u128(), &liquidator_bonus.denom);
+    available -= bonus.amount;
+    let fee = coin(min(protocol_fee.amount, available).u128(), &protocol_fee.denom);
+
+    if !bonus.amount.is_zero() {
+        decrement_coin_balance(deps.storage, liquidatee_account_id, &bonus)?;
+        increment_coin_balance(deps.storage, liquidator_account_id, &bonus)?;
+    }
+    if !fee.amount.is_zero() {
+        let rewards_collector_account = REWARDS_COLLECTOR.load(deps.storage)?.account_id;
+        decrement_coin_balance(deps.storage, liquidatee_account_id, &fee)?;
+        increment_coin_balance(deps.storage, &rewards_collector_account, &fee)?;
+    }
+
+    Ok(response
+        .add_attribute("action", "liquidate_perps")
+        .add_attribute("account_id", liquidator_account_id)
+        .add_attribute("liquidatee_account_id", liquidatee_account_id)
+        .add_attribute("close_ratio", close_ratio.to_string())
+        .add_attribute("liquidator_bonus", bonus.to_string())
+        .add_attribute("protocol_fee_coin", fee.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn modify_existing_position(
     mut deps: DepsMut,
     response: Response,
@@ -264,12 +694,40 @@ fn modify_existing_position(
     position: PerpPosition,
     order_size: Int128,
     reduce_only: Option<bool>,
+    limit_price: Option<Decimal>,
     discount_pct: Decimal,
     tier: &str,
+    action: Option<ActionKind>,
 ) -> ContractResult<Response> {
     let pnl = position.unrealized_pnl.to_coins(&position.base_denom).pnl;
     let pnl_string = position.unrealized_pnl.pnl.to_string();
-    let (funds, response) = update_state_based_on_pnl(&mut deps, account_id, pnl, None, response)?;
+    let new_size = position.size.checked_add(order_size)?;
+
+    let (funds, response) = match position.isolated_margin {
+        // Isolated-margin positions settle the PnL against their margin. Nothing is paid by the
+        // account, and what is left of the margin is returned once the position is closed.
+        Some(margin) => {
+            let closing = new_size.is_zero()
+                || (reduce_only.unwrap_or(false)
+                    && order_size.unsigned_abs() > position.size.unsigned_abs());
+            if closing {
+                let margin_left = match &pnl {
+                    PnL::Profit(profit) => margin.checked_add(profit.amount)?,
+                    PnL::Loss(loss) => margin.saturating_sub(loss.amount),
+                    PnL::BreakEven => margin,
+                };
+                if !margin_left.is_zero() {
+                    increment_coin_balance(
+                        deps.storage,
+                        account_id,
+                        &coin(margin_left.u128(), &position.base_denom),
+                    )?;
+                }
+            }
+            (None, response)
+        }
+        None => update_state_based_on_pnl(&mut deps, account_id, pnl, action.clone(), response)?,
+    };
     let funds = funds.map_or_else(Vec::new, |c| vec![c]);
 
     let msg = perps.execute_perp_order(
@@ -279,10 +737,11 @@ fn modify_existing_position(
         reduce_only,
         funds,
         Some(discount_pct),
+        limit_price,
+        None,
+        action,
     )?;
 
-    let new_size = position.size.checked_add(order_size)?;
-
     // When size is 0 or positions flips, any active (order is a default or parent, or child order
     // with parent being executed) with reduce_only should be removed.
     if new_size.is_zero() || (new_size.is_negative() != position.size.is_negative()) {
@@ -331,7 +790,7 @@ fn update_state_based_on_pnl(
 
 pub fn update_balance_after_deleverage(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     account_id: String,
     pnl: PnL,
@@ -352,6 +811,17 @@ pub fn update_balance_after_deleverage(
     let response = Response::new();
 
     let pnl_string = pnl.to_signed_uint()?.to_string();
+
+    // The loss the account can't back is covered by the perps insurance fund instead of becoming
+    // Red Bank debt. The perps contract absorbs what isn't sent.
+    let (pnl, bad_debt) = match pnl {
+        PnL::Loss(loss) => {
+            let bad_debt = query_perps_bad_debt(deps.as_ref(), env, &account_id, &loss, false)?;
+            (PnL::Loss(coin(loss.amount.checked_sub(bad_debt)?.u128(), loss.denom)), bad_debt)
+        }
+        pnl => (pnl, Uint128::zero()),
+    };
+
     let (funds, mut response) =
         update_state_based_on_pnl(&mut deps, &account_id, pnl, Some(action), response)?;
 
@@ -369,5 +839,6 @@ pub fn update_balance_after_deleverage(
     Ok(response
         .add_attribute("action", "update_balance_after_deleverage")
         .add_attribute("account_id", account_id)
-        .add_attribute("realized_pnl", pnl_string))
+        .add_attribute("realized_pnl", pnl_string)
+        .add_attribute("bad_debt", bad_debt.to_string()))
 }
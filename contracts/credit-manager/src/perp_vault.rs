@@ -6,7 +6,7 @@ use crate::{
     state::PERPS,
     utils::{
         assert_coin_is_whitelisted, decrement_coin_balance, get_amount_from_action_coin,
-        update_balance_msg,
+        update_balances_msgs,
     },
 };
 
@@ -62,21 +62,49 @@ pub fn withdraw_from_perp_vault(
 ) -> ContractResult<Response> {
     let perps = PERPS.load(deps.storage)?;
     let perp_config = perps.query_config(&deps.querier)?;
+    let perp_vault = perps.query_vault(&deps.querier)?;
+
+    // Collateral held by the perp vault is withdrawn in kind, proportionally to the unlocked shares
+    let unlocked_shares = perps
+        .query_vault_position(&deps.querier, &env.contract.address, account_id)?
+        .map(|position| {
+            position
+                .unlocks
+                .iter()
+                .filter(|unlock| unlock.cooldown_end <= env.block.time.seconds())
+                .map(|unlock| unlock.shares)
+                .sum::<Uint128>()
+        })
+        .unwrap_or_default();
+    let mut denoms = vec![];
+    if !perp_vault.total_shares.is_zero() {
+        for collateral in perp_vault.collaterals.iter() {
+            let amount = collateral
+                .amount
+                .checked_multiply_ratio(unlocked_shares, perp_vault.total_shares)?;
+            if !amount.is_zero() {
+                denoms.push(collateral.denom.as_str());
+            }
+        }
+    }
+    if !perp_vault.total_withdrawal_balance.is_zero() || denoms.is_empty() {
+        denoms.insert(0, &perp_config.base_denom);
+    }
 
     let withdraw_from_perp_vault_msg = perps.withdraw_msg(account_id, min_receive)?;
 
     // Updates coin balances for account after the withdraw has taken place
-    let update_coin_balance_msg = update_balance_msg(
+    let update_coin_balances_msgs = update_balances_msgs(
         &deps.querier,
         &env.contract.address,
         account_id,
-        &perp_config.base_denom,
+        denoms,
         ChangeExpected::Increase,
     )?;
 
     Ok(Response::new()
         .add_message(withdraw_from_perp_vault_msg)
-        .add_message(update_coin_balance_msg)
+        .add_messages(update_coin_balances_msgs)
         .add_attribute("action", "withdraw_from_perp_vault")
         .add_attribute("account_id", account_id))
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Coin, Decimal, Deps, Env, Order, StdResult};
+use cosmwasm_std::{Coin, Decimal, Deps, Env, Order, StdError, StdResult};
 use cw_paginate::{
     paginate_map, paginate_map_query, paginate_prefix_query, PaginationResponse, DEFAULT_LIMIT,
     MAX_LIMIT,
@@ -7,10 +7,11 @@ use cw_storage_plus::Bound;
 use mars_types::{
     adapters::vault::{Vault, VaultBase, VaultPosition, VaultPositionValue, VaultUnchecked},
     credit_manager::{
-        Account, AccountTierAndDiscountResponse, CoinBalanceResponseItem, ConfigResponse,
-        DebtAmount, DebtShares, FeeTierConfigResponse, PerpTradingFeeResponse, Positions,
-        SharesResponseItem, SpotTradingFeeResponse, TradingFeeResponse, TriggerOrderResponse,
-        VaultBinding, VaultPositionResponseItem, VaultUtilizationResponse,
+        Account, AccountTierAndDiscountResponse, CoinBalanceResponseItem, Condition,
+        ConfigResponse, DebtAmount, DebtShares, FeeTierConfigResponse, PerpTradingFeeResponse,
+        Positions, SharesResponseItem, SpotTradingFeeResponse, TradingFeeResponse,
+        TrailingMarkResponse, TriggerOrder, TriggerOrderResponse, VaultBinding,
+        VaultPositionResponseItem, VaultUtilizationResponse,
     },
     health::AccountKind,
     oracle::ActionKind,
@@ -22,10 +23,12 @@ use crate::{
     staking::get_account_tier_and_discount,
     state::{
         ACCOUNT_KINDS, ACCOUNT_NFT, COIN_BALANCES, DEBT_SHARES, FEE_TIER_CONFIG, HEALTH_CONTRACT,
-        INCENTIVES, KEEPER_FEE_CONFIG, MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS, ORACLE, OWNER,
-        PARAMS, PERPS, PERPS_LB_RATIO, RED_BANK, REWARDS_COLLECTOR, SWAPPER, SWAP_FEE,
-        TOTAL_DEBT_SHARES, TRIGGER_ORDERS, VAULTS, VAULT_POSITIONS, ZAPPER,
+        INCENTIVES, ISOLATED_DEBT_SHARES, KEEPER_FEE_CONFIG, MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS,
+        ORACLE, OWNER, PARAMS, PERPS, PERPS_LB_RATIO, PERPS_LIQUIDATION_TARGET_HF, RED_BANK,
+        REWARDS_COLLECTOR, SWAPPER, SWAP_FEE, TOTAL_DEBT_SHARES, TRAILING_MARKS, TRIGGER_ORDERS,
+        VAULTS, VAULT_POSITIONS, ZAPPER,
     },
+    trigger::trailing_trigger_price,
     utils::debt_shares_to_amount,
     vault::vault_utilization_in_deposit_cap_denom,
 };
@@ -70,6 +73,8 @@ pub fn query_config(deps: Deps) -> ContractResult<ConfigResponse> {
         rewards_collector: REWARDS_COLLECTOR.may_load(deps.storage)?,
         keeper_fee_config: KEEPER_FEE_CONFIG.load(deps.storage)?,
         perps_liquidation_bonus_ratio: PERPS_LB_RATIO.load(deps.storage)?,
+        perps_liquidation_target_health_factor: PERPS_LIQUIDATION_TARGET_HF
+            .may_load(deps.storage)?,
     })
 }
 
@@ -212,6 +217,69 @@ pub fn query_all_trigger_orders(
     )
 }
 
+pub fn query_trailing_mark(
+    deps: Deps,
+    account_id: &str,
+    trigger_order_id: &str,
+) -> StdResult<Option<TrailingMarkResponse>> {
+    let Some(mark) = TRAILING_MARKS.may_load(deps.storage, (account_id, trigger_order_id))? else {
+        return Ok(None);
+    };
+    let order = TRIGGER_ORDERS.load(deps.storage, (account_id, trigger_order_id))?;
+    to_trailing_mark_response(account_id, &order, mark).map(Some)
+}
+
+pub fn query_all_trailing_marks_for_account(
+    deps: Deps,
+    account_id: String,
+    start_after_order_id: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PaginationResponse<TrailingMarkResponse>> {
+    let start = start_after_order_id.as_ref().map(|order_id| Bound::exclusive(order_id.as_str()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    paginate_prefix_query(
+        &TRAILING_MARKS,
+        deps.storage,
+        &account_id,
+        start,
+        Some(limit),
+        |order_id, mark| {
+            let order =
+                TRIGGER_ORDERS.load(deps.storage, (account_id.as_str(), order_id.as_str()))?;
+            to_trailing_mark_response(&account_id, &order, mark)
+        },
+    )
+}
+
+fn to_trailing_mark_response(
+    account_id: &str,
+    order: &TriggerOrder,
+    mark: Decimal,
+) -> StdResult<TrailingMarkResponse> {
+    // Marks are only stored for orders with a TrailingOraclePrice condition
+    let (denom, trailing_pct, comparison) = order
+        .conditions
+        .iter()
+        .find_map(|condition| match condition {
+            Condition::TrailingOraclePrice {
+                denom,
+                trailing_pct,
+                comparison,
+            } => Some((denom, trailing_pct, comparison)),
+            _ => None,
+        })
+        .ok_or_else(|| StdError::not_found("TrailingOraclePrice condition"))?;
+
+    Ok(TrailingMarkResponse {
+        account_id: account_id.to_string(),
+        order_id: order.order_id.clone(),
+        denom: denom.clone(),
+        mark,
+        trigger_price: trailing_trigger_price(mark, *trailing_pct, comparison)?,
+    })
+}
+
 pub fn query_vault_utilization(
     deps: Deps,
     env: Env,
@@ -418,3 +486,15 @@ pub fn query_fee_tier_config(deps: Deps) -> ContractResult<FeeTierConfigResponse
         fee_tier_config: FEE_TIER_CONFIG.load(deps.storage)?,
     })
 }
+
+/// Debts backed by the given isolated collateral, with the interest accrued on them
+pub fn query_isolated_debts(deps: Deps, denom: String) -> ContractResult<Vec<Coin>> {
+    ISOLATED_DEBT_SHARES
+        .prefix(&denom)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (debt_denom, shares) = item?;
+            debt_shares_to_amount(deps, &debt_denom, shares)
+        })
+        .collect()
+}
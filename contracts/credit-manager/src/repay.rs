@@ -1,15 +1,19 @@
 use std::cmp::min;
 
 use cosmwasm_std::{
-    to_json_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
-    WasmMsg,
+    ensure, to_json_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    Uint128, WasmMsg,
 };
 use cw_utils::one_coin;
-use mars_types::credit_manager::{ActionCoin, CallbackMsg::Repay, ExecuteMsg};
+use mars_types::{
+    credit_manager::{ActionCoin, CallbackMsg, ExecuteMsg},
+    oracle::ActionKind,
+};
 
 use crate::{
     error::{ContractError, ContractResult},
-    state::{COIN_BALANCES, DEBT_SHARES, RED_BANK, TOTAL_DEBT_SHARES},
+    query::query_positions,
+    state::{COIN_BALANCES, DEBT_SHARES, PERPS, RED_BANK, TOTAL_DEBT_SHARES},
     utils::{debt_shares_to_amount, decrement_coin_balance, increment_coin_balance},
 };
 
@@ -103,7 +107,7 @@ pub fn repay_for_recipient(
     let repay_callback_msg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: env.contract.address.to_string(),
         funds: vec![],
-        msg: to_json_binary(&ExecuteMsg::Callback(Repay {
+        msg: to_json_binary(&ExecuteMsg::Callback(CallbackMsg::Repay {
             account_id: recipient_account_id.to_string(),
             coin: ActionCoin::from(coin_to_repay),
         }))?,
@@ -137,7 +141,7 @@ pub fn repay_from_wallet(
     let repay_callback_msg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: env.contract.address.to_string(),
         funds: vec![],
-        msg: to_json_binary(&ExecuteMsg::Callback(Repay {
+        msg: to_json_binary(&ExecuteMsg::Callback(CallbackMsg::Repay {
             account_id: account_id.to_string(),
             coin: ActionCoin::from(&coin_to_repay),
         }))?,
@@ -150,8 +154,15 @@ pub fn repay_from_wallet(
         Uint128::zero()
     };
 
+    let update_isolated_debts_msg = CallbackMsg::UpdateIsolatedDebts {
+        account_id: account_id.clone(),
+        enforce_ceiling: false,
+    }
+    .into_cosmos_msg(&env.contract.address)?;
+
     let mut response = Response::new()
         .add_message(repay_callback_msg)
+        .add_message(update_isolated_debts_msg)
         .add_attribute("action", "repay_from_wallet")
         .add_attribute("from_address", info.sender.to_string())
         .add_attribute("account_id", account_id)
@@ -171,3 +182,61 @@ pub fn repay_from_wallet(
 
     Ok(response)
 }
+
+/// Write off the debt of an account left without any collateral. The debt is removed from the
+/// account and written off in Red Bank, where the loss is covered by the reserves and depositors.
+/// Shares deposited into the perps vault, locked or unlocking, count as collateral.
+pub fn write_off_bad_debt(
+    deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    denom: &str,
+) -> ContractResult<Response> {
+    let positions = query_positions(deps.as_ref(), account_id, ActionKind::Liquidation)?;
+    let has_collateral = !positions.deposits.is_empty()
+        || !positions.lends.is_empty()
+        || !positions.vaults.is_empty()
+        || !positions.staked_astro_lps.is_empty()
+        || !positions.perps.is_empty()
+        || has_perp_vault_position(deps.as_ref(), &env, account_id)?;
+    ensure!(!has_collateral, ContractError::CannotWriteOffDebtWithCollateral {});
+
+    let (debt_amount, debt_shares) = current_debt_for_denom(deps.as_ref(), account_id, denom)?;
+
+    DEBT_SHARES.remove(deps.storage, (account_id, denom));
+    let total_debt_shares = TOTAL_DEBT_SHARES.load(deps.storage, denom)?;
+    TOTAL_DEBT_SHARES.save(deps.storage, denom, &total_debt_shares.checked_sub(debt_shares)?)?;
+
+    let coin_written_off = Coin {
+        denom: denom.to_string(),
+        amount: debt_amount,
+    };
+    let red_bank = RED_BANK.load(deps.storage)?;
+    let red_bank_write_off_msg = red_bank.write_off_bad_debt_msg(&coin_written_off)?;
+
+    let update_isolated_debts_msg = CallbackMsg::UpdateIsolatedDebts {
+        account_id: account_id.to_string(),
+        enforce_ceiling: false,
+    }
+    .into_cosmos_msg(&env.contract.address)?;
+
+    Ok(Response::new()
+        .add_message(red_bank_write_off_msg)
+        .add_message(update_isolated_debts_msg)
+        .add_attribute("action", "write_off_bad_debt")
+        .add_attribute("account_id", account_id)
+        .add_attribute("debt_shares_written_off", debt_shares)
+        .add_attribute("coin_written_off", coin_written_off.to_string()))
+}
+
+fn has_perp_vault_position(deps: Deps, env: &Env, account_id: &str) -> ContractResult<bool> {
+    let position = PERPS.load(deps.storage)?.query_vault_position(
+        &deps.querier,
+        &env.contract.address,
+        account_id,
+    )?;
+    Ok(position.is_some_and(|position| {
+        !position.deposit.shares.is_zero()
+            || position.unlocks.iter().any(|unlock| !unlock.shares.is_zero())
+    }))
+}
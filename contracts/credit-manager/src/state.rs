@@ -41,6 +41,9 @@ pub const ACCOUNT_KINDS: Map<&str, AccountKind> = Map::new("account_types"); //
 pub const COIN_BALANCES: Map<(&str, &str), Uint128> = Map::new("coin_balance"); // Map<(AccountId, Denom), Amount>
 pub const DEBT_SHARES: Map<(&str, &str), Uint128> = Map::new("debt_shares"); // Map<(AccountId, Denom), Shares>
 pub const TOTAL_DEBT_SHARES: Map<&str, Uint128> = Map::new("total_debt_shares"); // Map<Denom, Shares>
+pub const ISOLATED_DEBT_SHARES: Map<(&str, &str), Uint128> = Map::new("isolated_debt_shares"); // Map<(CollateralDenom, DebtDenom), Shares>
+pub const ACCOUNT_ISOLATED_DEBT_SHARES: Map<(&str, &str, &str), Uint128> =
+    Map::new("account_isolated_debt_shares"); // Map<(AccountId, CollateralDenom, DebtDenom), Shares>
 
 pub const VAULT_POSITIONS: Map<(&str, Addr), VaultPositionAmount> = Map::new("vault_positions"); // Map<(AccountId, VaultAddr), VaultPositionAmount>
 
@@ -54,6 +57,9 @@ pub const EXECUTED_TRIGGER_ORDERS: Map<(&str, &str), String> = Map::new("execute
 // First TriggerOrderId is the parent order, second is the child-order
 pub const TRIGGER_ORDER_RELATED_IDS: Map<(&str, &str, &str), String> =
     Map::new("trigger_order_related_ids");
+// Map<(AccountId, TriggerOrderId), Price>
+// High-water (or low-water) mark of the trigger orders with a TrailingOraclePrice condition
+pub const TRAILING_MARKS: Map<(&str, &str), Decimal> = Map::new("trailing_marks");
 
 // Temporary state to save variables to be used on reply handling
 pub const VAULT_REQUEST_TEMP_STORAGE: Item<RequestTempStorage> =
@@ -67,6 +73,9 @@ pub const VAULTS: Map<&str, Addr> = Map::new("vaults");
 
 pub const PERPS_LB_RATIO: Item<Decimal> = Item::new("perps_lb_ratio");
 
+// Target health factor for partial liquidation of perps positions
+pub const PERPS_LIQUIDATION_TARGET_HF: Item<Decimal> = Item::new("perps_liquidation_target_hf");
+
 pub const SWAP_FEE: Item<Decimal> = Item::new("swap_fee");
 
 // Fee tier discount configuration
@@ -1,13 +1,16 @@
-use cosmwasm_std::{Coin, Decimal, DepsMut, Env, Response, Uint128};
+use cosmwasm_std::{Coin, Decimal, Deps, DepsMut, Env, Response, Uint128};
 use mars_types::{
     credit_manager::{ActionAmount, ActionCoin, ChangeExpected},
+    oracle::ActionKind,
     swapper::SwapperRoute,
 };
 
 use crate::{
     error::{ContractError, ContractResult},
     staking::get_account_tier_and_discount,
-    state::{COIN_BALANCES, DUALITY_SWAPPER, REWARDS_COLLECTOR, SWAPPER, SWAP_FEE},
+    state::{
+        COIN_BALANCES, DUALITY_SWAPPER, MAX_SLIPPAGE, ORACLE, REWARDS_COLLECTOR, SWAPPER, SWAP_FEE,
+    },
     utils::{
         assert_withdraw_enabled, decrement_coin_balance, increment_coin_balance, update_balance_msg,
     },
@@ -21,6 +24,7 @@ pub fn swap_exact_in(
     denom_out: &str,
     min_receive: Uint128,
     route: Option<SwapperRoute>,
+    enforce_oracle_min_receive: bool,
 ) -> ContractResult<Response> {
     // Prevent swapping the asset if withdraw is disabled
     assert_withdraw_enabled(deps.storage, &deps.querier, &coin_in.denom)?;
@@ -60,6 +64,15 @@ pub fn swap_exact_in(
     let rewards_collector_account = REWARDS_COLLECTOR.load(deps.storage)?.account_id;
     increment_coin_balance(deps.storage, &rewards_collector_account, &rc_coin)?;
 
+    // The `min_receive` of a trigger order is set on order creation, so it can be far off from
+    // the market when the order gets executed. It is computed here, on the amount actually
+    // swapped, as the balance may have changed through earlier actions of the order.
+    let min_receive = if enforce_oracle_min_receive {
+        min_receive.max(query_oracle_min_receive(deps.as_ref(), &coin_in_to_trade, denom_out)?)
+    } else {
+        min_receive
+    };
+
     // Updates coin balances for account after the swap has taken place
     let update_coin_balance_msg = update_balance_msg(
         &deps.querier,
@@ -90,3 +103,23 @@ pub fn swap_exact_in(
         .add_attribute("base_swap_fee", base_swap_fee.to_string())
         .add_attribute("effective_swap_fee", effective_swap_fee.to_string()))
 }
+
+/// Amount of `denom_out` that `coin_in` is worth at the current oracle prices, minus the max
+/// slippage, so that the swap can't be executed at an arbitrary price
+fn query_oracle_min_receive(
+    deps: Deps,
+    coin_in: &Coin,
+    denom_out: &str,
+) -> ContractResult<Uint128> {
+    let oracle = ORACLE.load(deps.storage)?;
+    let max_slippage = MAX_SLIPPAGE.load(deps.storage)?;
+
+    let price_in = oracle.query_price(&deps.querier, &coin_in.denom, ActionKind::Default)?.price;
+    let price_out = oracle.query_price(&deps.querier, denom_out, ActionKind::Default)?.price;
+
+    Ok(coin_in
+        .amount
+        .checked_mul_floor(price_in)?
+        .checked_div_floor(price_out)?
+        .checked_mul_floor(Decimal::one().checked_sub(max_slippage)?)?)
+}
@@ -1,31 +1,33 @@
 use cosmwasm_std::{
-    ensure, ensure_eq, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Order,
-    Response, Storage,
+    ensure, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Storage,
 };
 use mars_types::{
     credit_manager::{
-        Action, Condition, CreateTriggerOrderType, ExecutePerpOrderType, TriggerOrder,
+        Action, Comparison, Condition, CreateTriggerOrderType, ExecutePerpOrderType, TriggerOrder,
     },
     oracle::ActionKind,
 };
 
 use crate::{
-    error::ContractError,
+    error::{ContractError, ContractResult},
     execute::dispatch_actions,
     health::query_health_values,
     state::{
         EXECUTED_TRIGGER_ORDERS, KEEPER_FEE_CONFIG, MAX_TRIGGER_ORDERS, NEXT_TRIGGER_ID, ORACLE,
-        TRIGGER_ORDERS, TRIGGER_ORDER_RELATED_IDS,
+        TRAILING_MARKS, TRIGGER_ORDERS, TRIGGER_ORDER_RELATED_IDS,
     },
-    utils::{decrement_coin_balance, increment_coin_balance},
+    utils::{assert_keeper_fee, decrement_coin_balance, increment_coin_balance},
 };
 
 pub fn create_trigger_order(
     deps: DepsMut,
+    env: Env,
     account_id: &str,
     actions: Vec<Action>,
     conditions: Vec<Condition>,
     keeper_fee: Coin,
+    expires_at: Option<u64>,
 ) -> Result<Response, ContractError> {
     let current_trigger_order_amount =
         TRIGGER_ORDERS.prefix(account_id).keys(deps.storage, None, None, Order::Ascending).count();
@@ -38,48 +40,83 @@ pub fn create_trigger_order(
         }
     );
 
-    // Ensure that the trigger order does not contain any illegal actions
-    // Initially, this is limited to just execute_perp_order, lend and close_perp_position
+    // Ensure that the trigger order does not contain any illegal actions.
+    // Repay is limited to the debt of the account itself.
     let contains_legal_actions = actions.iter().all(|action| {
         matches!(
             action,
-            Action::ExecutePerpOrder { .. } | Action::Lend(..) | Action::ClosePerpPosition { .. }
+            Action::ExecutePerpOrder { .. }
+                | Action::Lend(..)
+                | Action::ClosePerpPosition { .. }
+                | Action::SwapExactIn { .. }
+                | Action::Reclaim(..)
+                | Action::Repay {
+                    recipient_account_id: None,
+                    ..
+                }
         )
     });
     ensure!(contains_legal_actions, ContractError::IllegalTriggerAction);
 
-    // Generate & increment id
-    let order_id = NEXT_TRIGGER_ID.load(deps.storage)?;
-    NEXT_TRIGGER_ID.save(deps.storage, &(order_id + 1))?;
+    if let Some(expires_at) = expires_at {
+        let current_time = env.block.time.seconds();
+        ensure!(
+            expires_at > current_time,
+            ContractError::InvalidTriggerOrderExpiry {
+                expires_at,
+                current_time,
+            }
+        );
+    }
 
-    // Ensure keeper fees are valid according to configuration
-    let cfg = KEEPER_FEE_CONFIG.load(deps.storage)?;
+    // A single mark is stored per trigger order, so only one trailing condition is supported
+    let trailing_conditions = conditions
+        .iter()
+        .filter(|condition| matches!(condition, Condition::TrailingOraclePrice { .. }))
+        .count();
     ensure!(
-        keeper_fee.amount >= cfg.min_fee.amount,
-        ContractError::KeeperFeeTooSmall {
-            expected_min_amount: cfg.min_fee.amount,
-            received_amount: keeper_fee.amount,
-        }
-    );
-    ensure_eq!(
-        keeper_fee.denom,
-        cfg.min_fee.denom,
-        ContractError::InvalidKeeperFeeDenom {
-            expected_denom: cfg.min_fee.denom,
-            received_denom: keeper_fee.denom
+        trailing_conditions <= 1,
+        ContractError::InvalidOrderConditions {
+            reason: "Trigger order can have at most 1 TrailingOraclePrice condition".to_string()
         }
     );
 
+    // Generate & increment id
+    let order_id = NEXT_TRIGGER_ID.load(deps.storage)?;
+    NEXT_TRIGGER_ID.save(deps.storage, &(order_id + 1))?;
+
+    assert_keeper_fee(deps.storage, &keeper_fee)?;
+
     for condition in &conditions {
-        if let Condition::TriggerOrderExecuted {
-            trigger_order_id,
-        } = condition
-        {
-            TRIGGER_ORDER_RELATED_IDS.save(
-                deps.storage,
-                (account_id, trigger_order_id, &order_id.to_string()),
-                &order_id.to_string(),
-            )?;
+        match condition {
+            Condition::TriggerOrderExecuted {
+                trigger_order_id,
+            } => {
+                TRIGGER_ORDER_RELATED_IDS.save(
+                    deps.storage,
+                    (account_id, trigger_order_id, &order_id.to_string()),
+                    &order_id.to_string(),
+                )?;
+            }
+            Condition::TrailingOraclePrice {
+                denom,
+                trailing_pct,
+                ..
+            } => {
+                ensure!(
+                    !trailing_pct.is_zero() && *trailing_pct < Decimal::one(),
+                    ContractError::InvalidOrderConditions {
+                        reason: "TrailingOraclePrice trailing_pct must be between 0 and 1"
+                            .to_string()
+                    }
+                );
+
+                // The mark starts at the current oracle price
+                let oracle = ORACLE.load(deps.storage)?;
+                let price = oracle.query_price(&deps.querier, denom, ActionKind::Default)?.price;
+                TRAILING_MARKS.save(deps.storage, (account_id, &order_id.to_string()), &price)?;
+            }
+            _ => {}
         }
     }
 
@@ -95,6 +132,7 @@ pub fn create_trigger_order(
             actions,
             conditions,
             keeper_fee,
+            expires_at,
         },
     )?;
 
@@ -114,10 +152,14 @@ pub fn delete_trigger_order(
     increment_coin_balance(deps.storage, account_id, &order_to_remove.keeper_fee)?;
 
     // Remove order
-    TRIGGER_ORDERS.remove(deps.storage, (account_id, trigger_order_id));
+    remove_trigger_order(deps.storage, account_id, trigger_order_id);
 
-    // Remove any related trigger orders
-    remove_related_trigger_orders(deps.storage, account_id, trigger_order_id)?;
+    // Remove any related trigger orders and refund their keeper fees
+    let child_keeper_fees =
+        remove_related_trigger_orders(deps.storage, account_id, trigger_order_id)?;
+    for keeper_fee in &child_keeper_fees {
+        increment_coin_balance(deps.storage, account_id, keeper_fee)?;
+    }
 
     // Remove it from the executed orders
     EXECUTED_TRIGGER_ORDERS.remove(deps.storage, (account_id, trigger_order_id));
@@ -136,6 +178,17 @@ pub fn execute_trigger_order(
 ) -> Result<Response, ContractError> {
     let order = get_trigger_order(deps.storage, account_id, trigger_order_id)?;
 
+    if let Some(expires_at) = order.expires_at {
+        ensure!(
+            env.block.time.seconds() < expires_at,
+            ContractError::TriggerOrderExpired {
+                order_id: trigger_order_id.to_string(),
+                account_id: account_id.to_string(),
+                expires_at,
+            }
+        );
+    }
+
     let oracle = ORACLE.load(deps.storage)?;
     let mut used_conditional_types = vec![];
     let mut parent_order_id: Option<String> = None;
@@ -185,6 +238,25 @@ pub fn execute_trigger_order(
                 let relative_price = base_price.checked_div(quote_price)?;
                 comparison.is_met(relative_price, price)
             }
+            Condition::TrailingOraclePrice {
+                denom,
+                trailing_pct,
+                comparison,
+            } => {
+                used_conditional_types.push("trailing_price");
+                let mark = TRAILING_MARKS.load(deps.storage, (account_id, trigger_order_id))?;
+                let trigger_price = trailing_trigger_price(mark, trailing_pct, &comparison)?;
+                let oracle_price =
+                    oracle.query_price(&deps.querier, &denom, ActionKind::Default)?;
+                comparison.is_met(oracle_price.price, trigger_price)
+            }
+            Condition::BlockTime {
+                timestamp,
+                comparison,
+            } => {
+                used_conditional_types.push("block_time");
+                comparison.is_met(env.block.time.seconds(), timestamp)
+            }
             Condition::TriggerOrderExecuted {
                 trigger_order_id,
             } => {
@@ -201,12 +273,17 @@ pub fn execute_trigger_order(
     }
 
     // Remove the current trigger_order
-    TRIGGER_ORDERS.remove(deps.storage, (account_id, trigger_order_id));
+    remove_trigger_order(deps.storage, account_id, trigger_order_id);
 
     match parent_order_id {
         Some(parent_order_id) => {
             // Any related orders can be removed, as execution of one renders other child orders redundant (by design).
-            remove_related_trigger_orders(deps.storage, account_id, &parent_order_id)?;
+            // Their keeper fees are refunded, as they won't be executed.
+            let sibling_keeper_fees =
+                remove_related_trigger_orders(deps.storage, account_id, &parent_order_id)?;
+            for keeper_fee in &sibling_keeper_fees {
+                increment_coin_balance(deps.storage, account_id, keeper_fee)?;
+            }
         }
         None => {
             // Store this order in executed orders, but only if there are sub_orders
@@ -224,7 +301,9 @@ pub fn execute_trigger_order(
 
     let keeper_address = info.sender.to_string();
 
-    // Execute actions on behalf of user
+    // Execute actions on behalf of user. The health of the account is checked after the
+    // actions, the same way as for any other account update. Swaps are guarded by the oracle
+    // prices, see `swap_exact_in`.
     let mut res = dispatch_actions(
         deps,
         env,
@@ -233,6 +312,7 @@ pub fn execute_trigger_order(
         None,
         order.actions,
         false,
+        true,
     )?;
 
     // Add relevant attributes
@@ -250,6 +330,129 @@ pub fn execute_trigger_order(
     Ok(res.add_message(transfer_msg))
 }
 
+pub fn prune_expired_trigger_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: &str,
+    trigger_order_id: &str,
+) -> Result<Response, ContractError> {
+    let order = get_trigger_order(deps.storage, account_id, trigger_order_id)?;
+
+    let is_expired =
+        order.expires_at.is_some_and(|expires_at| env.block.time.seconds() >= expires_at);
+    ensure!(
+        is_expired,
+        ContractError::TriggerOrderNotExpired {
+            order_id: trigger_order_id.to_string(),
+            account_id: account_id.to_string(),
+        }
+    );
+
+    // Remove the order the same way as if it was deleted by the account owner. Its child orders
+    // can't be executed anymore, so they're removed too and their keeper fees are refunded in
+    // full, as they haven't expired themselves.
+    remove_trigger_order(deps.storage, account_id, trigger_order_id);
+    let child_keeper_fees =
+        remove_related_trigger_orders(deps.storage, account_id, trigger_order_id)?;
+    EXECUTED_TRIGGER_ORDERS.remove(deps.storage, (account_id, trigger_order_id));
+
+    for keeper_fee in &child_keeper_fees {
+        increment_coin_balance(deps.storage, account_id, keeper_fee)?;
+    }
+
+    // Split the keeper fee between the pruner and the account
+    let cfg = KEEPER_FEE_CONFIG.load(deps.storage)?;
+    let pruner_amount = order.keeper_fee.amount.checked_mul_floor(cfg.pruner_fee_pct)?;
+    let refund_amount = order.keeper_fee.amount.checked_sub(pruner_amount)?;
+
+    if !refund_amount.is_zero() {
+        increment_coin_balance(
+            deps.storage,
+            account_id,
+            &Coin {
+                denom: order.keeper_fee.denom.clone(),
+                amount: refund_amount,
+            },
+        )?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "prune_expired_trigger_order")
+        .add_attribute("account_id", account_id)
+        .add_attribute("order_id", trigger_order_id)
+        .add_attribute("pruner_fee", pruner_amount.to_string())
+        .add_attribute("refunded_fee", refund_amount.to_string());
+
+    if !pruner_amount.is_zero() {
+        res = res.add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: order.keeper_fee.denom,
+                amount: pruner_amount,
+            }],
+        }));
+    }
+
+    Ok(res)
+}
+
+pub fn refresh_trailing_mark(
+    deps: DepsMut,
+    account_id: &str,
+    trigger_order_id: &str,
+) -> Result<Response, ContractError> {
+    let order = get_trigger_order(deps.storage, account_id, trigger_order_id)?;
+
+    let (denom, comparison) = order
+        .conditions
+        .into_iter()
+        .find_map(|condition| match condition {
+            Condition::TrailingOraclePrice {
+                denom,
+                comparison,
+                ..
+            } => Some((denom, comparison)),
+            _ => None,
+        })
+        .ok_or(ContractError::NoTrailingCondition {
+            order_id: trigger_order_id.to_string(),
+            account_id: account_id.to_string(),
+        })?;
+
+    let oracle = ORACLE.load(deps.storage)?;
+    let price = oracle.query_price(&deps.querier, &denom, ActionKind::Default)?.price;
+
+    let mark = TRAILING_MARKS.load(deps.storage, (account_id, trigger_order_id))?;
+    let new_mark = match comparison {
+        Comparison::LessThan => mark.max(price),
+        Comparison::GreaterThan => mark.min(price),
+    };
+    if new_mark != mark {
+        TRAILING_MARKS.save(deps.storage, (account_id, trigger_order_id), &new_mark)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "refresh_trailing_mark")
+        .add_attribute("account_id", account_id)
+        .add_attribute("order_id", trigger_order_id)
+        .add_attribute("price", price.to_string())
+        .add_attribute("mark", new_mark.to_string()))
+}
+
+/// Price at which a `TrailingOraclePrice` condition is met, derived from the stored mark.
+pub fn trailing_trigger_price(
+    mark: Decimal,
+    trailing_pct: Decimal,
+    comparison: &Comparison,
+) -> StdResult<Decimal> {
+    let trigger_price = match comparison {
+        Comparison::LessThan => mark.checked_mul(Decimal::one().checked_sub(trailing_pct)?)?,
+        Comparison::GreaterThan => mark.checked_mul(Decimal::one().checked_add(trailing_pct)?)?,
+    };
+    Ok(trigger_price)
+}
+
 fn get_trigger_order(
     storage: &mut dyn Storage,
     account_id: &str,
@@ -263,11 +466,18 @@ fn get_trigger_order(
     )
 }
 
+/// Removes the trigger order together with its trailing mark (if any).
+fn remove_trigger_order(storage: &mut dyn Storage, account_id: &str, order_id: &str) {
+    TRIGGER_ORDERS.remove(storage, (account_id, order_id));
+    TRAILING_MARKS.remove(storage, (account_id, order_id));
+}
+
+/// Removes the child orders of a parent order and returns their keeper fees.
 fn remove_related_trigger_orders(
     storage: &mut dyn Storage,
     account_id: &str,
     parent_order_id: &str,
-) -> Result<(), ContractError> {
+) -> Result<Vec<Coin>, ContractError> {
     // Because it is not allowed to mutate the storage in a loop, order ids are stored here
     // and removed at the end of the function.
     let mut child_orders_to_remove: Vec<String> = vec![];
@@ -284,11 +494,15 @@ fn remove_related_trigger_orders(
         child_orders_to_remove.push(other_child_id.clone());
     }
 
+    let mut keeper_fees = vec![];
     for order_id in child_orders_to_remove {
-        TRIGGER_ORDERS.remove(storage, (account_id, &order_id));
+        if let Some(order) = TRIGGER_ORDERS.may_load(storage, (account_id, &order_id))? {
+            keeper_fees.push(order.keeper_fee);
+        }
+        remove_trigger_order(storage, account_id, &order_id);
     }
 
-    Ok(())
+    Ok(keeper_fees)
 }
 
 pub fn remove_invalid_trigger_orders(
@@ -350,7 +564,7 @@ pub fn remove_invalid_trigger_orders(
 
     // Remove invalid orders
     for order_id in order_ids_to_remove {
-        TRIGGER_ORDERS.remove(storage, (account_id, &order_id));
+        remove_trigger_order(storage, account_id, &order_id);
     }
 
     Ok(())
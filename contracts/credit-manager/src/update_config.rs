@@ -15,10 +15,13 @@ use crate::{
     state::{
         ACCOUNT_NFT, DUALITY_SWAPPER, FEE_TIER_CONFIG, GOVERNANCE, HEALTH_CONTRACT, INCENTIVES,
         KEEPER_FEE_CONFIG, MAX_SLIPPAGE, MAX_TRIGGER_ORDERS, MAX_UNLOCKING_POSITIONS, ORACLE,
-        OWNER, PARAMS, PERPS, PERPS_LB_RATIO, RED_BANK, REWARDS_COLLECTOR, SWAPPER, SWAP_FEE,
-        ZAPPER,
+        OWNER, PARAMS, PERPS, PERPS_LB_RATIO, PERPS_LIQUIDATION_TARGET_HF, RED_BANK,
+        REWARDS_COLLECTOR, SWAPPER, SWAP_FEE, ZAPPER,
+    },
+    utils::{
+        assert_max_slippage, assert_perps_lb_ratio, assert_perps_liquidation_target_hf,
+        assert_pruner_fee_pct, assert_swap_fee,
     },
-    utils::{assert_max_slippage, assert_perps_lb_ratio, assert_swap_fee},
 };
 
 pub fn update_config(
@@ -107,6 +110,14 @@ pub fn update_config(
             response.add_attribute("key", "perps_lb_ratio").add_attribute("value", num.to_string());
     }
 
+    if let Some(num) = updates.perps_liquidation_target_health_factor {
+        assert_perps_liquidation_target_hf(num)?;
+        PERPS_LIQUIDATION_TARGET_HF.save(deps.storage, &num)?;
+        response = response
+            .add_attribute("key", "perps_liquidation_target_hf")
+            .add_attribute("value", num.to_string());
+    }
+
     if let Some(num) = updates.swap_fee {
         assert_swap_fee(num)?;
         SWAP_FEE.save(deps.storage, &num)?;
@@ -157,11 +168,13 @@ pub fn update_config(
     }
 
     if let Some(kfc) = updates.keeper_fee_config {
+        assert_pruner_fee_pct(kfc.pruner_fee_pct)?;
         KEEPER_FEE_CONFIG.save(deps.storage, &kfc)?;
         response = response.add_attributes(vec![
             ("key", "keeper_fee_config"),
             ("keeper_fee_denom", &kfc.min_fee.denom),
             ("keeper_fee_min", &kfc.min_fee.to_string()),
+            ("keeper_fee_pruner_pct", &kfc.pruner_fee_pct.to_string()),
         ]);
     }
 
@@ -1,8 +1,8 @@
 use std::{collections::HashSet, hash::Hash};
 
 use cosmwasm_std::{
-    ensure, to_json_binary, Addr, Coin, ContractInfoResponse, CosmosMsg, Decimal, Deps, DepsMut,
-    QuerierWrapper, QueryRequest, StdResult, Storage, Uint128, WasmMsg,
+    ensure, ensure_eq, to_json_binary, Addr, Coin, ContractInfoResponse, CosmosMsg, Decimal, Deps,
+    DepsMut, QuerierWrapper, QueryRequest, StdResult, Storage, Uint128, WasmMsg,
 };
 use mars_types::{
     credit_manager::{ActionCoin, CallbackMsg, ChangeExpected, ExecuteMsg},
@@ -12,8 +12,8 @@ use mars_types::{
 use crate::{
     error::{ContractError, ContractResult},
     state::{
-        ACCOUNT_KINDS, ACCOUNT_NFT, COIN_BALANCES, MAX_SLIPPAGE, PARAMS, PERPS, RED_BANK,
-        TOTAL_DEBT_SHARES,
+        ACCOUNT_KINDS, ACCOUNT_NFT, COIN_BALANCES, KEEPER_FEE_CONFIG, MAX_SLIPPAGE, PARAMS, PERPS,
+        RED_BANK, TOTAL_DEBT_SHARES,
     },
     update_coin_balances::query_balance,
 };
@@ -101,6 +101,15 @@ pub fn assert_perps_lb_ratio(perps_lb_ratio: Decimal) -> ContractResult<()> {
     Ok(())
 }
 
+pub fn assert_perps_liquidation_target_hf(target_hf: Decimal) -> ContractResult<()> {
+    if target_hf <= Decimal::one() {
+        return Err(ContractError::InvalidConfig {
+            reason: "Perps liquidation target health factor must be greater than 1".to_string(),
+        });
+    }
+    Ok(())
+}
+
 pub fn assert_swap_fee(swap_fee: Decimal) -> ContractResult<()> {
     if swap_fee >= Decimal::one() {
         return Err(ContractError::InvalidConfig {
@@ -110,6 +119,36 @@ pub fn assert_swap_fee(swap_fee: Decimal) -> ContractResult<()> {
     Ok(())
 }
 
+pub fn assert_pruner_fee_pct(pruner_fee_pct: Decimal) -> ContractResult<()> {
+    if pruner_fee_pct > Decimal::one() {
+        return Err(ContractError::InvalidConfig {
+            reason: "Pruner fee percentage must be less than or equal to 1".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Ensure keeper fees are valid according to configuration
+pub fn assert_keeper_fee(storage: &dyn Storage, keeper_fee: &Coin) -> ContractResult<()> {
+    let cfg = KEEPER_FEE_CONFIG.load(storage)?;
+    ensure!(
+        keeper_fee.amount >= cfg.min_fee.amount,
+        ContractError::KeeperFeeTooSmall {
+            expected_min_amount: cfg.min_fee.amount,
+            received_amount: keeper_fee.amount,
+        }
+    );
+    ensure_eq!(
+        keeper_fee.denom,
+        cfg.min_fee.denom,
+        ContractError::InvalidKeeperFeeDenom {
+            expected_denom: cfg.min_fee.denom,
+            received_denom: keeper_fee.denom.clone()
+        }
+    );
+    Ok(())
+}
+
 pub fn assert_discount_pct(discount_pct: Decimal) -> ContractResult<()> {
     if discount_pct > Decimal::one() {
         return Err(ContractError::InvalidDiscountPercentage);
@@ -0,0 +1,80 @@
+#![allow(dead_code)]
+
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, Addr, Coin, Decimal};
+use mars_mock_oracle::msg::CoinPrice;
+pub use mars_testing::multitest::helpers::*;
+use mars_types::{credit_manager::Action::Deposit, oracle::ActionKind, params::PerpParamsUpdate};
+
+/// Builds the env with `user` funded with `funds` and creates a credit account for them.
+pub fn setup(builder: MockEnvBuilder, user: &Addr, funds: Vec<Coin>) -> (MockEnv, String) {
+    let mut mock = builder
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds,
+        })
+        .build()
+        .unwrap();
+
+    let account_id = mock.create_credit_account(user).unwrap();
+
+    (mock, account_id)
+}
+
+/// Same as [`setup`], but also lists the uatom perp, seeds the perp vault with `vault_deposit`
+/// uusdc from a separate credit account and deposits `deposits` into the user's account.
+/// Returns the user's account id followed by the vault depositor's.
+pub fn setup_perps(
+    builder: MockEnvBuilder,
+    user: &Addr,
+    funds: Vec<Coin>,
+    deposits: Vec<Coin>,
+    vault_deposit: u128,
+) -> (MockEnv, String, String) {
+    let vault_depositor = Addr::unchecked("vault_depositor");
+    let vault_coin_deposited = coin(vault_deposit, "uusdc");
+
+    let builder = builder.fund_account(AccountToFund {
+        addr: vault_depositor.clone(),
+        funds: vec![vault_coin_deposited.clone()],
+    });
+    let (mut mock, account_id) = setup(builder, user, funds);
+    let vault_depositor_account_id = mock.create_credit_account(&vault_depositor).unwrap();
+
+    mock.update_perp_params(PerpParamsUpdate::AddOrUpdate {
+        params: default_perp_params("uatom"),
+    });
+
+    mock.update_credit_account(
+        &vault_depositor_account_id,
+        &vault_depositor,
+        vec![Deposit(vault_coin_deposited.clone())],
+        &[vault_coin_deposited.clone()],
+    )
+    .unwrap();
+    mock.deposit_to_perp_vault(&vault_depositor_account_id, &vault_coin_deposited, None).unwrap();
+
+    if !deposits.is_empty() {
+        mock.update_credit_account(
+            &account_id,
+            user,
+            deposits.iter().cloned().map(Deposit).collect(),
+            &deposits,
+        )
+        .unwrap();
+    }
+
+    (mock, account_id, vault_depositor_account_id)
+}
+
+/// Sets both the default and the liquidation price of uatom.
+pub fn set_atom_price(mock: &mut MockEnv, price: &str) {
+    for pricing in [ActionKind::Default, ActionKind::Liquidation] {
+        mock.price_change(CoinPrice {
+            pricing,
+            denom: "uatom".to_string(),
+            price: Decimal::from_str(price).unwrap(),
+        });
+    }
+}
@@ -1,4 +1,4 @@
-pub use mars_testing::multitest::helpers;
+pub mod helpers;
 
 mod test_borrow;
 mod test_claim_astro_lp_rewards;
@@ -13,15 +13,18 @@ mod test_enumerate_coin_balances;
 mod test_enumerate_debt_shares;
 mod test_enumerate_total_debt_shares;
 mod test_enumerate_vault_positions;
+mod test_expiring_trigger;
 mod test_fund_manager_accounts;
 mod test_health;
 mod test_hls_accounts;
 mod test_instantiate;
+mod test_isolated_debt_ceiling;
 mod test_lend;
 mod test_liquidate_deposit;
 mod test_liquidate_guard;
 mod test_liquidate_if_perps_open;
 mod test_liquidate_lend;
+mod test_liquidate_perps_partially;
 mod test_liquidate_staked_astro_lp;
 mod test_liquidate_vault;
 mod test_liquidation_pricing;
@@ -34,7 +37,11 @@ mod test_migration_v2_4_1;
 mod test_no_health_check;
 mod test_order_relations;
 mod test_perp;
+mod test_perp_insurance_fund;
+mod test_perp_isolated_margin;
+mod test_perp_limit_orders;
 mod test_perp_vault;
+mod test_perp_vault_collateral;
 mod test_perps_deleverage;
 mod test_perps_with_discount;
 mod test_reclaim;
@@ -43,11 +50,13 @@ mod test_refund_balances;
 mod test_repay;
 mod test_repay_for_recipient;
 mod test_repay_from_wallet;
+mod test_spot_trigger;
 mod test_stake_astro_lp;
 mod test_staking_tiers;
 mod test_swap;
 mod test_swap_with_discount;
 mod test_trading_fee;
+mod test_trailing_trigger;
 mod test_trigger;
 mod test_unstake_astro_lp;
 mod test_update_admin;
@@ -63,5 +72,6 @@ mod test_vault_exit_unlocked;
 mod test_vault_query_value;
 mod test_vault_request_unlock;
 mod test_withdraw;
+mod test_write_off_bad_debt;
 mod test_zap_provide;
 mod test_zap_withdraw;
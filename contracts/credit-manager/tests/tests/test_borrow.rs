@@ -2,7 +2,10 @@ use std::ops::{Mul, Sub};
 
 use cosmwasm_std::{coin, coins, Addr, Uint128};
 use mars_credit_manager::{borrow::DEFAULT_DEBT_SHARES_PER_COIN_BORROWED, error::ContractError};
-use mars_types::credit_manager::Action::{Borrow, Deposit};
+use mars_types::{
+    credit_manager::Action::{Borrow, Deposit},
+    params::{AssetParamsUnchecked, AssetParamsUpdate},
+};
 
 use super::helpers::{
     assert_err, blacklisted_coin_info, uosmo_info, AccountToFund, MockEnv,
@@ -104,6 +107,44 @@ fn cannot_borrow_above_max_ltv() {
     );
 }
 
+#[test]
+fn cannot_borrow_above_borrow_cap() {
+    let coin_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[coin_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(300, coin_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let params = mock.query_asset_params(&coin_info.denom);
+    mock.update_asset_params(AssetParamsUpdate::AddOrUpdate {
+        params: AssetParamsUnchecked {
+            borrow_cap: Uint128::new(50),
+            ..params.into()
+        },
+    });
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(coin_info.to_coin(300)), Borrow(coin_info.to_coin(51))],
+        &[coin(300, coin_info.denom.clone())],
+    );
+
+    assert_err(
+        res,
+        ContractError::AboveAssetBorrowCap {
+            new_value: coin_info.to_coin(51),
+            maximum: Uint128::new(50),
+        },
+    );
+}
+
 #[test]
 fn success_when_new_debt_asset() {
     let coin_info = uosmo_info();
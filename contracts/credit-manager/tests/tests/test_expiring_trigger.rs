@@ -0,0 +1,387 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coin, Addr, Coin, Uint128};
+use cw_multi_test::AppResponse;
+use mars_credit_manager::error::ContractError;
+use mars_types::credit_manager::{
+    Action::{CreateTriggerOrder, DeleteTriggerOrder, Deposit, Lend},
+    ActionAmount, ActionCoin, Comparison,
+    Condition::{self, BlockTime, TriggerOrderExecuted},
+    CreateTriggerOrderType,
+};
+
+use super::helpers::{self, assert_err, coin_info, MockEnv};
+
+fn setup() -> (MockEnv, Addr, String) {
+    let user = Addr::unchecked("user");
+    let usdc_info = coin_info("uusdc");
+
+    let (mock, account_id) = helpers::setup(
+        MockEnv::new().set_params(&[usdc_info.clone()]),
+        &user,
+        vec![usdc_info.to_coin(10000000000000)],
+    );
+
+    (mock, user, account_id)
+}
+
+fn keeper_fee() -> Coin {
+    coin_info("uusdc").to_coin(1000000)
+}
+
+fn create_order(
+    mock: &mut MockEnv,
+    user: &Addr,
+    account_id: &str,
+    conditions: Vec<Condition>,
+    expires_at: Option<u64>,
+) -> AnyResult<AppResponse> {
+    mock.update_credit_account(
+        account_id,
+        user,
+        vec![
+            Deposit(keeper_fee()),
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Default),
+                actions: vec![Lend(ActionCoin {
+                    denom: "uusdc".to_string(),
+                    amount: ActionAmount::AccountBalance,
+                })],
+                conditions,
+                keeper_fee: keeper_fee(),
+                expires_at,
+            },
+        ],
+        &[keeper_fee()],
+    )
+}
+
+fn account_usdc_balance(mock: &MockEnv, account_id: &str) -> Uint128 {
+    mock.query_positions(account_id)
+        .deposits
+        .iter()
+        .find(|c| c.denom == "uusdc")
+        .map(|c| c.amount)
+        .unwrap_or_default()
+}
+
+#[test]
+fn block_time_condition() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    let execute_after = mock.query_block_time() + 100;
+    create_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![BlockTime {
+            timestamp: execute_after,
+            comparison: Comparison::GreaterThan,
+        }],
+        None,
+    )
+    .unwrap();
+
+    mock.set_block_time(execute_after);
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert_err(res, ContractError::IllegalExecuteTriggerOrder);
+
+    mock.set_block_time(execute_after + 1);
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+    assert!(mock.query_trigger_orders_for_account(account_id, None, None).data.is_empty());
+}
+
+#[test]
+fn expiry_must_be_in_the_future() {
+    let (mut mock, user, account_id) = setup();
+
+    let current_time = mock.query_block_time();
+    let res = create_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![BlockTime {
+            timestamp: current_time,
+            comparison: Comparison::GreaterThan,
+        }],
+        Some(current_time),
+    );
+    assert_err(
+        res,
+        ContractError::InvalidTriggerOrderExpiry {
+            expires_at: current_time,
+            current_time,
+        },
+    );
+}
+
+#[test]
+fn expired_order_cannot_be_executed() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    let current_time = mock.query_block_time();
+    let expires_at = current_time + 100;
+    create_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![BlockTime {
+            timestamp: current_time,
+            comparison: Comparison::GreaterThan,
+        }],
+        Some(expires_at),
+    )
+    .unwrap();
+
+    mock.set_block_time(expires_at);
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert_err(
+        res,
+        ContractError::TriggerOrderExpired {
+            order_id: "1".to_string(),
+            account_id: account_id.clone(),
+            expires_at,
+        },
+    );
+}
+
+#[test]
+fn prune_expired_order_splits_keeper_fee() {
+    let (mut mock, user, account_id) = setup();
+    let pruner = Addr::unchecked("pruner");
+
+    let current_time = mock.query_block_time();
+    let expires_at = current_time + 100;
+    create_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![BlockTime {
+            timestamp: expires_at + 1000,
+            comparison: Comparison::GreaterThan,
+        }],
+        Some(expires_at),
+    )
+    .unwrap();
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::zero());
+
+    // Order without expiry can't be pruned
+    create_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![BlockTime {
+            timestamp: expires_at + 1000,
+            comparison: Comparison::GreaterThan,
+        }],
+        None,
+    )
+    .unwrap();
+    mock.set_block_time(expires_at);
+    let res = mock.prune_expired_trigger_order(&pruner, &account_id, "2");
+    assert_err(
+        res,
+        ContractError::TriggerOrderNotExpired {
+            order_id: "2".to_string(),
+            account_id: account_id.clone(),
+        },
+    );
+
+    // Order not expired yet
+    mock.set_block_time(expires_at - 1);
+    let res = mock.prune_expired_trigger_order(&pruner, &account_id, "1");
+    assert_err(
+        res,
+        ContractError::TriggerOrderNotExpired {
+            order_id: "1".to_string(),
+            account_id: account_id.clone(),
+        },
+    );
+
+    mock.set_block_time(expires_at);
+    mock.prune_expired_trigger_order(&pruner, &account_id, "1").unwrap();
+
+    // Keeper fee is split 50/50 (default pruner_fee_pct in mock env)
+    assert_eq!(mock.query_balance(&pruner, "uusdc"), coin(500000, "uusdc"));
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::new(500000));
+
+    let orders = mock.query_trigger_orders_for_account(account_id, None, None).data;
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].order.order_id, "2");
+}
+
+#[test]
+fn prune_expired_parent_refunds_child_keeper_fees() {
+    let (mut mock, user, account_id) = setup();
+    let pruner = Addr::unchecked("pruner");
+
+    let current_time = mock.query_block_time();
+    let expires_at = current_time + 100;
+    let lend_action = Lend(ActionCoin {
+        denom: "uusdc".to_string(),
+        amount: ActionAmount::AccountBalance,
+    });
+    let block_time_condition = BlockTime {
+        timestamp: expires_at + 1000,
+        comparison: Comparison::GreaterThan,
+    };
+    let deposit = coin(keeper_fee().amount.u128() * 2, "uusdc");
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(deposit.clone()),
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Parent),
+                actions: vec![lend_action.clone()],
+                conditions: vec![block_time_condition.clone()],
+                keeper_fee: keeper_fee(),
+                expires_at: Some(expires_at),
+            },
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Child),
+                actions: vec![lend_action],
+                conditions: vec![
+                    block_time_condition,
+                    TriggerOrderExecuted {
+                        trigger_order_id: "".to_string(),
+                    },
+                ],
+                keeper_fee: keeper_fee(),
+                expires_at: None,
+            },
+        ],
+        &[deposit],
+    )
+    .unwrap();
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::zero());
+    assert_eq!(mock.query_trigger_orders_for_account(account_id.clone(), None, None).data.len(), 2);
+
+    mock.set_block_time(expires_at);
+    mock.prune_expired_trigger_order(&pruner, &account_id, "1").unwrap();
+
+    // The parent keeper fee is split 50/50, the child keeper fee is refunded in full
+    assert_eq!(mock.query_balance(&pruner, "uusdc"), coin(500000, "uusdc"));
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::new(1500000));
+    assert!(mock.query_trigger_orders_for_account(account_id, None, None).data.is_empty());
+}
+
+#[test]
+fn delete_parent_refunds_child_keeper_fees() {
+    let (mut mock, user, account_id) = setup();
+
+    let current_time = mock.query_block_time();
+    let lend_action = Lend(ActionCoin {
+        denom: "uusdc".to_string(),
+        amount: ActionAmount::AccountBalance,
+    });
+    let block_time_condition = BlockTime {
+        timestamp: current_time + 1000,
+        comparison: Comparison::GreaterThan,
+    };
+    let deposit = coin(keeper_fee().amount.u128() * 2, "uusdc");
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(deposit.clone()),
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Parent),
+                actions: vec![lend_action.clone()],
+                conditions: vec![block_time_condition.clone()],
+                keeper_fee: keeper_fee(),
+                expires_at: None,
+            },
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Child),
+                actions: vec![lend_action],
+                conditions: vec![
+                    block_time_condition,
+                    TriggerOrderExecuted {
+                        trigger_order_id: "".to_string(),
+                    },
+                ],
+                keeper_fee: keeper_fee(),
+                expires_at: None,
+            },
+        ],
+        &[deposit],
+    )
+    .unwrap();
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::zero());
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![DeleteTriggerOrder {
+            trigger_order_id: "1".to_string(),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // Both the parent and the child keeper fees are refunded
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::new(2000000));
+    assert!(mock.query_trigger_orders_for_account(account_id, None, None).data.is_empty());
+}
+
+#[test]
+fn executing_child_refunds_sibling_keeper_fees() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    let current_time = mock.query_block_time();
+    let lend_action = Lend(ActionCoin {
+        denom: "uusdc".to_string(),
+        amount: ActionAmount::Exact(Uint128::new(100)),
+    });
+    let block_time_condition = BlockTime {
+        timestamp: current_time,
+        comparison: Comparison::GreaterThan,
+    };
+    let child_order = CreateTriggerOrder {
+        order_type: Some(CreateTriggerOrderType::Child),
+        actions: vec![lend_action.clone()],
+        conditions: vec![
+            block_time_condition.clone(),
+            TriggerOrderExecuted {
+                trigger_order_id: "".to_string(),
+            },
+        ],
+        keeper_fee: keeper_fee(),
+        expires_at: None,
+    };
+    let deposit = coin(keeper_fee().amount.u128() * 3 + 300, "uusdc");
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(deposit.clone()),
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Parent),
+                actions: vec![lend_action],
+                conditions: vec![block_time_condition],
+                keeper_fee: keeper_fee(),
+                expires_at: None,
+            },
+            child_order.clone(),
+            child_order,
+        ],
+        &[deposit],
+    )
+    .unwrap();
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::new(300));
+
+    mock.set_block_time(current_time + 1);
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::new(200));
+
+    // Executing one child removes the other one and refunds its keeper fee
+    mock.execute_trigger_order(&keeper, &account_id, "2").unwrap();
+    assert_eq!(account_usdc_balance(&mock, &account_id), Uint128::new(1000100));
+    assert!(mock.query_trigger_orders_for_account(account_id, None, None).data.is_empty());
+}
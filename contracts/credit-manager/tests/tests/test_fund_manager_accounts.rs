@@ -531,6 +531,7 @@ fn vault_cannot_be_used_after_being_blacklisted() {
             order_size: Int128::from(-100),
             reduce_only: None,
             order_type: None,
+            limit_price: None,
         }],
         &[],
     );
@@ -353,6 +353,8 @@ fn successful_with_asset_correlations() {
             perps_pnl_profit: Uint128::zero(),
             perps_pnl_loss: Uint128::zero(),
             has_perps: false,
+            perps_liq_ltv_numerator: Uint128::zero(),
+            perps_liq_ltv_denominator: Uint128::zero(),
         },
         hls_health
     );
@@ -434,6 +436,8 @@ fn successful_with_vault_correlations() {
             perps_pnl_profit: Uint128::zero(),
             perps_pnl_loss: Uint128::zero(),
             has_perps: false,
+            perps_liq_ltv_numerator: Uint128::zero(),
+            perps_liq_ltv_denominator: Uint128::zero(),
         },
         hls_health
     );
@@ -495,6 +499,7 @@ fn cannot_have_perps_in_hls_account() {
                 order_size: Int128::from_str("400").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[osmo_coin_deposited.clone()],
@@ -1,4 +1,4 @@
-use cosmwasm_std::coin;
+use cosmwasm_std::{coin, Decimal};
 use mars_types::credit_manager::KeeperFeeConfig;
 
 use super::helpers::MockEnv;
@@ -73,6 +73,7 @@ fn raises_on_invalid_params_addr() {
 fn keeper_fee_config_set_on_instantiate() {
     let keeper_fee_config = KeeperFeeConfig {
         min_fee: coin(1000, "uusd"),
+        pruner_fee_pct: Decimal::percent(50),
     };
 
     let mock = MockEnv::new().keeper_fee_config(keeper_fee_config.clone()).build().unwrap();
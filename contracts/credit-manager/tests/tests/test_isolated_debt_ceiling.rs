@@ -0,0 +1,183 @@
+use cosmwasm_std::{coin, coins, Addr, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::{
+    credit_manager::Action::{Borrow, Deposit, Repay, Withdraw},
+    params::{AssetParamsUnchecked, AssetParamsUpdate, IsolationParams},
+};
+
+use super::helpers::{assert_err, uatom_info, uosmo_info, AccountToFund, MockEnv};
+
+#[test]
+fn debt_ceiling_caps_total_debt_across_accounts() {
+    let osmo_info = uosmo_info();
+    let atom_info = uatom_info();
+    let user_a = Addr::unchecked("user_a");
+    let user_b = Addr::unchecked("user_b");
+    let mut mock = MockEnv::new()
+        .set_params(&[osmo_info.clone(), atom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user_a.clone(),
+            funds: coins(1000, osmo_info.denom.clone()),
+        })
+        .fund_account(AccountToFund {
+            addr: user_b.clone(),
+            funds: coins(1000, osmo_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    let account_a = mock.create_credit_account(&user_a).unwrap();
+    let account_b = mock.create_credit_account(&user_b).unwrap();
+
+    // 1000 uosmo worth 250 only backs uatom, up to a total value of 100 across all accounts
+    set_isolation(&mut mock, 100);
+
+    // The borrowed coins are withdrawn, so that uosmo backs all of the debt
+    mock.update_credit_account(
+        &account_a,
+        &user_a,
+        vec![
+            Deposit(osmo_info.to_coin(1000)),
+            Borrow(atom_info.to_coin(60)),
+            Withdraw(atom_info.to_action_coin(60)),
+        ],
+        &[coin(1000, osmo_info.denom.clone())],
+    )
+    .unwrap();
+
+    // The debt includes the interest simulated by the mock Red Bank
+    let total = mock.query_total_isolated_debt(&osmo_info.denom);
+    assert_eq!(total.debts, vec![atom_info.to_coin(61)]);
+    assert_eq!(total.value, Uint128::new(61));
+
+    // Each account stays well below its max LTV, but together they would exceed the ceiling
+    let res = mock.update_credit_account(
+        &account_b,
+        &user_b,
+        vec![
+            Deposit(osmo_info.to_coin(1000)),
+            Borrow(atom_info.to_coin(40)),
+            Withdraw(atom_info.to_action_coin(40)),
+        ],
+        &[coin(1000, osmo_info.denom.clone())],
+    );
+    assert_err(
+        res,
+        ContractError::IsolatedDebtCeilingExceeded {
+            denom: osmo_info.denom.clone(),
+            ceiling: Uint128::new(100),
+            new_value: Uint128::new(103),
+        },
+    );
+
+    // Repaying releases room under the ceiling
+    mock.update_credit_account(
+        &account_a,
+        &user_a,
+        vec![
+            Deposit(atom_info.to_coin(20)),
+            Repay {
+                recipient_account_id: None,
+                coin: atom_info.to_action_coin(20),
+            },
+        ],
+        &[coin(20, atom_info.denom.clone())],
+    )
+    .unwrap();
+
+    let total = mock.query_total_isolated_debt(&osmo_info.denom);
+    assert_eq!(total.debts, vec![atom_info.to_coin(41)]);
+
+    mock.update_credit_account(
+        &account_b,
+        &user_b,
+        vec![
+            Deposit(osmo_info.to_coin(1000)),
+            Borrow(atom_info.to_coin(57)),
+            Withdraw(atom_info.to_action_coin(57)),
+        ],
+        &[coin(1000, osmo_info.denom.clone())],
+    )
+    .unwrap();
+
+    let total = mock.query_total_isolated_debt(&osmo_info.denom);
+    assert_eq!(total.debts, vec![atom_info.to_coin(99)]);
+    assert_eq!(total.value, Uint128::new(99));
+}
+
+#[test]
+fn withdrawing_other_collateral_counts_towards_debt_ceiling() {
+    let osmo_info = uosmo_info();
+    let atom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[osmo_info.clone(), atom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![coin(1000, osmo_info.denom.clone()), coin(200, atom_info.denom.clone())],
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    set_isolation(&mut mock, 40);
+
+    // uosmo with a max LTV adjusted value of 175 and uatom of 164 back the debt together, so
+    // only about half of it counts towards the ceiling
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(osmo_info.to_coin(1000)),
+            Deposit(atom_info.to_coin(200)),
+            Borrow(atom_info.to_coin(50)),
+            Withdraw(atom_info.to_action_coin(50)),
+        ],
+        &[coin(1000, osmo_info.denom.clone()), coin(200, atom_info.denom.clone())],
+    )
+    .unwrap();
+
+    let total = mock.query_total_isolated_debt(&osmo_info.denom);
+    assert_eq!(total.value, Uint128::new(27));
+
+    // Withdrawing the uatom leaves uosmo backing all of the debt, above the ceiling
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Withdraw(atom_info.to_action_coin(200))],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::IsolatedDebtCeilingExceeded {
+            denom: osmo_info.denom.clone(),
+            ceiling: Uint128::new(40),
+            new_value: Uint128::new(51),
+        },
+    );
+
+    // Withdrawing part of it keeps the isolated debt within the ceiling
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Withdraw(atom_info.to_action_coin(50))],
+        &[],
+    )
+    .unwrap();
+
+    let total = mock.query_total_isolated_debt(&osmo_info.denom);
+    assert_eq!(total.value, Uint128::new(30));
+}
+
+fn set_isolation(mock: &mut MockEnv, debt_ceiling: u128) {
+    let osmo_info = uosmo_info();
+    let params = mock.query_asset_params(&osmo_info.denom);
+    mock.update_asset_params(AssetParamsUpdate::AddOrUpdate {
+        params: AssetParamsUnchecked {
+            isolation: Some(IsolationParams {
+                borrowable_denoms: vec![uatom_info().denom],
+                debt_ceiling: Uint128::new(debt_ceiling),
+            }),
+            ..params.into()
+        },
+    });
+}
@@ -87,12 +87,14 @@ fn close_perps_when_enough_usdc_in_account_to_cover_loss() {
                 order_size: Int128::from_str("200").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
             ExecutePerpOrder {
                 denom: uatom_info.denom.clone(),
                 order_size: Int128::from_str("-400").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[uosmo_coin_deposited.clone(), uusdc_coin_deposited.clone()],
@@ -268,12 +270,14 @@ fn close_perps_when_not_enough_usdc_in_account_to_cover_loss() {
                 order_size: Int128::from_str("200").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
             ExecutePerpOrder {
                 denom: uatom_info.denom.clone(),
                 order_size: Int128::from_str("-400").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[uosmo_coin_deposited.clone(), uusdc_coin_deposited.clone()],
@@ -454,12 +458,14 @@ fn close_perps_with_profit() {
                 order_size: Int128::from_str("200").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
             ExecutePerpOrder {
                 denom: utia_info.denom.clone(),
                 order_size: Int128::from_str("-400").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[uosmo_coin_deposited.clone(), uusdc_coin_deposited.clone()],
@@ -643,6 +649,7 @@ fn liquidation_uses_correct_price_kind_if_perps_open() {
                 order_size: Int128::from_str("-400").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[uosmo_coin_deposited.clone()],
@@ -882,6 +889,7 @@ fn liquidate_if_no_debt_after_closing_perps(
                 order_size: Int128::from_str("-500").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[uusdc_coin_deposited.clone(), uosmo_coin_deposited.clone()],
@@ -1162,6 +1170,7 @@ fn liquidate_if_bad_debt_created(
                 order_size: Int128::from_str("-400").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
             Deposit(uatom_coin_deposited.clone()),
         ],
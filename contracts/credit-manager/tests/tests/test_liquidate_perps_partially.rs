@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Decimal, Int128, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::{
+    credit_manager::{
+        Action::{self, ExecutePerpOrder, Liquidate},
+        ConfigUpdates, ExecutePerpOrderType, LiquidateRequest,
+    },
+    oracle::ActionKind,
+};
+
+use super::helpers::{self, assert_err, get_coin, set_atom_price, uatom_info, uusdc_info, MockEnv};
+
+fn setup(target_hf: Option<Decimal>) -> (MockEnv, Addr, String, Addr, String) {
+    let liquidator = Addr::unchecked("liquidator");
+    let liquidatee = Addr::unchecked("liquidatee");
+    let uusdc_coin_deposited = uusdc_info().to_coin(1000);
+
+    let mut builder = MockEnv::new().set_params(&[uatom_info(), uusdc_info()]);
+    if let Some(target_hf) = target_hf {
+        builder = builder.perps_liquidation_target_health_factor(target_hf);
+    }
+    let (mut mock, liquidatee_account_id, _) = helpers::setup_perps(
+        builder,
+        &liquidatee,
+        vec![uusdc_coin_deposited.clone()],
+        vec![uusdc_coin_deposited],
+        100000,
+    );
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+
+    // Long perp position backed only by usdc
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![ExecutePerpOrder {
+            denom: "uatom".to_string(),
+            order_size: Int128::from_str("4000").unwrap(),
+            reduce_only: None,
+            order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    (mock, liquidator, liquidator_account_id, liquidatee, liquidatee_account_id)
+}
+
+fn liquidate_action(liquidatee_account_id: &str) -> Vec<Action> {
+    vec![Liquidate {
+        liquidatee_account_id: liquidatee_account_id.to_string(),
+        debt_coin: uusdc_info().to_coin(10),
+        request: LiquidateRequest::Deposit(uusdc_info().denom),
+    }]
+}
+
+#[test]
+fn target_health_factor_must_be_above_one() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = Addr::unchecked(mock.query_config().ownership.owner.unwrap());
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            perps_liquidation_target_health_factor: Some(Decimal::one()),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidConfig {
+            reason: "Perps liquidation target health factor must be greater than 1".to_string(),
+        },
+    );
+}
+
+#[test]
+fn perps_closed_partially_to_reach_target_health_factor() {
+    let target_hf = Decimal::percent(105);
+    let (mut mock, liquidator, liquidator_account_id, _, liquidatee_account_id) =
+        setup(Some(target_hf));
+
+    set_atom_price(&mut mock, "0.84");
+
+    let prev_health = mock.query_health(&liquidatee_account_id, ActionKind::Liquidation);
+    assert!(prev_health.liquidatable);
+    assert!(!prev_health.perps_liq_ltv_denominator.is_zero());
+
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        liquidate_action(&liquidatee_account_id),
+        &[],
+    )
+    .unwrap();
+
+    // Only a share of the position is closed
+    let position = mock.query_perp_position(&liquidatee_account_id, "uatom").position.unwrap();
+    assert!(position.size > Int128::zero());
+    assert!(position.size < Int128::from_str("4000").unwrap());
+
+    // The account is left around the target health factor
+    let health = mock.query_health(&liquidatee_account_id, ActionKind::Liquidation);
+    assert!(!health.liquidatable);
+    let hf = health.liquidation_health_factor.unwrap();
+    assert!(hf > Decimal::one());
+    assert!(hf < Decimal::percent(110));
+
+    // Spot positions are not liquidated
+    let positions = mock.query_positions(&liquidatee_account_id);
+    assert!(positions.debts.is_empty());
+
+    // The liquidator gets a bonus in usdc, part of it goes to the protocol
+    let liquidator_positions = mock.query_positions(&liquidator_account_id);
+    let bonus = get_coin("uusdc", &liquidator_positions.deposits);
+    assert!(bonus.amount > Uint128::zero());
+
+    let rewards_collector_acc_id = mock.query_rewards_collector_account();
+    let rewards_positions = mock.query_positions(&rewards_collector_acc_id);
+    let protocol_fee = get_coin("uusdc", &rewards_positions.deposits);
+    assert!(protocol_fee.amount < bonus.amount);
+}
+
+#[test]
+fn all_perps_closed_if_target_health_factor_unreachable() {
+    let (mut mock, liquidator, liquidator_account_id, _, liquidatee_account_id) =
+        setup(Some(Decimal::percent(105)));
+
+    // Losses too big to be fixed by closing perps
+    set_atom_price(&mut mock, "0.6");
+
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        liquidate_action(&liquidatee_account_id),
+        &[],
+    )
+    .unwrap();
+
+    assert!(mock.query_perp_position(&liquidatee_account_id, "uatom").position.is_none());
+}
+
+#[test]
+fn all_perps_closed_without_target_health_factor() {
+    let (mut mock, liquidator, liquidator_account_id, _, liquidatee_account_id) = setup(None);
+
+    set_atom_price(&mut mock, "0.84");
+
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        liquidate_action(&liquidatee_account_id),
+        &[],
+    )
+    .unwrap();
+
+    assert!(mock.query_perp_position(&liquidatee_account_id, "uatom").position.is_none());
+}
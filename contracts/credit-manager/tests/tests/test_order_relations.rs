@@ -222,6 +222,7 @@ use test_case::test_case;
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         },
     ],
     None;
@@ -561,6 +562,7 @@ fn market_order_with_child_orders() {
             ExecutePerpOrder {
                 denom: "uatom".to_string(),
                 order_type: Some(ExecutePerpOrderType::Parent),
+                limit_price: None,
                 order_size: Int128::from_str("10").unwrap(),
                 reduce_only: None,
             },
@@ -881,6 +883,7 @@ fn multiple_limit_orders() {
                 order_size: Int128::from_str("5").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Parent),
+                limit_price: None,
             },
             create_acc2_osmo_tp.clone(),
             create_acc2_osmo_sl.clone(),
@@ -921,6 +924,7 @@ fn multiple_limit_orders() {
                 order_size: Int128::from_str("-5").unwrap(),
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Parent),
+                limit_price: None,
             },
             create_acc2_atom_tp.clone(),
             create_acc2_atom_sl.clone(),
@@ -1124,6 +1128,7 @@ fn multiple_limit_orders() {
             order_size: Int128::from_str("10").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -1145,6 +1150,7 @@ fn multiple_limit_orders() {
             order_size: Int128::from_str("-10").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -1244,6 +1250,7 @@ fn multiple_limit_orders() {
             order_size: Int128::from_str("-40").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -1289,6 +1296,7 @@ fn create_parent_order(
             order_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![OraclePrice {
             denom: denom.to_string(),
@@ -1299,6 +1307,7 @@ fn create_parent_order(
             denom: "uusdc".to_string(),
             amount: Uint128::new(10000000),
         },
+        expires_at: None,
     }
 }
 
@@ -1312,6 +1321,7 @@ fn trigger_order_response(
             actions,
             conditions,
             keeper_fee,
+            expires_at,
             ..
         } => Some(TriggerOrderResponse {
             account_id: account_id.to_string(),
@@ -1320,6 +1330,7 @@ fn trigger_order_response(
                 actions,
                 conditions,
                 keeper_fee,
+                expires_at,
             },
         }),
         _ => None,
@@ -1341,6 +1352,7 @@ fn create_child_order(
             order_size,
             reduce_only,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![
             OraclePrice {
@@ -1356,6 +1368,7 @@ fn create_child_order(
             denom: "uusdc".to_string(),
             amount: Uint128::new(10000000),
         },
+        expires_at: None,
     }
 }
 
@@ -1370,6 +1383,7 @@ fn child_trigger_order_response(
             actions,
             mut conditions,
             keeper_fee,
+            expires_at,
             ..
         } => {
             for condition in &mut conditions {
@@ -1387,6 +1401,7 @@ fn child_trigger_order_response(
                     actions,
                     conditions,
                     keeper_fee,
+                    expires_at,
                 },
             })
         }
@@ -1407,6 +1422,7 @@ fn create_trigger_order(
             order_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![OraclePrice {
             denom: denom.to_string(),
@@ -1417,6 +1433,7 @@ fn create_trigger_order(
             denom: "uusdc".to_string(),
             amount: Uint128::new(10000000),
         },
+        expires_at: None,
     }
 }
 
@@ -1432,6 +1449,7 @@ fn child_order_no_parent_id() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![
             TriggerOrderExecuted {
@@ -1443,6 +1461,7 @@ fn child_order_no_parent_id() -> Action {
                 comparison: Comparison::GreaterThan,
             },
         ],
+        expires_at: None,
     }
 }
 
@@ -1458,6 +1477,7 @@ fn child_order_with_parent_id(parent_id: u64) -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![
             TriggerOrderExecuted {
@@ -1469,6 +1489,7 @@ fn child_order_with_parent_id(parent_id: u64) -> Action {
                 comparison: Comparison::GreaterThan,
             },
         ],
+        expires_at: None,
     }
 }
 
@@ -1484,10 +1505,12 @@ fn child_order_invalid_single_condition() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![TriggerOrderExecuted {
             trigger_order_id: "".to_string(),
         }],
+        expires_at: None,
     }
 }
 
@@ -1503,6 +1526,7 @@ fn child_order_invalid_double_trigger_condition() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![
             TriggerOrderExecuted {
@@ -1512,6 +1536,7 @@ fn child_order_invalid_double_trigger_condition() -> Action {
                 trigger_order_id: "".to_string(),
             },
         ],
+        expires_at: None,
     }
 }
 
@@ -1527,10 +1552,12 @@ fn parent_order_invalid_conditions() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![TriggerOrderExecuted {
             trigger_order_id: "2".to_string(),
         }],
+        expires_at: None,
     }
 }
 
@@ -1546,10 +1573,12 @@ fn default_order_invalid_conditions() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![TriggerOrderExecuted {
             trigger_order_id: "2".to_string(),
         }],
+        expires_at: None,
     }
 }
 
@@ -1565,12 +1594,14 @@ fn default_order() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![OraclePrice {
             denom: "perp1".to_string(),
             price: Decimal::from_str("100").unwrap(),
             comparison: Comparison::GreaterThan,
         }],
+        expires_at: None,
     }
 }
 
@@ -1586,12 +1617,14 @@ fn parent_order() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         conditions: vec![OraclePrice {
             denom: "perp1".to_string(),
             price: Decimal::from_str("100").unwrap(),
             comparison: Comparison::GreaterThan,
         }],
+        expires_at: None,
     }
 }
 
@@ -1601,6 +1634,7 @@ fn parent_market_order() -> Action {
         order_size: Int128::from_str("1").unwrap(),
         reduce_only: None,
         order_type: Some(ExecutePerpOrderType::Parent),
+        limit_price: None,
     }
 }
 
@@ -1610,6 +1644,7 @@ fn default_market_order() -> Action {
         order_size: Int128::from_str("1").unwrap(),
         reduce_only: None,
         order_type: Some(ExecutePerpOrderType::Default),
+        limit_price: None,
     }
 }
 
@@ -1625,12 +1660,14 @@ fn parent_order_parent_actions() -> Action {
             order_size: Int128::from_str("1").unwrap(),
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Parent),
+            limit_price: None,
         }],
         conditions: vec![OraclePrice {
             denom: "perp1".to_string(),
             price: Decimal::from_str("100").unwrap(),
             comparison: Comparison::GreaterThan,
         }],
+        expires_at: None,
     }
 }
 
@@ -108,6 +108,7 @@ fn open_position_with_correct_payment(
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -236,6 +237,7 @@ fn close_losing_position_with_correct_payment(
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -273,6 +275,7 @@ fn close_losing_position_with_correct_payment(
             order_size,
             reduce_only: Some(true),
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -324,6 +327,7 @@ fn close_perp_position_with_profit() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -371,6 +375,7 @@ fn close_perp_position_with_profit() {
             order_size: Int128::zero() - perp_size,
             reduce_only: Some(true),
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -428,6 +433,7 @@ fn increase_position_with_realized_pnl() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -472,6 +478,7 @@ fn increase_position_with_realized_pnl() {
             order_size: delta_change,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -531,6 +538,7 @@ fn increase_position_with_realized_pnl() {
             order_size: delta_change,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -585,6 +593,7 @@ fn decrease_position_with_realized_pnl() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -628,6 +637,7 @@ fn decrease_position_with_realized_pnl() {
             order_size: delta_change,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -687,6 +697,7 @@ fn decrease_position_with_realized_pnl() {
             order_size: delta_change,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -742,6 +753,7 @@ fn flip_position_with_realized_pnl() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -787,6 +799,7 @@ fn flip_position_with_realized_pnl() {
             order_size: delta_change,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -846,6 +859,7 @@ fn flip_position_with_realized_pnl() {
             order_size: delta_change,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -904,6 +918,7 @@ fn cannot_open_perp_above_max_ltv() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     );
@@ -947,6 +962,7 @@ fn health_check_works_if_no_spot_base_denom() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -984,6 +1000,7 @@ fn health_check_works_if_no_spot_base_denom() {
             order_size: Int128::zero().checked_sub(perp_size).unwrap(),
             reduce_only: Some(true),
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -1046,6 +1063,7 @@ fn close_perp_position() {
             order_size: perp_size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
@@ -0,0 +1,170 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Decimal, Int128, Uint128};
+use mars_types::{
+    credit_manager::{
+        Action::{Deposit, ExecutePerpOrder, Liquidate, OpenIsolatedPerpPosition},
+        ExecutePerpOrderType, LiquidateRequest,
+    },
+    perps::PnL,
+};
+
+use super::helpers::{self, coin_info, get_coin, set_atom_price, uatom_info, uusdc_info, MockEnv};
+
+fn setup() -> (MockEnv, Addr, String) {
+    let usdc_info = coin_info("uusdc");
+    let cm_user = Addr::unchecked("user");
+    let usdc_coin_deposited = usdc_info.to_coin(10000);
+
+    let (mock, account_id, _) = helpers::setup_perps(
+        MockEnv::new()
+            .perps_insurance_fund_fee_ratio(Decimal::percent(50))
+            .set_params(&[uatom_info(), usdc_info]),
+        &cm_user,
+        vec![usdc_coin_deposited.clone()],
+        vec![usdc_coin_deposited],
+        100000,
+    );
+
+    (mock, cm_user, account_id)
+}
+
+#[test]
+fn insurance_fund_absorbs_bad_debt_of_isolated_position() {
+    let (mut mock, user, account_id) = setup();
+    let liquidator = Addr::unchecked("liquidator");
+
+    let size = Int128::from_str("10000").unwrap();
+    let opening_fee = mock.query_perp_opening_fee("uatom", size, None).fee;
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![OpenIsolatedPerpPosition {
+            denom: "uatom".to_string(),
+            order_size: size,
+            margin: Uint128::new(2500),
+            limit_price: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // Half of the opening fee goes to the insurance fund of the market
+    let fund = mock.query_perp_insurance_fund("uatom");
+    assert_eq!(fund.balance, opening_fee.amount.checked_mul_floor(Decimal::percent(50)).unwrap());
+    assert!(mock.query_perp_insurance_fund_draws("uatom").is_empty());
+
+    // Loss of ~5000 exceeds the 2500 margin
+    set_atom_price(&mut mock, "0.5");
+    mock.liquidate_isolated_perp_position(&liquidator, &account_id, "uatom").unwrap();
+
+    // The whole fund, including its slice of the closing fee, is drawn to cover the bad debt
+    let draws = mock.query_perp_insurance_fund_draws("uatom");
+    assert_eq!(draws.len(), 1);
+    assert_eq!(draws[0].draw_id, 1);
+    assert_eq!(draws[0].account_id, account_id);
+    assert!(draws[0].amount > fund.balance);
+    assert!(draws[0].bad_debt > draws[0].amount);
+    assert_eq!(mock.query_perp_insurance_fund("uatom").balance, Uint128::zero());
+}
+
+#[test]
+fn insurance_fund_absorbs_bad_debt_of_cross_margin_positions() {
+    let uatom_info = uatom_info();
+    let mut uusdc_info = uusdc_info();
+    uusdc_info.price = Decimal::one();
+
+    let liquidator = Addr::unchecked("liquidator");
+    let liquidatee = Addr::unchecked("liquidatee");
+
+    let uusdc_coin_deposited = uusdc_info.to_coin(600);
+    let uatom_coin_deposited = uatom_info.to_coin(5);
+
+    let (mut mock, liquidatee_account_id, _) = helpers::setup_perps(
+        MockEnv::new()
+            .perps_insurance_fund_fee_ratio(Decimal::percent(50))
+            .set_params(&[uatom_info.clone(), uusdc_info.clone()]),
+        &liquidatee,
+        vec![uusdc_coin_deposited.clone(), uatom_coin_deposited.clone()],
+        vec![uusdc_coin_deposited],
+        100000,
+    );
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![
+            ExecutePerpOrder {
+                denom: uatom_info.denom.clone(),
+                order_size: Int128::from_str("-400").unwrap(),
+                reduce_only: None,
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
+            },
+            Deposit(uatom_coin_deposited.clone()),
+        ],
+        &[uatom_coin_deposited],
+    )
+    .unwrap();
+    assert!(!mock.query_perp_insurance_fund(&uatom_info.denom).balance.is_zero());
+
+    // The loss of the short position exceeds the value of all the collateral of the account
+    set_atom_price(&mut mock, "3");
+
+    let usdc_deposit_before_liq =
+        get_coin(&uusdc_info.denom, &mock.query_positions(&liquidatee_account_id).deposits);
+    let perps_balance_before_liq = mock.query_balance(mock.perps.address(), &uusdc_info.denom);
+    let pnl = mock
+        .query_perp_position(&liquidatee_account_id, &uatom_info.denom)
+        .position
+        .unwrap()
+        .unrealized_pnl
+        .to_coins(&uusdc_info.denom)
+        .pnl;
+    let PnL::Loss(loss) = pnl else {
+        panic!("expected loss");
+    };
+
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Liquidate {
+            liquidatee_account_id: liquidatee_account_id.clone(),
+            debt_coin: uusdc_info.to_coin(0),
+            request: LiquidateRequest::Deposit(uusdc_info.denom.clone()),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // The part of the loss the account can't back is drawn from the insurance fund
+    let draws = mock.query_perp_insurance_fund_draws(&uatom_info.denom);
+    assert_eq!(draws.len(), 1);
+    assert_eq!(draws[0].account_id, liquidatee_account_id);
+    assert!(!draws[0].bad_debt.is_zero());
+    assert!(draws[0].amount < draws[0].bad_debt);
+    assert_eq!(mock.query_perp_insurance_fund(&uatom_info.denom).balance, Uint128::zero());
+
+    // The bad debt is not paid to the perps contract, so it doesn't become Red Bank debt
+    let perps_balance = mock.query_balance(mock.perps.address(), &uusdc_info.denom);
+    assert_eq!(
+        perps_balance.amount,
+        perps_balance_before_liq.amount + loss.amount - draws[0].bad_debt
+    );
+
+    let position = mock.query_positions(&liquidatee_account_id);
+    assert!(position.perps.is_empty());
+    let usdc_debt = position
+        .debts
+        .iter()
+        .find(|debt| debt.denom == uusdc_info.denom)
+        .map(|debt| debt.amount)
+        .unwrap_or_default();
+    assert!(usdc_debt < loss.amount - usdc_deposit_before_liq.amount);
+    assert!(
+        usdc_debt + draws[0].bad_debt
+            <= loss.amount - usdc_deposit_before_liq.amount + Uint128::one()
+    );
+}
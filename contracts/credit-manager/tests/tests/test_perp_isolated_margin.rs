@@ -0,0 +1,156 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Int128, Uint128};
+use mars_perps::error::ContractError as PerpsContractError;
+use mars_types::{
+    credit_manager::Action::{AddPerpIsolatedMargin, ClosePerpPosition, OpenIsolatedPerpPosition},
+    oracle::ActionKind,
+};
+
+use super::helpers::{self, coin_info, get_coin, set_atom_price, uatom_info, MockEnv};
+
+fn setup() -> (MockEnv, Addr, String) {
+    let usdc_info = coin_info("uusdc");
+    let cm_user = Addr::unchecked("user");
+    let usdc_coin_deposited = usdc_info.to_coin(10000);
+
+    let (mock, account_id, _) = helpers::setup_perps(
+        MockEnv::new().set_params(&[uatom_info(), usdc_info]),
+        &cm_user,
+        vec![usdc_coin_deposited.clone()],
+        vec![usdc_coin_deposited],
+        100000,
+    );
+
+    (mock, cm_user, account_id)
+}
+
+fn open_isolated_position(mock: &mut MockEnv, user: &Addr, account_id: &str, margin: u128) {
+    mock.update_credit_account(
+        account_id,
+        user,
+        vec![OpenIsolatedPerpPosition {
+            denom: "uatom".to_string(),
+            order_size: Int128::from_str("10000").unwrap(),
+            margin: Uint128::new(margin),
+            limit_price: None,
+        }],
+        &[],
+    )
+    .unwrap();
+}
+
+fn account_usdc(mock: &MockEnv, account_id: &str) -> Uint128 {
+    get_coin("uusdc", &mock.query_positions(account_id).deposits).amount
+}
+
+#[test]
+fn open_and_close_isolated_position() {
+    let (mut mock, user, account_id) = setup();
+
+    let opening_fee =
+        mock.query_perp_opening_fee("uatom", Int128::from_str("10000").unwrap(), None).fee;
+
+    open_isolated_position(&mut mock, &user, &account_id, 2500);
+
+    // Margin and opening fee leave the account
+    assert_eq!(
+        account_usdc(&mock, &account_id),
+        Uint128::new(10000) - Uint128::new(2500) - opening_fee.amount
+    );
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.perps.len(), 1);
+    assert_eq!(positions.perps[0].isolated_margin, Some(Uint128::new(2500)));
+
+    // Margin can be topped up from the account's usdc
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AddPerpIsolatedMargin {
+            denom: "uatom".to_string(),
+            amount: Uint128::new(500),
+        }],
+        &[],
+    )
+    .unwrap();
+    let position = mock.query_perp_position(&account_id, "uatom").position.unwrap();
+    assert_eq!(position.isolated_margin, Some(Uint128::new(3000)));
+
+    let usdc_before_close = account_usdc(&mock, &account_id);
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![ClosePerpPosition {
+            denom: "uatom".to_string(),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // What is left of the margin after fees is returned to the account
+    assert!(mock.query_perp_position(&account_id, "uatom").position.is_none());
+    let returned = account_usdc(&mock, &account_id) - usdc_before_close;
+    assert!(returned > Uint128::zero());
+    assert!(returned < Uint128::new(3000));
+}
+
+#[test]
+fn isolated_margin_must_cover_position() {
+    let (mut mock, user, account_id) = setup();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![OpenIsolatedPerpPosition {
+            denom: "uatom".to_string(),
+            order_size: Int128::from_str("10000").unwrap(),
+            margin: Uint128::new(100),
+            limit_price: None,
+        }],
+        &[],
+    );
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert!(matches!(err, PerpsContractError::IsolatedMarginTooLow { .. }));
+}
+
+#[test]
+fn liquidate_isolated_position() {
+    let (mut mock, user, account_id) = setup();
+    let liquidator = Addr::unchecked("liquidator");
+
+    open_isolated_position(&mut mock, &user, &account_id, 2500);
+    let usdc_after_open = account_usdc(&mock, &account_id);
+
+    // Healthy position can't be liquidated
+    let res = mock.liquidate_isolated_perp_position(&liquidator, &account_id, "uatom");
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert!(matches!(err, PerpsContractError::IsolatedPositionHealthy { .. }));
+
+    // Losses of ~2000 out of 2500 margin
+    set_atom_price(&mut mock, "0.8");
+
+    // The account itself is healthy, the isolated position is not part of its health
+    let health = mock.query_health(&account_id, ActionKind::Default);
+    assert!(!health.liquidatable);
+
+    let res = mock.liquidate_isolated_perp_position(&liquidator, &account_id, "uatom").unwrap();
+    assert!(mock.query_perp_position(&account_id, "uatom").position.is_none());
+
+    // The liquidator is paid a bonus out of the remaining margin
+    let liquidator_bonus = mock.query_balance(&liquidator, "uusdc").amount;
+    assert!(liquidator_bonus > Uint128::zero());
+    let bonus_attr = res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "liquidator_bonus")
+        .unwrap();
+    assert_eq!(bonus_attr.value, liquidator_bonus.to_string());
+
+    // The rest of the margin is returned to the account
+    let returned = account_usdc(&mock, &account_id) - usdc_after_open;
+    assert!(returned > Uint128::zero());
+    assert!(returned + liquidator_bonus < Uint128::new(500));
+}
@@ -0,0 +1,290 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Coin, Decimal, Int128, Uint128};
+use cw_multi_test::AppResponse;
+use mars_perps::error::ContractError as PerpsContractError;
+use mars_types::{
+    credit_manager::Action::{
+        CancelPerpLimitOrder, ClosePerpPosition, Deposit, PlacePerpLimitOrder,
+    },
+    perps::LimitOrder,
+};
+
+use super::helpers::{self, coin_info, get_coin, set_atom_price, uatom_info, uosmo_info, MockEnv};
+
+fn keeper_fee() -> Coin {
+    coin_info("uusdc").to_coin(1000000)
+}
+
+fn is_keeper_fee_refunded(res: &AppResponse) -> bool {
+    res.events.iter().any(|event| {
+        event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "action" && attr.value == "refund_perp_limit_order_fee")
+    })
+}
+
+fn setup() -> (MockEnv, Addr, String) {
+    let usdc_info = coin_info("uusdc");
+    let cm_user = Addr::unchecked("user");
+
+    let osmo_coin_deposited = uosmo_info().to_coin(10000);
+    let usdc_coin_deposited = usdc_info.to_coin(1000);
+    let keeper_fees = keeper_fee().amount.u128() * 10;
+
+    let (mock, account_id, _) = helpers::setup_perps(
+        MockEnv::new().set_params(&[uosmo_info(), uatom_info(), usdc_info.clone()]),
+        &cm_user,
+        vec![
+            osmo_coin_deposited.clone(),
+            usdc_info.to_coin(usdc_coin_deposited.amount.u128() + keeper_fees),
+        ],
+        vec![osmo_coin_deposited, usdc_coin_deposited],
+        100000,
+    );
+
+    (mock, cm_user, account_id)
+}
+
+fn place_order(
+    mock: &mut MockEnv,
+    user: &Addr,
+    account_id: &str,
+    size: &str,
+    limit_price: &str,
+    reduce_only: Option<bool>,
+) -> LimitOrder {
+    mock.update_credit_account(
+        account_id,
+        user,
+        vec![
+            Deposit(keeper_fee()),
+            PlacePerpLimitOrder {
+                denom: "uatom".to_string(),
+                order_size: Int128::from_str(size).unwrap(),
+                limit_price: Decimal::from_str(limit_price).unwrap(),
+                reduce_only,
+                keeper_fee: keeper_fee(),
+            },
+        ],
+        &[keeper_fee()],
+    )
+    .unwrap();
+
+    mock.query_perp_limit_orders_by_account(account_id).pop().unwrap()
+}
+
+#[test]
+fn buy_limit_order_filled_when_price_drops() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    let order = place_order(&mut mock, &user, &account_id, "1200", "0.9", None);
+    assert_eq!(order.size, Int128::from_str("1200").unwrap());
+    assert_eq!(order.limit_price, Decimal::from_str("0.9").unwrap());
+    assert_eq!(order.keeper_fee, keeper_fee());
+
+    // The keeper fee is escrowed
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(get_coin("uusdc", &positions.deposits).amount.u128(), 1000);
+
+    // Price is above the limit, nothing to fill
+    let res = mock.fill_perp_limit_orders(&keeper, "uatom");
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        PerpsContractError::NoLimitOrdersToFill {
+            denom: "uatom".to_string()
+        }
+    );
+
+    set_atom_price(&mut mock, "0.85");
+
+    let vault_usdc_balance = mock.query_balance(mock.perps.address(), "uusdc");
+    let opening_fee = mock.query_perp_opening_fee("uatom", order.size, None);
+    assert!(!opening_fee.fee.amount.is_zero());
+
+    mock.fill_perp_limit_orders(&keeper, "uatom").unwrap();
+
+    // The order is executed as a market order of the account
+    let position = mock.query_perp_position(&account_id, "uatom").position.unwrap();
+    assert_eq!(position.size, order.size);
+    assert_eq!(position.entry_price, Decimal::from_str("0.85").unwrap());
+
+    // The opening fee is charged from the account
+    let current_vault_usdc_balance = mock.query_balance(mock.perps.address(), "uusdc");
+    assert_eq!(
+        current_vault_usdc_balance.amount,
+        vault_usdc_balance.amount + opening_fee.fee.amount
+    );
+
+    assert!(mock.query_perp_limit_order(order.order_id).is_none());
+    assert!(mock.query_perp_limit_orders_by_account(&account_id).is_empty());
+
+    // The keeper is paid for filling the order
+    assert_eq!(mock.query_balance(&keeper, "uusdc"), keeper_fee());
+}
+
+#[test]
+fn sell_limit_order_filled_when_price_rises() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    let order = place_order(&mut mock, &user, &account_id, "-1200", "1.1", None);
+
+    set_atom_price(&mut mock, "1.05");
+    assert!(mock.fill_perp_limit_orders(&keeper, "uatom").is_err());
+
+    set_atom_price(&mut mock, "1.15");
+    mock.fill_perp_limit_orders(&keeper, "uatom").unwrap();
+
+    let position = mock.query_perp_position(&account_id, "uatom").position.unwrap();
+    assert_eq!(position.size, order.size);
+    assert!(mock.query_perp_limit_order(order.order_id).is_none());
+}
+
+#[test]
+fn cancel_limit_order() {
+    let (mut mock, user, account_id) = setup();
+
+    let order = place_order(&mut mock, &user, &account_id, "1200", "0.9", None);
+
+    // Orders of other accounts can't be cancelled
+    let other_user = Addr::unchecked("other_user");
+    let other_account_id = mock.create_credit_account(&other_user).unwrap();
+    let res = mock.update_credit_account(
+        &other_account_id,
+        &other_user,
+        vec![CancelPerpLimitOrder {
+            order_id: order.order_id,
+        }],
+        &[],
+    );
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        PerpsContractError::LimitOrderNotFound {
+            account_id: other_account_id,
+            order_id: order.order_id,
+        }
+    );
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![CancelPerpLimitOrder {
+            order_id: order.order_id,
+        }],
+        &[],
+    )
+    .unwrap();
+    assert!(mock.query_perp_limit_order(order.order_id).is_none());
+
+    // The keeper fee is refunded
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(
+        get_coin("uusdc", &positions.deposits).amount,
+        keeper_fee().amount + Uint128::new(1000)
+    );
+
+    // Nothing left to fill
+    set_atom_price(&mut mock, "0.85");
+    assert!(mock.fill_perp_limit_orders(&Addr::unchecked("keeper"), "uatom").is_err());
+}
+
+#[test]
+fn unhealthy_limit_order_is_dropped() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    // Too big for the account's collateral
+    let unhealthy_order = place_order(&mut mock, &user, &account_id, "1000000", "0.9", None);
+    let order = place_order(&mut mock, &user, &account_id, "1200", "0.9", None);
+
+    set_atom_price(&mut mock, "0.85");
+    let res = mock.fill_perp_limit_orders(&keeper, "uatom").unwrap();
+
+    // The unhealthy order failed and is removed, the other one is filled
+    let position = mock.query_perp_position(&account_id, "uatom").position.unwrap();
+    assert_eq!(position.size, order.size);
+    assert!(mock.query_perp_limit_order(unhealthy_order.order_id).is_none());
+    assert!(mock.query_perp_limit_order(order.order_id).is_none());
+
+    // The failed order is reported and its keeper fee refunded. The keeper is only paid for the
+    // filled order.
+    assert!(res.events.iter().any(|event| {
+        event.attributes.iter().any(|attr| attr.key == "status" && attr.value == "failed")
+    }));
+    assert!(is_keeper_fee_refunded(&res));
+    assert_eq!(mock.query_balance(&keeper, "uusdc"), keeper_fee());
+}
+
+#[test]
+fn closing_position_removes_reduce_only_limit_orders() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    place_order(&mut mock, &user, &account_id, "1200", "0.9", None);
+    set_atom_price(&mut mock, "0.85");
+    mock.fill_perp_limit_orders(&keeper, "uatom").unwrap();
+
+    // Take profit order resting when the position gets closed
+    let take_profit_order = place_order(&mut mock, &user, &account_id, "-1200", "1.5", Some(true));
+
+    let res = mock
+        .update_credit_account(
+            &account_id,
+            &user,
+            vec![ClosePerpPosition {
+                denom: "uatom".to_string(),
+            }],
+            &[],
+        )
+        .unwrap();
+
+    // The order can't open a new position later on and its keeper fee is refunded
+    assert!(mock.query_perp_position(&account_id, "uatom").position.is_none());
+    assert!(mock.query_perp_limit_order(take_profit_order.order_id).is_none());
+    assert!(is_keeper_fee_refunded(&res));
+
+    set_atom_price(&mut mock, "1.6");
+    assert!(mock.fill_perp_limit_orders(&keeper, "uatom").is_err());
+}
+
+#[test]
+fn closing_position_keeps_entry_limit_orders() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    place_order(&mut mock, &user, &account_id, "1200", "0.9", None);
+    set_atom_price(&mut mock, "0.85");
+    mock.fill_perp_limit_orders(&keeper, "uatom").unwrap();
+
+    // Entry order resting when the position gets closed
+    let entry_order = place_order(&mut mock, &user, &account_id, "1200", "0.5", None);
+
+    let res = mock
+        .update_credit_account(
+            &account_id,
+            &user,
+            vec![ClosePerpPosition {
+                denom: "uatom".to_string(),
+            }],
+            &[],
+        )
+        .unwrap();
+
+    // The order doesn't depend on the position, it stays in the book with its keeper fee
+    assert!(mock.query_perp_position(&account_id, "uatom").position.is_none());
+    assert_eq!(mock.query_perp_limit_order(entry_order.order_id), Some(entry_order.clone()));
+    assert!(!is_keeper_fee_refunded(&res));
+
+    // And opens a new position once its limit price is met
+    set_atom_price(&mut mock, "0.45");
+    mock.fill_perp_limit_orders(&keeper, "uatom").unwrap();
+
+    let position = mock.query_perp_position(&account_id, "uatom").position.unwrap();
+    assert_eq!(position.size, entry_order.size);
+    assert!(mock.query_perp_limit_order(entry_order.order_id).is_none());
+}
@@ -0,0 +1,258 @@
+use cosmwasm_std::{coin, Addr, Decimal, Int128, Uint128};
+use cw_multi_test::Executor;
+use mars_owner::OwnerError::NotOwner;
+use mars_perps::error::ContractError as PerpsContractError;
+use mars_types::{
+    address_provider::MarsAddressType,
+    credit_manager::Action::{DepositToPerpVault, UnlockFromPerpVault, WithdrawFromPerpVault},
+    oracle::ActionKind,
+    params::{AssetParamsUnchecked, AssetParamsUpdate},
+    perps,
+};
+
+use super::helpers::{self, coin_info, uatom_info, MockEnv};
+
+fn setup() -> (MockEnv, String, Addr, String) {
+    let atom_depositor = Addr::unchecked("atom_depositor");
+    let atom_coin_deposited = uatom_info().to_coin(1000);
+
+    let (mock, atom_account_id, usdc_account_id) = helpers::setup_perps(
+        MockEnv::new().set_params(&[uatom_info(), coin_info("uusdc")]),
+        &atom_depositor,
+        vec![atom_coin_deposited.clone()],
+        vec![atom_coin_deposited],
+        1000,
+    );
+
+    (mock, usdc_account_id, atom_depositor, atom_account_id)
+}
+
+fn set_perps_vault_haircut(mock: &mut MockEnv, denom: &str, haircut: Decimal) {
+    let params = mock.query_asset_params(denom);
+    mock.update_asset_params(AssetParamsUpdate::AddOrUpdate {
+        params: AssetParamsUnchecked {
+            perps_vault_haircut: Some(haircut),
+            ..params.into()
+        },
+    });
+}
+
+#[test]
+fn cannot_deposit_collateral_without_haircut() {
+    let (mut mock, _, atom_depositor, atom_account_id) = setup();
+
+    let res = mock.update_credit_account(
+        &atom_account_id,
+        &atom_depositor,
+        vec![DepositToPerpVault {
+            coin: uatom_info().to_action_coin(1000),
+            max_receivable_shares: None,
+        }],
+        &[],
+    );
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        PerpsContractError::VaultCollateralNotWhitelisted {
+            denom: "uatom".to_string(),
+        }
+    );
+}
+
+#[test]
+fn collateral_deposit_is_valued_with_haircut() {
+    let (mut mock, usdc_account_id, atom_depositor, atom_account_id) = setup();
+    set_perps_vault_haircut(&mut mock, "uatom", Decimal::percent(20));
+
+    mock.update_credit_account(
+        &atom_account_id,
+        &atom_depositor,
+        vec![DepositToPerpVault {
+            coin: uatom_info().to_action_coin(1000),
+            max_receivable_shares: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // 1000 uatom at price 1 with a 20% haircut is worth 3200 uusdc at price 0.25
+    let vault = mock.query_perp_vault(None).unwrap();
+    assert_eq!(vault.collaterals, vec![coin(1000, "uatom")]);
+    assert_eq!(vault.total_collateral_value, Uint128::new(3200));
+    assert_eq!(vault.total_withdrawal_balance, Uint128::new(1000));
+    assert_eq!(vault.total_shares, Uint128::new(4_200_000_000));
+
+    let usdc_position = mock.query_perp_vault_position(&usdc_account_id).unwrap();
+    assert_eq!(usdc_position.deposit.shares, Uint128::new(1_000_000_000));
+    assert_eq!(usdc_position.deposit.amount, Uint128::new(1000));
+    let atom_position = mock.query_perp_vault_position(&atom_account_id).unwrap();
+    assert_eq!(atom_position.deposit.shares, Uint128::new(3_200_000_000));
+    assert_eq!(atom_position.deposit.amount, Uint128::new(3200));
+
+    let positions = mock.query_positions(&atom_account_id);
+    assert!(positions.deposits.is_empty());
+}
+
+#[test]
+fn collateral_is_withdrawn_in_kind() {
+    let (mut mock, _, atom_depositor, atom_account_id) = setup();
+    set_perps_vault_haircut(&mut mock, "uatom", Decimal::percent(20));
+
+    mock.update_credit_account(
+        &atom_account_id,
+        &atom_depositor,
+        vec![
+            DepositToPerpVault {
+                coin: uatom_info().to_action_coin(1000),
+                max_receivable_shares: None,
+            },
+            UnlockFromPerpVault {
+                shares: Uint128::new(3_200_000_000),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+
+    let perp_config = mock.query_perp_config();
+    mock.set_block_time(mock.query_block_time() + perp_config.cooldown_period + 1);
+
+    mock.update_credit_account(
+        &atom_account_id,
+        &atom_depositor,
+        vec![WithdrawFromPerpVault {
+            min_receive: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // 32/42 of both the base denom liquidity and the collateral, rounded down
+    let positions = mock.query_positions(&atom_account_id);
+    assert_eq!(positions.deposits.len(), 2);
+    assert_eq!(positions.deposits[0], coin(761, "uatom"));
+    assert_eq!(positions.deposits[1], coin(761, "uusdc"));
+
+    let vault = mock.query_perp_vault(None).unwrap();
+    assert_eq!(vault.collaterals, vec![coin(239, "uatom")]);
+    assert_eq!(vault.total_shares, Uint128::new(1_000_000_000));
+    assert_eq!(mock.query_balance(mock.perps.address(), "uatom").amount, Uint128::new(239));
+}
+
+#[test]
+fn collateral_counts_as_liquidity_and_can_be_swapped_to_base_denom() {
+    let (mut mock, _, atom_depositor, atom_account_id) = setup();
+    set_perps_vault_haircut(&mut mock, "uatom", Decimal::percent(80));
+
+    mock.update_credit_account(
+        &atom_account_id,
+        &atom_depositor,
+        vec![DepositToPerpVault {
+            coin: uatom_info().to_action_coin(1000),
+            max_receivable_shares: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // 1000 uatom at price 1 with an 80% haircut is worth 800 uusdc at price 0.25
+    let vault = mock.query_perp_vault(None).unwrap();
+    assert_eq!(vault.total_collateral_value, Uint128::new(800));
+    assert_eq!(vault.total_liquidity, Uint128::new(1800));
+
+    let res = mock.swap_perp_vault_collateral(&atom_depositor, "uatom", Uint128::new(500));
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, PerpsContractError::Owner(NotOwner {}));
+
+    let owner = Addr::unchecked("owner");
+    let res = mock.swap_perp_vault_collateral(&owner, "uatom", Uint128::new(1001));
+    let err: PerpsContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        PerpsContractError::InsufficientVaultCollateral {
+            denom: "uatom".to_string(),
+            held: Uint128::new(1000),
+            requested: Uint128::new(1001),
+        }
+    );
+
+    // The mock swapper returns the same amount of the base denom
+    let swapper = mock.query_address_provider(MarsAddressType::Swapper);
+    mock.fund_addr(&swapper, vec![coin(500, "uusdc")]);
+    mock.swap_perp_vault_collateral(&owner, "uatom", Uint128::new(500)).unwrap();
+
+    let vault = mock.query_perp_vault(None).unwrap();
+    assert_eq!(vault.collaterals, vec![coin(500, "uatom")]);
+    assert_eq!(vault.total_balance, Int128::new(1500));
+    assert_eq!(vault.total_collateral_value, Uint128::new(400));
+    assert_eq!(vault.total_liquidity, Uint128::new(1900));
+    assert_eq!(mock.query_balance(mock.perps.address(), "uatom").amount, Uint128::new(500));
+    assert_eq!(mock.query_balance(mock.perps.address(), "uusdc").amount, Uint128::new(1500));
+}
+
+#[test]
+fn unavailable_collateral_price_does_not_block_withdrawals() {
+    let (mut mock, _, atom_depositor, atom_account_id) = setup();
+    set_perps_vault_haircut(&mut mock, "uatom", Decimal::percent(20));
+
+    mock.update_credit_account(
+        &atom_account_id,
+        &atom_depositor,
+        vec![DepositToPerpVault {
+            coin: uatom_info().to_action_coin(1000),
+            max_receivable_shares: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let lp = Addr::unchecked("lp");
+    mock.fund_addr(&lp, vec![coin(1000, "uusdc")]);
+    let perps_addr = mock.perps.address().clone();
+    mock.app
+        .execute_contract(
+            lp.clone(),
+            perps_addr.clone(),
+            &perps::ExecuteMsg::Deposit {
+                account_id: None,
+                max_shares_receivable: None,
+            },
+            &[coin(1000, "uusdc")],
+        )
+        .unwrap();
+    mock.app
+        .execute_contract(
+            lp.clone(),
+            perps_addr.clone(),
+            &perps::ExecuteMsg::Unlock {
+                account_id: None,
+                shares: Uint128::new(1_000_000_000),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let perp_config = mock.query_perp_config();
+    mock.set_block_time(mock.query_block_time() + perp_config.cooldown_period + 1);
+
+    // Collateral without a price is valued at zero
+    mock.remove_price("uatom", ActionKind::Default);
+    let vault = mock.query_perp_vault(None).unwrap();
+    assert_eq!(vault.total_collateral_value, Uint128::zero());
+
+    mock.app
+        .execute_contract(
+            lp.clone(),
+            perps_addr,
+            &perps::ExecuteMsg::Withdraw {
+                account_id: None,
+                min_receive: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // 10/52 of both the base denom liquidity and the collateral, rounded down
+    assert_eq!(mock.query_balance(&lp, "uusdc").amount, Uint128::new(384));
+    assert_eq!(mock.query_balance(&lp, "uatom").amount, Uint128::new(192));
+}
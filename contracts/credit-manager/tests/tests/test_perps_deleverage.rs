@@ -478,6 +478,7 @@ fn open_perp(mock: &mut MockEnv, user: &Addr, acc_id: &str, denom: &str, size: I
             order_size: size,
             reduce_only: None,
             order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None,
         }],
         &[],
     )
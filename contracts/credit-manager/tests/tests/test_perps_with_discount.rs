@@ -61,6 +61,7 @@ fn open_perp(
                 order_size: size,
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             },
         ],
         &[Coin::new(usdc_to_deposit, "uusdc")],
@@ -429,6 +430,7 @@ fn execute_perp_order_with_tier_validation(
                 order_size: size,
                 reduce_only: None,
                 order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None,
             }],
             &[],
         )
@@ -787,6 +789,7 @@ fn test_perp_fee_discount_comparison_two_users() {
                     order_size: Int128::new(size),
                     reduce_only: None,
                     order_type: Some(ExecutePerpOrderType::Default),
+                    limit_price: None,
                 }],
                 &[],
             )
@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_swapper_mock::contract::MOCK_SWAP_RESULT;
+use mars_types::{
+    credit_manager::{
+        Action::{self, Borrow, CreateTriggerOrder, Deposit, Lend, Reclaim, Repay, SwapExactIn},
+        ActionAmount, ActionCoin, Comparison,
+        Condition::OraclePrice,
+        CreateTriggerOrderType,
+    },
+    oracle::ActionKind,
+};
+
+use super::helpers::{self, assert_err, coin_info, get_coin, uatom_info, uosmo_info, MockEnv};
+
+fn keeper_fee() -> Coin {
+    coin_info("uusdc").to_coin(1000000)
+}
+
+fn setup() -> (MockEnv, Addr, String) {
+    let user = Addr::unchecked("user");
+
+    let (mock, account_id) = helpers::setup(
+        MockEnv::new().max_slippage(Decimal::percent(5)).set_params(&[
+            uatom_info(),
+            uosmo_info(),
+            coin_info("uusdc"),
+        ]),
+        &user,
+        vec![uatom_info().to_coin(10000), uosmo_info().to_coin(10000), keeper_fee()],
+    );
+
+    (mock, user, account_id)
+}
+
+fn trigger_order(actions: Vec<Action>) -> Action {
+    CreateTriggerOrder {
+        order_type: Some(CreateTriggerOrderType::Default),
+        actions,
+        conditions: vec![OraclePrice {
+            denom: "uatom".to_string(),
+            price: Decimal::from_str("2").unwrap(),
+            comparison: Comparison::LessThan,
+        }],
+        keeper_fee: keeper_fee(),
+        expires_at: None,
+    }
+}
+
+#[test]
+fn swap_in_trigger_order_uses_oracle_min_receive() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(keeper_fee()),
+            Deposit(uatom_info().to_coin(1000)),
+            trigger_order(vec![SwapExactIn {
+                coin_in: ActionCoin {
+                    denom: "uatom".to_string(),
+                    amount: ActionAmount::AccountBalance,
+                },
+                denom_out: "uosmo".to_string(),
+                min_receive: Uint128::zero(),
+                route: None,
+            }]),
+        ],
+        &[keeper_fee(), uatom_info().to_coin(1000)],
+    )
+    .unwrap();
+
+    // 1000 uatom at price 1 is worth 4000 uosmo at price 0.25. With 5% max slippage the swap has
+    // to return at least 3800 uosmo, more than the mocked swap result.
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert!(res.is_err());
+    assert_eq!(mock.query_trigger_orders_for_account(account_id.clone(), None, None).data.len(), 1);
+
+    // At uatom price 0.3 the swap has to return at least 1140 uosmo
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Default,
+        denom: "uatom".to_string(),
+        price: Decimal::from_str("0.3").unwrap(),
+    });
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(get_coin("uosmo", &positions.deposits).amount, MOCK_SWAP_RESULT);
+    assert!(positions.deposits.iter().all(|c| c.denom != "uatom"));
+}
+
+#[test]
+fn swap_in_trigger_order_min_receive_uses_balance_at_execution() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    // The whole uatom balance is lent, so the account holds no uatom when the order is created
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(keeper_fee()),
+            Deposit(uatom_info().to_coin(1000)),
+            Lend(uatom_info().to_action_coin(1000)),
+            trigger_order(vec![
+                Reclaim(uatom_info().to_action_coin_full_balance()),
+                SwapExactIn {
+                    coin_in: ActionCoin {
+                        denom: "uatom".to_string(),
+                        amount: ActionAmount::AccountBalance,
+                    },
+                    denom_out: "uosmo".to_string(),
+                    min_receive: Uint128::zero(),
+                    route: None,
+                },
+            ]),
+        ],
+        &[keeper_fee(), uatom_info().to_coin(1000)],
+    )
+    .unwrap();
+
+    // The swap is guarded by the reclaimed 1000 uatom, which have to return at least 3800 uosmo
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert!(res.is_err());
+    assert_eq!(mock.query_trigger_orders_for_account(account_id.clone(), None, None).data.len(), 1);
+
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Default,
+        denom: "uatom".to_string(),
+        price: Decimal::from_str("0.3").unwrap(),
+    });
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(get_coin("uosmo", &positions.deposits).amount, MOCK_SWAP_RESULT);
+    assert_eq!(positions.lends.len(), 0);
+}
+
+#[test]
+fn reclaim_and_repay_in_trigger_order() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(keeper_fee()),
+            Deposit(uosmo_info().to_coin(300)),
+            Borrow(uosmo_info().to_coin(50)),
+            Lend(uosmo_info().to_action_coin(300)),
+            trigger_order(vec![
+                Reclaim(uosmo_info().to_action_coin_full_balance()),
+                Repay {
+                    recipient_account_id: None,
+                    coin: uosmo_info().to_action_coin_full_balance(),
+                },
+            ]),
+        ],
+        &[keeper_fee(), uosmo_info().to_coin(300)],
+    )
+    .unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.debts.len(), 1);
+    assert_eq!(positions.lends.len(), 1);
+
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.debts.len(), 0);
+    assert_eq!(positions.lends.len(), 0);
+    assert_eq!(mock.query_balance(&keeper, "uusdc"), keeper_fee());
+}
+
+#[test]
+fn repay_for_recipient_not_allowed_in_trigger_order() {
+    let (mut mock, user, account_id) = setup();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(keeper_fee()),
+            trigger_order(vec![Repay {
+                recipient_account_id: Some("2".to_string()),
+                coin: uosmo_info().to_action_coin_full_balance(),
+            }]),
+        ],
+        &[keeper_fee()],
+    );
+    assert_err(res, ContractError::IllegalTriggerAction);
+}
@@ -0,0 +1,278 @@
+use std::str::FromStr;
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{Addr, Coin, Decimal};
+use cw_multi_test::AppResponse;
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_types::{
+    credit_manager::{
+        Action::{CreateTriggerOrder, DeleteTriggerOrder, Deposit, Lend},
+        ActionAmount, ActionCoin, Comparison,
+        Condition::{self, OraclePrice, TrailingOraclePrice},
+        CreateTriggerOrderType, TrailingMarkResponse,
+    },
+    oracle::ActionKind,
+};
+use test_case::test_case;
+
+use super::helpers::{self, assert_err, coin_info, uosmo_info, MockEnv};
+
+fn setup() -> (MockEnv, Addr, String) {
+    let user = Addr::unchecked("user");
+    let usdc_info = coin_info("uusdc");
+
+    let (mut mock, account_id) = helpers::setup(
+        MockEnv::new().set_params(&[uosmo_info(), usdc_info.clone()]),
+        &user,
+        vec![usdc_info.to_coin(10000000000000)],
+    );
+
+    set_osmo_price(&mut mock, "10");
+
+    (mock, user, account_id)
+}
+
+fn set_osmo_price(mock: &mut MockEnv, price: &str) {
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Default,
+        denom: "uosmo".to_string(),
+        price: Decimal::from_str(price).unwrap(),
+    });
+}
+
+fn keeper_fee() -> Coin {
+    coin_info("uusdc").to_coin(1000000)
+}
+
+fn create_trailing_order(
+    mock: &mut MockEnv,
+    user: &Addr,
+    account_id: &str,
+    conditions: Vec<Condition>,
+) -> AnyResult<AppResponse> {
+    let lend_amount = coin_info("uusdc").to_coin(1000);
+    mock.update_credit_account(
+        account_id,
+        user,
+        vec![
+            Deposit(keeper_fee()),
+            Deposit(lend_amount.clone()),
+            CreateTriggerOrder {
+                order_type: Some(CreateTriggerOrderType::Default),
+                actions: vec![Lend(ActionCoin {
+                    denom: lend_amount.denom.clone(),
+                    amount: ActionAmount::AccountBalance,
+                })],
+                conditions,
+                keeper_fee: keeper_fee(),
+                expires_at: None,
+            },
+        ],
+        &[keeper_fee(), lend_amount],
+    )
+}
+
+#[test]
+fn trailing_stop_follows_highest_price() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    create_trailing_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![TrailingOraclePrice {
+            denom: "uosmo".to_string(),
+            trailing_pct: Decimal::percent(10),
+            comparison: Comparison::LessThan,
+        }],
+    )
+    .unwrap();
+
+    // The mark starts at the oracle price on creation
+    let mark = mock.query_trailing_mark(&account_id, "1").unwrap();
+    assert_eq!(
+        mark,
+        TrailingMarkResponse {
+            account_id: account_id.clone(),
+            order_id: "1".to_string(),
+            denom: "uosmo".to_string(),
+            mark: Decimal::from_str("10").unwrap(),
+            trigger_price: Decimal::from_str("9").unwrap(),
+        }
+    );
+
+    // Price drops, but not below the trigger price. The mark is not lowered.
+    set_osmo_price(&mut mock, "9.5");
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert_err(res, ContractError::IllegalExecuteTriggerOrder);
+    mock.refresh_trailing_mark(&keeper, &account_id, "1").unwrap();
+    let mark = mock.query_trailing_mark(&account_id, "1").unwrap();
+    assert_eq!(mark.mark, Decimal::from_str("10").unwrap());
+
+    // Price rises, the mark follows after refreshing
+    set_osmo_price(&mut mock, "12");
+    mock.refresh_trailing_mark(&keeper, &account_id, "1").unwrap();
+    let mark = mock.query_trailing_mark(&account_id, "1").unwrap();
+    assert_eq!(mark.mark, Decimal::from_str("12").unwrap());
+    assert_eq!(mark.trigger_price, Decimal::from_str("10.8").unwrap());
+
+    // Price retraces, but less than 10% from the mark
+    set_osmo_price(&mut mock, "11");
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert_err(res, ContractError::IllegalExecuteTriggerOrder);
+
+    // Price retraces more than 10% from the mark
+    set_osmo_price(&mut mock, "10.7");
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+
+    // The order and its mark are removed
+    assert!(mock.query_trigger_orders_for_account(account_id.clone(), None, None).data.is_empty());
+    assert!(mock.query_trailing_mark(&account_id, "1").is_none());
+    assert_eq!(mock.query_balance(&keeper, "uusdc"), keeper_fee());
+}
+
+#[test]
+fn trailing_entry_follows_lowest_price() {
+    let (mut mock, user, account_id) = setup();
+    let keeper = Addr::unchecked("keeper");
+
+    create_trailing_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![TrailingOraclePrice {
+            denom: "uosmo".to_string(),
+            trailing_pct: Decimal::percent(5),
+            comparison: Comparison::GreaterThan,
+        }],
+    )
+    .unwrap();
+
+    // Price rises, the mark is not raised
+    set_osmo_price(&mut mock, "10.4");
+    mock.refresh_trailing_mark(&keeper, &account_id, "1").unwrap();
+    let mark = mock.query_trailing_mark(&account_id, "1").unwrap();
+    assert_eq!(mark.mark, Decimal::from_str("10").unwrap());
+    assert_eq!(mark.trigger_price, Decimal::from_str("10.5").unwrap());
+
+    // Price drops, the mark follows after refreshing
+    set_osmo_price(&mut mock, "8");
+    mock.refresh_trailing_mark(&keeper, &account_id, "1").unwrap();
+    let marks = mock.query_trailing_marks_for_account(account_id.clone(), None, None);
+    assert_eq!(marks.data.len(), 1);
+    assert_eq!(marks.data[0].mark, Decimal::from_str("8").unwrap());
+    assert_eq!(marks.data[0].trigger_price, Decimal::from_str("8.4").unwrap());
+
+    set_osmo_price(&mut mock, "8.3");
+    let res = mock.execute_trigger_order(&keeper, &account_id, "1");
+    assert_err(res, ContractError::IllegalExecuteTriggerOrder);
+
+    set_osmo_price(&mut mock, "8.5");
+    mock.execute_trigger_order(&keeper, &account_id, "1").unwrap();
+    assert!(mock.query_trailing_mark(&account_id, "1").is_none());
+}
+
+#[test]
+fn deleting_order_removes_mark() {
+    let (mut mock, user, account_id) = setup();
+
+    create_trailing_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![TrailingOraclePrice {
+            denom: "uosmo".to_string(),
+            trailing_pct: Decimal::percent(10),
+            comparison: Comparison::LessThan,
+        }],
+    )
+    .unwrap();
+    assert!(mock.query_trailing_mark(&account_id, "1").is_some());
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![DeleteTriggerOrder {
+            trigger_order_id: "1".to_string(),
+        }],
+        &[],
+    )
+    .unwrap();
+    assert!(mock.query_trailing_mark(&account_id, "1").is_none());
+    assert!(mock.query_trailing_marks_for_account(account_id, None, None).data.is_empty());
+}
+
+#[test]
+fn cannot_refresh_order_without_trailing_condition() {
+    let (mut mock, user, account_id) = setup();
+
+    create_trailing_order(
+        &mut mock,
+        &user,
+        &account_id,
+        vec![OraclePrice {
+            denom: "uosmo".to_string(),
+            price: Decimal::from_str("12").unwrap(),
+            comparison: Comparison::GreaterThan,
+        }],
+    )
+    .unwrap();
+    assert!(mock.query_trailing_mark(&account_id, "1").is_none());
+
+    let res = mock.refresh_trailing_mark(&Addr::unchecked("keeper"), &account_id, "1");
+    assert_err(
+        res,
+        ContractError::NoTrailingCondition {
+            order_id: "1".to_string(),
+            account_id,
+        },
+    );
+}
+
+#[test_case(
+    vec![TrailingOraclePrice {
+        denom: "uosmo".to_string(),
+        trailing_pct: Decimal::zero(),
+        comparison: Comparison::LessThan,
+    }],
+    "TrailingOraclePrice trailing_pct must be between 0 and 1";
+    "zero trailing pct"
+)]
+#[test_case(
+    vec![TrailingOraclePrice {
+        denom: "uosmo".to_string(),
+        trailing_pct: Decimal::one(),
+        comparison: Comparison::LessThan,
+    }],
+    "TrailingOraclePrice trailing_pct must be between 0 and 1";
+    "trailing pct of 100%"
+)]
+#[test_case(
+    vec![
+        TrailingOraclePrice {
+            denom: "uosmo".to_string(),
+            trailing_pct: Decimal::percent(10),
+            comparison: Comparison::LessThan,
+        },
+        TrailingOraclePrice {
+            denom: "uosmo".to_string(),
+            trailing_pct: Decimal::percent(10),
+            comparison: Comparison::GreaterThan,
+        },
+    ],
+    "Trigger order can have at most 1 TrailingOraclePrice condition";
+    "multiple trailing conditions"
+)]
+fn invalid_trailing_conditions(conditions: Vec<Condition>, reason: &str) {
+    let (mut mock, user, account_id) = setup();
+
+    let res = create_trailing_order(&mut mock, &user, &account_id, conditions);
+    assert_err(
+        res,
+        ContractError::InvalidOrderConditions {
+            reason: reason.to_string(),
+        },
+    );
+}
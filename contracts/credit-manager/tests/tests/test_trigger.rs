@@ -59,6 +59,7 @@ fn error_when_exceeding_max_trigger_orders() {
                     order_size: Int128::from_str("10").unwrap(),
                     reduce_only: None,
                     order_type: Some(ExecutePerpOrderType::Default),
+                    limit_price: None,
                 }],
                 conditions: vec![OraclePrice {
                     denom: "perp1".to_string(),
@@ -66,6 +67,7 @@ fn error_when_exceeding_max_trigger_orders() {
                     comparison: Comparison::GreaterThan,
                 }],
                 keeper_fee: keeper_fee.clone(),
+                expires_at: None,
             },
         ],
         &[keeper_fee.clone()],
@@ -84,6 +86,7 @@ fn error_when_exceeding_max_trigger_orders() {
                     order_size: Int128::from_str("10").unwrap(),
                     reduce_only: None,
                     order_type: Some(ExecutePerpOrderType::Default),
+                    limit_price: None,
                 }],
                 conditions: vec![OraclePrice {
                     denom: "perp1".to_string(),
@@ -91,6 +94,7 @@ fn error_when_exceeding_max_trigger_orders() {
                     comparison: Comparison::GreaterThan,
                 }],
                 keeper_fee: keeper_fee.clone(),
+                expires_at: None,
             },
         ],
         &[keeper_fee.clone()],
@@ -137,6 +141,7 @@ fn lend_action_whitelisted_in_trigger_orders() {
                         order_size: Int128::from_str("10").unwrap(),
                         reduce_only: None,
                         order_type: Some(ExecutePerpOrderType::Default),
+                        limit_price: None,
                     },
                     Lend(ActionCoin {
                         denom: keeper_fee.denom.clone(),
@@ -149,6 +154,7 @@ fn lend_action_whitelisted_in_trigger_orders() {
                     comparison: Comparison::GreaterThan,
                 }],
                 keeper_fee: keeper_fee.clone(),
+                expires_at: None,
             },
         ],
         &[keeper_fee.clone()],
@@ -203,6 +209,7 @@ fn close_perp_position_action_whitelisted_in_trigger_orders() {
                     comparison: Comparison::GreaterThan,
                 }],
                 keeper_fee: keeper_fee.clone(),
+                expires_at: None,
             },
         ],
         &[keeper_fee.clone()],
@@ -246,6 +253,7 @@ fn query_all_trigger_orders() {
                     order_size,
                     reduce_only: None,
                     order_type: Some(ExecutePerpOrderType::Default),
+                    limit_price: None,
                 }],
                 conditions: vec![OraclePrice {
                     denom: "perp1".to_string(),
@@ -253,6 +261,7 @@ fn query_all_trigger_orders() {
                     comparison: Comparison::GreaterThan,
                 }],
                 keeper_fee: keeper_fee.clone(),
+                expires_at: None,
             },
         });
     }
@@ -269,6 +278,7 @@ fn query_all_trigger_orders() {
                     actions: order.order.actions.clone(),
                     conditions: order.order.conditions.clone(),
                     keeper_fee: order.order.keeper_fee.clone(),
+                    expires_at: order.order.expires_at,
                 },
             ],
             &[order.order.keeper_fee.clone()],
@@ -317,6 +327,7 @@ fn delete_trigger_order() {
                     order_size: Int128::from_str("-10").unwrap(),
                     reduce_only: None,
                     order_type: Some(ExecutePerpOrderType::Default),
+                    limit_price: None,
                 }],
                 conditions: vec![OraclePrice {
                     denom: "perp1".to_string(),
@@ -324,6 +335,7 @@ fn delete_trigger_order() {
                     comparison: Comparison::GreaterThan,
                 }],
                 keeper_fee: keeper_fee.clone(),
+                expires_at: None,
             },
         ],
         &[keeper_fee.clone()],
@@ -341,7 +353,8 @@ fn delete_trigger_order() {
             denom: "perp1".to_string(),
             order_size: Int128::from_str("-10").unwrap(),
             reduce_only: None,
-            order_type: Some(ExecutePerpOrderType::Default)
+            order_type: Some(ExecutePerpOrderType::Default),
+            limit_price: None
         }]
     );
     assert_eq!(
@@ -388,7 +401,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-10").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![OraclePrice {
                 denom: uatom_info().denom.to_string(),
@@ -396,6 +410,7 @@ fn delete_trigger_order() {
                 comparison: Comparison::GreaterThan,
             }],
             keeper_fee: coin_info("uusdc").to_coin(1),
+            expires_at: None,
         }
     ],
     None,
@@ -415,7 +430,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-10").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![OraclePrice {
                 denom: uatom_info().denom.to_string(),
@@ -423,6 +439,7 @@ fn delete_trigger_order() {
                 comparison: Comparison::GreaterThan,
             }],
             keeper_fee: coin_info("untrn").to_coin(100000000),
+            expires_at: None,
         }
     ],
     None,
@@ -443,7 +460,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-10").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![OraclePrice {
                 denom: uatom_info().denom.to_string(),
@@ -451,6 +469,7 @@ fn delete_trigger_order() {
                 comparison: Comparison::GreaterThan,
             }],
             keeper_fee: coin_info("uusdc").to_coin(100000000),
+            expires_at: None,
         }
     ],
     None,
@@ -474,7 +493,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-10").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![OraclePrice {
                 denom: uatom_info().denom.to_string(),
@@ -482,6 +502,7 @@ fn delete_trigger_order() {
                 comparison: Comparison::GreaterThan,
             }],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     Some(12347),
@@ -500,7 +521,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-10").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![OraclePrice {
                 denom: uatom_info().denom.to_string(),
@@ -508,6 +530,7 @@ fn delete_trigger_order() {
                 comparison: Comparison::GreaterThan,
             }],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     Some(1),
@@ -526,7 +549,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-1").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![OraclePrice {
                 denom: uatom_info().denom.to_string(),
@@ -534,6 +558,7 @@ fn delete_trigger_order() {
                 comparison: Comparison::LessThan,
             }],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     Some(1),
@@ -552,7 +577,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-1").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![
                 OraclePrice {
@@ -566,6 +592,7 @@ fn delete_trigger_order() {
                 }
             ],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     Some(1),
@@ -583,6 +610,7 @@ fn delete_trigger_order() {
             actions: vec![Deposit(coin_info("uusdc").to_coin(100000000))],
             conditions: vec![],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     None,
@@ -604,6 +632,7 @@ fn delete_trigger_order() {
             }],
             conditions: vec![],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     None,
@@ -622,7 +651,8 @@ fn delete_trigger_order() {
                 denom: uatom_info().denom.to_string(),
                 order_size: Int128::from_str("-1").unwrap(),
                 reduce_only: None,
-                order_type: Some(ExecutePerpOrderType::Default)
+                order_type: Some(ExecutePerpOrderType::Default),
+                limit_price: None
             }],
             conditions: vec![
                 OraclePrice {
@@ -636,6 +666,7 @@ fn delete_trigger_order() {
                 }
             ],
             keeper_fee: coin_info("uusdc").to_coin(1000000),
+            expires_at: None,
         },
     ],
     Some(1),
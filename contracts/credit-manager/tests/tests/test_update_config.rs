@@ -40,6 +40,7 @@ fn only_owner_can_update_config() {
             perps: None,
             keeper_fee_config: None,
             perps_liquidation_bonus_ratio: None,
+            perps_liquidation_target_health_factor: None,
             swap_fee: None,
             fee_tier_config: None,
             governance_address: None,
@@ -99,6 +100,7 @@ fn update_config_works_with_full_config() {
     let new_unlocking_max = Uint128::new(321);
     let new_max_slippage = Decimal::percent(12);
     let new_perps_lb_ratio = Decimal::percent(39);
+    let new_perps_target_hf = Decimal::percent(110);
     let new_swapper = SwapperBase::new("new_swapper".to_string());
     let new_duality_swapper = SwapperBase::new("new_duality_swapper".to_string());
     let new_health_contract = HealthContractUnchecked::new("new_health_contract".to_string());
@@ -107,6 +109,7 @@ fn update_config_works_with_full_config() {
     let new_perps_contract = PerpsUnchecked::new("new_perps_contract".to_string());
     let keeper_fee_config = KeeperFeeConfig {
         min_fee: coin(100000, "uusdc"),
+        pruner_fee_pct: Decimal::percent(20),
     };
     let new_swap_fee = Decimal::percent(1);
 
@@ -129,6 +132,7 @@ fn update_config_works_with_full_config() {
             perps: Some(new_perps_contract.clone()),
             keeper_fee_config: Some(keeper_fee_config.clone()),
             perps_liquidation_bonus_ratio: Some(new_perps_lb_ratio),
+            perps_liquidation_target_health_factor: Some(new_perps_target_hf),
             swap_fee: Some(new_swap_fee),
             fee_tier_config: None,
             governance_address: None,
@@ -167,6 +171,9 @@ fn update_config_works_with_full_config() {
         original_config.perps_liquidation_bonus_ratio
     );
 
+    assert_eq!(new_config.perps_liquidation_target_health_factor, Some(new_perps_target_hf));
+    assert_eq!(original_config.perps_liquidation_target_health_factor, None);
+
     assert_eq!(&new_config.swapper, new_swapper.address());
     assert_ne!(new_config.swapper, original_config.swapper);
 
@@ -0,0 +1,102 @@
+use cosmwasm_std::{coin, coins, Addr};
+use mars_credit_manager::error::ContractError;
+use mars_types::credit_manager::Action::{
+    Borrow, Deposit, DepositToPerpVault, UnlockFromPerpVault,
+};
+
+use super::helpers::{assert_err, coin_info, uosmo_info, AccountToFund, MockEnv};
+
+#[test]
+fn no_debt_on_account() {
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().build().unwrap();
+
+    // Credit account doesn't exist
+    let res = mock.write_off_bad_debt(&user, "123", "uosmo");
+    assert_err(res, ContractError::NoDebt);
+
+    // Exists but no debt
+    let account_id = mock.create_credit_account(&user).unwrap();
+    let res = mock.write_off_bad_debt(&user, &account_id, "uosmo");
+    assert_err(res, ContractError::NoDebt);
+}
+
+#[test]
+fn cannot_write_off_debt_of_account_with_collateral() {
+    let coin_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[coin_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(300, coin_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(coin(300, coin_info.denom.clone())),
+            Borrow(coin(42, coin_info.denom.clone())),
+        ],
+        &[coin(300, coin_info.denom.clone())],
+    )
+    .unwrap();
+
+    let res = mock.write_off_bad_debt(&Addr::unchecked("keeper"), &account_id, &coin_info.denom);
+    assert_err(res, ContractError::CannotWriteOffDebtWithCollateral {});
+}
+
+#[test]
+fn cannot_write_off_debt_of_account_with_perp_vault_shares() {
+    let coin_info = coin_info("uusdc");
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[coin_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(300, coin_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(coin_info.to_coin(300)),
+            DepositToPerpVault {
+                coin: coin_info.to_action_coin(300),
+                max_receivable_shares: None,
+            },
+        ],
+        &[coin_info.to_coin(300)],
+    )
+    .unwrap();
+
+    // Everything the account holds sits in the perp vault
+    let position = mock.query_positions(&account_id);
+    assert!(position.deposits.is_empty());
+
+    let res = mock.write_off_bad_debt(&Addr::unchecked("keeper"), &account_id, &coin_info.denom);
+    assert_err(res, ContractError::CannotWriteOffDebtWithCollateral {});
+
+    // Shares being unlocked are still withdrawable by the account
+    let shares = mock.query_perp_vault_position(&account_id).unwrap().deposit.shares;
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![UnlockFromPerpVault {
+            shares,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let res = mock.write_off_bad_debt(&Addr::unchecked("keeper"), &account_id, &coin_info.denom);
+    assert_err(res, ContractError::CannotWriteOffDebtWithCollateral {});
+}
@@ -20,6 +20,31 @@ pub fn compute_health(
     positions: Positions,
     action: ActionKind,
 ) -> HealthResult<HealthValuesResponse> {
+    let computer = health_computer(deps, q, positions, action)?;
+    Ok(computer.compute_health()?.into())
+}
+
+/// Portion of the account's debts backed by each of its collaterals in isolation mode
+pub fn compute_isolated_debt_portions(
+    deps: Deps,
+    q: HealthQuerier,
+    positions: Positions,
+    action: ActionKind,
+) -> HealthResult<HashMap<String, Decimal>> {
+    if positions.debts.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let computer = health_computer(deps, q, positions, action)?;
+    computer.isolated_debt_portions()
+}
+
+fn health_computer(
+    deps: Deps,
+    q: HealthQuerier,
+    positions: Positions,
+    action: ActionKind,
+) -> HealthResult<HealthComputer> {
     // Get the denoms that need prices + markets
     let deposit_denoms = positions.deposits.iter().map(|d| &d.denom).collect::<Vec<_>>();
     let debt_denoms = positions.debts.iter().map(|d| &d.denom).collect::<Vec<_>>();
@@ -80,16 +105,14 @@ pub fn compute_health(
         Ok(())
     })?;
 
-    let computer = HealthComputer {
+    Ok(HealthComputer {
         kind: positions.account_kind.clone(),
         positions,
         asset_params,
         oracle_prices,
         vaults_data,
         perps_data,
-    };
-
-    Ok(computer.compute_health()?.into())
+    })
 }
 
 pub fn compute_health_state(
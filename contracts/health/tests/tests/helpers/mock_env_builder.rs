@@ -188,6 +188,7 @@ impl MockEnvBuilder {
                         perps: "n/a".to_string(),
                         keeper_fee_config: Default::default(),
                         perps_liquidation_bonus_ratio: Decimal::percent(60),
+                        perps_liquidation_target_health_factor: None,
                     },
                 },
                 &[],
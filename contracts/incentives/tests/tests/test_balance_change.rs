@@ -385,6 +385,8 @@ fn with_zero_previous_balance_and_asset_with_zero_index_accumulates_rewards(
                     share_price: None,
                     total_debt: Uint128::zero(),
                     total_withdrawal_balance: Uint128::zero(),
+                    collaterals: vec![],
+                    total_collateral_value: Uint128::zero(),
                 });
                 deps.querier.set_perp_vault_position(
                     &user_addr,
@@ -365,6 +365,8 @@ fn execute_claim_rewards_perp_vault() {
         share_price: None,
         total_debt: Uint128::zero(),
         total_withdrawal_balance: Uint128::zero(),
+        collaterals: vec![],
+        total_collateral_value: Uint128::zero(),
     });
 
     deps.querier.set_perp_vault_position(
@@ -92,6 +92,7 @@ fn query_price(
     Ok(PriceResponse {
         denom,
         price,
+        fallback_used: false,
     })
 }
 
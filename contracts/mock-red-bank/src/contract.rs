@@ -1,12 +1,12 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    to_json_binary, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
 };
 use mars_types::red_bank;
 
 use crate::{
-    execute::{borrow, deposit, repay, update_market_params, withdraw},
+    execute::{borrow, deposit, repay, update_market_params, withdraw, write_off_bad_debt},
     query::{query_collateral, query_collaterals, query_collaterals_v2, query_debt, query_market},
 };
 
@@ -37,6 +37,10 @@ pub fn execute(
         red_bank::ExecuteMsg::Repay {
             ..
         } => repay(deps, info),
+        red_bank::ExecuteMsg::WriteOffCreditManagerBadDebt {
+            denom,
+            amount,
+        } => write_off_bad_debt(deps, info, denom, amount),
         red_bank::ExecuteMsg::Deposit {
             account_id,
             on_behalf_of: _,
@@ -81,6 +85,9 @@ pub fn query(deps: Deps, _env: Env, msg: red_bank::QueryMsg) -> StdResult<Binary
             start_after,
             limit,
         } => to_json_binary(&query_collaterals_v2(deps, user, account_id, start_after, limit)?),
+        red_bank::QueryMsg::IsolatedDebts {
+            ..
+        } => to_json_binary(&Vec::<Coin>::new()),
         _ => unimplemented!("Query not supported!"),
     }
 }
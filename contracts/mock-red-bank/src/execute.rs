@@ -32,7 +32,11 @@ pub fn update_market_params(
                 indexes_last_updated: env.block.time.seconds(),
                 collateral_total_scaled: Uint128::zero(),
                 debt_total_scaled: Uint128::zero(),
+                term_debt_total: Uint128::zero(),
+                term_interest_per_year: Uint128::zero(),
                 interest_rate_model: params.interest_rate_model.unwrap(),
+                flash_loan_fee_rate: params.flash_loan_fee_rate.unwrap_or_default(),
+                adaptive_rate_state: None,
             };
 
             MARKETS.save(deps.storage, &params.denom, &market)?;
@@ -78,6 +82,19 @@ pub fn repay(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
     Ok(Response::new())
 }
 
+pub fn write_off_bad_debt(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let debt_amount = load_debt_amount(deps.storage, &info.sender, &denom)?;
+
+    DEBT_AMOUNT.save(deps.storage, (info.sender, denom), &debt_amount.checked_sub(amount)?)?;
+
+    Ok(Response::new())
+}
+
 pub fn deposit(
     deps: DepsMut,
     info: MessageInfo,
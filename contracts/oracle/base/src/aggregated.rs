@@ -0,0 +1,105 @@
+use cosmwasm_std::Decimal;
+
+use crate::{ContractError, ContractResult};
+
+/// Validate the parameters of an aggregated price source
+pub fn assert_aggregated(
+    sources_len: usize,
+    min_sources: u8,
+    max_deviation: Decimal,
+) -> ContractResult<()> {
+    if sources_len < 2 {
+        return Err(ContractError::InvalidPriceSource {
+            reason: "aggregated price source must have at least two sources".to_string(),
+        });
+    }
+
+    if min_sources == 0 || min_sources as usize > sources_len {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("min_sources must be between 1 and {sources_len}"),
+        });
+    }
+
+    if max_deviation.is_zero() || max_deviation > Decimal::one() {
+        return Err(ContractError::InvalidPriceSource {
+            reason: "max_deviation must be greater than 0 and less than or equal to 1".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Aggregate the prices of the sources that could be queried into their median.
+///
+/// The spread of the prices (difference between the highest and the lowest price) relative to the
+/// median can't be greater than `max_deviation`, otherwise at least one source is off and we can't
+/// tell which one.
+pub fn aggregate_prices(
+    mut prices: Vec<Decimal>,
+    min_sources: u8,
+    max_deviation: Decimal,
+) -> ContractResult<Decimal> {
+    if prices.len() < min_sources as usize {
+        return Err(ContractError::InvalidPrice {
+            reason: format!(
+                "not enough price sources available: {}, required: {min_sources}",
+                prices.len()
+            ),
+        });
+    }
+
+    prices.sort();
+
+    let mid = prices.len() / 2;
+    let median = if prices.len() % 2 == 0 {
+        (prices[mid - 1] + prices[mid]) / Decimal::from_ratio(2u128, 1u128)
+    } else {
+        prices[mid]
+    };
+
+    let spread = prices[prices.len() - 1] - prices[0];
+    let deviation = spread.checked_div(median)?;
+    if deviation > max_deviation {
+        return Err(ContractError::InvalidPrice {
+            reason: format!(
+                "price deviation between sources is too high: {deviation}, max allowed: {max_deviation}"
+            ),
+        });
+    }
+
+    Ok(median)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_prices() {
+        let prices = vec![Decimal::percent(101), Decimal::percent(100), Decimal::percent(102)];
+        let median = aggregate_prices(prices, 2, Decimal::percent(5)).unwrap();
+        assert_eq!(median, Decimal::percent(101));
+
+        let prices = vec![Decimal::percent(100), Decimal::percent(102)];
+        let median = aggregate_prices(prices, 2, Decimal::percent(5)).unwrap();
+        assert_eq!(median, Decimal::percent(101));
+    }
+
+    #[test]
+    fn not_enough_prices() {
+        let err = aggregate_prices(vec![Decimal::one()], 2, Decimal::percent(5)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPrice {
+                reason: "not enough price sources available: 1, required: 2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn prices_deviate_too_much() {
+        let prices = vec![Decimal::percent(100), Decimal::percent(101), Decimal::percent(120)];
+        let err = aggregate_prices(prices, 2, Decimal::percent(5)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPrice { .. }));
+    }
+}
@@ -2,21 +2,29 @@ use std::{collections::HashMap, marker::PhantomData};
 
 use cosmwasm_std::{
     to_json_binary, Addr, Binary, CustomQuery, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
-    Response, StdError, StdResult,
+    QuerierWrapper, Response, StdError, StdResult, Storage,
 };
 use cw_storage_plus::{Bound, Item, Map};
 use mars_owner::{Owner, OwnerInit::SetInitialOwner, OwnerUpdate};
 use mars_types::oracle::{
-    ActionKind, Config, ConfigResponse, ExecuteMsg, HasPriceSourceResponse, InstantiateMsg,
-    PriceResponse, PriceSourceResponse, QueryMsg,
+    AcceptedPrice, ActionKind, CircuitBreaker, CircuitBreakerResponse, Config, ConfigResponse,
+    ExecuteMsg, HasPriceSourceResponse, InstantiateMsg, PriceHistoryConfig, PriceHistoryResponse,
+    PriceResponse, PriceSample, PriceSourceResponse, QueryMsg,
 };
 use mars_utils::helpers::validate_native_denom;
 
-use crate::{error::ContractResult, ContractError, PriceSourceChecked, PriceSourceUnchecked};
+use crate::{
+    error::ContractResult,
+    managed_vault::{init_managed_vault_share_price, record_managed_vault_share_price},
+    ContractError, PriceSourceChecked, PriceSourceUnchecked,
+};
 
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
 
+/// The maximum number of prices kept in the price history of a coin
+const MAX_PRICE_HISTORY_CAPACITY: u32 = 1_000;
+
 pub struct OracleBase<'a, P, PU, C, I, E>
 where
     P: PriceSourceChecked<C>,
@@ -27,8 +35,21 @@ where
     pub owner: Owner<'a>,
     /// The contract's config
     pub config: Item<'a, Config>,
+    /// The keeper allowed to record prices alongside the owner, if any
+    pub keeper: Item<'a, Addr>,
     /// The price source of each coin denom
     pub price_sources: Map<'a, &'a str, P>,
+    /// The circuit breaker of each coin denom, if any
+    pub circuit_breakers: Map<'a, &'a str, CircuitBreaker>,
+    /// The last price accepted by the circuit breaker of each coin denom
+    pub last_accepted_prices: Map<'a, &'a str, AcceptedPrice>,
+    /// The price history config of each coin denom, if enabled
+    pub price_history_configs: Map<'a, &'a str, PriceHistoryConfig>,
+    /// Ring buffer of the recorded prices of each coin denom, indexed by slot
+    pub price_history: Map<'a, (&'a str, u32), PriceSample>,
+    /// The total number of prices recorded for each coin denom, used to find the next slot of its
+    /// ring buffer
+    pub price_history_counts: Map<'a, &'a str, u64>,
     /// Phantom data holds the unchecked price source type
     pub unchecked_price_source: PhantomData<PU>,
     /// Phantom data holds the custom query type
@@ -49,7 +70,13 @@ where
         Self {
             owner: Owner::new("owner"),
             config: Item::new("config"),
+            keeper: Item::new("keeper"),
             price_sources: Map::new("price_sources"),
+            circuit_breakers: Map::new("circuit_breakers"),
+            last_accepted_prices: Map::new("last_accepted_prices"),
+            price_history_configs: Map::new("price_history_configs"),
+            price_history: Map::new("price_history"),
+            price_history_counts: Map::new("price_history_counts"),
             unchecked_price_source: PhantomData,
             custom_query: PhantomData,
             instantiate_msg: PhantomData,
@@ -92,6 +119,7 @@ where
     pub fn execute(
         &self,
         deps: DepsMut<C>,
+        env: Env,
         info: MessageInfo,
         msg: ExecuteMsg<PU, E>,
     ) -> ContractResult<Response> {
@@ -100,13 +128,36 @@ where
             ExecuteMsg::SetPriceSource {
                 denom,
                 price_source,
-            } => self.set_price_source(deps, info.sender, denom, price_source),
+            } => self.set_price_source(deps, env, info.sender, denom, price_source),
             ExecuteMsg::RemovePriceSource {
                 denom,
             } => self.remove_price_source(deps, info.sender, denom),
             ExecuteMsg::UpdateConfig {
                 base_denom,
             } => self.update_config(deps, info.sender, base_denom),
+            ExecuteMsg::SetKeeper {
+                keeper,
+            } => self.set_keeper(deps, info.sender, keeper),
+            ExecuteMsg::SetCircuitBreaker {
+                denom,
+                circuit_breaker,
+            } => self.set_circuit_breaker(deps, info.sender, denom, circuit_breaker),
+            ExecuteMsg::RecordCircuitBreakerPrices {
+                denoms,
+            } => self.record_circuit_breaker_prices(deps, env, info.sender, denoms),
+            ExecuteMsg::ResetCircuitBreaker {
+                denom,
+            } => self.reset_circuit_breaker(deps, env, info.sender, denom),
+            ExecuteMsg::SetPriceHistory {
+                denom,
+                config,
+            } => self.set_price_history(deps, info.sender, denom, config),
+            ExecuteMsg::RecordPrices {
+                denoms,
+            } => self.record_prices(deps, env, info.sender, denoms),
+            ExecuteMsg::RecordManagedVaultSharePrices {
+                denoms,
+            } => self.record_managed_vault_share_prices(deps, env, info.sender, denoms),
             // Custom messages should be handled by the implementing contract
             ExecuteMsg::Custom(_) => Err(ContractError::MissingCustomExecuteParams {}),
         }
@@ -154,6 +205,18 @@ where
             QueryMsg::HasPriceSource {
                 denom,
             } => to_json_binary(&self.query_has_price_source(deps, denom)?),
+            QueryMsg::CircuitBreaker {
+                denom,
+            } => to_json_binary(&self.query_circuit_breaker(deps, denom)?),
+            QueryMsg::PriceAt {
+                denom,
+                timestamp,
+            } => to_json_binary(&self.query_price_at(deps, denom, timestamp)?),
+            QueryMsg::PriceHistory {
+                denom,
+                start_after,
+                limit,
+            } => to_json_binary(&self.query_price_history(deps, denom, start_after, limit)?),
         };
         res.map_err(Into::into)
     }
@@ -170,6 +233,7 @@ where
     fn set_price_source(
         &self,
         deps: DepsMut<C>,
+        env: Env,
         sender_addr: Addr,
         denom: String,
         price_source: PU,
@@ -183,6 +247,17 @@ where
             price_source.validate(&deps.as_ref(), &denom, &cfg.base_denom, &self.price_sources)?;
         self.price_sources.save(deps.storage, &denom, &price_source)?;
 
+        // the first share price of a managed vault is recorded by the owner, so that no one can
+        // inflate the reference bounding the following ones
+        for (vault_addr, _) in price_source.managed_vaults() {
+            init_managed_vault_share_price(
+                deps.storage,
+                &QuerierWrapper::new(&*deps.querier),
+                &env,
+                &vault_addr,
+            )?;
+        }
+
         Ok(Response::new()
             .add_attribute("action", "set_price_source")
             .add_attribute("denom", denom)
@@ -229,6 +304,352 @@ where
         Ok(response)
     }
 
+    fn set_keeper(
+        &self,
+        deps: DepsMut<C>,
+        sender_addr: Addr,
+        keeper: Option<String>,
+    ) -> ContractResult<Response> {
+        self.owner.assert_owner(deps.storage, &sender_addr)?;
+
+        let Some(keeper) = keeper else {
+            self.keeper.remove(deps.storage);
+            return Ok(Response::new().add_attribute("action", "remove_keeper"));
+        };
+
+        let keeper = deps.api.addr_validate(&keeper)?;
+        self.keeper.save(deps.storage, &keeper)?;
+
+        Ok(Response::new().add_attribute("action", "set_keeper").add_attribute("keeper", keeper))
+    }
+
+    /// Assert that the sender is the owner or the keeper, allowed to record prices
+    pub fn assert_owner_or_keeper(
+        &self,
+        store: &dyn Storage,
+        sender_addr: &Addr,
+    ) -> ContractResult<()> {
+        if self.owner.is_owner(store, sender_addr)? {
+            return Ok(());
+        }
+        if self.keeper.may_load(store)?.as_ref() == Some(sender_addr) {
+            return Ok(());
+        }
+        Err(ContractError::NotOwnerOrKeeper {})
+    }
+
+    fn set_circuit_breaker(
+        &self,
+        deps: DepsMut<C>,
+        sender_addr: Addr,
+        denom: String,
+        circuit_breaker: Option<CircuitBreaker>,
+    ) -> ContractResult<Response> {
+        self.owner.assert_owner(deps.storage, &sender_addr)?;
+
+        // The reference price of the previous circuit breaker (if any) is discarded
+        self.last_accepted_prices.remove(deps.storage, &denom);
+
+        let Some(circuit_breaker) = circuit_breaker else {
+            self.circuit_breakers.remove(deps.storage, &denom);
+            return Ok(Response::new()
+                .add_attribute("action", "remove_circuit_breaker")
+                .add_attribute("denom", denom));
+        };
+
+        if circuit_breaker.max_price_change.is_zero() {
+            return Err(ContractError::InvalidCircuitBreaker {
+                reason: "max_price_change must be greater than 0".to_string(),
+            });
+        }
+
+        if circuit_breaker.window == 0 {
+            return Err(ContractError::InvalidCircuitBreaker {
+                reason: "window must be greater than 0".to_string(),
+            });
+        }
+
+        self.circuit_breakers.save(deps.storage, &denom, &circuit_breaker)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_circuit_breaker")
+            .add_attribute("denom", denom)
+            .add_attribute("max_price_change", circuit_breaker.max_price_change.to_string())
+            .add_attribute("window", circuit_breaker.window.to_string()))
+    }
+
+    fn record_circuit_breaker_prices(
+        &self,
+        deps: DepsMut<C>,
+        env: Env,
+        sender_addr: Addr,
+        denoms: Vec<String>,
+    ) -> ContractResult<Response> {
+        self.assert_owner_or_keeper(deps.storage, &sender_addr)?;
+
+        let mut response = Response::new().add_attribute("action", "record_circuit_breaker_prices");
+
+        for denom in denoms {
+            let Some(circuit_breaker) = self.circuit_breakers.may_load(deps.storage, &denom)?
+            else {
+                return Err(ContractError::CircuitBreakerNotFound {
+                    denom,
+                });
+            };
+
+            // The reference price moves at most once per window, so that the price can't be
+            // walked by `max_price_change` on every record
+            let last_accepted = self.last_accepted_prices.may_load(deps.storage, &denom)?;
+            if let Some(last_accepted) = last_accepted {
+                if env.block.time.seconds() < last_accepted.timestamp + circuit_breaker.window {
+                    continue;
+                }
+            }
+
+            // The price is checked against the last accepted price before being recorded. A
+            // tripped circuit breaker doesn't prevent recording the prices of the other coins.
+            let price = match self.query_price(
+                deps.as_ref(),
+                env.clone(),
+                denom.clone(),
+                ActionKind::Default,
+            ) {
+                Ok(price) => price,
+                Err(err) => {
+                    response = response.add_attribute(denom, format!("skipped: {err}"));
+                    continue;
+                }
+            };
+            self.last_accepted_prices.save(
+                deps.storage,
+                &denom,
+                &AcceptedPrice {
+                    price: price.price,
+                    timestamp: env.block.time.seconds(),
+                },
+            )?;
+
+            response = response.add_attribute(denom, price.price.to_string());
+        }
+
+        Ok(response)
+    }
+
+    fn reset_circuit_breaker(
+        &self,
+        deps: DepsMut<C>,
+        env: Env,
+        sender_addr: Addr,
+        denom: String,
+    ) -> ContractResult<Response> {
+        self.owner.assert_owner(deps.storage, &sender_addr)?;
+
+        if !self.circuit_breakers.has(deps.storage, &denom) {
+            return Err(ContractError::CircuitBreakerNotFound {
+                denom,
+            });
+        }
+
+        let cfg = self.config.load(deps.storage)?;
+        let price_source = self.query_price_source(deps.as_ref(), denom.clone())?.price_source;
+        let price = price_source.query_price(
+            &deps.as_ref(),
+            &env,
+            &denom,
+            &cfg,
+            &self.price_sources,
+            ActionKind::Default,
+        )?;
+
+        self.last_accepted_prices.save(
+            deps.storage,
+            &denom,
+            &AcceptedPrice {
+                price,
+                timestamp: env.block.time.seconds(),
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "reset_circuit_breaker")
+            .add_attribute("denom", denom)
+            .add_attribute("price", price.to_string()))
+    }
+
+    fn set_price_history(
+        &self,
+        deps: DepsMut<C>,
+        sender_addr: Addr,
+        denom: String,
+        config: Option<PriceHistoryConfig>,
+    ) -> ContractResult<Response> {
+        self.owner.assert_owner(deps.storage, &sender_addr)?;
+
+        let Some(config) = config else {
+            self.clear_price_history(deps.storage, &denom)?;
+            self.price_history_configs.remove(deps.storage, &denom);
+            return Ok(Response::new()
+                .add_attribute("action", "remove_price_history")
+                .add_attribute("denom", denom));
+        };
+
+        if config.capacity == 0 || config.capacity > MAX_PRICE_HISTORY_CAPACITY {
+            return Err(ContractError::InvalidPriceHistory {
+                reason: format!("capacity must be between 1 and {MAX_PRICE_HISTORY_CAPACITY}"),
+            });
+        }
+
+        // Recorded prices must have strictly increasing timestamps to be searchable
+        if config.min_interval == 0 {
+            return Err(ContractError::InvalidPriceHistory {
+                reason: "min_interval must be greater than 0".to_string(),
+            });
+        }
+
+        // The slots of the ring buffer depend on its capacity, so the prices recorded with the
+        // previous config (if any) are moved to the new ring buffer, keeping the most recent ones
+        // if they don't all fit
+        let prices = match self.price_history_configs.may_load(deps.storage, &denom)? {
+            Some(prev_config) => {
+                let (first, count) =
+                    self.price_history_bounds(deps.storage, &denom, &prev_config)?;
+                let first = first.max(count.saturating_sub(config.capacity as u64));
+                let prices = (first..count)
+                    .map(|index| self.load_price_sample(deps.storage, &denom, &prev_config, index))
+                    .collect::<StdResult<Vec<_>>>()?;
+                self.clear_price_history(deps.storage, &denom)?;
+                prices
+            }
+            None => vec![],
+        };
+        for (slot, price) in prices.iter().enumerate() {
+            self.price_history.save(deps.storage, (&denom, slot as u32), price)?;
+        }
+        if !prices.is_empty() {
+            self.price_history_counts.save(deps.storage, &denom, &(prices.len() as u64))?;
+        }
+
+        self.price_history_configs.save(deps.storage, &denom, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_price_history")
+            .add_attribute("denom", denom)
+            .add_attribute("capacity", config.capacity.to_string())
+            .add_attribute("min_interval", config.min_interval.to_string()))
+    }
+
+    fn record_prices(
+        &self,
+        deps: DepsMut<C>,
+        env: Env,
+        sender_addr: Addr,
+        denoms: Vec<String>,
+    ) -> ContractResult<Response> {
+        self.assert_owner_or_keeper(deps.storage, &sender_addr)?;
+
+        let timestamp = env.block.time.seconds();
+        let mut response = Response::new().add_attribute("action", "record_prices");
+
+        for denom in denoms {
+            // A denom without price history doesn't prevent recording the prices of the others
+            let Some(config) = self.price_history_configs.may_load(deps.storage, &denom)? else {
+                response = response.add_attribute(denom, "skipped: no price history");
+                continue;
+            };
+            let count = self.price_history_counts.may_load(deps.storage, &denom)?.unwrap_or(0);
+
+            // Skip the denom if its last price is too recent, so that the history can't be
+            // overwritten by recording prices repeatedly
+            if count > 0 {
+                let last = self.load_price_sample(deps.storage, &denom, &config, count - 1)?;
+                if timestamp < last.timestamp + config.min_interval {
+                    continue;
+                }
+            }
+
+            // A price that can't be queried doesn't prevent recording the prices of the other coins
+            let price = match self.query_price(
+                deps.as_ref(),
+                env.clone(),
+                denom.clone(),
+                ActionKind::Default,
+            ) {
+                Ok(price) => price,
+                Err(err) => {
+                    response = response.add_attribute(denom, format!("skipped: {err}"));
+                    continue;
+                }
+            };
+            self.price_history.save(
+                deps.storage,
+                (&denom, (count % config.capacity as u64) as u32),
+                &PriceSample {
+                    price: price.price,
+                    timestamp,
+                },
+            )?;
+            self.price_history_counts.save(deps.storage, &denom, &(count + 1))?;
+
+            response = response.add_attribute(denom, price.price.to_string());
+        }
+
+        Ok(response)
+    }
+
+    fn record_managed_vault_share_prices(
+        &self,
+        deps: DepsMut<C>,
+        env: Env,
+        sender_addr: Addr,
+        denoms: Vec<String>,
+    ) -> ContractResult<Response> {
+        self.assert_owner_or_keeper(deps.storage, &sender_addr)?;
+
+        let mut response =
+            Response::new().add_attribute("action", "record_managed_vault_share_prices");
+
+        for denom in denoms {
+            let vaults = self
+                .price_sources
+                .may_load(deps.storage, &denom)?
+                .map(|price_source| price_source.managed_vaults())
+                .unwrap_or_default();
+            if vaults.is_empty() {
+                response = response.add_attribute(denom, "skipped: no managed vault price source");
+                continue;
+            }
+
+            for (vault_addr, max_daily_growth) in vaults {
+                let attr = match record_managed_vault_share_price(
+                    deps.storage,
+                    &QuerierWrapper::new(&*deps.querier),
+                    &env,
+                    &vault_addr,
+                    max_daily_growth,
+                ) {
+                    Ok(share_price) => share_price.to_string(),
+                    Err(err) => format!("skipped: {err}"),
+                };
+                response = response.add_attribute(vault_addr, attr);
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn clear_price_history(&self, store: &mut dyn Storage, denom: &str) -> StdResult<()> {
+        let slots = self
+            .price_history
+            .prefix(denom)
+            .keys(store, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for slot in slots {
+            self.price_history.remove(store, (denom, slot));
+        }
+        self.price_history_counts.remove(store, denom);
+        Ok(())
+    }
+
     fn query_config(&self, deps: Deps<C>) -> StdResult<ConfigResponse> {
         let owner_state = self.owner.query(deps.storage)?;
         let cfg = self.config.load(deps.storage)?;
@@ -236,6 +657,7 @@ where
             owner: owner_state.owner,
             proposed_new_owner: owner_state.proposed,
             base_denom: cfg.base_denom,
+            keeper: self.keeper.may_load(deps.storage)?.map(|keeper| keeper.to_string()),
         })
     }
 
@@ -285,16 +707,21 @@ where
 
         let price_source = self.query_price_source(deps, denom.clone())?.price_source;
 
+        let (price, fallback_used) = price_source.query_price_with_fallback_info(
+            &deps,
+            &env,
+            &denom,
+            &cfg,
+            &self.price_sources,
+            kind.clone(),
+        )?;
+
+        self.assert_circuit_breaker(deps, &env, &denom, price, kind)?;
+
         Ok(PriceResponse {
-            price: price_source.query_price(
-                &deps,
-                &env,
-                &denom,
-                &cfg,
-                &self.price_sources,
-                kind,
-            )?,
             denom,
+            price,
+            fallback_used,
         })
     }
 
@@ -316,16 +743,19 @@ where
             .take(limit)
             .map(|item| {
                 let (k, v) = item?;
+                let (price, fallback_used) = v.query_price_with_fallback_info(
+                    &deps,
+                    &env,
+                    &k,
+                    &cfg,
+                    &self.price_sources,
+                    kind.clone(),
+                )?;
+                self.assert_circuit_breaker(deps, &env, &k, price, kind.clone())?;
                 Ok(PriceResponse {
-                    price: v.query_price(
-                        &deps,
-                        &env,
-                        &k,
-                        &cfg,
-                        &self.price_sources,
-                        kind.clone(),
-                    )?,
                     denom: k,
+                    price,
+                    fallback_used,
                 })
             })
             .collect()
@@ -348,6 +778,189 @@ where
         Ok(prices)
     }
 
+    fn query_circuit_breaker(
+        &self,
+        deps: Deps<C>,
+        denom: String,
+    ) -> ContractResult<CircuitBreakerResponse> {
+        let circuit_breaker =
+            self.circuit_breakers.may_load(deps.storage, &denom)?.ok_or_else(|| {
+                ContractError::CircuitBreakerNotFound {
+                    denom: denom.clone(),
+                }
+            })?;
+        let last_accepted_price = self.last_accepted_prices.may_load(deps.storage, &denom)?;
+
+        Ok(CircuitBreakerResponse {
+            denom,
+            circuit_breaker,
+            last_accepted_price,
+        })
+    }
+
+    fn query_price_at(
+        &self,
+        deps: Deps<C>,
+        denom: String,
+        timestamp: u64,
+    ) -> ContractResult<PriceSample> {
+        let config = self.load_price_history_config(deps.storage, &denom)?;
+        let (first, count) = self.price_history_bounds(deps.storage, &denom, &config)?;
+
+        // The last price recorded at or before the timestamp precedes the first one recorded after
+        let index = self.price_history_partition_point(
+            deps.storage,
+            &denom,
+            &config,
+            first,
+            count,
+            timestamp,
+        )?;
+        if index == first {
+            return Err(ContractError::PriceNotRecorded {
+                denom,
+                timestamp,
+            });
+        }
+
+        Ok(self.load_price_sample(deps.storage, &denom, &config, index - 1)?)
+    }
+
+    fn query_price_history(
+        &self,
+        deps: Deps<C>,
+        denom: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> ContractResult<PriceHistoryResponse> {
+        let config = self.load_price_history_config(deps.storage, &denom)?;
+        let (first, count) = self.price_history_bounds(deps.storage, &denom, &config)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+
+        let start = match start_after {
+            Some(timestamp) => self.price_history_partition_point(
+                deps.storage,
+                &denom,
+                &config,
+                first,
+                count,
+                timestamp,
+            )?,
+            None => first,
+        };
+
+        let prices = (start..count.min(start + limit))
+            .map(|index| self.load_price_sample(deps.storage, &denom, &config, index))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(PriceHistoryResponse {
+            denom,
+            config,
+            prices,
+        })
+    }
+
+    fn load_price_history_config(
+        &self,
+        store: &dyn Storage,
+        denom: &str,
+    ) -> ContractResult<PriceHistoryConfig> {
+        self.price_history_configs.may_load(store, denom)?.ok_or_else(|| {
+            ContractError::PriceHistoryNotFound {
+                denom: denom.to_string(),
+            }
+        })
+    }
+
+    /// Range [first, count) of the indexes of the prices still kept in the ring buffer, where the
+    /// index of a price is the number of prices recorded before it
+    fn price_history_bounds(
+        &self,
+        store: &dyn Storage,
+        denom: &str,
+        config: &PriceHistoryConfig,
+    ) -> StdResult<(u64, u64)> {
+        let count = self.price_history_counts.may_load(store, denom)?.unwrap_or(0);
+        Ok((count.saturating_sub(config.capacity as u64), count))
+    }
+
+    fn load_price_sample(
+        &self,
+        store: &dyn Storage,
+        denom: &str,
+        config: &PriceHistoryConfig,
+        index: u64,
+    ) -> StdResult<PriceSample> {
+        self.price_history.load(store, (denom, (index % config.capacity as u64) as u32))
+    }
+
+    /// Binary search for the index of the first price in [first, count) recorded after
+    /// `timestamp`, or `count` if there's none
+    fn price_history_partition_point(
+        &self,
+        store: &dyn Storage,
+        denom: &str,
+        config: &PriceHistoryConfig,
+        first: u64,
+        count: u64,
+        timestamp: u64,
+    ) -> StdResult<u64> {
+        let mut low = first;
+        let mut high = count;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.load_price_sample(store, denom, config, mid)?.timestamp <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Reject a price moving more than allowed by the denom's circuit breaker since the last
+    /// accepted price. Liquidations aren't blocked, so that they keep working in volatile markets.
+    ///
+    /// The allowed change grows by `max_price_change` for every full window elapsed since the last
+    /// accepted price, so that a stale reference still bounds the price instead of accepting any.
+    fn assert_circuit_breaker(
+        &self,
+        deps: Deps<C>,
+        env: &Env,
+        denom: &str,
+        price: Decimal,
+        kind: ActionKind,
+    ) -> ContractResult<()> {
+        if kind == ActionKind::Liquidation {
+            return Ok(());
+        }
+
+        let Some(circuit_breaker) = self.circuit_breakers.may_load(deps.storage, denom)? else {
+            return Ok(());
+        };
+
+        let Some(last_accepted) = self.last_accepted_prices.may_load(deps.storage, denom)? else {
+            return Ok(());
+        };
+
+        let elapsed_windows = env.block.time.seconds().saturating_sub(last_accepted.timestamp)
+            / circuit_breaker.window;
+        let max_price_change = circuit_breaker
+            .max_price_change
+            .checked_mul(Decimal::from_ratio(elapsed_windows.max(1), 1u128))?;
+
+        let price_change = price.abs_diff(last_accepted.price).checked_div(last_accepted.price)?;
+        if price_change > max_price_change {
+            return Err(ContractError::CircuitBreakerTripped {
+                denom: denom.to_string(),
+                price_change,
+                max_price_change,
+            });
+        }
+
+        Ok(())
+    }
+
     fn query_has_price_source(
         &self,
         deps: Deps<C>,
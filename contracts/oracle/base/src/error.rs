@@ -42,6 +42,9 @@ pub enum ContractError {
     #[error("{0}")]
     DecimalRangeExceeded(#[from] DecimalRangeExceeded),
 
+    #[error("Caller is neither the owner nor the keeper")]
+    NotOwnerOrKeeper {},
+
     #[error("Invalid price source: {reason}")]
     InvalidPriceSource {
         reason: String,
@@ -52,6 +55,39 @@ pub enum ContractError {
         reason: String,
     },
 
+    #[error("Invalid circuit breaker: {reason}")]
+    InvalidCircuitBreaker {
+        reason: String,
+    },
+
+    #[error("No circuit breaker found for denom: {denom}")]
+    CircuitBreakerNotFound {
+        denom: String,
+    },
+
+    #[error("Circuit breaker tripped for {denom}: price changed by {price_change} since the last accepted price, max allowed: {max_price_change}")]
+    CircuitBreakerTripped {
+        denom: String,
+        price_change: Decimal,
+        max_price_change: Decimal,
+    },
+
+    #[error("Invalid price history: {reason}")]
+    InvalidPriceHistory {
+        reason: String,
+    },
+
+    #[error("No price history found for denom: {denom}")]
+    PriceHistoryNotFound {
+        denom: String,
+    },
+
+    #[error("No price recorded for {denom} at or before {timestamp}")]
+    PriceNotRecorded {
+        denom: String,
+        timestamp: u64,
+    },
+
     #[error("Missing custom init params")]
     MissingCustomInitParams {},
 
@@ -0,0 +1,130 @@
+use cosmwasm_std::Decimal;
+use mars_types::oracle::ActionKind;
+
+use crate::{ContractError, ContractResult};
+
+/// Validate the parameters of a price source with fallback
+pub fn assert_with_fallback(max_divergence: Decimal) -> ContractResult<()> {
+    if max_divergence.is_zero() || max_divergence > Decimal::one() {
+        return Err(ContractError::InvalidPriceSource {
+            reason: "max_divergence must be greater than 0 and less than or equal to 1".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Pick the price of a price source with fallback. Returns the price and whether it was provided
+/// by the fallback source.
+///
+/// The fallback is only used for liquidations, so that they keep working if the primary source is
+/// unavailable (e.g. stale Pyth price). Other actions stay strict and fail with the error of the
+/// primary source.
+///
+/// For other actions, the primary price can't diverge from the fallback price, if available, by
+/// more than `max_divergence` (relative to the primary price), otherwise one of them is off and we
+/// can't tell which one. Liquidations use the primary price as is whenever it is available, so the
+/// fallback source isn't queried for them.
+pub fn resolve_with_fallback(
+    primary: ContractResult<Decimal>,
+    fallback: impl FnOnce() -> ContractResult<Decimal>,
+    max_divergence: Decimal,
+    kind: ActionKind,
+) -> ContractResult<(Decimal, bool)> {
+    match (primary, kind) {
+        (Ok(primary_price), ActionKind::Default) => {
+            if let Ok(fallback_price) = fallback() {
+                let diff = primary_price.abs_diff(fallback_price);
+                let divergence = diff.checked_div(primary_price)?;
+                if divergence > max_divergence {
+                    return Err(ContractError::InvalidPrice {
+                        reason: format!(
+                            "primary and fallback prices diverge too much: {divergence}, max allowed: {max_divergence}"
+                        ),
+                    });
+                }
+            }
+            Ok((primary_price, false))
+        }
+        (Ok(primary_price), ActionKind::Liquidation) => Ok((primary_price, false)),
+        (Err(err), ActionKind::Default) => Err(err),
+        (Err(_), ActionKind::Liquidation) => fallback().map(|price| (price, true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unavailable() -> ContractResult<Decimal> {
+        Err(ContractError::InvalidPrice {
+            reason: "price is stale".to_string(),
+        })
+    }
+
+    #[test]
+    fn primary_price_is_used_if_available() {
+        let res = resolve_with_fallback(
+            Ok(Decimal::percent(100)),
+            || Ok(Decimal::percent(101)),
+            Decimal::percent(5),
+            ActionKind::Default,
+        )
+        .unwrap();
+        assert_eq!(res, (Decimal::percent(100), false));
+
+        let res = resolve_with_fallback(
+            Ok(Decimal::percent(100)),
+            unavailable,
+            Decimal::percent(5),
+            ActionKind::Liquidation,
+        )
+        .unwrap();
+        assert_eq!(res, (Decimal::percent(100), false));
+    }
+
+    #[test]
+    fn fallback_price_is_only_used_for_liquidations() {
+        let err = resolve_with_fallback(
+            unavailable(),
+            || Ok(Decimal::percent(101)),
+            Decimal::percent(5),
+            ActionKind::Default,
+        )
+        .unwrap_err();
+        assert_eq!(err, unavailable().unwrap_err());
+
+        let res = resolve_with_fallback(
+            unavailable(),
+            || Ok(Decimal::percent(101)),
+            Decimal::percent(5),
+            ActionKind::Liquidation,
+        )
+        .unwrap();
+        assert_eq!(res, (Decimal::percent(101), true));
+    }
+
+    #[test]
+    fn prices_diverge_too_much() {
+        let err = resolve_with_fallback(
+            Ok(Decimal::percent(100)),
+            || Ok(Decimal::percent(110)),
+            Decimal::percent(5),
+            ActionKind::Default,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPrice { .. }));
+    }
+
+    #[test]
+    fn fallback_is_not_queried_for_liquidations_if_primary_is_available() {
+        let res = resolve_with_fallback(
+            Ok(Decimal::percent(100)),
+            || panic!("fallback should not be queried"),
+            Decimal::percent(5),
+            ActionKind::Liquidation,
+        )
+        .unwrap();
+        assert_eq!(res, (Decimal::percent(100), false));
+    }
+}
@@ -2,7 +2,10 @@ mod contract;
 mod error;
 mod traits;
 
+pub mod aggregated;
+pub mod fallback;
 pub mod lp_pricing;
+pub mod managed_vault;
 pub mod pyth;
 pub mod redemption_rate;
 
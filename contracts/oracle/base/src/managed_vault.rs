@@ -0,0 +1,210 @@
+use std::cmp::min;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Deps, Empty, Env, QuerierWrapper, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+use mars_types::oracle::{ActionKind, Config, PriceSample};
+use serde::Deserialize;
+
+use crate::{ContractError, ContractResult, PriceSourceChecked};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// The last recorded share price of each managed vault, used as the reference bounding its share
+/// price
+pub const MANAGED_VAULT_SHARE_PRICES: Map<&Addr, PriceSample> =
+    Map::new("managed_vault_share_prices");
+
+#[cw_serde]
+enum ManagedVaultQueryMsg {
+    VaultExtension(ManagedVaultExtensionQueryMsg),
+}
+
+#[cw_serde]
+enum ManagedVaultExtensionQueryMsg {
+    VaultInfo {},
+}
+
+/// Subset of the vault info returned by a Mars managed vault (`contracts/vault`)
+#[derive(Deserialize)]
+pub struct ManagedVaultInfo {
+    /// The token that is accepted for deposits and used for accounting in the vault
+    pub base_token: String,
+    /// Vault token denom
+    pub vault_token: String,
+    /// Total base tokens in the vault
+    pub total_base_tokens: Uint128,
+    /// Total vault tokens minted
+    pub total_vault_tokens: Uint128,
+}
+
+pub fn query_managed_vault_info(
+    querier: &QuerierWrapper,
+    vault_addr: &Addr,
+) -> StdResult<ManagedVaultInfo> {
+    querier.query_wasm_smart(
+        vault_addr,
+        &ManagedVaultQueryMsg::VaultExtension(ManagedVaultExtensionQueryMsg::VaultInfo {}),
+    )
+}
+
+/// Validate the parameters of a managed vault share price source
+pub fn assert_managed_vault_share<P: PriceSourceChecked<Empty>>(
+    deps: &Deps,
+    denom: &str,
+    vault_addr: &Addr,
+    max_daily_growth: Decimal,
+    price_sources: &Map<&str, P>,
+) -> ContractResult<()> {
+    if max_daily_growth.is_zero() || max_daily_growth > Decimal::one() {
+        return Err(ContractError::InvalidPriceSource {
+            reason: "max_daily_growth must be greater than 0 and less than or equal to 1"
+                .to_string(),
+        });
+    }
+
+    let vault_info = query_managed_vault_info(&deps.querier, vault_addr)?;
+    if vault_info.vault_token != denom {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!(
+                "vault token does not match. Vault token returned by contract: {}, expected: {}",
+                vault_info.vault_token, denom
+            ),
+        });
+    }
+
+    if !price_sources.has(deps.storage, &vault_info.base_token) {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("missing price source for {}", vault_info.base_token),
+        });
+    }
+
+    Ok(())
+}
+
+/// Price of a managed vault share token: the share price multiplied by the price of the vault's
+/// base token
+pub fn query_managed_vault_share_token_price<P: PriceSourceChecked<Empty>>(
+    deps: &Deps,
+    env: &Env,
+    config: &Config,
+    price_sources: &Map<&str, P>,
+    vault_addr: &Addr,
+    max_daily_growth: Decimal,
+    kind: ActionKind,
+) -> ContractResult<Decimal> {
+    let (vault_info, share_price) = query_managed_vault_share_price(
+        &deps.querier,
+        deps.storage,
+        env,
+        vault_addr,
+        max_daily_growth,
+    )?;
+
+    let base_token_price = price_sources.load(deps.storage, &vault_info.base_token)?.query_price(
+        deps,
+        env,
+        &vault_info.base_token,
+        config,
+        price_sources,
+        kind,
+    )?;
+
+    share_price.checked_mul(base_token_price).map_err(Into::into)
+}
+
+/// How many base tokens one vault token is worth, bounded by the last recorded share price.
+///
+/// Anyone can donate base tokens to the vault to inflate the share price within a single block, so
+/// the share price can't grow by more than `max_daily_growth` per day since the last recorded share
+/// price. A share price growing faster, e.g. because of a donation or of large
+/// profits, is capped rather than rejected, so that the shares can still be valued and liquidated.
+/// Decreases of the share price aren't bounded.
+pub fn query_managed_vault_share_price(
+    querier: &QuerierWrapper,
+    store: &dyn Storage,
+    env: &Env,
+    vault_addr: &Addr,
+    max_daily_growth: Decimal,
+) -> ContractResult<(ManagedVaultInfo, Decimal)> {
+    let (vault_info, share_price) = query_managed_vault_spot_share_price(querier, vault_addr)?;
+
+    let reference = MANAGED_VAULT_SHARE_PRICES.may_load(store, vault_addr)?.ok_or_else(|| {
+        ContractError::InvalidPrice {
+            reason: format!("no share price recorded for managed vault {vault_addr}"),
+        }
+    })?;
+
+    let elapsed = env.block.time.seconds().saturating_sub(reference.timestamp);
+    let growth = max_daily_growth.checked_mul(Decimal::from_ratio(elapsed, SECONDS_PER_DAY))?;
+    let max_share_price = reference.price.checked_mul(Decimal::one().checked_add(growth)?)?;
+
+    Ok((vault_info, min(share_price, max_share_price)))
+}
+
+/// Record the current share price of a managed vault as the first reference bounding its share
+/// prices, when its price source is registered. The reference of a vault that already has one is
+/// kept, as the share price couldn't be bounded otherwise.
+pub fn init_managed_vault_share_price(
+    store: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    vault_addr: &Addr,
+) -> ContractResult<()> {
+    if MANAGED_VAULT_SHARE_PRICES.has(store, vault_addr) {
+        return Ok(());
+    }
+
+    let (_, share_price) = query_managed_vault_spot_share_price(querier, vault_addr)?;
+    save_managed_vault_share_price(store, env, vault_addr, share_price)
+}
+
+/// Record the current share price of a managed vault as the reference bounding its following
+/// share prices. The recorded share price is bounded by the previous reference.
+pub fn record_managed_vault_share_price(
+    store: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    vault_addr: &Addr,
+    max_daily_growth: Decimal,
+) -> ContractResult<Decimal> {
+    let (_, share_price) =
+        query_managed_vault_share_price(querier, store, env, vault_addr, max_daily_growth)?;
+    save_managed_vault_share_price(store, env, vault_addr, share_price)?;
+    Ok(share_price)
+}
+
+fn save_managed_vault_share_price(
+    store: &mut dyn Storage,
+    env: &Env,
+    vault_addr: &Addr,
+    share_price: Decimal,
+) -> ContractResult<()> {
+    MANAGED_VAULT_SHARE_PRICES.save(
+        store,
+        vault_addr,
+        &PriceSample {
+            price: share_price,
+            timestamp: env.block.time.seconds(),
+        },
+    )?;
+    Ok(())
+}
+
+fn query_managed_vault_spot_share_price(
+    querier: &QuerierWrapper,
+    vault_addr: &Addr,
+) -> ContractResult<(ManagedVaultInfo, Decimal)> {
+    let vault_info = query_managed_vault_info(querier, vault_addr)?;
+
+    if vault_info.total_vault_tokens.is_zero() {
+        return Err(ContractError::InvalidPrice {
+            reason: "managed vault has no vault tokens minted".to_string(),
+        });
+    }
+
+    let share_price =
+        Decimal::checked_from_ratio(vault_info.total_base_tokens, vault_info.total_vault_tokens)?;
+
+    Ok((vault_info, share_price))
+}
@@ -1,6 +1,6 @@
 use std::fmt::{Debug, Display};
 
-use cosmwasm_std::{CustomQuery, Decimal, Deps, Env};
+use cosmwasm_std::{Addr, CustomQuery, Decimal, Deps, Env};
 use cw_storage_plus::Map;
 use mars_types::oracle::{ActionKind, Config};
 use schemars::JsonSchema;
@@ -51,4 +51,26 @@ where
         price_sources: &Map<&str, Self>,
         kind: ActionKind,
     ) -> ContractResult<Decimal>;
+
+    /// Query the price of an asset, along with whether it was provided by the fallback of a price
+    /// source with fallback. Price sources without fallback never use one.
+    fn query_price_with_fallback_info(
+        &self,
+        deps: &Deps<C>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<(Decimal, bool)> {
+        let price = self.query_price(deps, env, denom, config, price_sources, kind)?;
+        Ok((price, false))
+    }
+
+    /// Mars managed vaults whose share price the price source depends on, along with the maximum
+    /// daily growth of their share price. Their share prices are recorded as references bounding
+    /// the following ones.
+    fn managed_vaults(&self) -> Vec<(Addr, Decimal)> {
+        vec![]
+    }
 }
@@ -1,6 +1,6 @@
 use mars_oracle_base::{ContractError, ContractResult};
 use mars_osmosis::{
-    helpers::{CommonPoolData, Pool},
+    helpers::{CommonPoolData, Pool, MAX_TICK, MIN_INITIALIZED_TICK},
     BalancerPool,
 };
 
@@ -56,6 +56,44 @@ pub fn assert_osmosis_xyk_lp_pool(pool: &Pool) -> ContractResult<()> {
     Ok(())
 }
 
+/// Assert the Osmosis pool indicated by `pool_id` is ConcentratedLiquidity type and the tick range
+/// of the position is valid
+pub fn assert_osmosis_cl_position(
+    pool: &Pool,
+    lower_tick: i64,
+    upper_tick: i64,
+) -> ContractResult<()> {
+    let cl_pool = match pool {
+        Pool::ConcentratedLiquidity(cl_pool) => cl_pool,
+        _ => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("pool {} is not a ConcentratedLiquidity pool", pool.get_pool_id()),
+            });
+        }
+    };
+
+    if lower_tick >= upper_tick {
+        return Err(ContractError::InvalidPriceSource {
+            reason: "lower tick must be less than upper tick".to_string(),
+        });
+    }
+
+    if lower_tick < MIN_INITIALIZED_TICK || upper_tick > MAX_TICK {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("ticks must be within [{MIN_INITIALIZED_TICK}, {MAX_TICK}]"),
+        });
+    }
+
+    let tick_spacing = cl_pool.tick_spacing as i64;
+    if lower_tick % tick_spacing != 0 || upper_tick % tick_spacing != 0 {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("ticks must be multiples of the tick spacing {tick_spacing}"),
+        });
+    }
+
+    Ok(())
+}
+
 /// Assert the Osmosis pool has exactly two assets
 fn assert_pool_has_two_assets(pool: &Pool) -> ContractResult<()> {
     let pool_id = pool.get_pool_id();
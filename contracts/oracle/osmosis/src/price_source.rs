@@ -1,17 +1,18 @@
 use std::{cmp::min, fmt};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Deps, Empty, Env, QuerierWrapper, StdResult};
+use cosmwasm_std::{Addr, Decimal, Decimal256, Deps, Empty, Env, QuerierWrapper, StdResult};
 use cw_storage_plus::Map;
 use mars_oracle_base::{
     lp_pricing,
+    managed_vault::{assert_managed_vault_share, query_managed_vault_share_token_price},
     redemption_rate::{assert_rr_not_too_old, query_redemption_rate, RedemptionRate},
     ContractError::{self, InvalidPrice},
     ContractResult, PriceSourceChecked, PriceSourceUnchecked,
 };
 use mars_osmosis::helpers::{
-    query_arithmetic_twap_price, query_geometric_twap_price, query_pool, query_spot_price,
-    recovered_since_downtime_of_length, Pool,
+    concentrated_liquidity_position_value, query_arithmetic_twap_price, query_geometric_twap_price,
+    query_pool, query_spot_price, recovered_since_downtime_of_length, CommonPoolData, Pool,
 };
 use mars_types::oracle::{ActionKind, Config};
 use mars_utils::helpers::validate_native_denom;
@@ -89,6 +90,23 @@ pub enum OsmosisPriceSource<T> {
     XykLiquidityToken {
         pool_id: u64,
     },
+    /// Price of one unit of liquidity of an Osmosis concentrated liquidity position in the range
+    /// [lower_tick, upper_tick], quoted in OSMO.
+    ///
+    /// The position is valued at the price implied by the oracle prices of the two pool assets,
+    /// not at the current price of the pool, so it can't be manipulated by swapping in the pool.
+    ///
+    /// NOTE: Price sources must exist for both assets in the pool. They can't be spot price
+    /// sources, as the pool price could be moved along with them.
+    ConcentratedLiquidityPosition {
+        pool_id: u64,
+
+        /// Lower tick of the position, must be a multiple of the tick spacing of the pool
+        lower_tick: i64,
+
+        /// Upper tick of the position, must be a multiple of the tick spacing of the pool
+        upper_tick: i64,
+    },
     /// Osmosis geometric twap price quoted in OSMO for staked asset.
     ///
     /// Equation to calculate the price:
@@ -181,6 +199,48 @@ pub enum OsmosisPriceSource<T> {
         /// Params to query redemption rate
         redemption_rate: RedemptionRate<T>,
     },
+    /// Median of the prices returned by several price sources. Sources failing to return a price
+    /// (e.g. a stale Pyth feed) are skipped.
+    Aggregated {
+        /// Price sources to aggregate. They can't be aggregated price sources themselves.
+        sources: Vec<OsmosisPriceSource<T>>,
+
+        /// The minimum number of sources that must return a price
+        min_sources: u8,
+
+        /// The maximum deviation (percentage) between the highest and lowest price returned by
+        /// the sources, relative to the median
+        max_deviation: Decimal,
+    },
+    /// Price source with a fallback, used for liquidations when the primary source fails to return
+    /// a price (e.g. a stale Pyth feed). Other actions only use the primary source.
+    WithFallback {
+        /// Price source used by default. It can't have a fallback itself.
+        primary: Box<OsmosisPriceSource<T>>,
+
+        /// Price source used for liquidations if the primary source is unavailable. It can't have
+        /// a fallback itself.
+        fallback: Box<OsmosisPriceSource<T>>,
+
+        /// The maximum divergence (percentage) between the primary and fallback prices, relative
+        /// to the primary price, when both are available. Only checked for default actions.
+        max_divergence: Decimal,
+    },
+    /// Mars managed vault share token. Its price is the vault's share price (total base tokens per
+    /// vault token) multiplied by the price of the vault's base token.
+    ///
+    /// NOTE: The base token price source should be available in the Mars Oracle contract. The
+    /// share price is recorded when the price source is set, and then with
+    /// `RecordManagedVaultSharePrices`, as the share price can only grow by a bounded rate since
+    /// the last recorded one.
+    MarsManagedVaultShare {
+        /// Address of the managed vault
+        vault_addr: T,
+
+        /// The maximum growth (percentage) of the share price per day since the last recorded
+        /// share price
+        max_daily_growth: Decimal,
+    },
 }
 
 #[cw_serde]
@@ -273,6 +333,11 @@ impl fmt::Display for OsmosisPriceSourceChecked {
             OsmosisPriceSource::XykLiquidityToken {
                 pool_id,
             } => format!("xyk_liquidity_token:{pool_id}"),
+            OsmosisPriceSource::ConcentratedLiquidityPosition {
+                pool_id,
+                lower_tick,
+                upper_tick,
+            } => format!("concentrated_liquidity_position:{pool_id}:{lower_tick}:{upper_tick}"),
             OsmosisPriceSource::StakedGeometricTwap {
                 transitive_denom,
                 pool_id,
@@ -310,6 +375,23 @@ impl fmt::Display for OsmosisPriceSourceChecked {
                 } = redemption_rate;
                 format!("lsd:{transitive_denom}:{pool_id}:{window_size}:{dd_fmt}:{kind}:{contract_addr}:{max_staleness}")
             }
+            OsmosisPriceSource::Aggregated {
+                sources,
+                min_sources,
+                max_deviation,
+            } => {
+                let sources = sources.iter().map(|source| source.to_string()).collect::<Vec<_>>();
+                format!("aggregated:{min_sources}:{max_deviation}:[{}]", sources.join(","))
+            }
+            OsmosisPriceSource::WithFallback {
+                primary,
+                fallback,
+                max_divergence,
+            } => format!("with_fallback:{max_divergence}:[{primary},{fallback}]"),
+            OsmosisPriceSource::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => format!("mars_managed_vault_share:{vault_addr}:{max_daily_growth}"),
         };
         write!(f, "{label}")
     }
@@ -375,6 +457,22 @@ impl PriceSourceUnchecked<OsmosisPriceSourceChecked, Empty> for OsmosisPriceSour
                     pool_id: *pool_id,
                 })
             }
+            OsmosisPriceSourceUnchecked::ConcentratedLiquidityPosition {
+                pool_id,
+                lower_tick,
+                upper_tick,
+            } => {
+                let pool = query_pool(&deps.querier, *pool_id)?;
+                helpers::assert_osmosis_cl_position(&pool, *lower_tick, *upper_tick)?;
+                for token in pool.get_pool_denoms() {
+                    assert_cl_token_price_source(deps, price_sources, &token)?;
+                }
+                Ok(OsmosisPriceSourceChecked::ConcentratedLiquidityPosition {
+                    pool_id: *pool_id,
+                    lower_tick: *lower_tick,
+                    upper_tick: *upper_tick,
+                })
+            }
             OsmosisPriceSourceUnchecked::StakedGeometricTwap {
                 transitive_denom,
                 pool_id,
@@ -437,10 +535,110 @@ impl PriceSourceUnchecked<OsmosisPriceSourceChecked, Empty> for OsmosisPriceSour
                     },
                 })
             }
+            OsmosisPriceSourceUnchecked::Aggregated {
+                sources,
+                min_sources,
+                max_deviation,
+            } => {
+                mars_oracle_base::aggregated::assert_aggregated(
+                    sources.len(),
+                    *min_sources,
+                    *max_deviation,
+                )?;
+
+                let sources = sources
+                    .iter()
+                    .map(|source| {
+                        if matches!(source, OsmosisPriceSource::Aggregated { .. }) {
+                            return Err(ContractError::InvalidPriceSource {
+                                reason: "aggregated price sources can't be nested".to_string(),
+                            });
+                        }
+                        source.clone().validate(deps, denom, base_denom, price_sources)
+                    })
+                    .collect::<ContractResult<Vec<_>>>()?;
+
+                Ok(OsmosisPriceSourceChecked::Aggregated {
+                    sources,
+                    min_sources: *min_sources,
+                    max_deviation: *max_deviation,
+                })
+            }
+            OsmosisPriceSourceUnchecked::WithFallback {
+                primary,
+                fallback,
+                max_divergence,
+            } => {
+                mars_oracle_base::fallback::assert_with_fallback(*max_divergence)?;
+
+                if matches!(**primary, OsmosisPriceSource::WithFallback { .. })
+                    || matches!(**fallback, OsmosisPriceSource::WithFallback { .. })
+                {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: "price sources with fallback can't be nested".to_string(),
+                    });
+                }
+
+                Ok(OsmosisPriceSourceChecked::WithFallback {
+                    primary: Box::new((**primary).clone().validate(
+                        deps,
+                        denom,
+                        base_denom,
+                        price_sources,
+                    )?),
+                    fallback: Box::new((**fallback).clone().validate(
+                        deps,
+                        denom,
+                        base_denom,
+                        price_sources,
+                    )?),
+                    max_divergence: *max_divergence,
+                })
+            }
+            OsmosisPriceSourceUnchecked::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => {
+                let vault_addr = deps.api.addr_validate(vault_addr)?;
+                assert_managed_vault_share(
+                    deps,
+                    denom,
+                    &vault_addr,
+                    *max_daily_growth,
+                    price_sources,
+                )?;
+
+                Ok(OsmosisPriceSourceChecked::MarsManagedVaultShare {
+                    vault_addr,
+                    max_daily_growth: *max_daily_growth,
+                })
+            }
         }
     }
 }
 
+/// Assert that the price source of a token of a concentrated liquidity pool exists and doesn't use
+/// a spot price, which can be manipulated within a single block
+fn assert_cl_token_price_source(
+    deps: &Deps,
+    price_sources: &Map<&str, OsmosisPriceSourceChecked>,
+    denom: &str,
+) -> ContractResult<()> {
+    let Some(price_source) = price_sources.may_load(deps.storage, denom)? else {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("missing price source for {denom}"),
+        });
+    };
+
+    if price_source.uses_spot_price() {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("price source for {denom} can't use a spot price"),
+        });
+    }
+
+    Ok(())
+}
+
 impl PriceSourceChecked<Empty> for OsmosisPriceSourceChecked {
     fn query_price(
         &self,
@@ -503,6 +701,20 @@ impl PriceSourceChecked<Empty> for OsmosisPriceSourceChecked {
                 price_sources,
                 kind,
             ),
+            OsmosisPriceSourceChecked::ConcentratedLiquidityPosition {
+                pool_id,
+                lower_tick,
+                upper_tick,
+            } => Self::query_cl_position_price(
+                deps,
+                env,
+                *pool_id,
+                *lower_tick,
+                *upper_tick,
+                config,
+                price_sources,
+                kind,
+            ),
             OsmosisPriceSourceChecked::StakedGeometricTwap {
                 transitive_denom,
                 pool_id,
@@ -562,11 +774,109 @@ impl PriceSourceChecked<Empty> for OsmosisPriceSourceChecked {
                     kind,
                 )
             }
+            OsmosisPriceSourceChecked::Aggregated {
+                sources,
+                min_sources,
+                max_deviation,
+            } => {
+                let prices = sources
+                    .iter()
+                    .filter_map(|source| {
+                        source
+                            .query_price(deps, env, denom, config, price_sources, kind.clone())
+                            .ok()
+                    })
+                    .collect();
+                mars_oracle_base::aggregated::aggregate_prices(prices, *min_sources, *max_deviation)
+            }
+            OsmosisPriceSourceChecked::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => query_managed_vault_share_token_price(
+                deps,
+                env,
+                config,
+                price_sources,
+                vault_addr,
+                *max_daily_growth,
+                kind,
+            ),
+            OsmosisPriceSourceChecked::WithFallback {
+                ..
+            } => self
+                .query_price_with_fallback_info(deps, env, denom, config, price_sources, kind)
+                .map(|(price, _)| price),
+        }
+    }
+
+    fn query_price_with_fallback_info(
+        &self,
+        deps: &Deps<'_, Empty>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<(Decimal, bool)> {
+        match self {
+            OsmosisPriceSourceChecked::WithFallback {
+                primary,
+                fallback,
+                max_divergence,
+            } => mars_oracle_base::fallback::resolve_with_fallback(
+                primary.query_price(deps, env, denom, config, price_sources, kind.clone()),
+                || fallback.query_price(deps, env, denom, config, price_sources, kind.clone()),
+                *max_divergence,
+                kind.clone(),
+            ),
+            _ => {
+                let price = self.query_price(deps, env, denom, config, price_sources, kind)?;
+                Ok((price, false))
+            }
+        }
+    }
+
+    fn managed_vaults(&self) -> Vec<(Addr, Decimal)> {
+        match self {
+            OsmosisPriceSourceChecked::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => vec![(vault_addr.clone(), *max_daily_growth)],
+            OsmosisPriceSourceChecked::Aggregated {
+                sources,
+                ..
+            } => sources.iter().flat_map(|source| source.managed_vaults()).collect(),
+            OsmosisPriceSourceChecked::WithFallback {
+                primary,
+                fallback,
+                ..
+            } => [primary, fallback].iter().flat_map(|source| source.managed_vaults()).collect(),
+            _ => vec![],
         }
     }
 }
 
 impl OsmosisPriceSourceChecked {
+    /// Whether the price source, or any of the sources it is made of, uses the spot price of a
+    /// pool
+    fn uses_spot_price(&self) -> bool {
+        match self {
+            OsmosisPriceSourceChecked::Spot {
+                ..
+            } => true,
+            OsmosisPriceSourceChecked::Aggregated {
+                sources,
+                ..
+            } => sources.iter().any(|source| source.uses_spot_price()),
+            OsmosisPriceSourceChecked::WithFallback {
+                primary,
+                fallback,
+                ..
+            } => primary.uses_spot_price() || fallback.uses_spot_price(),
+            _ => false,
+        }
+    }
+
     fn chain_recovered(
         deps: &Deps,
         downtime_detector: &Option<DowntimeDetector>,
@@ -632,6 +942,57 @@ impl OsmosisPriceSourceChecked {
         )
     }
 
+    /// Price of one unit of liquidity of a concentrated liquidity position, see
+    /// [`concentrated_liquidity_position_value`].
+    #[allow(clippy::too_many_arguments)]
+    fn query_cl_position_price(
+        deps: &Deps,
+        env: &Env,
+        pool_id: u64,
+        lower_tick: i64,
+        upper_tick: i64,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<Decimal> {
+        // ConcentratedLiquidity pool asserted during price source creation
+        let pool = match query_pool(&deps.querier, pool_id)? {
+            Pool::ConcentratedLiquidity(pool) => pool,
+            _ => {
+                return Err(ContractError::InvalidPrice {
+                    reason: format!("pool {pool_id} is not a ConcentratedLiquidity pool"),
+                })
+            }
+        };
+
+        let token0_price = price_sources.load(deps.storage, &pool.token0)?.query_price(
+            deps,
+            env,
+            &pool.token0,
+            config,
+            price_sources,
+            kind.clone(),
+        )?;
+        let token1_price = price_sources.load(deps.storage, &pool.token1)?.query_price(
+            deps,
+            env,
+            &pool.token1,
+            config,
+            price_sources,
+            kind,
+        )?;
+
+        let value = concentrated_liquidity_position_value(
+            Decimal256::one(),
+            lower_tick,
+            upper_tick,
+            token0_price.into(),
+            token1_price.into(),
+        )?;
+
+        Ok(value.try_into()?)
+    }
+
     /// Staked asset price quoted in OSMO.
     ///
     /// stAsset/OSMO = stAsset/Asset * Asset/OSMO
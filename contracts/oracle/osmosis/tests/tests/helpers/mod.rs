@@ -5,13 +5,14 @@ use std::{marker::PhantomData, str::FromStr};
 use cosmwasm_std::{
     coin, from_json,
     testing::{mock_env, MockApi, MockQuerier, MockStorage},
-    to_json_vec, Coin, Decimal, Deps, DepsMut, OwnedDeps,
+    to_json_vec, Coin, Decimal, Deps, DepsMut, OwnedDeps, Uint128,
 };
 use mars_oracle_base::ContractError;
 use mars_oracle_osmosis::{contract::entry, msg::ExecuteMsg, OsmosisPriceSourceUnchecked};
 use mars_osmosis::{BalancerPool, ConcentratedLiquidityPool, StableSwapPool};
 use mars_testing::{mock_info, MarsMockQuerier};
 use mars_types::oracle::{InstantiateMsg, QueryMsg};
+use mars_vault::{msg::VaultInfoResponseExt, performance_fee::PerformanceFeeConfig};
 use osmosis_std::types::osmosis::{
     cosmwasmpool::v1beta1::{CosmWasmPool, InstantiateMsg as CosmwasmPoolInstantiateMsg},
     gamm::v1beta1::PoolAsset,
@@ -152,6 +153,27 @@ pub fn setup_test() -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
     deps
 }
 
+/// Managed vault with uatom as base token, minting the `factory/managed_vault/mvault` vault token
+pub fn prepare_managed_vault_info(
+    total_base_tokens: u128,
+    total_vault_tokens: u128,
+) -> VaultInfoResponseExt {
+    VaultInfoResponseExt {
+        base_token: "uatom".to_string(),
+        vault_token: "factory/managed_vault/mvault".to_string(),
+        title: None,
+        subtitle: None,
+        description: None,
+        credit_manager: "credit_manager".to_string(),
+        vault_account_id: Some("1".to_string()),
+        cooldown_period: 60,
+        performance_fee_config: PerformanceFeeConfig::default(),
+        total_base_tokens: Uint128::new(total_base_tokens),
+        total_vault_tokens: Uint128::new(total_vault_tokens),
+        share_price: Some(Decimal::from_ratio(total_base_tokens, total_vault_tokens)),
+    }
+}
+
 pub fn prepare_query_balancer_pool_response(
     pool_id: u64,
     assets: &[Coin],
@@ -292,6 +314,18 @@ pub fn set_price_source(deps: DepsMut, denom: &str, price_source: OsmosisPriceSo
     .unwrap();
 }
 
+pub fn set_keeper(deps: DepsMut, keeper: &str) {
+    entry::execute(
+        deps,
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetKeeper {
+            keeper: Some(keeper.to_string()),
+        },
+    )
+    .unwrap();
+}
+
 pub fn query<T: serde::de::DeserializeOwned>(deps: Deps, msg: QueryMsg) -> T {
     from_json(entry::query(deps, mock_env(), msg).unwrap()).unwrap()
 }
@@ -1,7 +1,9 @@
 mod helpers;
 
 mod test_admin;
+mod test_circuit_breaker;
 mod test_custom_execute;
+mod test_price_history;
 mod test_price_source_fmt;
 mod test_query_price;
 mod test_query_price_for_pyth;
@@ -130,3 +130,26 @@ fn update_config_with_new_params() {
     assert_eq!(cfg.proposed_new_owner, None);
     assert_eq!(cfg.base_denom, "uusdc".to_string());
 }
+
+#[test]
+fn setting_keeper() {
+    let mut deps = helpers::setup_test();
+
+    let msg = ExecuteMsg::SetKeeper {
+        keeper: Some("keeper".to_string()),
+    };
+    let res_err =
+        entry::execute(deps.as_mut(), mock_env(), mock_info("somebody"), msg.clone()).unwrap_err();
+    assert_eq!(res_err, ContractError::Owner(NotOwner {}));
+
+    entry::execute(deps.as_mut(), mock_env(), mock_info("owner"), msg).unwrap();
+    let cfg: ConfigResponse = helpers::query(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(cfg.keeper, Some("keeper".to_string()));
+
+    let msg = ExecuteMsg::SetKeeper {
+        keeper: None,
+    };
+    entry::execute(deps.as_mut(), mock_env(), mock_info("owner"), msg).unwrap();
+    let cfg: ConfigResponse = helpers::query(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(cfg.keeper, None);
+}
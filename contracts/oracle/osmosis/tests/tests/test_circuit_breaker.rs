@@ -0,0 +1,340 @@
+use cosmwasm_std::{from_json, testing::mock_env, Decimal, Deps, DepsMut};
+use mars_oracle_base::ContractError;
+use mars_oracle_osmosis::{contract::entry, msg::ExecuteMsg, OsmosisPriceSourceUnchecked};
+use mars_owner::OwnerError::NotOwner;
+use mars_testing::{mock_env_at_block_time, mock_info};
+use mars_types::oracle::{
+    AcceptedPrice, ActionKind, CircuitBreaker, CircuitBreakerResponse, PriceResponse, QueryMsg,
+};
+
+use super::helpers;
+
+fn query_price_at(deps: Deps, timestamp: u64, kind: ActionKind) -> Result<Decimal, ContractError> {
+    let res = entry::query(
+        deps,
+        mock_env_at_block_time(timestamp),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: Some(kind),
+        },
+    )?;
+    let res: PriceResponse = from_json(res).unwrap();
+    Ok(res.price)
+}
+
+fn record_circuit_breaker_price(deps: DepsMut, timestamp: u64) {
+    entry::execute(
+        deps,
+        mock_env_at_block_time(timestamp),
+        mock_info("keeper"),
+        ExecuteMsg::RecordCircuitBreakerPrices {
+            denoms: vec!["umars".to_string()],
+        },
+    )
+    .unwrap();
+}
+
+fn query_last_accepted_price(deps: Deps) -> AcceptedPrice {
+    let res: CircuitBreakerResponse = helpers::query(
+        deps,
+        QueryMsg::CircuitBreaker {
+            denom: "umars".to_string(),
+        },
+    );
+    res.last_accepted_price.unwrap()
+}
+
+#[test]
+fn setting_invalid_circuit_breaker() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake"),
+        ExecuteMsg::SetCircuitBreaker {
+            denom: "umars".to_string(),
+            circuit_breaker: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Owner(NotOwner {}));
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetCircuitBreaker {
+            denom: "umars".to_string(),
+            circuit_breaker: Some(CircuitBreaker {
+                max_price_change: Decimal::zero(),
+                window: 600,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidCircuitBreaker {
+            reason: "max_price_change must be greater than 0".to_string()
+        }
+    );
+}
+
+#[test]
+fn circuit_breaker_rejects_large_price_moves() {
+    let mut deps = helpers::setup_test_with_pools();
+    let timestamp = mock_env().block.time.seconds();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    let circuit_breaker = CircuitBreaker {
+        max_price_change: Decimal::percent(10),
+        window: 600,
+    };
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetCircuitBreaker {
+            denom: "umars".to_string(),
+            circuit_breaker: Some(circuit_breaker.clone()),
+        },
+    )
+    .unwrap();
+    helpers::set_keeper(deps.as_mut(), "keeper");
+
+    // only the owner or the keeper can record prices
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake"),
+        ExecuteMsg::RecordCircuitBreakerPrices {
+            denoms: vec!["umars".to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotOwnerOrKeeper {});
+
+    record_circuit_breaker_price(deps.as_mut(), timestamp);
+
+    let res: CircuitBreakerResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::CircuitBreaker {
+            denom: "umars".to_string(),
+        },
+    );
+    assert_eq!(
+        res,
+        CircuitBreakerResponse {
+            denom: "umars".to_string(),
+            circuit_breaker,
+            last_accepted_price: Some(AcceptedPrice {
+                price: Decimal::one(),
+                timestamp,
+            }),
+        }
+    );
+
+    // the price moves by 50%
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(150),
+        },
+    );
+
+    let expected_err = ContractError::CircuitBreakerTripped {
+        denom: "umars".to_string(),
+        price_change: Decimal::percent(50),
+        max_price_change: Decimal::percent(10),
+    };
+    let err = query_price_at(deps.as_ref(), timestamp + 599, ActionKind::Default).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    // the tripped price is skipped instead of being recorded
+    record_circuit_breaker_price(deps.as_mut(), timestamp + 600);
+    assert_eq!(
+        query_last_accepted_price(deps.as_ref()),
+        AcceptedPrice {
+            price: Decimal::one(),
+            timestamp,
+        }
+    );
+
+    // liquidations are not blocked
+    let price = query_price_at(deps.as_ref(), timestamp, ActionKind::Liquidation).unwrap();
+    assert_eq!(price, Decimal::percent(150));
+
+    // a stale reference still bounds the price, by max_price_change per elapsed window
+    let err = query_price_at(deps.as_ref(), timestamp + 2999, ActionKind::Default).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CircuitBreakerTripped {
+            denom: "umars".to_string(),
+            price_change: Decimal::percent(50),
+            max_price_change: Decimal::percent(40),
+        }
+    );
+    let price = query_price_at(deps.as_ref(), timestamp + 3000, ActionKind::Default).unwrap();
+    assert_eq!(price, Decimal::percent(150));
+}
+
+#[test]
+fn circuit_breaker_reference_moves_once_per_window() {
+    let mut deps = helpers::setup_test_with_pools();
+    let timestamp = mock_env().block.time.seconds();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetCircuitBreaker {
+            denom: "umars".to_string(),
+            circuit_breaker: Some(CircuitBreaker {
+                max_price_change: Decimal::percent(10),
+                window: 600,
+            }),
+        },
+    )
+    .unwrap();
+    helpers::set_keeper(deps.as_mut(), "keeper");
+    record_circuit_breaker_price(deps.as_mut(), timestamp);
+
+    // the price moves by 10% and is recorded again within the window
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(110),
+        },
+    );
+    record_circuit_breaker_price(deps.as_mut(), timestamp + 1);
+
+    // the reference didn't move, so a further 10% move is still rejected
+    assert_eq!(
+        query_last_accepted_price(deps.as_ref()),
+        AcceptedPrice {
+            price: Decimal::one(),
+            timestamp,
+        }
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(121),
+        },
+    );
+    assert!(query_price_at(deps.as_ref(), timestamp + 2, ActionKind::Default).is_err());
+
+    // once the window passed, the reference moves to the current price
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(110),
+        },
+    );
+    record_circuit_breaker_price(deps.as_mut(), timestamp + 600);
+    assert_eq!(
+        query_last_accepted_price(deps.as_ref()),
+        AcceptedPrice {
+            price: Decimal::percent(110),
+            timestamp: timestamp + 600,
+        }
+    );
+}
+
+#[test]
+fn resetting_circuit_breaker() {
+    let mut deps = helpers::setup_test_with_pools();
+    let timestamp = mock_env().block.time.seconds();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::ResetCircuitBreaker {
+            denom: "umars".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CircuitBreakerNotFound {
+            denom: "umars".to_string()
+        }
+    );
+
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetCircuitBreaker {
+            denom: "umars".to_string(),
+            circuit_breaker: Some(CircuitBreaker {
+                max_price_change: Decimal::percent(10),
+                window: 600,
+            }),
+        },
+    )
+    .unwrap();
+    record_circuit_breaker_price(deps.as_mut(), timestamp);
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(150),
+        },
+    );
+    assert!(query_price_at(deps.as_ref(), timestamp, ActionKind::Default).is_err());
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake"),
+        ExecuteMsg::ResetCircuitBreaker {
+            denom: "umars".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Owner(NotOwner {}));
+
+    // the owner accepts the new price
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::ResetCircuitBreaker {
+            denom: "umars".to_string(),
+        },
+    )
+    .unwrap();
+
+    let price = query_price_at(deps.as_ref(), timestamp, ActionKind::Default).unwrap();
+    assert_eq!(price, Decimal::percent(150));
+}
@@ -0,0 +1,445 @@
+use cosmwasm_std::{attr, testing::mock_env, Decimal, Deps, DepsMut};
+use mars_oracle_base::ContractError;
+use mars_oracle_osmosis::{contract::entry, msg::ExecuteMsg, OsmosisPriceSourceUnchecked};
+use mars_owner::OwnerError::NotOwner;
+use mars_testing::{mock_env_at_block_time, mock_info};
+use mars_types::oracle::{PriceHistoryConfig, PriceHistoryResponse, PriceSample, QueryMsg};
+
+use super::helpers;
+
+fn set_umars_price(deps: DepsMut, price: u128) {
+    helpers::set_price_source(
+        deps,
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(price, 1u128),
+        },
+    );
+}
+
+fn record_prices(deps: DepsMut, timestamp: u64) {
+    entry::execute(
+        deps,
+        mock_env_at_block_time(timestamp),
+        mock_info("keeper"),
+        ExecuteMsg::RecordPrices {
+            denoms: vec!["umars".to_string()],
+        },
+    )
+    .unwrap();
+}
+
+fn sample(price: u128, timestamp: u64) -> PriceSample {
+    PriceSample {
+        price: Decimal::from_ratio(price, 1u128),
+        timestamp,
+    }
+}
+
+#[test]
+fn setting_invalid_price_history() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake"),
+        ExecuteMsg::SetPriceHistory {
+            denom: "umars".to_string(),
+            config: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Owner(NotOwner {}));
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceHistory {
+            denom: "umars".to_string(),
+            config: Some(PriceHistoryConfig {
+                capacity: 1_001,
+                min_interval: 3600,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceHistory {
+            reason: "capacity must be between 1 and 1000".to_string()
+        }
+    );
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceHistory {
+            denom: "umars".to_string(),
+            config: Some(PriceHistoryConfig {
+                capacity: 3,
+                min_interval: 0,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceHistory {
+            reason: "min_interval must be greater than 0".to_string()
+        }
+    );
+
+    // no price is recorded for a denom without price history
+    helpers::set_keeper(deps.as_mut(), "keeper");
+    set_umars_price(deps.as_mut(), 1);
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper"),
+        ExecuteMsg::RecordPrices {
+            denoms: vec!["umars".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("umars", "skipped: no price history"));
+
+    let err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "umars".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(
+        err,
+        ContractError::PriceHistoryNotFound {
+            denom: "umars".to_string()
+        }
+    );
+}
+
+#[test]
+fn recording_and_querying_price_history() {
+    let mut deps = helpers::setup_test_with_pools();
+    let config = PriceHistoryConfig {
+        capacity: 3,
+        min_interval: 100,
+    };
+
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceHistory {
+            denom: "umars".to_string(),
+            config: Some(config.clone()),
+        },
+    )
+    .unwrap();
+    helpers::set_keeper(deps.as_mut(), "keeper");
+
+    // only the owner or the keeper can record prices
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake"),
+        ExecuteMsg::RecordPrices {
+            denoms: vec!["umars".to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotOwnerOrKeeper {});
+
+    set_umars_price(deps.as_mut(), 1);
+    record_prices(deps.as_mut(), 1000);
+
+    // skipped, the last price is too recent
+    set_umars_price(deps.as_mut(), 2);
+    record_prices(deps.as_mut(), 1050);
+
+    record_prices(deps.as_mut(), 1100);
+    set_umars_price(deps.as_mut(), 3);
+    record_prices(deps.as_mut(), 1200);
+
+    let res: PriceHistoryResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "umars".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(
+        res,
+        PriceHistoryResponse {
+            denom: "umars".to_string(),
+            config,
+            prices: vec![sample(1, 1000), sample(2, 1100), sample(3, 1200)],
+        }
+    );
+
+    // the oldest price is overwritten once the capacity is reached
+    set_umars_price(deps.as_mut(), 4);
+    record_prices(deps.as_mut(), 1300);
+
+    let res: PriceHistoryResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "umars".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(res.prices, vec![sample(2, 1100), sample(3, 1200), sample(4, 1300)]);
+
+    let res: PriceHistoryResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "umars".to_string(),
+            start_after: Some(1100),
+            limit: Some(1),
+        },
+    );
+    assert_eq!(res.prices, vec![sample(3, 1200)]);
+
+    // the last price recorded at or before the timestamp is returned
+    let res: PriceSample = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceAt {
+            denom: "umars".to_string(),
+            timestamp: 1250,
+        },
+    );
+    assert_eq!(res, sample(3, 1200));
+
+    let res: PriceSample = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceAt {
+            denom: "umars".to_string(),
+            timestamp: 1300,
+        },
+    );
+    assert_eq!(res, sample(4, 1300));
+
+    // the price at 1000 has been overwritten
+    let err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::PriceAt {
+            denom: "umars".to_string(),
+            timestamp: 1050,
+        },
+    );
+    assert_eq!(
+        err,
+        ContractError::PriceNotRecorded {
+            denom: "umars".to_string(),
+            timestamp: 1050,
+        }
+    );
+
+    // disabling the price history deletes the recorded prices
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceHistory {
+            denom: "umars".to_string(),
+            config: None,
+        },
+    )
+    .unwrap();
+
+    let err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::PriceAt {
+            denom: "umars".to_string(),
+            timestamp: 1300,
+        },
+    );
+    assert_eq!(
+        err,
+        ContractError::PriceHistoryNotFound {
+            denom: "umars".to_string()
+        }
+    );
+}
+
+#[test]
+fn reconfiguring_price_history_keeps_recorded_prices() {
+    let mut deps = helpers::setup_test_with_pools();
+    helpers::set_keeper(deps.as_mut(), "keeper");
+
+    let set_price_history = |deps: DepsMut, capacity: u32| {
+        entry::execute(
+            deps,
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceHistory {
+                denom: "umars".to_string(),
+                config: Some(PriceHistoryConfig {
+                    capacity,
+                    min_interval: 100,
+                }),
+            },
+        )
+        .unwrap();
+    };
+    let query_prices = |deps: Deps| {
+        let res: PriceHistoryResponse = helpers::query(
+            deps,
+            QueryMsg::PriceHistory {
+                denom: "umars".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        );
+        res.prices
+    };
+
+    set_price_history(deps.as_mut(), 3);
+    for (price, timestamp) in [(1, 1000), (2, 1100), (3, 1200), (4, 1300)] {
+        set_umars_price(deps.as_mut(), price);
+        record_prices(deps.as_mut(), timestamp);
+    }
+    assert_eq!(
+        query_prices(deps.as_ref()),
+        vec![sample(2, 1100), sample(3, 1200), sample(4, 1300)]
+    );
+
+    // growing the capacity keeps all the recorded prices
+    set_price_history(deps.as_mut(), 5);
+    assert_eq!(
+        query_prices(deps.as_ref()),
+        vec![sample(2, 1100), sample(3, 1200), sample(4, 1300)]
+    );
+
+    set_umars_price(deps.as_mut(), 5);
+    record_prices(deps.as_mut(), 1400);
+    assert_eq!(
+        query_prices(deps.as_ref()),
+        vec![sample(2, 1100), sample(3, 1200), sample(4, 1300), sample(5, 1400)]
+    );
+
+    // shrinking the capacity keeps the most recent prices
+    set_price_history(deps.as_mut(), 2);
+    assert_eq!(query_prices(deps.as_ref()), vec![sample(4, 1300), sample(5, 1400)]);
+
+    set_umars_price(deps.as_mut(), 6);
+    record_prices(deps.as_mut(), 1500);
+    assert_eq!(query_prices(deps.as_ref()), vec![sample(5, 1400), sample(6, 1500)]);
+}
+
+#[test]
+fn recording_prices_skips_denoms_without_price() {
+    let mut deps = helpers::setup_test_with_pools();
+    helpers::set_keeper(deps.as_mut(), "keeper");
+
+    for denom in ["umars", "uatom"] {
+        entry::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceHistory {
+                denom: denom.to_string(),
+                config: Some(PriceHistoryConfig {
+                    capacity: 3,
+                    min_interval: 100,
+                }),
+            },
+        )
+        .unwrap();
+    }
+
+    // uatom has no price source, which doesn't prevent recording the price of umars
+    set_umars_price(deps.as_mut(), 1);
+    entry::execute(
+        deps.as_mut(),
+        mock_env_at_block_time(1000),
+        mock_info("keeper"),
+        ExecuteMsg::RecordPrices {
+            denoms: vec!["uatom".to_string(), "umars".to_string()],
+        },
+    )
+    .unwrap();
+
+    let res: PriceHistoryResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "umars".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(res.prices, vec![sample(1, 1000)]);
+
+    let res: PriceHistoryResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "uatom".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(res.prices.is_empty());
+}
+
+#[test]
+fn recording_prices_skips_denoms_without_price_history() {
+    let mut deps = helpers::setup_test_with_pools();
+    helpers::set_keeper(deps.as_mut(), "keeper");
+
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceHistory {
+            denom: "umars".to_string(),
+            config: Some(PriceHistoryConfig {
+                capacity: 3,
+                min_interval: 100,
+            }),
+        },
+    )
+    .unwrap();
+
+    // uatom has a price but no price history, which doesn't prevent recording the price of umars
+    set_umars_price(deps.as_mut(), 1);
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    let res = entry::execute(
+        deps.as_mut(),
+        mock_env_at_block_time(1000),
+        mock_info("keeper"),
+        ExecuteMsg::RecordPrices {
+            denoms: vec!["uatom".to_string(), "umars".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "record_prices"),
+            attr("uatom", "skipped: no price history"),
+            attr("umars", "1"),
+        ]
+    );
+
+    let res: PriceHistoryResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceHistory {
+            denom: "umars".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(res.prices, vec![sample(1, 1000)]);
+}
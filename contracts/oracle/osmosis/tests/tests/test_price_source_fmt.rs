@@ -99,6 +99,16 @@ fn display_xyk_lp_price_source() {
     assert_eq!(ps.to_string(), "xyk_liquidity_token:224")
 }
 
+#[test]
+fn display_cl_position_price_source() {
+    let ps = OsmosisPriceSourceChecked::ConcentratedLiquidityPosition {
+        pool_id: 1066,
+        lower_tick: -1_000_000,
+        upper_tick: 8_000_000,
+    };
+    assert_eq!(ps.to_string(), "concentrated_liquidity_position:1066:-1000000:8000000")
+}
+
 #[test]
 fn display_pyth_price_source() {
     let ps = OsmosisPriceSourceChecked::Pyth {
@@ -174,3 +184,43 @@ fn display_lsd_price_source() {
     };
     assert_eq!(ps.to_string(), "lsd:transitive:456:380:Some(Duration30m:552):geometric_twap:osmo1zw4fxj4pt0pu0jdd7cs6gecdj3pvfxhhtgkm4w2y44jp60hywzvssud6uc:1234");
 }
+
+#[test]
+fn display_aggregated_price_source() {
+    let ps = OsmosisPriceSourceChecked::Aggregated {
+        sources: vec![
+            OsmosisPriceSourceChecked::Fixed {
+                price: Decimal::from_ratio(1u128, 2u128),
+            },
+            OsmosisPriceSourceChecked::Spot {
+                pool_id: 123,
+            },
+        ],
+        min_sources: 1,
+        max_deviation: Decimal::percent(5),
+    };
+    assert_eq!(ps.to_string(), "aggregated:1:0.05:[fixed:0.5,spot:123]");
+}
+
+#[test]
+fn display_with_fallback_price_source() {
+    let ps = OsmosisPriceSourceChecked::WithFallback {
+        primary: Box::new(OsmosisPriceSourceChecked::Spot {
+            pool_id: 123,
+        }),
+        fallback: Box::new(OsmosisPriceSourceChecked::Fixed {
+            price: Decimal::from_ratio(1u128, 2u128),
+        }),
+        max_divergence: Decimal::percent(5),
+    };
+    assert_eq!(ps.to_string(), "with_fallback:0.05:[spot:123,fixed:0.5]");
+}
+
+#[test]
+fn display_managed_vault_share_price_source() {
+    let ps = OsmosisPriceSourceChecked::MarsManagedVaultShare {
+        vault_addr: Addr::unchecked("managed_vault"),
+        max_daily_growth: Decimal::percent(1),
+    };
+    assert_eq!(ps.to_string(), "mars_managed_vault_share:managed_vault:0.01");
+}
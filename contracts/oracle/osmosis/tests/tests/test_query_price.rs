@@ -3,17 +3,17 @@ use std::{collections::HashMap, str::FromStr};
 use cosmwasm_std::{
     coin, from_json,
     testing::{mock_env, MockApi, MockStorage},
-    Decimal, OwnedDeps, StdError,
+    Decimal, Deps, OwnedDeps, StdError,
     StdError::GenericErr,
 };
 use helpers::prepare_query_balancer_pool_response;
 use ica_oracle::msg::RedemptionRateResponse;
 use mars_oracle_base::{pyth::scale_pyth_price, redemption_rate::RedemptionRate, ContractError};
 use mars_oracle_osmosis::{
-    contract::entry, DowntimeDetector, OsmosisPriceSourceUnchecked, Twap, TwapKind,
+    contract::entry, msg::ExecuteMsg, DowntimeDetector, OsmosisPriceSourceUnchecked, Twap, TwapKind,
 };
-use mars_testing::{mock_env_at_block_time, MarsMockQuerier};
-use mars_types::oracle::{PriceResponse, QueryMsg};
+use mars_testing::{mock_env_at_block_time, mock_info, MarsMockQuerier};
+use mars_types::oracle::{ActionKind, PriceResponse, QueryMsg};
 use osmosis_std::types::osmosis::{
     downtimedetector::v1beta1::Downtime,
     poolmanager::v1beta1::SpotPriceResponse,
@@ -65,6 +65,254 @@ fn querying_fixed_price_price_source_not_set() {
     );
 }
 
+#[test]
+fn querying_aggregated_price() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    // no spot price is set for the pool, so the spot source fails and is skipped
+    let sources = vec![
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(100),
+        },
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(102),
+        },
+        OsmosisPriceSourceUnchecked::Spot {
+            pool_id: 89,
+        },
+    ];
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Aggregated {
+            sources: sources.clone(),
+            min_sources: 2,
+            max_deviation: Decimal::percent(5),
+        },
+    );
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::percent(101));
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Aggregated {
+            sources,
+            min_sources: 3,
+            max_deviation: Decimal::percent(5),
+        },
+    );
+
+    let err = entry::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPrice {
+            reason: "not enough price sources available: 2, required: 3".to_string()
+        }
+    );
+}
+
+#[test]
+fn querying_managed_vault_share_price() {
+    let mut deps = helpers::setup_test_with_pools();
+    let timestamp = mock_env().block.time.seconds();
+
+    // 2 uatom per 1_000_000 vault tokens
+    deps.querier.set_managed_vault_info(
+        "managed_vault",
+        helpers::prepare_managed_vault_info(2_000_000, 10u128.pow(12)),
+    );
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(5u128, 2u128),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "factory/managed_vault/mvault",
+        OsmosisPriceSourceUnchecked::MarsManagedVaultShare {
+            vault_addr: "managed_vault".to_string(),
+            max_daily_growth: Decimal::percent(1),
+        },
+    );
+
+    let query_price_at = |deps: Deps, timestamp: u64| {
+        entry::query(
+            deps,
+            mock_env_at_block_time(timestamp),
+            QueryMsg::Price {
+                denom: "factory/managed_vault/mvault".to_string(),
+                kind: None,
+            },
+        )
+        .map(|res| from_json::<PriceResponse>(res).unwrap().price)
+    };
+
+    // the share price is recorded along with the price source
+    let price = query_price_at(deps.as_ref(), timestamp).unwrap();
+    assert_eq!(price, Decimal::from_ratio(5u128, 1_000_000u128));
+
+    // a donation to the vault doubles the share price, which is capped by the recorded one
+    deps.querier.set_managed_vault_info(
+        "managed_vault",
+        helpers::prepare_managed_vault_info(4_000_000, 10u128.pow(12)),
+    );
+    let price = query_price_at(deps.as_ref(), timestamp).unwrap();
+    assert_eq!(price, Decimal::from_ratio(5u128, 1_000_000u128));
+
+    // the share price can grow by 1% per day since the recorded one
+    let price = query_price_at(deps.as_ref(), timestamp + 86_400).unwrap();
+    assert_eq!(price, Decimal::from_ratio(505u128, 100_000_000u128));
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env_at_block_time(timestamp + 86_400),
+        mock_info("jake"),
+        ExecuteMsg::RecordManagedVaultSharePrices {
+            denoms: vec!["factory/managed_vault/mvault".to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotOwnerOrKeeper {});
+
+    // the recorded share price is bounded by the previous one as well
+    entry::execute(
+        deps.as_mut(),
+        mock_env_at_block_time(timestamp + 86_400),
+        mock_info("owner"),
+        ExecuteMsg::RecordManagedVaultSharePrices {
+            denoms: vec!["factory/managed_vault/mvault".to_string()],
+        },
+    )
+    .unwrap();
+
+    let price = query_price_at(deps.as_ref(), timestamp + 2 * 86_400).unwrap();
+    assert_eq!(price, Decimal::from_ratio(51005u128, 10_000_000_000u128));
+
+    // a decrease of the share price isn't bounded
+    deps.querier.set_managed_vault_info(
+        "managed_vault",
+        helpers::prepare_managed_vault_info(1_000_000, 10u128.pow(12)),
+    );
+    let price = query_price_at(deps.as_ref(), timestamp + 2 * 86_400).unwrap();
+    assert_eq!(price, Decimal::from_ratio(25u128, 10_000_000u128));
+}
+
+#[test]
+fn querying_price_with_fallback() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    // no spot price is set for the pool, so the primary source fails
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::WithFallback {
+            primary: Box::new(OsmosisPriceSourceUnchecked::Spot {
+                pool_id: 89,
+            }),
+            fallback: Box::new(OsmosisPriceSourceUnchecked::Fixed {
+                price: Decimal::percent(101),
+            }),
+            max_divergence: Decimal::percent(5),
+        },
+    );
+
+    // the fallback is not used for default actions
+    let res = entry::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: Some(ActionKind::Default),
+        },
+    );
+    assert!(res.is_err());
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: Some(ActionKind::Liquidation),
+        },
+    );
+    assert_eq!(
+        res,
+        PriceResponse {
+            denom: "umars".to_string(),
+            price: Decimal::percent(101),
+            fallback_used: true,
+        }
+    );
+
+    // both sources are available but diverge too much
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::WithFallback {
+            primary: Box::new(OsmosisPriceSourceUnchecked::Fixed {
+                price: Decimal::percent(100),
+            }),
+            fallback: Box::new(OsmosisPriceSourceUnchecked::Fixed {
+                price: Decimal::percent(110),
+            }),
+            max_divergence: Decimal::percent(5),
+        },
+    );
+
+    let err = entry::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: Some(ActionKind::Default),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPrice {
+            reason: "primary and fallback prices diverge too much: 0.1, max allowed: 0.05"
+                .to_string()
+        }
+    );
+
+    // liquidations use the primary price without checking the divergence
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: Some(ActionKind::Liquidation),
+        },
+    );
+    assert_eq!(
+        res,
+        PriceResponse {
+            denom: "umars".to_string(),
+            price: Decimal::percent(100),
+            fallback_used: false,
+        }
+    );
+}
+
 #[test]
 fn querying_spot_price() {
     let mut deps = helpers::setup_test_with_pools();
@@ -1007,6 +1255,77 @@ fn querying_xyk_lp_price() {
     assert_eq!(res.price, Decimal::from_ratio(1769874_u128, 10000_u128));
 }
 
+#[test]
+fn querying_cl_position_price() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    deps.querier.set_query_pool_response(
+        10004,
+        helpers::prepare_query_cl_pool_response(10004, "uatom", "umars"),
+    );
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(4u128, 1u128),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    // position in the price range [1, 9]
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom_umars_cl",
+        OsmosisPriceSourceUnchecked::ConcentratedLiquidityPosition {
+            pool_id: 10004,
+            lower_tick: 0,
+            upper_tick: 8_000_000,
+        },
+    );
+
+    // Atom price in Mars implied by the oracle prices: 4, sqrt price: 2
+    // Sqrt price range: [1, 3]
+    // amount0 = (3 - 2) / (2 * 3) = 1/6
+    // amount1 = 2 - 1 = 1
+    // value = 4 * 1/6 + 1 * 1 = 1.666666666666666666
+    //
+    // The current price of the pool (tick 102311912) is not used, so the price can't be
+    // manipulated by swapping in the pool.
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "uatom_umars_cl".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_str("1.666666666666666666").unwrap());
+
+    // Atom price moves above the range, the position only holds Mars
+    // value = 1 * (3 - 1) = 2
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(16u128, 1u128),
+        },
+    );
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "uatom_umars_cl".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_ratio(2u128, 1u128));
+}
+
 #[test]
 fn querying_all_prices() {
     let mut deps = helpers::setup_test_with_pools();
@@ -1065,14 +1384,17 @@ fn querying_all_prices() {
             PriceResponse {
                 denom: "uatom".to_string(),
                 price: Decimal::from_ratio(77777u128, 12345u128),
+                fallback_used: false,
             },
             PriceResponse {
                 denom: "umars".to_string(),
                 price: Decimal::from_ratio(88888u128, 12345u128),
+                fallback_used: false,
             },
             PriceResponse {
                 denom: "uosmo".to_string(),
                 price: Decimal::one(),
+                fallback_used: false,
             },
         ]
     );
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{testing::mock_env, Addr, Decimal};
+use cosmwasm_std::{testing::mock_env, Addr, Decimal, DepsMut};
 use mars_oracle_base::{redemption_rate::RedemptionRate, ContractError};
 use mars_oracle_osmosis::{
     contract::entry::execute,
@@ -9,7 +9,7 @@ use mars_oracle_osmosis::{
 };
 use mars_owner::OwnerError::NotOwner;
 use mars_testing::mock_info;
-use mars_types::oracle::QueryMsg;
+use mars_types::oracle::{PriceResponse, QueryMsg};
 use mars_utils::error::ValidationError;
 use osmosis_std::types::osmosis::downtimedetector::v1beta1::Downtime;
 use pyth_sdk_cw::PriceIdentifier;
@@ -1064,6 +1064,121 @@ fn setting_price_source_xyk_lp() {
     );
 }
 
+#[test]
+fn setting_price_source_cl_position() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let set_price_source_cl_position =
+        |deps: DepsMut, pool_id: u64, lower_tick: i64, upper_tick: i64| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("owner"),
+                ExecuteMsg::SetPriceSource {
+                    denom: "ujuno_uosmo_cl".to_string(),
+                    price_source: OsmosisPriceSourceUnchecked::ConcentratedLiquidityPosition {
+                        pool_id,
+                        lower_tick,
+                        upper_tick,
+                    },
+                },
+            )
+        };
+
+    // attempting to use not ConcentratedLiquidity pool
+    let err = set_price_source_cl_position(deps.as_mut(), 1, 0, 100).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "pool 1 is not a ConcentratedLiquidity pool".to_string()
+        }
+    );
+
+    // attempting to use invalid tick ranges
+    let err = set_price_source_cl_position(deps.as_mut(), 7777, 100, 100).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "lower tick must be less than upper tick".to_string()
+        }
+    );
+
+    let err = set_price_source_cl_position(deps.as_mut(), 7777, -108_000_100, 100).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "ticks must be within [-108000000, 342000000]".to_string()
+        }
+    );
+
+    let err = set_price_source_cl_position(deps.as_mut(), 7777, 0, 150).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "ticks must be multiples of the tick spacing 100".to_string()
+        }
+    );
+
+    // the pool assets must have price sources
+    let err = set_price_source_cl_position(deps.as_mut(), 7777, -1_000_000, 8_000_000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "missing price source for ujuno".to_string()
+        }
+    );
+
+    // the price sources of the pool assets can't use a spot price
+    helpers::set_price_source(
+        deps.as_mut(),
+        "ujuno",
+        OsmosisPriceSourceUnchecked::Spot {
+            pool_id: 7777,
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uosmo",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    let err = set_price_source_cl_position(deps.as_mut(), 7777, -1_000_000, 8_000_000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "price source for ujuno can't use a spot price".to_string()
+        }
+    );
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "ujuno",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    // properly set cl position price source
+    let res = set_price_source_cl_position(deps.as_mut(), 7777, -1_000_000, 8_000_000).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "ujuno_uosmo_cl".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::ConcentratedLiquidityPosition {
+            pool_id: 7777,
+            lower_tick: -1_000_000,
+            upper_tick: 8_000_000,
+        }
+    );
+}
+
 #[test]
 fn setting_price_source_pyth_with_invalid_params() {
     let mut deps = helpers::setup_test();
@@ -1208,6 +1323,230 @@ fn setting_price_source_pyth_successfully() {
     );
 }
 
+#[test]
+fn setting_price_source_aggregated_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_aggregated =
+        |sources: Vec<OsmosisPriceSourceUnchecked>, min_sources: u8, max_deviation: Decimal| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner"),
+                ExecuteMsg::SetPriceSource {
+                    denom: "umars".to_string(),
+                    price_source: OsmosisPriceSourceUnchecked::Aggregated {
+                        sources,
+                        min_sources,
+                        max_deviation,
+                    },
+                },
+            )
+        };
+
+    let fixed = OsmosisPriceSourceUnchecked::Fixed {
+        price: Decimal::one(),
+    };
+
+    let err = set_price_source_aggregated(vec![fixed.clone()], 1, Decimal::percent(5)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "aggregated price source must have at least two sources".to_string()
+        }
+    );
+
+    let err =
+        set_price_source_aggregated(vec![fixed.clone(), fixed.clone()], 3, Decimal::percent(5))
+            .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "min_sources must be between 1 and 2".to_string()
+        }
+    );
+
+    let err = set_price_source_aggregated(vec![fixed.clone(), fixed.clone()], 1, Decimal::zero())
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "max_deviation must be greater than 0 and less than or equal to 1".to_string()
+        }
+    );
+
+    let nested = OsmosisPriceSourceUnchecked::Aggregated {
+        sources: vec![fixed.clone(), fixed.clone()],
+        min_sources: 1,
+        max_deviation: Decimal::percent(5),
+    };
+    let err = set_price_source_aggregated(vec![fixed, nested], 1, Decimal::percent(5)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "aggregated price sources can't be nested".to_string()
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_managed_vault_share() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    deps.querier.set_managed_vault_info(
+        "managed_vault",
+        helpers::prepare_managed_vault_info(1_000_000, 10u128.pow(12)),
+    );
+
+    let price_source = OsmosisPriceSourceUnchecked::MarsManagedVaultShare {
+        vault_addr: "managed_vault".to_string(),
+        max_daily_growth: Decimal::percent(1),
+    };
+
+    // the denom must be the vault token
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "umars".to_string(),
+            price_source: price_source.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "vault token does not match. Vault token returned by contract: factory/managed_vault/mvault, expected: umars".to_string()
+        }
+    );
+
+    // the base token must have a price source
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "factory/managed_vault/mvault".to_string(),
+            price_source: price_source.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "missing price source for uatom".to_string()
+        }
+    );
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    // the max daily growth must be within (0, 1]
+    for max_daily_growth in [Decimal::zero(), Decimal::percent(101)] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceSource {
+                denom: "factory/managed_vault/mvault".to_string(),
+                price_source: OsmosisPriceSourceUnchecked::MarsManagedVaultShare {
+                    vault_addr: "managed_vault".to_string(),
+                    max_daily_growth,
+                },
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceSource {
+                reason: "max_daily_growth must be greater than 0 and less than or equal to 1"
+                    .to_string()
+            }
+        );
+    }
+
+    helpers::set_price_source(deps.as_mut(), "factory/managed_vault/mvault", price_source);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "factory/managed_vault/mvault".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::MarsManagedVaultShare {
+            vault_addr: Addr::unchecked("managed_vault"),
+            max_daily_growth: Decimal::percent(1),
+        }
+    );
+
+    // the first share price is recorded along with the price source
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "factory/managed_vault/mvault".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_ratio(1u128, 1_000_000u128));
+}
+
+#[test]
+fn setting_price_source_with_fallback_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_with_fallback =
+        |primary: OsmosisPriceSourceUnchecked, max_divergence: Decimal| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner"),
+                ExecuteMsg::SetPriceSource {
+                    denom: "umars".to_string(),
+                    price_source: OsmosisPriceSourceUnchecked::WithFallback {
+                        primary: Box::new(primary),
+                        fallback: Box::new(OsmosisPriceSourceUnchecked::Fixed {
+                            price: Decimal::one(),
+                        }),
+                        max_divergence,
+                    },
+                },
+            )
+        };
+
+    let fixed = OsmosisPriceSourceUnchecked::Fixed {
+        price: Decimal::one(),
+    };
+
+    let err = set_price_source_with_fallback(fixed.clone(), Decimal::zero()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "max_divergence must be greater than 0 and less than or equal to 1".to_string()
+        }
+    );
+
+    let nested = OsmosisPriceSourceUnchecked::WithFallback {
+        primary: Box::new(fixed.clone()),
+        fallback: Box::new(fixed),
+        max_divergence: Decimal::percent(5),
+    };
+    let err = set_price_source_with_fallback(nested, Decimal::percent(5)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "price sources with fallback can't be nested".to_string()
+        }
+    );
+}
+
 #[test]
 fn querying_price_source() {
     let mut deps = helpers::setup_test_with_pools();
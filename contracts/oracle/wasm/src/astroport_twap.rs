@@ -1,17 +1,34 @@
-use cosmwasm_std::{attr, Attribute, DepsMut, Env, Response};
+use cosmwasm_std::{attr, Addr, Attribute, DepsMut, Env, Response};
 use mars_oracle_base::{ContractError, ContractResult};
 use mars_types::oracle::AstroportTwapSnapshot;
 
 use crate::{
-    contract::WasmOracle, helpers::query_astroport_cumulative_price,
-    state::ASTROPORT_TWAP_SNAPSHOTS, WasmPriceSourceChecked,
+    contract::WasmOracle,
+    duality::{next_duality_price_cumulative, query_duality_spot_price},
+    helpers::query_astroport_cumulative_price,
+    state::{ASTROPORT_TWAP_SNAPSHOTS, DUALITY_SPOT_PRICES},
+    WasmPriceSourceChecked,
 };
 
+/// Where the cumulative price of a TWAP price source comes from
+enum CumulativePriceSource {
+    /// Queried from the Astroport pair
+    Astroport {
+        pair_address: Addr,
+    },
+    /// Accumulated by the contract from Duality spot prices
+    Duality {
+        other_denom: String,
+        denom_decimals: u8,
+    },
+}
+
 pub trait ExecuteTwapSnapshots {
     fn execute_record_astroport_twap_snapshots(
         &self,
         deps: DepsMut,
         env: Env,
+        sender_addr: Addr,
         denoms: Vec<String>,
     ) -> ContractResult<Response>;
 }
@@ -21,6 +38,7 @@ impl ExecuteTwapSnapshots for WasmOracle<'_> {
         &self,
         deps: DepsMut,
         env: Env,
+        sender_addr: Addr,
         denoms: Vec<String>,
     ) -> ContractResult<Response> {
         let timestamp = env.block.time.seconds();
@@ -30,17 +48,47 @@ impl ExecuteTwapSnapshots for WasmOracle<'_> {
             let price_source = self.price_sources.load(deps.storage, &denom)?;
 
             // Asset must be configured to use TWAP price source
-            let (pair_address, window_size, tolerance) = match price_source {
+            let (source, window_size, tolerance) = match price_source {
                 WasmPriceSourceChecked::AstroportTwap {
                     pair_address,
                     window_size,
                     tolerance,
-                } => (pair_address, window_size, tolerance),
+                } => (
+                    CumulativePriceSource::Astroport {
+                        pair_address,
+                    },
+                    window_size,
+                    tolerance,
+                ),
                 WasmPriceSourceChecked::Lsd {
                     transitive_denom: _,
                     twap,
                     redemption_rate: _,
-                } => (twap.pair_address, twap.window_size, twap.tolerance),
+                } => (
+                    CumulativePriceSource::Astroport {
+                        pair_address: twap.pair_address,
+                    },
+                    twap.window_size,
+                    twap.tolerance,
+                ),
+                WasmPriceSourceChecked::DualityTwap {
+                    other_denom,
+                    denom_decimals,
+                    window_size,
+                    tolerance,
+                } => {
+                    // Unlike Astroport pairs, Duality pools don't accumulate prices, so the spot
+                    // prices recorded by anyone could be manipulated within the recording block
+                    self.assert_owner_or_keeper(deps.storage, &sender_addr)?;
+                    (
+                        CumulativePriceSource::Duality {
+                            other_denom,
+                            denom_decimals,
+                        },
+                        window_size,
+                        tolerance,
+                    )
+                }
                 _ => {
                     return Err(ContractError::PriceSourceNotTwap {});
                 }
@@ -61,8 +109,32 @@ impl ExecuteTwapSnapshots for WasmOracle<'_> {
             }
 
             // Query new price data
-            let price_cumulative =
-                query_astroport_cumulative_price(&deps.querier, &pair_address, &denom)?;
+            let price_cumulative = match source {
+                CumulativePriceSource::Astroport {
+                    pair_address,
+                } => query_astroport_cumulative_price(&deps.querier, &pair_address, &denom)?,
+                CumulativePriceSource::Duality {
+                    other_denom,
+                    denom_decimals,
+                } => {
+                    let spot_price = query_duality_spot_price(
+                        &deps.as_ref(),
+                        &env,
+                        &denom,
+                        &other_denom,
+                        denom_decimals,
+                    )?;
+                    let last_spot_price = DUALITY_SPOT_PRICES.may_load(deps.storage, &denom)?;
+                    DUALITY_SPOT_PRICES.save(deps.storage, &denom, &spot_price)?;
+
+                    // Snapshots recorded without a spot price (e.g. by a previous Astroport TWAP
+                    // price source) don't belong to the same accumulation
+                    if last_spot_price.is_none() {
+                        snapshots.clear();
+                    }
+                    next_duality_price_cumulative(snapshots.last().zip(last_spot_price), timestamp)?
+                }
+            };
 
             // Purge snapshots that are too old, i.e. more than (window_size + tolerance) away from the
             // current timestamp. These snapshots will never be used in the future for calculating
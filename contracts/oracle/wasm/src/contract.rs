@@ -69,9 +69,11 @@ pub mod entry {
             ExecuteMsg::Custom(custom_msg) => match custom_msg {
                 WasmOracleCustomExecuteMsg::RecordTwapSnapshots {
                     denoms,
-                } => contract.execute_record_astroport_twap_snapshots(deps, env, denoms),
+                } => {
+                    contract.execute_record_astroport_twap_snapshots(deps, env, info.sender, denoms)
+                }
             },
-            _ => contract.execute(deps, info, msg),
+            _ => contract.execute(deps, env, info, msg),
         }
     }
 
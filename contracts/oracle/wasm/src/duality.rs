@@ -0,0 +1,198 @@
+use cosmwasm_std::{Decimal, Deps, Env, StdError, Uint128};
+use cw_storage_plus::Map;
+use mars_oracle_base::{ContractError, ContractResult, PriceSourceChecked, MAX_DENOM_DECIMALS};
+use mars_types::oracle::{ActionKind, AstroportTwapSnapshot, Config};
+use neutron_sdk::stargate::dex::{
+    query::get_estimate_multi_hop_swap, types::EstimateMultiHopSwapRequest,
+};
+
+use crate::{
+    helpers::period_diff,
+    price_source::{WasmPriceSourceChecked, PRICE_PRECISION},
+    state::ASTROPORT_TWAP_SNAPSHOTS,
+};
+
+/// Lowest exit limit price accepted by Duality, i.e. 10^-27 (PrecDec has 27 decimal places and
+/// the limit is passed as the raw integer value). We only simulate the swap, so we accept any price.
+const MIN_EXIT_LIMIT_PRICE: &str = "1";
+
+/// Assert Duality price source configuration
+pub fn assert_duality(
+    deps: &Deps,
+    denom: &str,
+    other_denom: &str,
+    base_denom: &str,
+    denom_decimals: u8,
+    price_sources: &Map<&str, WasmPriceSourceChecked>,
+) -> ContractResult<()> {
+    if denom_decimals > MAX_DENOM_DECIMALS {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("denom_decimals must be <= {}", MAX_DENOM_DECIMALS),
+        });
+    }
+
+    if other_denom == denom {
+        return Err(ContractError::InvalidPriceSource {
+            reason: "other_denom must be different from the denom".to_string(),
+        });
+    }
+
+    if other_denom != base_denom && !price_sources.has(deps.storage, other_denom) {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!("missing price source for {}", other_denom),
+        });
+    }
+
+    Ok(())
+}
+
+/// Queries the spot price of `denom` denominated in `other_denom` from the Duality DEX, by
+/// simulating a swap of one unit (10^denom_decimals) of `denom`.
+pub fn query_duality_spot_price(
+    deps: &Deps,
+    env: &Env,
+    denom: &str,
+    other_denom: &str,
+    denom_decimals: u8,
+) -> ContractResult<Decimal> {
+    let one = Uint128::new(10_u128.pow(denom_decimals.into()));
+
+    let res = get_estimate_multi_hop_swap(
+        *deps,
+        EstimateMultiHopSwapRequest {
+            creator: env.contract.address.to_string(),
+            receiver: env.contract.address.to_string(),
+            routes: vec![vec![denom.to_string(), other_denom.to_string()]],
+            amount_in: one.to_string(),
+            exit_limit_price: MIN_EXIT_LIMIT_PRICE.to_string(),
+            pick_best_route: true,
+        },
+    )
+    .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let price = Decimal::checked_from_ratio(res.coin_out.amount, one)?;
+    if price.is_zero() {
+        return Err(ContractError::InvalidPrice {
+            reason: format!("no liquidity for {denom} in Duality pool with {other_denom}"),
+        });
+    }
+
+    Ok(price)
+}
+
+/// Duality pools don't track cumulative prices, so the contract accumulates the spot prices it
+/// records. The spot price recorded with the last snapshot is held over the period until the new
+/// snapshot, so that a spot price never weighs on the period before it was recorded.
+pub fn next_duality_price_cumulative(
+    last: Option<(&AstroportTwapSnapshot, Decimal)>,
+    timestamp: u64,
+) -> ContractResult<Uint128> {
+    let Some((last_snapshot, last_spot_price)) = last else {
+        return Ok(Uint128::zero());
+    };
+
+    let period = timestamp - last_snapshot.timestamp;
+    let price_delta = PRICE_PRECISION.mul_floor(last_spot_price).checked_mul(period.into())?;
+
+    Ok(last_snapshot.price_cumulative.checked_add(price_delta)?)
+}
+
+/// Queries the TWAP price of `denom` from the spot price snapshots recorded by the contract
+#[allow(clippy::too_many_arguments)]
+pub fn query_duality_twap_price(
+    deps: &Deps,
+    env: &Env,
+    denom: &str,
+    other_denom: &str,
+    config: &Config,
+    price_sources: &Map<&str, WasmPriceSourceChecked>,
+    window_size: u64,
+    tolerance: u64,
+    kind: ActionKind,
+) -> ContractResult<Decimal> {
+    let snapshots = ASTROPORT_TWAP_SNAPSHOTS
+        .may_load(deps.storage, denom)?
+        .ok_or(ContractError::NoSnapshots {})?;
+
+    if snapshots.len() < 2 {
+        return Err(ContractError::NotEnoughSnapshots {});
+    }
+
+    // The cumulative price can't be queried from the pool, so the most recent snapshot is used as
+    // the end of the period. It can't be older than `tolerance`.
+    let current_snapshot = snapshots.last().ok_or(ContractError::NoSnapshots {})?;
+    if env.block.time.seconds() - current_snapshot.timestamp > tolerance {
+        return Err(ContractError::NoSnapshotWithinTolerance {});
+    }
+
+    let previous_snapshot = snapshots
+        .iter()
+        .find(|snapshot| period_diff(current_snapshot, snapshot, window_size) <= tolerance)
+        .ok_or(ContractError::NoSnapshotWithinTolerance {})?;
+
+    let period = current_snapshot.timestamp - previous_snapshot.timestamp;
+    if period == 0 {
+        return Err(ContractError::NoSnapshotWithinTolerance {});
+    }
+
+    let price_delta = current_snapshot.price_cumulative - previous_snapshot.price_cumulative;
+    let price = Decimal::from_ratio(price_delta, PRICE_PRECISION.checked_mul(period.into())?);
+
+    normalize_duality_price(deps, env, config, price_sources, other_denom, price, kind)
+}
+
+/// Convert a price denominated in `other_denom` to the base denom
+pub fn normalize_duality_price(
+    deps: &Deps,
+    env: &Env,
+    config: &Config,
+    price_sources: &Map<&str, WasmPriceSourceChecked>,
+    other_denom: &str,
+    price: Decimal,
+    kind: ActionKind,
+) -> ContractResult<Decimal> {
+    if other_denom == config.base_denom {
+        return Ok(price);
+    }
+
+    let other_price = price_sources.load(deps.storage, other_denom)?.query_price(
+        deps,
+        env,
+        other_denom,
+        config,
+        price_sources,
+        kind,
+    )?;
+
+    Ok(price.checked_mul(other_price)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spot_price_is_held_until_the_next_snapshot() {
+        // the first snapshot starts the accumulation
+        let cumulative = next_duality_price_cumulative(None, 1_000).unwrap();
+        assert_eq!(cumulative, Uint128::zero());
+
+        // the spot price recorded with the first snapshot is accumulated over the following period,
+        // whatever the spot price at the time of the new snapshot
+        let first = AstroportTwapSnapshot {
+            timestamp: 1_000,
+            price_cumulative: cumulative,
+        };
+        let cumulative =
+            next_duality_price_cumulative(Some((&first, Decimal::percent(200))), 1_060).unwrap();
+        assert_eq!(cumulative, PRICE_PRECISION * Uint128::new(120));
+
+        let second = AstroportTwapSnapshot {
+            timestamp: 1_060,
+            price_cumulative: cumulative,
+        };
+        let cumulative =
+            next_duality_price_cumulative(Some((&second, Decimal::percent(50))), 1_120).unwrap();
+        assert_eq!(cumulative, PRICE_PRECISION * Uint128::new(150));
+    }
+}
@@ -1,5 +1,6 @@
 mod astroport_twap;
 pub mod contract;
+mod duality;
 pub mod helpers;
 pub mod lp_pricing;
 pub mod migrations;
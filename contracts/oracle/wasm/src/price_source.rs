@@ -12,6 +12,7 @@ use cosmwasm_std::{from_json, Addr, Decimal, Deps, Empty, Env, Uint128};
 use cw_storage_plus::Map;
 use mars_oracle_base::{
     is_one_followed_by_zeros, lp_pricing,
+    managed_vault::{assert_managed_vault_share, query_managed_vault_share_token_price},
     redemption_rate::{assert_rr_not_too_old, query_redemption_rate, RedemptionRate},
     ContractError, ContractResult, PriceSourceChecked, PriceSourceUnchecked, USD_DENOM,
 };
@@ -19,6 +20,9 @@ use mars_types::oracle::{ActionKind, AstroportTwapSnapshot, Config};
 use pyth_sdk_cw::PriceIdentifier;
 
 use crate::{
+    duality::{
+        assert_duality, normalize_duality_price, query_duality_spot_price, query_duality_twap_price,
+    },
     helpers::{
         adjust_precision, astro_native_asset, get_astroport_pair_denoms,
         get_other_astroport_pair_denom, normalize_price, period_diff, query_astroport_config,
@@ -65,6 +69,39 @@ pub enum WasmPriceSource<A> {
         /// The tolerance in seconds for the sliding TWAP window.
         tolerance: u64,
     },
+    /// Duality spot price, based on a simulated swap of one unit of the denom into the other denom
+    /// of the pair
+    DualitySpot {
+        /// The other denom of the Duality pair. Its price source should be available in the Mars
+        /// Oracle contract, unless it's the base denom.
+        other_denom: String,
+        /// Number of decimals of the denom, used to simulate a swap of one unit
+        denom_decimals: u8,
+    },
+    /// Duality TWAP price
+    ///
+    /// Duality pools don't track cumulative prices, so the contract records spot price snapshots
+    /// (see `RecordTwapSnapshots`, only callable by owner or keeper for this price source) and
+    /// accumulates them, each spot price being held until the next snapshot. The average price is
+    /// calculated the same way as for `AstroportTwap`, between the most recent snapshot (which
+    /// can't be older than tolerance) and a snapshot in the range of window_size +/- tolerance.
+    ///
+    /// NOTE: The spot price is sampled rather than accumulated by the pool. A pool manipulated in
+    /// the block of a snapshot moves the average price by the manipulated price deviation times
+    /// the time until the next snapshot, relative to window_size. The keeper should record
+    /// snapshots every `tolerance` seconds, so that a single sample weighs about
+    /// tolerance / window_size of the average price.
+    DualityTwap {
+        /// The other denom of the Duality pair. Its price source should be available in the Mars
+        /// Oracle contract, unless it's the base denom.
+        other_denom: String,
+        /// Number of decimals of the denom, used to simulate a swap of one unit
+        denom_decimals: u8,
+        /// The size of the sliding TWAP window in seconds.
+        window_size: u64,
+        /// The tolerance in seconds for the sliding TWAP window.
+        tolerance: u64,
+    },
     Pyth {
         /// Contract address of Pyth
         contract_addr: A,
@@ -178,6 +215,48 @@ pub enum WasmPriceSource<A> {
         /// rejecting the price as too stale
         max_blocks_old: u8,
     },
+    /// Median of the prices returned by several price sources. Sources failing to return a price
+    /// (e.g. a stale Pyth feed) are skipped.
+    Aggregated {
+        /// Price sources to aggregate. They can't be aggregated price sources themselves.
+        sources: Vec<WasmPriceSource<A>>,
+
+        /// The minimum number of sources that must return a price
+        min_sources: u8,
+
+        /// The maximum deviation (percentage) between the highest and lowest price returned by
+        /// the sources, relative to the median
+        max_deviation: Decimal,
+    },
+    /// Price source with a fallback, used for liquidations when the primary source fails to return
+    /// a price (e.g. a stale Pyth feed). Other actions only use the primary source.
+    WithFallback {
+        /// Price source used by default. It can't have a fallback itself.
+        primary: Box<WasmPriceSource<A>>,
+
+        /// Price source used for liquidations if the primary source is unavailable. It can't have
+        /// a fallback itself.
+        fallback: Box<WasmPriceSource<A>>,
+
+        /// The maximum divergence (percentage) between the primary and fallback prices, relative
+        /// to the primary price, when both are available. Only checked for default actions.
+        max_divergence: Decimal,
+    },
+    /// Mars managed vault share token. Its price is the vault's share price (total base tokens per
+    /// vault token) multiplied by the price of the vault's base token.
+    ///
+    /// NOTE: The base token price source should be available in the Mars Oracle contract. The
+    /// share price is recorded when the price source is set, and then with
+    /// `RecordManagedVaultSharePrices`, as the share price can only grow by a bounded rate since
+    /// the last recorded one.
+    MarsManagedVaultShare {
+        /// Address of the managed vault
+        vault_addr: A,
+
+        /// The maximum growth (percentage) of the share price per day since the last recorded
+        /// share price
+        max_daily_growth: Decimal,
+    },
 }
 
 #[cw_serde]
@@ -213,6 +292,16 @@ impl fmt::Display for WasmPriceSourceChecked {
                     "astroport_twap:{pair_address}. Window Size: {window_size}. Tolerance: {tolerance}."
                 )
             }
+            WasmPriceSource::DualitySpot {
+                other_denom,
+                denom_decimals,
+            } => format!("duality_spot:{other_denom}:{denom_decimals}"),
+            WasmPriceSource::DualityTwap {
+                other_denom,
+                denom_decimals,
+                window_size,
+                tolerance,
+            } => format!("duality_twap:{other_denom}:{denom_decimals}:{window_size}:{tolerance}"),
             WasmPriceSource::Pyth {
                 contract_addr,
                 price_feed_id,
@@ -251,6 +340,16 @@ impl fmt::Display for WasmPriceSourceChecked {
             WasmPriceSource::Slinky { base_symbol, denom_decimals, max_blocks_old } => {
                 format!("slinky:{base_symbol}:{denom_decimals}:{max_blocks_old}")
             },
+            WasmPriceSource::Aggregated { sources, min_sources, max_deviation } => {
+                let sources = sources.iter().map(|source| source.to_string()).collect::<Vec<_>>();
+                format!("aggregated:{min_sources}:{max_deviation}:[{}]", sources.join(","))
+            },
+            WasmPriceSource::WithFallback { primary, fallback, max_divergence } => {
+                format!("with_fallback:{max_divergence}:[{primary},{fallback}]")
+            },
+            WasmPriceSource::MarsManagedVaultShare { vault_addr, max_daily_growth } => {
+                format!("mars_managed_vault_share:{vault_addr}:{max_daily_growth}")
+            },
         };
         write!(f, "{label}")
     }
@@ -335,6 +434,52 @@ impl PriceSourceUnchecked<WasmPriceSourceChecked, Empty> for WasmPriceSourceUnch
                     tolerance,
                 })
             }
+            WasmPriceSource::DualitySpot {
+                other_denom,
+                denom_decimals,
+            } => {
+                assert_duality(
+                    deps,
+                    denom,
+                    &other_denom,
+                    base_denom,
+                    denom_decimals,
+                    price_sources,
+                )?;
+
+                Ok(WasmPriceSourceChecked::DualitySpot {
+                    other_denom,
+                    denom_decimals,
+                })
+            }
+            WasmPriceSource::DualityTwap {
+                other_denom,
+                denom_decimals,
+                window_size,
+                tolerance,
+            } => {
+                if tolerance >= window_size {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: "tolerance must be less than window size".to_string(),
+                    });
+                }
+
+                assert_duality(
+                    deps,
+                    denom,
+                    &other_denom,
+                    base_denom,
+                    denom_decimals,
+                    price_sources,
+                )?;
+
+                Ok(WasmPriceSourceChecked::DualityTwap {
+                    other_denom,
+                    denom_decimals,
+                    window_size,
+                    tolerance,
+                })
+            }
             WasmPriceSource::Pyth {
                 contract_addr,
                 price_feed_id,
@@ -519,6 +664,84 @@ impl PriceSourceUnchecked<WasmPriceSourceChecked, Empty> for WasmPriceSourceUnch
                     max_blocks_old,
                 })
             }
+            WasmPriceSource::Aggregated {
+                sources,
+                min_sources,
+                max_deviation,
+            } => {
+                mars_oracle_base::aggregated::assert_aggregated(
+                    sources.len(),
+                    min_sources,
+                    max_deviation,
+                )?;
+
+                let sources = sources
+                    .into_iter()
+                    .map(|source| {
+                        if matches!(source, WasmPriceSource::Aggregated { .. }) {
+                            return Err(ContractError::InvalidPriceSource {
+                                reason: "aggregated price sources can't be nested".to_string(),
+                            });
+                        }
+                        source.validate(deps, denom, base_denom, price_sources)
+                    })
+                    .collect::<ContractResult<Vec<_>>>()?;
+
+                Ok(WasmPriceSourceChecked::Aggregated {
+                    sources,
+                    min_sources,
+                    max_deviation,
+                })
+            }
+            WasmPriceSource::WithFallback {
+                primary,
+                fallback,
+                max_divergence,
+            } => {
+                mars_oracle_base::fallback::assert_with_fallback(max_divergence)?;
+
+                if matches!(*primary, WasmPriceSource::WithFallback { .. })
+                    || matches!(*fallback, WasmPriceSource::WithFallback { .. })
+                {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: "price sources with fallback can't be nested".to_string(),
+                    });
+                }
+
+                Ok(WasmPriceSourceChecked::WithFallback {
+                    primary: Box::new((*primary).validate(
+                        deps,
+                        denom,
+                        base_denom,
+                        price_sources,
+                    )?),
+                    fallback: Box::new((*fallback).validate(
+                        deps,
+                        denom,
+                        base_denom,
+                        price_sources,
+                    )?),
+                    max_divergence,
+                })
+            }
+            WasmPriceSource::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => {
+                let vault_addr = deps.api.addr_validate(&vault_addr)?;
+                assert_managed_vault_share(
+                    deps,
+                    denom,
+                    &vault_addr,
+                    max_daily_growth,
+                    price_sources,
+                )?;
+
+                Ok(WasmPriceSourceChecked::MarsManagedVaultShare {
+                    vault_addr,
+                    max_daily_growth,
+                })
+            }
         }
     }
 }
@@ -565,6 +788,30 @@ impl PriceSourceChecked<Empty> for WasmPriceSourceChecked {
                 kind,
                 true,
             ),
+            WasmPriceSource::DualitySpot {
+                other_denom,
+                denom_decimals,
+            } => {
+                let price =
+                    query_duality_spot_price(deps, env, denom, other_denom, *denom_decimals)?;
+                normalize_duality_price(deps, env, config, price_sources, other_denom, price, kind)
+            }
+            WasmPriceSource::DualityTwap {
+                other_denom,
+                window_size,
+                tolerance,
+                ..
+            } => query_duality_twap_price(
+                deps,
+                env,
+                denom,
+                other_denom,
+                config,
+                price_sources,
+                *window_size,
+                *tolerance,
+                kind,
+            ),
             WasmPriceSource::Pyth {
                 contract_addr,
                 price_feed_id,
@@ -674,6 +921,84 @@ impl PriceSourceChecked<Empty> for WasmPriceSourceChecked {
                 *denom_decimals,
                 *max_blocks_old,
             ),
+            WasmPriceSource::Aggregated {
+                sources,
+                min_sources,
+                max_deviation,
+            } => {
+                let prices = sources
+                    .iter()
+                    .filter_map(|source| {
+                        source
+                            .query_price(deps, env, denom, config, price_sources, kind.clone())
+                            .ok()
+                    })
+                    .collect();
+                mars_oracle_base::aggregated::aggregate_prices(prices, *min_sources, *max_deviation)
+            }
+            WasmPriceSource::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => query_managed_vault_share_token_price(
+                deps,
+                env,
+                config,
+                price_sources,
+                vault_addr,
+                *max_daily_growth,
+                kind,
+            ),
+            WasmPriceSource::WithFallback {
+                ..
+            } => self
+                .query_price_with_fallback_info(deps, env, denom, config, price_sources, kind)
+                .map(|(price, _)| price),
+        }
+    }
+
+    fn query_price_with_fallback_info(
+        &self,
+        deps: &Deps,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<(Decimal, bool)> {
+        match self {
+            WasmPriceSource::WithFallback {
+                primary,
+                fallback,
+                max_divergence,
+            } => mars_oracle_base::fallback::resolve_with_fallback(
+                primary.query_price(deps, env, denom, config, price_sources, kind.clone()),
+                || fallback.query_price(deps, env, denom, config, price_sources, kind.clone()),
+                *max_divergence,
+                kind.clone(),
+            ),
+            _ => {
+                let price = self.query_price(deps, env, denom, config, price_sources, kind)?;
+                Ok((price, false))
+            }
+        }
+    }
+
+    fn managed_vaults(&self) -> Vec<(Addr, Decimal)> {
+        match self {
+            WasmPriceSource::MarsManagedVaultShare {
+                vault_addr,
+                max_daily_growth,
+            } => vec![(vault_addr.clone(), *max_daily_growth)],
+            WasmPriceSource::Aggregated {
+                sources,
+                ..
+            } => sources.iter().flat_map(|source| source.managed_vaults()).collect(),
+            WasmPriceSource::WithFallback {
+                primary,
+                fallback,
+                ..
+            } => [primary, fallback].iter().flat_map(|source| source.managed_vaults()).collect(),
+            _ => vec![],
         }
     }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Decimal};
 use cw_storage_plus::{Item, Map};
 use mars_types::oracle::AstroportTwapSnapshot;
 
@@ -7,3 +7,7 @@ pub const ASTROPORT_FACTORY: Item<Addr> = Item::new("astroport_factory");
 
 /// TWAP snapshots indexed by denom
 pub const ASTROPORT_TWAP_SNAPSHOTS: Map<&str, Vec<AstroportTwapSnapshot>> = Map::new("snapshots");
+
+/// The Duality spot price recorded with the most recent TWAP snapshot, indexed by denom. It's
+/// accumulated over the period until the next snapshot.
+pub const DUALITY_SPOT_PRICES: Map<&str, Decimal> = Map::new("duality_spot_prices");
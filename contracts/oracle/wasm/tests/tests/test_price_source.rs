@@ -157,6 +157,174 @@ fn validate_fixed_price_source() {
     assert!(res.is_ok());
 }
 
+#[test]
+fn display_aggregated_price_source() {
+    let ps = WasmPriceSource::Aggregated {
+        sources: vec![
+            WasmPriceSource::Fixed {
+                price: Decimal::from_ratio(1u128, 2u128),
+            },
+            WasmPriceSource::Fixed {
+                price: ONE,
+            },
+        ],
+        min_sources: 2,
+        max_deviation: Decimal::percent(5),
+    };
+    assert_eq!(ps.to_string(), "aggregated:2:0.05:[fixed:0.5,fixed:1]")
+}
+
+#[test]
+fn validate_aggregated_price_source() {
+    let deps = mock_dependencies();
+    let price_sources = Map::new("price_sources");
+    let fixed = WasmPriceSourceUnchecked::Fixed {
+        price: ONE,
+    };
+
+    let ps = WasmPriceSource::Aggregated {
+        sources: vec![fixed.clone(), fixed.clone()],
+        min_sources: 1,
+        max_deviation: Decimal::percent(5),
+    };
+    let res = ps.clone().validate(&deps.as_ref(), "uosmo", "uusd", &price_sources);
+    assert!(res.is_ok());
+
+    let nested = WasmPriceSource::Aggregated {
+        sources: vec![fixed, ps],
+        min_sources: 1,
+        max_deviation: Decimal::percent(5),
+    };
+    let err = nested.validate(&deps.as_ref(), "uosmo", "uusd", &price_sources).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "aggregated price sources can't be nested".to_string()
+        }
+    );
+}
+
+#[test]
+fn display_with_fallback_price_source() {
+    let ps = WasmPriceSource::WithFallback {
+        primary: Box::new(WasmPriceSource::Fixed {
+            price: ONE,
+        }),
+        fallback: Box::new(WasmPriceSource::Fixed {
+            price: Decimal::from_ratio(1u128, 2u128),
+        }),
+        max_divergence: Decimal::percent(5),
+    };
+    assert_eq!(ps.to_string(), "with_fallback:0.05:[fixed:1,fixed:0.5]")
+}
+
+#[test]
+fn display_managed_vault_share_price_source() {
+    let ps = WasmPriceSource::MarsManagedVaultShare {
+        vault_addr: Addr::unchecked("managed_vault"),
+        max_daily_growth: Decimal::percent(1),
+    };
+    assert_eq!(ps.to_string(), "mars_managed_vault_share:managed_vault:0.01")
+}
+
+#[test]
+fn display_duality_price_sources() {
+    let ps = WasmPriceSource::DualitySpot {
+        other_denom: "untrn".to_string(),
+        denom_decimals: 6,
+    };
+    assert_eq!(ps.to_string(), "duality_spot:untrn:6");
+
+    let ps = WasmPriceSource::DualityTwap {
+        other_denom: "untrn".to_string(),
+        denom_decimals: 6,
+        window_size: 1800,
+        tolerance: 60,
+    };
+    assert_eq!(ps.to_string(), "duality_twap:untrn:6:1800:60")
+}
+
+#[test]
+fn validate_duality_price_sources() {
+    let deps = mock_dependencies();
+    let price_sources = Map::new("price_sources");
+
+    let ps = WasmPriceSource::DualitySpot {
+        other_denom: "uusd".to_string(),
+        denom_decimals: 6,
+    };
+    let res = ps.validate(&deps.as_ref(), "untrn", "uusd", &price_sources);
+    assert!(res.is_ok());
+
+    let ps = WasmPriceSource::DualitySpot {
+        other_denom: "uatom".to_string(),
+        denom_decimals: 6,
+    };
+    let err = ps.validate(&deps.as_ref(), "untrn", "uusd", &price_sources).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "missing price source for uatom".to_string()
+        }
+    );
+
+    let ps = WasmPriceSource::DualitySpot {
+        other_denom: "uusd".to_string(),
+        denom_decimals: 19,
+    };
+    let err = ps.validate(&deps.as_ref(), "untrn", "uusd", &price_sources).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "denom_decimals must be <= 18".to_string()
+        }
+    );
+
+    let ps = WasmPriceSource::DualityTwap {
+        other_denom: "uusd".to_string(),
+        denom_decimals: 6,
+        window_size: 60,
+        tolerance: 60,
+    };
+    let err = ps.validate(&deps.as_ref(), "untrn", "uusd", &price_sources).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "tolerance must be less than window size".to_string()
+        }
+    );
+}
+
+#[test]
+fn validate_with_fallback_price_source() {
+    let deps = mock_dependencies();
+    let price_sources = Map::new("price_sources");
+    let fixed = WasmPriceSourceUnchecked::Fixed {
+        price: ONE,
+    };
+
+    let ps = WasmPriceSource::WithFallback {
+        primary: Box::new(fixed.clone()),
+        fallback: Box::new(fixed.clone()),
+        max_divergence: Decimal::percent(5),
+    };
+    let res = ps.clone().validate(&deps.as_ref(), "uosmo", "uusd", &price_sources);
+    assert!(res.is_ok());
+
+    let nested = WasmPriceSource::WithFallback {
+        primary: Box::new(fixed),
+        fallback: Box::new(ps),
+        max_divergence: Decimal::percent(5),
+    };
+    let err = nested.validate(&deps.as_ref(), "uosmo", "uusd", &price_sources).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "price sources with fallback can't be nested".to_string()
+        }
+    );
+}
+
 #[test]
 fn test_set_price_source_fixed() {
     let owned_runner = get_test_runner();
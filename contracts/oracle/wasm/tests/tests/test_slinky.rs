@@ -484,6 +484,7 @@ fn querying_slinky_price_successfully() {
         PriceResponse {
             denom: "untrn".to_string(),
             price: Decimal::from_str("0.0052630171").unwrap(),
+            fallback_used: false,
         }
     );
 }
@@ -12,21 +12,25 @@ use crate::{
     emergency_powers::{
         disable_borrowing, disable_counterparty_vault_withdraw, disable_deleverage,
         disable_perp_trading, disable_withdraw_cm, disable_withdraw_rb, disallow_coin,
-        set_zero_deposit_cap, set_zero_max_ltv,
+        set_zero_borrow_cap, set_zero_deposit_cap, set_zero_max_ltv,
     },
     error::{ContractError, ContractResult},
     execute::{
-        reset_risk_manager, update_asset_params, update_config, update_managed_vault_config,
-        update_perp_params, update_vault_config,
+        reset_risk_manager, update_asset_params, update_config, update_emode_category,
+        update_managed_vault_config, update_perp_params, update_vault_config,
     },
     migrations,
     query::{
-        query_all_asset_params, query_all_asset_params_v2, query_all_perp_params,
-        query_all_perp_params_v2, query_all_total_deposits_v2, query_all_vault_configs,
-        query_all_vault_configs_v2, query_config, query_managed_vault_config, query_total_deposit,
-        query_vault_config,
+        query_all_asset_params, query_all_asset_params_v2, query_all_emode_categories,
+        query_all_perp_params, query_all_perp_params_v2, query_all_total_deposits_v2,
+        query_all_vault_configs, query_all_vault_configs_v2, query_config,
+        query_managed_vault_config, query_total_borrow, query_total_deposit,
+        query_total_isolated_debt, query_vault_config,
+    },
+    state::{
+        ADDRESS_PROVIDER, ASSET_PARAMS, EMODE_CATEGORIES, MAX_PERP_PARAMS, OWNER, PERP_PARAMS,
+        RISK_MANAGER,
     },
-    state::{ADDRESS_PROVIDER, ASSET_PARAMS, MAX_PERP_PARAMS, OWNER, PERP_PARAMS, RISK_MANAGER},
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -83,6 +87,7 @@ pub fn execute(
         ExecuteMsg::UpdateAssetParams(update) => update_asset_params(deps, info, update),
         ExecuteMsg::UpdateVaultConfig(update) => update_vault_config(deps, info, update),
         ExecuteMsg::UpdatePerpParams(update) => update_perp_params(deps, info, update),
+        ExecuteMsg::UpdateEModeCategory(update) => update_emode_category(deps, info, update),
         ExecuteMsg::EmergencyUpdate(update) => match update {
             EmergencyUpdate::RedBank(rb_u) => match rb_u {
                 RedBankEmergencyUpdate::DisableBorrowing(denom) => {
@@ -91,6 +96,9 @@ pub fn execute(
                 RedBankEmergencyUpdate::DisableWithdraw(denom) => {
                     disable_withdraw_rb(deps, info, &denom)
                 }
+                RedBankEmergencyUpdate::SetZeroBorrowCap(denom) => {
+                    set_zero_borrow_cap(deps, info, &denom)
+                }
             },
             EmergencyUpdate::CreditManager(rv_u) => match rv_u {
                 CmEmergencyUpdate::DisallowCoin(denom) => disallow_coin(deps, info, &denom),
@@ -158,6 +166,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             start_after,
             limit,
         } => to_json_binary(&query_all_perp_params_v2(deps, start_after, limit)?),
+        QueryMsg::EModeCategory {
+            name,
+        } => to_json_binary(&EMODE_CATEGORIES.may_load(deps.storage, &name)?),
+        QueryMsg::AllEModeCategories {
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_emode_categories(deps, start_after, limit)?),
         QueryMsg::TotalDeposit {
             denom,
         } => to_json_binary(&query_total_deposit(deps, &env, denom)?),
@@ -165,6 +180,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             start_after,
             limit,
         } => to_json_binary(&query_all_total_deposits_v2(deps, start_after, limit)?),
+        QueryMsg::TotalBorrow {
+            denom,
+        } => to_json_binary(&query_total_borrow(deps, &env, denom)?),
+        QueryMsg::TotalIsolatedDebt {
+            denom,
+        } => to_json_binary(&query_total_isolated_debt(deps, denom)?),
     };
     res.map_err(Into::into)
 }
@@ -177,5 +198,6 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
             reserve_factor,
             interest_rate_model,
         } => migrations::v2_3_0::migrate(deps, reserve_factor, interest_rate_model),
+        MigrateMsg::V2_4_0 {} => migrations::v2_4_0::migrate(deps),
     }
 }
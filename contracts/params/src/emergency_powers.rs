@@ -105,6 +105,24 @@ pub fn disable_withdraw_rb(
     Ok(response)
 }
 
+pub fn set_zero_borrow_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: &str,
+) -> Result<Response, ContractError> {
+    OWNER.assert_emergency_owner(deps.storage, &info.sender)?;
+
+    let mut params = ASSET_PARAMS.load(deps.storage, denom)?;
+    params.borrow_cap = Uint128::zero();
+    ASSET_PARAMS.save(deps.storage, denom, &params)?;
+
+    let response = Response::new()
+        .add_attribute("action", "emergency_set_zero_borrow_cap")
+        .add_attribute("denom", denom.to_string());
+
+    Ok(response)
+}
+
 pub fn disable_withdraw_cm(
     deps: DepsMut,
     info: MessageInfo,
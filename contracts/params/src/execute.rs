@@ -8,8 +8,8 @@ use mars_types::{
     adapters::oracle::OracleBase,
     address_provider::{self, helpers::query_contract_addr, MarsAddressType},
     params::{
-        AssetParams, AssetParamsUpdate, ManagedVaultConfigUpdate, PerpParams, PerpParamsUpdate,
-        VaultConfigUpdate,
+        AssetParams, AssetParamsUpdate, EModeCategoryUpdate, ManagedVaultConfigUpdate, PerpParams,
+        PerpParamsUpdate, VaultConfigUpdate,
     },
     perps::ExecuteMsg,
     red_bank::{ExecuteMsg as RedBankExecuteMsg, MarketParams, MarketParamsUpdate},
@@ -19,9 +19,9 @@ use mars_utils::helpers::option_string_to_addr;
 use crate::{
     error::{ContractError, ContractResult},
     state::{
-        ADDRESS_PROVIDER, ASSET_PARAMS, BLACKLISTED_VAULTS, MANAGED_VAULT_CODE_IDS,
-        MANAGED_VAULT_MIN_CREATION_FEE_IN_UUSD, MAX_PERP_PARAMS, OWNER, PERP_PARAMS, RISK_MANAGER,
-        RISK_MANAGER_KEY, VAULT_CONFIGS,
+        ADDRESS_PROVIDER, ASSET_PARAMS, BLACKLISTED_VAULTS, EMODE_CATEGORIES,
+        MANAGED_VAULT_CODE_IDS, MANAGED_VAULT_MIN_CREATION_FEE_IN_UUSD, MAX_PERP_PARAMS, OWNER,
+        PERP_PARAMS, RISK_MANAGER, RISK_MANAGER_KEY, VAULT_CONFIGS,
     },
 };
 
@@ -179,6 +179,30 @@ pub fn update_vault_config(
     Ok(response)
 }
 
+pub fn update_emode_category(
+    deps: DepsMut,
+    info: MessageInfo,
+    update: EModeCategoryUpdate,
+) -> ContractResult<Response> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+
+    let mut response = Response::new().add_attribute("action", "update_emode_category");
+
+    match update {
+        EModeCategoryUpdate::AddOrUpdate {
+            category,
+        } => {
+            category.validate()?;
+            EMODE_CATEGORIES.save(deps.storage, &category.name, &category)?;
+            response = response
+                .add_attribute("action_type", "add_or_update")
+                .add_attribute("name", category.name);
+        }
+    }
+
+    Ok(response)
+}
+
 pub fn update_perp_params(
     deps: DepsMut,
     info: MessageInfo,
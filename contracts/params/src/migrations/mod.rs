@@ -1,2 +1,3 @@
 pub mod v2_2_3;
 pub mod v2_3_0;
+pub mod v2_4_0;
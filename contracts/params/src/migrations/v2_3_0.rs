@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal, DepsMut, Order, Response, StdResult};
+use cosmwasm_std::{Decimal, DepsMut, Order, Response, StdResult, Uint128};
 use cw2::{assert_contract_version, set_contract_version};
 use mars_types::{
     address_provider::{self, MarsAddressType},
@@ -7,12 +7,13 @@ use mars_types::{
 };
 
 use crate::{
-    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    contract::CONTRACT_NAME,
     error::ContractError,
     state::{ADDRESS_PROVIDER, ASSET_PARAMS},
 };
 
 const FROM_VERSION: &str = "2.2.3";
+const TO_VERSION: &str = "2.3.0";
 
 /// Copy paste of the state structs from the v2.2.0 of the contract (https://github.com/mars-protocol/core-contracts/releases/tag/v2.2.0-perps).
 pub mod v2_2_3_state {
@@ -47,7 +48,7 @@ pub fn migrate(
     // Make sure we're migrating the correct contract and from the correct version.
     assert_contract_version(deps.storage, &format!("crates.io:{CONTRACT_NAME}"), FROM_VERSION)?;
 
-    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), CONTRACT_VERSION)?;
+    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), TO_VERSION)?;
 
     // Get the address of the Red Bank contract
     let ap_addr = ADDRESS_PROVIDER.load(deps.storage)?;
@@ -90,7 +91,7 @@ pub fn migrate(
     Ok(Response::new()
         .add_attribute("action", "migrate")
         .add_attribute("from_version", FROM_VERSION)
-        .add_attribute("to_version", CONTRACT_VERSION))
+        .add_attribute("to_version", TO_VERSION))
 }
 
 fn from_v2_2_3_to_v2_3_0_asset_param(
@@ -107,8 +108,13 @@ fn from_v2_2_3_to_v2_3_0_asset_param(
         liquidation_bonus: value.liquidation_bonus,
         protocol_liquidation_fee: value.protocol_liquidation_fee,
         deposit_cap: value.deposit_cap,
+        borrow_cap: Uint128::MAX,
         close_factor: value.close_factor,
         reserve_factor,
         interest_rate_model,
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
@@ -0,0 +1,93 @@
+use cosmwasm_std::{Decimal, DepsMut, Order, Response, StdResult, Uint128};
+use cw2::{assert_contract_version, set_contract_version};
+use mars_types::{params::AssetParams, red_bank::InterestRateModel};
+
+use crate::{
+    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    error::ContractError,
+    state::ASSET_PARAMS,
+};
+
+const FROM_VERSION: &str = "2.3.0";
+
+/// Copy paste of the state structs from the v2.3.0 of the contract, where the interest rate model
+/// was a two-slope model stored without an enum tag.
+pub mod v2_3_0_state {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Addr, Decimal, Uint128};
+    use cw_storage_plus::Map;
+    use mars_types::params::{CmSettings, LiquidationBonus, RedBankSettings};
+
+    #[cw_serde]
+    pub struct InterestRateModel {
+        pub optimal_utilization_rate: Decimal,
+        pub base: Decimal,
+        pub slope_1: Decimal,
+        pub slope_2: Decimal,
+    }
+
+    #[cw_serde]
+    pub struct AssetParamsBase<T> {
+        pub denom: String,
+        pub credit_manager: CmSettings<T>,
+        pub red_bank: RedBankSettings,
+        pub max_loan_to_value: Decimal,
+        pub liquidation_threshold: Decimal,
+        pub liquidation_bonus: LiquidationBonus,
+        pub protocol_liquidation_fee: Decimal,
+        pub deposit_cap: Uint128,
+        pub close_factor: Decimal,
+        pub reserve_factor: Decimal,
+        pub interest_rate_model: InterestRateModel,
+    }
+
+    pub type AssetParams = AssetParamsBase<Addr>;
+
+    pub const ASSET_PARAMS: Map<&str, AssetParams> = Map::new("asset_params");
+}
+
+pub fn migrate(deps: DepsMut) -> Result<Response, ContractError> {
+    // Make sure we're migrating the correct contract and from the correct version.
+    assert_contract_version(deps.storage, &format!("crates.io:{CONTRACT_NAME}"), FROM_VERSION)?;
+
+    // Interest rate models are now stored as a tagged enum
+    let asset_params = v2_3_0_state::ASSET_PARAMS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (denom, asset_param) in asset_params {
+        ASSET_PARAMS.save(deps.storage, &denom, &from_v2_3_0_to_v2_4_0_asset_param(asset_param))?;
+    }
+
+    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", FROM_VERSION)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+fn from_v2_3_0_to_v2_4_0_asset_param(value: v2_3_0_state::AssetParams) -> AssetParams {
+    AssetParams {
+        denom: value.denom,
+        credit_manager: value.credit_manager,
+        red_bank: value.red_bank,
+        max_loan_to_value: value.max_loan_to_value,
+        liquidation_threshold: value.liquidation_threshold,
+        liquidation_bonus: value.liquidation_bonus,
+        protocol_liquidation_fee: value.protocol_liquidation_fee,
+        deposit_cap: value.deposit_cap,
+        borrow_cap: Uint128::MAX,
+        close_factor: value.close_factor,
+        reserve_factor: value.reserve_factor,
+        interest_rate_model: InterestRateModel::TwoSlope {
+            optimal_utilization_rate: value.interest_rate_model.optimal_utilization_rate,
+            base: value.interest_rate_model.base,
+            slope_1: value.interest_rate_model.slope_1,
+            slope_2: value.interest_rate_model.slope_2,
+        },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
+    }
+}
@@ -1,12 +1,17 @@
-use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Uint128};
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{coin, Addr, Coin, Deps, Env, Order, StdResult, Uint128};
 use cw_paginate::{paginate_map_query, PaginationResponse};
 use cw_storage_plus::Bound;
-use mars_interest_rate::get_underlying_liquidity_amount;
+use mars_interest_rate::{get_underlying_debt_amount, get_underlying_liquidity_amount};
 use mars_types::{
+    adapters::oracle::OracleBase,
     address_provider::{self, helpers::query_contract_addrs, MarsAddressType},
+    credit_manager,
+    oracle::ActionKind,
     params::{
-        AssetParams, ConfigResponse, ManagedVaultConfigResponse, PerpParams, TotalDepositResponse,
-        VaultConfig,
+        AssetParams, ConfigResponse, EModeCategory, ManagedVaultConfigResponse, PerpParams,
+        TotalBorrowResponse, TotalDepositResponse, TotalIsolatedDebtResponse, VaultConfig,
     },
     red_bank::{self, Market, MarketV2Response},
 };
@@ -14,8 +19,9 @@ use mars_types::{
 use crate::{
     error::{ContractError, ContractResult},
     state::{
-        ADDRESS_PROVIDER, ASSET_PARAMS, BLACKLISTED_VAULTS, MANAGED_VAULT_CODE_IDS,
-        MANAGED_VAULT_MIN_CREATION_FEE_IN_UUSD, MAX_PERP_PARAMS, PERP_PARAMS, VAULT_CONFIGS,
+        ADDRESS_PROVIDER, ASSET_PARAMS, BLACKLISTED_VAULTS, EMODE_CATEGORIES,
+        MANAGED_VAULT_CODE_IDS, MANAGED_VAULT_MIN_CREATION_FEE_IN_UUSD, MAX_PERP_PARAMS,
+        PERP_PARAMS, VAULT_CONFIGS,
     },
 };
 
@@ -59,6 +65,20 @@ pub fn query_all_asset_params(
         .collect()
 }
 
+pub fn query_all_emode_categories(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<EModeCategory>> {
+    let start = start_after.as_ref().map(|name| Bound::exclusive(name.as_str()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    EMODE_CATEGORIES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| Ok(res?.1))
+        .collect()
+}
+
 pub fn query_all_asset_params_v2(
     deps: Deps,
     start_after: Option<String>,
@@ -230,6 +250,105 @@ pub fn query_total_deposit(
     })
 }
 
+/// Query the total amount of an asset borrowed from Red Bank (RB).
+///
+/// Credit Manager (CM) borrows from RB on behalf of its accounts, so the debt
+/// of CM is already included in the RB debt of the market.
+pub fn query_total_borrow(deps: Deps, env: &Env, denom: String) -> StdResult<TotalBorrowResponse> {
+    let current_timestamp = env.block.time.seconds();
+
+    let address_provider_addr = ADDRESS_PROVIDER.load(deps.storage)?;
+    let red_bank_addr = address_provider::helpers::query_contract_addr(
+        deps,
+        &address_provider_addr,
+        MarsAddressType::RedBank,
+    )?;
+
+    // if the market doesn't exist on RB, we default to zero
+    let amount = deps
+        .querier
+        .query_wasm_smart::<Option<Market>>(
+            red_bank_addr,
+            &red_bank::QueryMsg::Market {
+                denom: denom.clone(),
+            },
+        )?
+        .map(|market| -> StdResult<_> {
+            // fixed-rate term loans are not part of the scaled debt of the market
+            let debt =
+                get_underlying_debt_amount(market.debt_total_scaled, &market, current_timestamp)?;
+            Ok(debt.checked_add(market.term_debt_total)?)
+        })
+        .transpose()?
+        .unwrap_or_else(Uint128::zero);
+
+    let asset_params = ASSET_PARAMS.load(deps.storage, &denom)?;
+
+    Ok(TotalBorrowResponse {
+        denom,
+        cap: asset_params.borrow_cap,
+        amount,
+        remaining: asset_params.borrow_cap.saturating_sub(amount),
+    })
+}
+
+/// Sums up the debts backed by the isolated collateral in Red Bank and Credit Manager, and values
+/// them with the oracle prices.
+pub fn query_total_isolated_debt(
+    deps: Deps,
+    denom: String,
+) -> ContractResult<TotalIsolatedDebtResponse> {
+    let address_provider_addr = ADDRESS_PROVIDER.load(deps.storage)?;
+    let addresses = query_contract_addrs(
+        deps,
+        &address_provider_addr,
+        vec![MarsAddressType::RedBank, MarsAddressType::CreditManager, MarsAddressType::Oracle],
+    )?;
+    let red_bank_addr = &addresses[&MarsAddressType::RedBank];
+    let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
+    let oracle = OracleBase::new(addresses[&MarsAddressType::Oracle].clone());
+
+    let ceiling = ASSET_PARAMS
+        .load(deps.storage, &denom)?
+        .isolation
+        .map(|isolation| isolation.debt_ceiling)
+        .unwrap_or_default();
+
+    let rb_debts: Vec<Coin> = deps.querier.query_wasm_smart(
+        red_bank_addr,
+        &red_bank::QueryMsg::IsolatedDebts {
+            denom: denom.clone(),
+        },
+    )?;
+    let cm_debts: Vec<Coin> = deps.querier.query_wasm_smart(
+        credit_manager_addr,
+        &credit_manager::QueryMsg::IsolatedDebts {
+            denom: denom.clone(),
+        },
+    )?;
+
+    let mut amounts: BTreeMap<String, Uint128> = BTreeMap::new();
+    for debt in rb_debts.into_iter().chain(cm_debts) {
+        let amount = amounts.entry(debt.denom).or_default();
+        *amount = amount.checked_add(debt.amount)?;
+    }
+
+    let mut value = Uint128::zero();
+    let mut debts = vec![];
+    for (debt_denom, amount) in amounts {
+        let price = oracle.query_price(&deps.querier, &debt_denom, ActionKind::Default)?.price;
+        value = value.checked_add(amount.checked_mul_ceil(price)?)?;
+        debts.push(coin(amount.u128(), debt_denom));
+    }
+
+    Ok(TotalIsolatedDebtResponse {
+        denom,
+        ceiling,
+        debts,
+        value,
+    })
+}
+
 pub fn query_all_total_deposits_v2(
     deps: Deps,
     start_after: Option<String>,
@@ -2,7 +2,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
 use cw_storage_plus::{Item, Map};
 use mars_owner::Owner;
-use mars_types::params::{AssetParams, PerpParams, VaultConfig};
+use mars_types::params::{AssetParams, EModeCategory, PerpParams, VaultConfig};
 
 pub const RISK_MANAGER_KEY: &str = "risk_manager";
 
@@ -13,6 +13,7 @@ pub const MAX_PERP_PARAMS: Item<u8> = Item::new("max_perp_params");
 pub const ASSET_PARAMS: Map<&str, AssetParams> = Map::new("asset_params");
 pub const VAULT_CONFIGS: Map<&Addr, VaultConfig> = Map::new("vault_configs");
 pub const PERP_PARAMS: Map<&str, PerpParams> = Map::new("perp_params");
+pub const EMODE_CATEGORIES: Map<&str, EModeCategory> = Map::new("emode_categories");
 
 // Managed vault min creation fee in uusd
 pub const MANAGED_VAULT_MIN_CREATION_FEE_IN_UUSD: Item<u128> = Item::new("vault_min_creation_fee");
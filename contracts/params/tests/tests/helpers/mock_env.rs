@@ -10,9 +10,10 @@ use mars_types::{
     address_provider::{self, AddressResponseItem, MarsAddressType},
     incentives, oracle,
     params::{
-        AssetParams, AssetParamsUpdate, ConfigResponse, EmergencyUpdate, ExecuteMsg,
-        InstantiateMsg, ManagedVaultConfigResponse, ManagedVaultConfigUpdate, PerpParams,
-        PerpParamsUpdate, QueryMsg, VaultConfig, VaultConfigUpdate,
+        AssetParams, AssetParamsUpdate, ConfigResponse, EModeCategory, EModeCategoryUpdate,
+        EmergencyUpdate, ExecuteMsg, InstantiateMsg, ManagedVaultConfigResponse,
+        ManagedVaultConfigUpdate, PerpParams, PerpParamsUpdate, QueryMsg, VaultConfig,
+        VaultConfigUpdate,
     },
     perps::{self, Config},
     red_bank::{self, Market},
@@ -100,6 +101,19 @@ impl MockEnv {
         )
     }
 
+    pub fn update_emode_category(
+        &mut self,
+        sender: &Addr,
+        update: EModeCategoryUpdate,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params_contract.clone(),
+            &ExecuteMsg::UpdateEModeCategory(update),
+            &[],
+        )
+    }
+
     pub fn update_owner(&mut self, sender: &Addr, update: OwnerUpdate) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             sender.clone(),
@@ -258,6 +272,35 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_emode_category(&self, name: &str) -> Option<EModeCategory> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.params_contract.clone(),
+                &QueryMsg::EModeCategory {
+                    name: name.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_all_emode_categories(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Vec<EModeCategory> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.params_contract.clone(),
+                &QueryMsg::AllEModeCategories {
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_vault_config(&self, addr: &str) -> VaultConfig {
         self.app
             .wrap()
@@ -471,6 +514,7 @@ impl MockEnvBuilder {
                     cooldown_period: 0,
                     max_positions: 4,
                     protocol_fee_rate: Decimal::from_ratio(1u128, 100u128),
+                    insurance_fund_fee_rate: Decimal::zero(),
                     target_vault_collateralization_ratio: Decimal::from_ratio(125u128, 100u128),
                     deleverage_enabled: true,
                     vault_withdraw_enabled: true,
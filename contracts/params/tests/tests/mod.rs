@@ -2,16 +2,19 @@ mod helpers;
 
 mod test_all_total_deposits_v2;
 mod test_asset_validation;
+mod test_borrow_cap;
 mod test_deposit_cap;
 mod test_emergency_powers;
 mod test_migration_v2;
 mod test_migration_v2_2_3;
+mod test_migration_v2_4_0;
 mod test_owner;
 mod test_perp_validation;
 mod test_query_all_vault_configs_v2;
 mod test_risk_manager;
 mod test_update_asset_params;
 mod test_update_config;
+mod test_update_emode_category;
 mod test_update_managed_vault_config;
 mod test_update_perp_params;
 mod test_vault_validation;
@@ -1,10 +1,10 @@
 use std::str::FromStr;
 
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Uint128};
 use mars_params::error::ContractError;
 use mars_types::{
     error::MarsError::Validation,
-    params::{AssetParamsUpdate, HlsAssetType, HlsParamsUnchecked},
+    params::{AssetParamsUpdate, HlsAssetType, HlsParamsUnchecked, IsolationParams},
 };
 use mars_utils::error::ValidationError::{InvalidDenom, InvalidParam};
 
@@ -386,3 +386,48 @@ fn close_factor_less_than_or_equal_to_one() {
         })),
     );
 }
+
+#[test]
+fn perps_vault_haircut_less_than_one() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let mut params = default_asset_params("denom_xyz");
+    params.perps_vault_haircut = Some(Decimal::one());
+
+    let res = mock.update_asset_params(
+        &mock.query_owner(),
+        AssetParamsUpdate::AddOrUpdate {
+            params,
+        },
+    );
+    assert_err(
+        res,
+        ContractError::Mars(Validation(InvalidParam {
+            param_name: "perps_vault_haircut".to_string(),
+            invalid_value: "1".to_string(),
+            predicate: "< 1".to_string(),
+        })),
+    );
+}
+
+#[test]
+fn isolation_borrowable_denoms_must_be_valid() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let mut params = default_asset_params("denom_xyz");
+    params.isolation = Some(IsolationParams {
+        borrowable_denoms: vec!["uusdc".to_string(), "AA".to_string()],
+        debt_ceiling: Uint128::new(1_000_000),
+    });
+
+    let res = mock.update_asset_params(
+        &mock.query_owner(),
+        AssetParamsUpdate::AddOrUpdate {
+            params,
+        },
+    );
+    assert_err(
+        res,
+        ContractError::Mars(Validation(InvalidDenom {
+            reason: "Invalid denom length".to_string(),
+        })),
+    );
+}
@@ -0,0 +1,68 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use mars_params::{
+    query::query_total_borrow,
+    state::{ADDRESS_PROVIDER, ASSET_PARAMS},
+};
+use mars_testing::{mock_dependencies, mock_env_at_block_time};
+use mars_types::{params::TotalBorrowResponse, red_bank::Market};
+
+use super::helpers::default_asset_params;
+
+const MOCK_DENOM: &str = "utoken";
+const TIMESTAMP: u64 = 1690573960;
+
+#[test]
+fn querying_total_borrow() {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env_at_block_time(TIMESTAMP);
+
+    let mut params_unchecked = default_asset_params(MOCK_DENOM);
+    params_unchecked.borrow_cap = Uint128::new(1_000_000);
+    let params = params_unchecked.check(deps.as_ref().api).unwrap();
+
+    deps.querier.set_redbank_market(Market {
+        denom: MOCK_DENOM.into(),
+        borrow_index: Decimal::percent(120),
+        debt_total_scaled: Uint128::new(500_000_000_000),
+        indexes_last_updated: TIMESTAMP,
+        ..Default::default()
+    });
+    ADDRESS_PROVIDER.save(deps.as_mut().storage, &Addr::unchecked("address_provider")).unwrap();
+    ASSET_PARAMS.save(deps.as_mut().storage, MOCK_DENOM, &params).unwrap();
+
+    // 500_000 scaled debt at a borrow index of 1.2
+    let res = query_total_borrow(deps.as_ref(), &env, MOCK_DENOM.to_string()).unwrap();
+    assert_eq!(
+        res,
+        TotalBorrowResponse {
+            denom: MOCK_DENOM.to_string(),
+            cap: Uint128::new(1_000_000),
+            amount: Uint128::new(600_000),
+            remaining: Uint128::new(400_000),
+        }
+    );
+}
+
+#[test]
+fn remaining_borrow_is_zero_above_cap() {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env_at_block_time(TIMESTAMP);
+
+    let mut params_unchecked = default_asset_params(MOCK_DENOM);
+    params_unchecked.borrow_cap = Uint128::zero();
+    let params = params_unchecked.check(deps.as_ref().api).unwrap();
+
+    deps.querier.set_redbank_market(Market {
+        denom: MOCK_DENOM.into(),
+        borrow_index: Decimal::one(),
+        debt_total_scaled: Uint128::new(1_000_000_000),
+        indexes_last_updated: TIMESTAMP,
+        ..Default::default()
+    });
+    ADDRESS_PROVIDER.save(deps.as_mut().storage, &Addr::unchecked("address_provider")).unwrap();
+    ASSET_PARAMS.save(deps.as_mut().storage, MOCK_DENOM, &params).unwrap();
+
+    let res = query_total_borrow(deps.as_ref(), &env, MOCK_DENOM.to_string()).unwrap();
+    assert_eq!(res.amount, Uint128::new(1_000));
+    assert_eq!(res.remaining, Uint128::zero());
+}
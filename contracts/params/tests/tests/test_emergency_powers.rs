@@ -173,6 +173,37 @@ fn disabled_withdraw_rb() {
     assert!(!params.red_bank.withdraw_enabled);
 }
 
+#[test]
+fn set_zero_borrow_cap() {
+    let emergency_owner = Addr::unchecked("miles_morales");
+    let mut mock = MockEnv::new().emergency_owner(emergency_owner.as_str()).build().unwrap();
+    let denom = "atom".to_string();
+
+    let params = default_asset_params(&denom);
+
+    mock.set_price_source_fixed(&denom, Decimal::one());
+
+    mock.update_asset_params(
+        &mock.query_owner(),
+        AssetParamsUpdate::AddOrUpdate {
+            params,
+        },
+    )
+    .unwrap();
+
+    let params = mock.query_asset_params(&denom);
+    assert!(!params.borrow_cap.is_zero());
+
+    mock.emergency_update(
+        &emergency_owner,
+        EmergencyUpdate::RedBank(RedBankEmergencyUpdate::SetZeroBorrowCap(denom.clone())),
+    )
+    .unwrap();
+
+    let params = mock.query_asset_params(&denom);
+    assert!(params.borrow_cap.is_zero());
+}
+
 #[test]
 fn set_zero_max_ltv() {
     let emergency_owner = Addr::unchecked("miles_morales");
@@ -133,7 +133,7 @@ fn successful_migration() {
 fn migration_msg() -> MigrateMsg {
     MigrateMsg::V2_3_0 {
         reserve_factor: Decimal::percent(49),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(82u64),
             base: Decimal::from_str("2").unwrap(),
             slope_1: Decimal::percent(17u64),
@@ -204,9 +204,14 @@ fn expected_asset_1() -> AssetParams {
         },
         protocol_liquidation_fee: Decimal::from_str("0.05").unwrap(),
         deposit_cap: Uint128::from(1230000u128),
+        borrow_cap: Uint128::MAX,
         close_factor: Decimal::from_str("0.9").unwrap(),
         reserve_factor,
         interest_rate_model,
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
 
@@ -255,7 +260,7 @@ fn market_2() -> Market {
     Market {
         denom: "asset_2".to_string(),
         reserve_factor: Decimal::percent(28),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(80u64),
             base: Decimal::one(),
             slope_1: Decimal::percent(7u64),
@@ -303,8 +308,13 @@ fn expected_asset_2() -> AssetParams {
         },
         protocol_liquidation_fee: Decimal::from_str("0.15").unwrap(),
         deposit_cap: Uint128::from(123u128),
+        borrow_cap: Uint128::MAX,
         close_factor: Decimal::from_str("0.6").unwrap(),
         reserve_factor: market.reserve_factor,
         interest_rate_model: market.interest_rate_model,
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
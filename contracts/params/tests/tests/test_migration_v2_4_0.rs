@@ -0,0 +1,128 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{attr, Decimal, Uint128};
+use cw2::{ContractVersion, VersionError};
+use mars_params::{
+    error::ContractError,
+    migrations::{self, v2_4_0::v2_3_0_state},
+    state::ASSET_PARAMS,
+};
+use mars_testing::mock_dependencies;
+use mars_types::{
+    params::{AssetParams, CmSettings, LiquidationBonus, RedBankSettings},
+    red_bank::InterestRateModel,
+};
+
+#[test]
+fn wrong_contract_version() {
+    let mut deps = mock_dependencies(&[]);
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:mars-params", "2.2.3").unwrap();
+
+    let err = migrations::v2_4_0::migrate(deps.as_mut()).unwrap_err();
+
+    assert_eq!(
+        err,
+        ContractError::Version(VersionError::WrongVersion {
+            expected: "2.3.0".to_string(),
+            found: "2.2.3".to_string()
+        })
+    );
+}
+
+#[test]
+fn successful_migration() {
+    let mut deps = mock_dependencies(&[]);
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:mars-params", "2.3.0").unwrap();
+
+    v2_3_0_state::ASSET_PARAMS.save(deps.as_mut().storage, "asset_1", &asset_1()).unwrap();
+
+    let res = migrations::v2_4_0::migrate(deps.as_mut()).unwrap();
+
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "migrate"), attr("from_version", "2.3.0"), attr("to_version", "2.4.0")]
+    );
+
+    let new_contract_version = ContractVersion {
+        contract: "crates.io:mars-params".to_string(),
+        version: "2.4.0".to_string(),
+    };
+    assert_eq!(cw2::get_contract_version(deps.as_ref().storage).unwrap(), new_contract_version);
+
+    let asset_params = ASSET_PARAMS.load(deps.as_ref().storage, "asset_1").unwrap();
+    assert_eq!(asset_params, expected_asset_1());
+}
+
+fn asset_1() -> v2_3_0_state::AssetParams {
+    v2_3_0_state::AssetParams {
+        denom: "asset_1".to_string(),
+        credit_manager: CmSettings {
+            whitelisted: true,
+            hls: None,
+            withdraw_enabled: true,
+        },
+        red_bank: RedBankSettings {
+            deposit_enabled: true,
+            borrow_enabled: true,
+            withdraw_enabled: true,
+        },
+        max_loan_to_value: Decimal::from_str("0.6").unwrap(),
+        liquidation_threshold: Decimal::from_str("0.65").unwrap(),
+        liquidation_bonus: LiquidationBonus {
+            starting_lb: Decimal::from_str("0.1").unwrap(),
+            slope: Decimal::from_str("0.2").unwrap(),
+            min_lb: Decimal::from_str("0.3").unwrap(),
+            max_lb: Decimal::from_str("0.4").unwrap(),
+        },
+        protocol_liquidation_fee: Decimal::from_str("0.05").unwrap(),
+        deposit_cap: Uint128::from(1230000u128),
+        close_factor: Decimal::from_str("0.9").unwrap(),
+        reserve_factor: Decimal::percent(10),
+        interest_rate_model: v2_3_0_state::InterestRateModel {
+            optimal_utilization_rate: Decimal::percent(80),
+            base: Decimal::zero(),
+            slope_1: Decimal::percent(7),
+            slope_2: Decimal::percent(45),
+        },
+    }
+}
+
+fn expected_asset_1() -> AssetParams {
+    AssetParams {
+        denom: "asset_1".to_string(),
+        credit_manager: CmSettings {
+            whitelisted: true,
+            hls: None,
+            withdraw_enabled: true,
+        },
+        red_bank: RedBankSettings {
+            deposit_enabled: true,
+            borrow_enabled: true,
+            withdraw_enabled: true,
+        },
+        max_loan_to_value: Decimal::from_str("0.6").unwrap(),
+        liquidation_threshold: Decimal::from_str("0.65").unwrap(),
+        liquidation_bonus: LiquidationBonus {
+            starting_lb: Decimal::from_str("0.1").unwrap(),
+            slope: Decimal::from_str("0.2").unwrap(),
+            min_lb: Decimal::from_str("0.3").unwrap(),
+            max_lb: Decimal::from_str("0.4").unwrap(),
+        },
+        protocol_liquidation_fee: Decimal::from_str("0.05").unwrap(),
+        deposit_cap: Uint128::from(1230000u128),
+        borrow_cap: Uint128::MAX,
+        close_factor: Decimal::from_str("0.9").unwrap(),
+        reserve_factor: Decimal::percent(10),
+        interest_rate_model: InterestRateModel::TwoSlope {
+            optimal_utilization_rate: Decimal::percent(80),
+            base: Decimal::zero(),
+            slope_1: Decimal::percent(7),
+            slope_2: Decimal::percent(45),
+        },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
+    }
+}
@@ -0,0 +1,85 @@
+use cosmwasm_std::{Addr, Decimal};
+use mars_owner::OwnerError;
+use mars_params::error::ContractError;
+use mars_types::params::{EModeCategory, EModeCategoryUpdate};
+use mars_utils::error::ValidationError;
+
+use super::helpers::{assert_err, MockEnv};
+
+fn stables_category() -> EModeCategory {
+    EModeCategory {
+        name: "stables".to_string(),
+        denoms: vec!["uusdc".to_string(), "uusdt".to_string()],
+        max_loan_to_value: Decimal::percent(93),
+        liquidation_threshold: Decimal::percent(95),
+    }
+}
+
+#[test]
+fn only_owner_can_update_emode_categories() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let bad_guy = Addr::unchecked("doctor_otto_983");
+
+    let res = mock.update_emode_category(
+        &bad_guy,
+        EModeCategoryUpdate::AddOrUpdate {
+            category: stables_category(),
+        },
+    );
+    assert_err(res, ContractError::Owner(OwnerError::NotOwner {}));
+}
+
+#[test]
+fn add_and_update_emode_category() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = mock.query_owner();
+
+    assert!(mock.query_emode_category("stables").is_none());
+    assert!(mock.query_all_emode_categories(None, None).is_empty());
+
+    mock.update_emode_category(
+        &owner,
+        EModeCategoryUpdate::AddOrUpdate {
+            category: stables_category(),
+        },
+    )
+    .unwrap();
+    assert_eq!(mock.query_emode_category("stables"), Some(stables_category()));
+
+    let updated = EModeCategory {
+        max_loan_to_value: Decimal::percent(90),
+        ..stables_category()
+    };
+    mock.update_emode_category(
+        &owner,
+        EModeCategoryUpdate::AddOrUpdate {
+            category: updated.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(mock.query_all_emode_categories(None, None), vec![updated]);
+}
+
+#[test]
+fn emode_liquidation_threshold_must_be_above_max_ltv() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = mock.query_owner();
+
+    let res = mock.update_emode_category(
+        &owner,
+        EModeCategoryUpdate::AddOrUpdate {
+            category: EModeCategory {
+                liquidation_threshold: Decimal::percent(93),
+                ..stables_category()
+            },
+        },
+    );
+    assert_err(
+        res,
+        ContractError::Validation(ValidationError::InvalidParam {
+            param_name: "liquidation_threshold".to_string(),
+            invalid_value: "0.93".to_string(),
+            predicate: "> 0.93 (max LTV)".to_string(),
+        }),
+    );
+}
@@ -11,18 +11,28 @@ use crate::{
     deleverage::{deleverage, handle_deleverage_request_reply, DELEVERAGE_REQUEST_REPLY_ID},
     error::{ContractError, ContractResult},
     initialize::initialize,
+    isolated_margin::{add_isolated_margin, liquidate_isolated_position},
+    limit_order::{
+        cancel_limit_order, fill_limit_orders, handle_limit_order_fill_reply, place_limit_order,
+        LIMIT_ORDER_FILL_REPLY_ID,
+    },
     market_management::update_market,
     migrations,
     position_management::{close_all_positions, execute_order},
     query::{
-        query_config, query_market, query_market_accounting, query_market_state, query_markets,
-        query_opening_fee, query_position, query_position_fees, query_positions,
+        query_average_funding_rate, query_config, query_funding_history, query_insurance_fund,
+        query_insurance_fund_draws, query_limit_order, query_limit_orders,
+        query_limit_orders_by_account, query_market, query_market_accounting, query_market_state,
+        query_markets, query_opening_fee, query_position, query_position_fees, query_positions,
         query_positions_by_account, query_realized_pnl_by_account_and_market,
         query_total_accounting, query_vault, query_vault_position,
     },
     state::OWNER,
     update_config::update_config,
-    vault::{deposit, unlock, withdraw},
+    vault::{
+        deposit, handle_vault_collateral_swap_reply, swap_vault_collateral, unlock, withdraw,
+        VAULT_COLLATERAL_SWAP_REPLY_ID,
+    },
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -76,6 +86,7 @@ pub fn execute(
             account_id,
             action,
             discount_pct,
+            bad_debt,
         } => close_all_positions(
             deps,
             env,
@@ -83,6 +94,7 @@ pub fn execute(
             account_id,
             action.unwrap_or(ActionKind::Default),
             discount_pct,
+            bad_debt.unwrap_or_default(),
         ),
         ExecuteMsg::ExecuteOrder {
             account_id,
@@ -90,17 +102,70 @@ pub fn execute(
             size,
             reduce_only,
             discount_pct,
-        } => execute_order(deps, env, info, account_id, denom, size, reduce_only, discount_pct),
+            limit_price,
+            isolated_margin,
+            action,
+        } => execute_order(
+            deps,
+            env,
+            info,
+            account_id,
+            denom,
+            size,
+            reduce_only,
+            discount_pct,
+            limit_price,
+            isolated_margin,
+            action.unwrap_or(ActionKind::Default),
+        ),
         ExecuteMsg::Deleverage {
             account_id,
             denom,
         } => deleverage(deps, env, account_id, denom),
+        ExecuteMsg::PlaceLimitOrder {
+            account_id,
+            denom,
+            size,
+            limit_price,
+            reduce_only,
+            keeper_fee,
+        } => place_limit_order(
+            deps,
+            env,
+            info,
+            account_id,
+            denom,
+            size,
+            limit_price,
+            reduce_only,
+            keeper_fee,
+        ),
+        ExecuteMsg::CancelLimitOrder {
+            account_id,
+            order_id,
+        } => cancel_limit_order(deps, info, account_id, order_id),
+        ExecuteMsg::AddIsolatedMargin {
+            account_id,
+            denom,
+        } => add_isolated_margin(deps, info, account_id, denom),
+        ExecuteMsg::LiquidateIsolatedPosition {
+            account_id,
+            denom,
+        } => liquidate_isolated_position(deps, env, info, account_id, denom),
+        ExecuteMsg::FillLimitOrders {
+            denom,
+        } => fill_limit_orders(deps, info, denom),
         ExecuteMsg::UpdateMarket {
             params,
         } => update_market(deps, env, info.sender, params),
         ExecuteMsg::UpdateConfig {
             updates,
         } => update_config(deps, info.sender, updates),
+        ExecuteMsg::SwapVaultCollateral {
+            denom,
+            amount,
+            route,
+        } => swap_vault_collateral(deps, env, info, denom, amount, route),
     }
 }
 
@@ -108,6 +173,8 @@ pub fn execute(
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> ContractResult<Response> {
     match reply.id {
         DELEVERAGE_REQUEST_REPLY_ID => handle_deleverage_request_reply(deps, env, reply),
+        LIMIT_ORDER_FILL_REPLY_ID => handle_limit_order_fill_reply(deps, reply),
+        VAULT_COLLATERAL_SWAP_REPLY_ID => handle_vault_collateral_swap_reply(deps, env, reply),
         id => Err(ContractError::ReplyIdError(id)),
     }
 }
@@ -148,6 +215,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             denom,
             order_size,
             reduce_only,
+            action,
         } => to_json_binary(&query_position(
             deps,
             env.block.time.seconds(),
@@ -155,6 +223,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             denom,
             order_size,
             reduce_only,
+            action.unwrap_or(ActionKind::Default),
         )?),
         QueryMsg::Positions {
             start_after,
@@ -192,6 +261,47 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
         QueryMsg::MarketState {
             denom,
         } => to_json_binary(&query_market_state(deps.storage, denom)?),
+        QueryMsg::LimitOrder {
+            order_id,
+        } => to_json_binary(&query_limit_order(deps.storage, order_id)?),
+        QueryMsg::LimitOrders {
+            start_after,
+            limit,
+        } => to_json_binary(&query_limit_orders(deps.storage, start_after, limit)?),
+        QueryMsg::LimitOrdersByAccount {
+            account_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_limit_orders_by_account(
+            deps.storage,
+            &account_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::FundingHistory {
+            denom,
+            start_after,
+            limit,
+        } => to_json_binary(&query_funding_history(deps.storage, &denom, start_after, limit)?),
+        QueryMsg::AverageFundingRate {
+            denom,
+            start,
+            end,
+        } => to_json_binary(&query_average_funding_rate(
+            deps.storage,
+            denom,
+            start,
+            end,
+            env.block.time.seconds(),
+        )?),
+        QueryMsg::InsuranceFund {
+            denom,
+        } => to_json_binary(&query_insurance_fund(deps.storage, denom)?),
+        QueryMsg::InsuranceFundDraws {
+            denom,
+            start_after,
+            limit,
+        } => to_json_binary(&query_insurance_fund_draws(deps.storage, &denom, start_after, limit)?),
     }
     .map_err(Into::into)
 }
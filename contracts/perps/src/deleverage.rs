@@ -16,6 +16,10 @@ use mars_types::{
 
 use crate::{
     error::{ContractError, ContractResult},
+    funding::record_funding_snapshot,
+    insurance_fund::absorb_bad_debt,
+    isolated_margin::{return_isolated_margin_msg, settle_isolated_margin},
+    limit_order::remove_limit_orders_of_position,
     market::MarketStateExt,
     position::{PositionExt, PositionModification},
     position_management::{apply_pnl_and_fees, compute_discounted_fee_rates},
@@ -53,6 +57,10 @@ pub const DELEVERAGE_REQUEST_REPLY_ID: u64 = 10_001;
 /// 4. **PnL Transfer:** If all checks pass, the realized PnL is converted to the
 ///    base denomination and transferred to the account via a CosmosMsg. The function
 ///    then returns a successful response with appropriate attributes.
+///    Isolated-margin positions settle the PnL against their margin instead, and what is left
+///    of the margin is returned to the account.
+///    Losses the account can't back with its collateral are not paid by the credit manager, but
+///    absorbed by the insurance fund of the market when handling the reply.
 ///
 /// The function ensures that the deleverage process is only performed when necessary,
 /// and that the resulting position adjustments are valid according to the configured
@@ -155,6 +163,8 @@ pub fn deleverage(
     // Apply the new PnL amounts to the accumulators
     let mut msgs = vec![];
     let total_protocol_fee = apply_pnl_and_fees(
+        deps.storage,
+        &denom,
         &cfg,
         &rewards_collector_addr,
         &mut ms,
@@ -165,11 +175,29 @@ pub fn deleverage(
         &mut msgs,
     )?;
 
+    // Isolated-margin positions settle their PnL against the margin instead of the credit account
+    let isolated_margin_left = position
+        .isolated_margin
+        .map(|margin| {
+            settle_isolated_margin(
+                deps.storage,
+                &denom,
+                &account_id,
+                current_time,
+                margin,
+                pnl_amounts.pnl,
+                &mut ms,
+                &mut tcf,
+            )
+        })
+        .transpose()?;
+
     // Save updated states
     POSITIONS.remove(deps.storage, (&account_id, &denom));
 
     REALIZED_PNL.save(deps.storage, (&account_id, &denom), &realized_pnl)?;
     MARKET_STATES.save(deps.storage, &denom, &ms)?;
+    record_funding_snapshot(deps.storage, &denom, &ms)?;
     TOTAL_CASH_FLOW.save(deps.storage, &tcf)?;
 
     // Assert CR after deleverage.
@@ -177,6 +205,31 @@ pub fn deleverage(
     let cr_after = query_vault_cr(deps.as_ref(), current_time, pricing.clone())?;
     assert_cr_after_deleverage(cr_before, cr_after, cfg.target_vault_collateralization_ratio)?;
 
+    let cm_address =
+        query_contract_addr(deps.as_ref(), &cfg.address_provider, MarsAddressType::CreditManager)?;
+
+    msgs.extend(remove_limit_orders_of_position(deps.storage, &cm_address, &account_id, &denom)?);
+
+    if let Some(margin_left) = isolated_margin_left {
+        if !margin_left.is_zero() {
+            msgs.push(return_isolated_margin_msg(
+                &cm_address,
+                &account_id,
+                &cfg.base_denom,
+                margin_left,
+            )?);
+        }
+
+        return Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "deleverage")
+            .add_attribute("account_id", account_id)
+            .add_attribute("cr_before", cr_before.to_string())
+            .add_attribute("cr_after", cr_after.to_string())
+            .add_attribute("isolated_margin_returned", margin_left.to_string())
+            .add_attributes(attrs));
+    }
+
     // Convert PnL amounts to coins
     let pnl = pnl_amounts.to_coins(&cfg.base_denom).pnl;
     let signed_uint_pnl = pnl.to_signed_uint()?;
@@ -209,12 +262,11 @@ pub fn deleverage(
         denom: cfg.base_denom.clone(),
         contract_balance: balance_res.amount.amount.checked_sub(send_amount_from_perps)?, // Subtract the amount send from the contract
         requested_amount: requested_amount_from_cm,
+        account_id: account_id.clone(),
+        market: denom.clone(),
     };
     DELEVERAGE_REQUEST_TEMP_STORAGE.save(deps.storage, &temp_storage)?;
 
-    let cm_address =
-        query_contract_addr(deps.as_ref(), &cfg.address_provider, MarsAddressType::CreditManager)?;
-
     // Send a message to the credit manager to update the account's balance
     let msg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: cm_address.to_string(),
@@ -311,10 +363,13 @@ pub fn handle_deleverage_request_reply(
     // Process the reply from the credit manager
     reply.result.into_result().map_err(StdError::generic_err)?;
 
-    // Compare contract balance after deleverage. If the difference is not equal to the requested amount, throw an error.
+    // Compare contract balance after deleverage. If the difference exceeds the requested amount, throw an error.
     // Requested amount is the amount that the credit manager should have sent to the contract.
     // If the requested amount is zero, it means that the contract should not have received any funds (closed position was in profit or break even).
+    // The credit manager doesn't pay the part of the loss the account can't back with its collateral.
+    // That bad debt is absorbed by the insurance fund of the market, and then by the vault.
     let temp_storage = DELEVERAGE_REQUEST_TEMP_STORAGE.load(deps.storage)?;
+    let mut bad_debt = Uint128::zero();
     if !temp_storage.requested_amount.is_zero() {
         let balance_res: BalanceResponse =
             deps.querier.query(&QueryRequest::Bank(BankQuery::Balance {
@@ -322,14 +377,33 @@ pub fn handle_deleverage_request_reply(
                 denom: temp_storage.denom,
             }))?;
         let balance_diff = balance_res.amount.amount.checked_sub(temp_storage.contract_balance)?;
-        if balance_diff != temp_storage.requested_amount {
+        if balance_diff > temp_storage.requested_amount {
             return Err(ContractError::InvalidFundsAfterDeleverage {
                 expected: temp_storage.requested_amount,
                 received: balance_diff,
             });
         }
+
+        bad_debt = temp_storage.requested_amount.checked_sub(balance_diff)?;
+        if !bad_debt.is_zero() {
+            let mut ms = MARKET_STATES.load(deps.storage, &temp_storage.market)?;
+            let mut tcf = TOTAL_CASH_FLOW.may_load(deps.storage)?.unwrap_or_default();
+            absorb_bad_debt(
+                deps.storage,
+                &temp_storage.market,
+                &temp_storage.account_id,
+                bad_debt,
+                env.block.time.seconds(),
+                &mut ms,
+                &mut tcf,
+            )?;
+            MARKET_STATES.save(deps.storage, &temp_storage.market, &ms)?;
+            TOTAL_CASH_FLOW.save(deps.storage, &tcf)?;
+        }
     }
     DELEVERAGE_REQUEST_TEMP_STORAGE.remove(deps.storage);
 
-    Ok(Response::new().add_attribute("action", "deleverage/handle_reply"))
+    Ok(Response::new()
+        .add_attribute("action", "deleverage/handle_reply")
+        .add_attribute("bad_debt", bad_debt.to_string()))
 }
@@ -119,6 +119,18 @@ pub enum ContractError {
     #[error("withdrawing from the counterparty vault is currently disabled")]
     VaultWithdrawDisabled,
 
+    #[error("{denom} is not accepted as collateral by the counterparty vault")]
+    VaultCollateralNotWhitelisted {
+        denom: String,
+    },
+
+    #[error("cannot swap {requested} {denom} of vault collateral, the vault only holds {held}")]
+    InsufficientVaultCollateral {
+        denom: String,
+        held: Uint128,
+        requested: Uint128,
+    },
+
     #[error("cannot compute deposit amount when there is zero total shares")]
     ZeroTotalShares,
 
@@ -174,6 +186,65 @@ pub enum ContractError {
         max_positions: u8,
     },
 
+    #[error("Execution price {exec_price} is worse than the limit price {limit_price}")]
+    LimitPriceExceeded {
+        exec_price: Decimal,
+        limit_price: Decimal,
+    },
+
+    #[error("Invalid limit order: {reason}")]
+    InvalidLimitOrder {
+        reason: String,
+    },
+
+    #[error("Limit order {order_id} not found for account {account_id}")]
+    LimitOrderNotFound {
+        account_id: String,
+        order_id: u64,
+    },
+
+    #[error(
+        "Account `{account_id}` has reached the maximum number of limit orders: {max_limit_orders}"
+    )]
+    MaxLimitOrdersReached {
+        account_id: String,
+        max_limit_orders: u8,
+    },
+
+    #[error("No limit orders to fill for denom {denom}")]
+    NoLimitOrdersToFill {
+        denom: String,
+    },
+
+    #[error("Funding history of denom {denom} is not available at timestamp {timestamp}")]
+    FundingHistoryNotAvailable {
+        denom: String,
+        timestamp: u64,
+    },
+
+    #[error("Invalid funding window: {reason}")]
+    InvalidFundingWindow {
+        reason: String,
+    },
+
+    #[error(
+        "Position of account {account_id} in denom {denom} is not an isolated-margin position"
+    )]
+    PositionNotIsolated {
+        account_id: String,
+        denom: String,
+    },
+
+    #[error("Isolated margin does not cover the position: health factor {health_factor}")]
+    IsolatedMarginTooLow {
+        health_factor: Decimal,
+    },
+
+    #[error("Isolated-margin position is healthy: health factor {health_factor}")]
+    IsolatedPositionHealthy {
+        health_factor: Decimal,
+    },
+
     #[error("Invalid position flip: {reason}")]
     InvalidPositionFlip {
         reason: String,
@@ -199,6 +270,12 @@ pub enum ContractError {
         received: Uint128,
     },
 
+    #[error("Bad debt of {bad_debt} exceeds the loss of {loss}")]
+    BadDebtExceedsLoss {
+        bad_debt: Uint128,
+        loss: Uint128,
+    },
+
     #[error("Collateralization ratio of the vault below threshold: {current_cr} < {threshold_cr}")]
     VaultUndercollateralized {
         current_cr: Decimal,
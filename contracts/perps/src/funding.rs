@@ -0,0 +1,134 @@
+use cosmwasm_std::{Decimal, Order, SignedDecimal, Storage};
+use cw_storage_plus::Bound;
+use mars_types::perps::{FundingSnapshot, MarketState};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    market::{MarketStateExt, SECONDS_IN_DAY},
+    state::{FUNDING_SNAPSHOTS, FUNDING_SNAPSHOT_COUNTS},
+};
+
+/// Maximum number of funding snapshots kept per market. Once reached, the oldest snapshot is
+/// dropped for every new one.
+pub const MAX_FUNDING_SNAPSHOTS: u32 = 2000;
+
+/// Records the funding state of a market after it has been updated.
+///
+/// The funding rate changes linearly between two market updates, so the cumulative funding rate
+/// is increased by the average of the previous and the current rate over the elapsed time.
+/// Nothing is recorded if the market has already been snapshotted in the current block.
+pub fn record_funding_snapshot(
+    store: &mut dyn Storage,
+    denom: &str,
+    ms: &MarketState,
+) -> ContractResult<()> {
+    let last_snapshot = last_snapshot_at_or_before(store, denom, ms.last_updated)?;
+
+    let cumulative_funding_rate = match last_snapshot {
+        Some(last) if last.timestamp == ms.last_updated => return Ok(()),
+        Some(last) => {
+            next_cumulative_funding_rate(&last, ms.funding.last_funding_rate, ms.last_updated)?
+        }
+        None => SignedDecimal::zero(),
+    };
+
+    FUNDING_SNAPSHOTS.save(
+        store,
+        (denom, ms.last_updated),
+        &FundingSnapshot {
+            timestamp: ms.last_updated,
+            funding_rate: ms.funding.last_funding_rate,
+            funding_accrued_per_unit_in_base_denom: ms
+                .funding
+                .last_funding_accrued_per_unit_in_base_denom,
+            cumulative_funding_rate,
+        },
+    )?;
+
+    // Drop the oldest snapshot once the buffer is full
+    let count = FUNDING_SNAPSHOT_COUNTS.may_load(store, denom)?.unwrap_or_default() + 1;
+    if count > MAX_FUNDING_SNAPSHOTS {
+        let oldest = FUNDING_SNAPSHOTS
+            .prefix(denom)
+            .keys(store, None, None, Order::Ascending)
+            .next()
+            .transpose()?;
+        if let Some(timestamp) = oldest {
+            FUNDING_SNAPSHOTS.remove(store, (denom, timestamp));
+        }
+        FUNDING_SNAPSHOT_COUNTS.save(store, denom, &MAX_FUNDING_SNAPSHOTS)?;
+    } else {
+        FUNDING_SNAPSHOT_COUNTS.save(store, denom, &count)?;
+    }
+
+    Ok(())
+}
+
+/// Computes the cumulative funding rate of a market at a given point in time.
+///
+/// Between two snapshots the funding rate is interpolated linearly. After the last snapshot the
+/// current funding rate of the market state is used.
+pub fn cumulative_funding_rate_at(
+    store: &dyn Storage,
+    denom: &str,
+    ms: &MarketState,
+    timestamp: u64,
+) -> ContractResult<SignedDecimal> {
+    let prev = last_snapshot_at_or_before(store, denom, timestamp)?.ok_or_else(|| {
+        ContractError::FundingHistoryNotAvailable {
+            denom: denom.to_string(),
+            timestamp,
+        }
+    })?;
+
+    if prev.timestamp == timestamp {
+        return Ok(prev.cumulative_funding_rate);
+    }
+
+    let next = FUNDING_SNAPSHOTS
+        .prefix(denom)
+        .range(store, Some(Bound::exclusive(timestamp)), None, Order::Ascending)
+        .next()
+        .transpose()?
+        .map(|(_, snapshot)| snapshot);
+
+    let funding_rate = match next {
+        Some(next) => {
+            let progress: SignedDecimal =
+                Decimal::from_ratio(timestamp - prev.timestamp, next.timestamp - prev.timestamp)
+                    .try_into()?;
+            let rate_diff = next.funding_rate.checked_sub(prev.funding_rate)?;
+            prev.funding_rate.checked_add(rate_diff.checked_mul(progress)?)?
+        }
+        None => ms.current_funding_rate(timestamp)?,
+    };
+
+    next_cumulative_funding_rate(&prev, funding_rate, timestamp)
+}
+
+fn last_snapshot_at_or_before(
+    store: &dyn Storage,
+    denom: &str,
+    timestamp: u64,
+) -> ContractResult<Option<FundingSnapshot>> {
+    Ok(FUNDING_SNAPSHOTS
+        .prefix(denom)
+        .range(store, None, Some(Bound::inclusive(timestamp)), Order::Descending)
+        .next()
+        .transpose()?
+        .map(|(_, snapshot)| snapshot))
+}
+
+fn next_cumulative_funding_rate(
+    prev: &FundingSnapshot,
+    funding_rate: SignedDecimal,
+    timestamp: u64,
+) -> ContractResult<SignedDecimal> {
+    let elapsed_days: SignedDecimal =
+        Decimal::from_ratio(timestamp - prev.timestamp, SECONDS_IN_DAY).try_into()?;
+    let avg_funding_rate = prev
+        .funding_rate
+        .checked_add(funding_rate)?
+        .checked_div(SignedDecimal::from_atomics(2i128, 0)?)?;
+    Ok(prev.cumulative_funding_rate.checked_add(avg_funding_rate.checked_mul(elapsed_days)?)?)
+}
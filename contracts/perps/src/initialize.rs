@@ -4,9 +4,12 @@ use mars_types::perps::{CashFlow, Config, VaultState};
 use crate::{
     error::ContractResult,
     state::{CONFIG, TOTAL_CASH_FLOW, VAULT_STATE},
+    utils::ensure_fee_rates,
 };
 
 pub fn initialize(store: &mut dyn Storage, cfg: Config<Addr>) -> ContractResult<Response> {
+    ensure_fee_rates(&cfg)?;
+
     CONFIG.save(store, &cfg)?;
 
     // Initialize vault state to zero total liquidity and zero total shares
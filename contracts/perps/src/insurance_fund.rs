@@ -0,0 +1,75 @@
+use cosmwasm_std::{Int128, Storage, Uint128};
+use mars_types::perps::{CashFlow, InsuranceFundDraw, MarketState};
+
+use crate::{
+    error::ContractResult,
+    state::{INSURANCE_FUNDS, INSURANCE_FUND_DRAWS, NEXT_INSURANCE_FUND_DRAW_ID},
+};
+
+/// Adds the insurance fund slice of the fees charged in a market to its insurance fund.
+///
+/// The coins stay in the contract, but they are excluded from the vault's cash flow.
+pub fn increase_insurance_fund(
+    store: &mut dyn Storage,
+    denom: &str,
+    amount: Uint128,
+) -> ContractResult<Uint128> {
+    let balance =
+        INSURANCE_FUNDS.may_load(store, denom)?.unwrap_or_default().checked_add(amount)?;
+    INSURANCE_FUNDS.save(store, denom, &balance)?;
+    Ok(balance)
+}
+
+/// Covers the bad debt realized in a market with its insurance fund, as far as the fund allows.
+/// Every bad debt is recorded in the draw history of the market.
+///
+/// Returns the amount drawn from the fund. The rest of the bad debt has to be absorbed by the vault.
+pub fn cover_bad_debt(
+    store: &mut dyn Storage,
+    denom: &str,
+    account_id: &str,
+    bad_debt: Uint128,
+    current_time: u64,
+) -> ContractResult<Uint128> {
+    let balance = INSURANCE_FUNDS.may_load(store, denom)?.unwrap_or_default();
+    let amount = balance.min(bad_debt);
+    INSURANCE_FUNDS.save(store, denom, &balance.checked_sub(amount)?)?;
+
+    let draw_id = NEXT_INSURANCE_FUND_DRAW_ID.may_load(store)?.unwrap_or(1);
+    NEXT_INSURANCE_FUND_DRAW_ID.save(store, &(draw_id + 1))?;
+
+    INSURANCE_FUND_DRAWS.save(
+        store,
+        (denom, draw_id),
+        &InsuranceFundDraw {
+            draw_id,
+            account_id: account_id.to_string(),
+            bad_debt,
+            amount,
+            timestamp: current_time,
+        },
+    )?;
+
+    Ok(amount)
+}
+
+/// Absorbs the bad debt realized in a market. The insurance fund of the market covers it first,
+/// and what the fund can't cover is written off from the vault's cash flow, which assumed the full
+/// loss to be paid.
+pub fn absorb_bad_debt(
+    store: &mut dyn Storage,
+    denom: &str,
+    account_id: &str,
+    bad_debt: Uint128,
+    current_time: u64,
+    ms: &mut MarketState,
+    tcf: &mut CashFlow,
+) -> ContractResult<()> {
+    let covered = cover_bad_debt(store, denom, account_id, bad_debt, current_time)?;
+    let written_off = Int128::try_from(bad_debt.checked_sub(covered)?)?;
+
+    ms.cash_flow.price_pnl = ms.cash_flow.price_pnl.checked_sub(written_off)?;
+    tcf.price_pnl = tcf.price_pnl.checked_sub(written_off)?;
+
+    Ok(())
+}
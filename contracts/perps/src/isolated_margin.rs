@@ -0,0 +1,339 @@
+use std::cmp::min;
+
+use cosmwasm_std::{
+    coins, ensure_eq, to_json_binary, Addr, BankMsg, CosmosMsg, Decimal, DepsMut, Env, Int128,
+    MessageInfo, Response, Storage, Uint128, WasmMsg,
+};
+use cw_utils::must_pay;
+use mars_perps_common::margin::isolated_health_factor;
+use mars_types::{
+    address_provider::{self, helpers::query_contract_addrs, MarsAddressType},
+    credit_manager,
+    oracle::ActionKind,
+    params::AssetParams,
+    perps::{CashFlow, MarketState},
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    funding::record_funding_snapshot,
+    insurance_fund::absorb_bad_debt,
+    limit_order::remove_limit_orders_of_position,
+    market::MarketStateExt,
+    position::{PositionExt, PositionModification},
+    position_management::{apply_pnl_and_fees, compute_discounted_fee_rates},
+    state::{CONFIG, MARKET_STATES, POSITIONS, REALIZED_PNL, TOTAL_CASH_FLOW},
+    utils::{
+        get_credit_manager_adapter, get_oracle_adapter, get_params_adapter,
+        update_position_attributes,
+    },
+};
+
+/// Settles the realized PnL of an isolated-margin position against its margin and returns what is
+/// left of the margin.
+///
+/// Losses exceeding the margin can't be collected from the credit account. The shortfall is
+/// covered by the insurance fund of the market first. What the fund can't cover is written off
+/// from the vault's cash flow, which assumed the full loss to be paid.
+pub fn settle_isolated_margin(
+    store: &mut dyn Storage,
+    denom: &str,
+    account_id: &str,
+    current_time: u64,
+    margin: Uint128,
+    pnl: Int128,
+    ms: &mut MarketState,
+    tcf: &mut CashFlow,
+) -> ContractResult<Uint128> {
+    let margin_after = Int128::try_from(margin)?.checked_add(pnl)?;
+    if !margin_after.is_negative() {
+        return Ok(margin_after.unsigned_abs());
+    }
+
+    absorb_bad_debt(store, denom, account_id, margin_after.unsigned_abs(), current_time, ms, tcf)?;
+
+    Ok(Uint128::zero())
+}
+
+/// Adds collateral to an isolated-margin position.
+pub fn add_isolated_margin(
+    deps: DepsMut,
+    info: MessageInfo,
+    account_id: String,
+    denom: String,
+) -> ContractResult<Response> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let cm_address = address_provider::helpers::query_contract_addr(
+        deps.as_ref(),
+        &cfg.address_provider,
+        MarsAddressType::CreditManager,
+    )?;
+
+    // Only the credit manager contract can add margin
+    ensure_eq!(info.sender, cm_address, ContractError::SenderIsNotCreditManager);
+
+    let amount = must_pay(&info, &cfg.base_denom)?;
+
+    let mut position =
+        POSITIONS.may_load(deps.storage, (&account_id, &denom))?.ok_or_else(|| {
+            ContractError::PositionNotFound {
+                account_id: account_id.clone(),
+                denom: denom.clone(),
+            }
+        })?;
+    let margin = position.isolated_margin.ok_or_else(|| ContractError::PositionNotIsolated {
+        account_id: account_id.clone(),
+        denom: denom.clone(),
+    })?;
+
+    let new_margin = margin.checked_add(amount)?;
+    position.isolated_margin = Some(new_margin);
+    POSITIONS.save(deps.storage, (&account_id, &denom), &position)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_isolated_margin")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("isolated_margin", new_margin.to_string()))
+}
+
+/// Closes an isolated-margin position whose margin no longer covers its liquidation requirement.
+///
+/// The health of the position is evaluated only against its own margin, using the market's
+/// liquidation threshold. Once closed, the PnL is settled against the margin. The liquidator is
+/// paid a bonus out of what is left of it, the rest is returned to the credit account. The rest of
+/// the account is never touched.
+pub fn liquidate_isolated_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    denom: String,
+) -> ContractResult<Response> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Use Liquidation pricing because we want to be sure that the position will be closed
+    let pricing = ActionKind::Liquidation;
+
+    let position = POSITIONS.may_load(deps.storage, (&account_id, &denom))?.ok_or_else(|| {
+        ContractError::PositionNotFound {
+            account_id: account_id.clone(),
+            denom: denom.clone(),
+        }
+    })?;
+    let margin = position.isolated_margin.ok_or_else(|| ContractError::PositionNotIsolated {
+        account_id: account_id.clone(),
+        denom: denom.clone(),
+    })?;
+
+    let addresses = query_contract_addrs(
+        deps.as_ref(),
+        &cfg.address_provider,
+        vec![
+            MarsAddressType::CreditManager,
+            MarsAddressType::Oracle,
+            MarsAddressType::Params,
+            MarsAddressType::RewardsCollector,
+        ],
+    )?;
+
+    let oracle = get_oracle_adapter(&addresses[&MarsAddressType::Oracle]);
+    let params = get_params_adapter(&addresses[&MarsAddressType::Params]);
+
+    let base_denom_price =
+        oracle.query_price(&deps.querier, &cfg.base_denom, pricing.clone())?.price;
+    let denom_price = oracle.query_price(&deps.querier, &denom, pricing)?.price;
+    let perp_params = params.query_perp_params(&deps.querier, &denom)?;
+    let base_denom_params = params.query_asset_params(&deps.querier, &cfg.base_denom)?;
+    let perps_lb_ratio = get_credit_manager_adapter(&addresses[&MarsAddressType::CreditManager])
+        .query_perps_liquidation_bonus_ratio(&deps.querier)?;
+
+    let mut realized_pnl =
+        REALIZED_PNL.may_load(deps.storage, (&account_id, &denom))?.unwrap_or_default();
+    let mut ms = MARKET_STATES.load(deps.storage, &denom)?;
+    let mut tcf = TOTAL_CASH_FLOW.may_load(deps.storage)?.unwrap_or_default();
+
+    // Close the position
+    let initial_skew = ms.skew()?;
+    ms.close_position(env.block.time.seconds(), denom_price, base_denom_price, &position)?;
+
+    // No fee discount is applied to liquidations
+    let (opening_fee_rate, closing_fee_rate) = compute_discounted_fee_rates(&perp_params, None)?;
+    let pnl_amounts = position.compute_pnl(
+        &ms.funding,
+        initial_skew,
+        denom_price,
+        base_denom_price,
+        opening_fee_rate,
+        closing_fee_rate,
+        PositionModification::Decrease(position.size),
+    )?;
+
+    // Only positions below the liquidation threshold can be liquidated
+    let position_value = position.size.unsigned_abs().checked_mul_floor(denom_price)?;
+    let health_factor = isolated_health_factor(
+        margin,
+        pnl_amounts.pnl,
+        base_denom_price,
+        position_value,
+        perp_params.liquidation_threshold,
+    )?
+    .unwrap_or(Decimal::MAX);
+    if health_factor >= Decimal::one() {
+        return Err(ContractError::IsolatedPositionHealthy {
+            health_factor,
+        });
+    }
+
+    let mut attrs = vec![];
+    update_position_attributes(
+        &mut attrs,
+        &denom,
+        &position,
+        Int128::zero(),
+        denom_price,
+        initial_skew,
+        ms.funding.last_funding_accrued_per_unit_in_base_denom,
+        &pnl_amounts,
+    );
+
+    let mut msgs = vec![];
+    apply_pnl_and_fees(
+        deps.storage,
+        &denom,
+        &cfg,
+        &addresses[&MarsAddressType::RewardsCollector],
+        &mut ms,
+        &mut tcf,
+        &mut realized_pnl,
+        &pnl_amounts,
+        &mut attrs,
+        &mut msgs,
+    )?;
+    let margin_left = settle_isolated_margin(
+        deps.storage,
+        &denom,
+        &account_id,
+        env.block.time.seconds(),
+        margin,
+        pnl_amounts.pnl,
+        &mut ms,
+        &mut tcf,
+    )?;
+
+    POSITIONS.remove(deps.storage, (&account_id, &denom));
+    msgs.extend(remove_limit_orders_of_position(
+        deps.storage,
+        &addresses[&MarsAddressType::CreditManager],
+        &account_id,
+        &denom,
+    )?);
+    REALIZED_PNL.save(deps.storage, (&account_id, &denom), &realized_pnl)?;
+    MARKET_STATES.save(deps.storage, &denom, &ms)?;
+    record_funding_snapshot(deps.storage, &denom, &ms)?;
+    TOTAL_CASH_FLOW.save(deps.storage, &tcf)?;
+
+    // The bonus is paid out of the margin left, it can't put the vault at a loss
+    let (liquidator_bonus, protocol_fee) = compute_isolated_liquidation_bonus(
+        health_factor,
+        pnl_amounts.pnl,
+        base_denom_params.as_ref(),
+        perps_lb_ratio,
+        margin_left,
+    )?;
+    if !liquidator_bonus.is_zero() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(liquidator_bonus.u128(), &cfg.base_denom),
+        }));
+    }
+    if !protocol_fee.is_zero() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: addresses[&MarsAddressType::RewardsCollector].to_string(),
+            amount: coins(protocol_fee.u128(), &cfg.base_denom),
+        }));
+    }
+    let margin_left = margin_left.checked_sub(liquidator_bonus)?.checked_sub(protocol_fee)?;
+
+    if !margin_left.is_zero() {
+        msgs.push(return_isolated_margin_msg(
+            &addresses[&MarsAddressType::CreditManager],
+            &account_id,
+            &cfg.base_denom,
+            margin_left,
+        )?);
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "liquidate_isolated_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("health_factor", health_factor.to_string())
+        .add_attribute("liquidator", info.sender)
+        .add_attribute("liquidator_bonus", liquidator_bonus.to_string())
+        .add_attribute("protocol_fee", protocol_fee.to_string())
+        .add_attribute("isolated_margin_returned", margin_left.to_string())
+        .add_attributes(attrs))
+}
+
+/// Calculates the bonus paid to the liquidator of an isolated-margin position, in line with the
+/// bonus of cross-margin perps liquidated by the credit manager:
+///
+/// `bonus = perps_lb_ratio * LB * PnL loss`
+///
+/// where the LB of the base denom grows as the HF of the position drops:
+/// `LB = min(starting_lb + slope * (1 - HF), max_lb)`
+///
+/// The protocol liquidation fee of the base denom is charged as a % of the bonus. Both are capped
+/// by the margin left after settling the PnL.
+///
+/// Returns -> (Liquidator Bonus, Protocol Fee)
+fn compute_isolated_liquidation_bonus(
+    health_factor: Decimal,
+    pnl: Int128,
+    base_denom_params: Option<&AssetParams>,
+    perps_lb_ratio: Decimal,
+    margin_left: Uint128,
+) -> ContractResult<(Uint128, Uint128)> {
+    let Some(base_denom_params) = base_denom_params else {
+        return Ok((Uint128::zero(), Uint128::zero()));
+    };
+    if !pnl.is_negative() {
+        return Ok((Uint128::zero(), Uint128::zero()));
+    }
+
+    let lb_params = &base_denom_params.liquidation_bonus;
+    let liquidation_bonus = min(
+        lb_params.starting_lb.checked_add(
+            lb_params.slope.checked_mul(Decimal::one().saturating_sub(health_factor))?,
+        )?,
+        lb_params.max_lb,
+    );
+    let perps_lb = perps_lb_ratio.checked_mul(liquidation_bonus)?;
+
+    let total_bonus = min(pnl.unsigned_abs().checked_mul_floor(perps_lb)?, margin_left);
+
+    // Use ceiling in favour of protocol
+    let protocol_fee = total_bonus.checked_mul_ceil(base_denom_params.protocol_liquidation_fee)?;
+
+    Ok((total_bonus.checked_sub(protocol_fee)?, protocol_fee))
+}
+
+/// Message returning the margin left of a closed isolated-margin position to the credit account,
+/// for positions closed on the perps contract's own initiative.
+pub fn return_isolated_margin_msg(
+    cm_address: &Addr,
+    account_id: &str,
+    base_denom: &str,
+    amount: Uint128,
+) -> ContractResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: cm_address.to_string(),
+        msg: to_json_binary(&credit_manager::ExecuteMsg::ReturnIsolatedMargin {
+            account_id: account_id.to_string(),
+        })?,
+        funds: coins(amount.u128(), base_denom),
+    }))
+}
@@ -3,7 +3,11 @@ pub mod accounting;
 pub mod contract;
 pub mod deleverage;
 pub mod error;
+pub mod funding;
 pub mod initialize;
+pub mod insurance_fund;
+pub mod isolated_margin;
+pub mod limit_order;
 pub mod market;
 pub mod market_management;
 pub mod migrations;
@@ -0,0 +1,388 @@
+use cosmwasm_std::{
+    ensure, ensure_eq, to_json_binary, Addr, Coin, CosmosMsg, Decimal, DepsMut, Empty, Env, Int128,
+    MessageInfo, Order, Reply, Response, StdResult, Storage, SubMsg, SubMsgResult, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use mars_perps_common::pricing::opening_execution_price;
+use mars_types::{
+    address_provider::{
+        helpers::{query_contract_addr, query_contract_addrs},
+        MarsAddressType,
+    },
+    credit_manager,
+    oracle::ActionKind,
+    perps::LimitOrder,
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    market::MarketStateExt,
+    state::{
+        ACCOUNT_LIMIT_ORDERS, BUY_LIMIT_ORDERS, CONFIG, LIMIT_ORDERS,
+        LIMIT_ORDER_FILL_TEMP_STORAGE, MARKET_STATES, NEXT_LIMIT_ORDER_ID, SELL_LIMIT_ORDERS,
+    },
+    utils::{ensure_limit_price, get_oracle_adapter},
+};
+
+pub const LIMIT_ORDER_FILL_REPLY_ID: u64 = 10_002;
+
+/// Maximum number of resting limit orders per account
+pub const MAX_LIMIT_ORDERS_PER_ACCOUNT: u8 = 10;
+
+/// Maximum number of orders per side considered in a single `FillLimitOrders` call
+const MAX_FILLS_PER_SIDE: usize = 10;
+
+/// Places a limit order in the book of the given market.
+///
+/// The opening fee is charged when the order is filled, the same way as for a market order. The
+/// keeper fee is escrowed by the credit manager, the order only records it.
+#[allow(clippy::too_many_arguments)]
+pub fn place_limit_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    denom: String,
+    size: Int128,
+    limit_price: Decimal,
+    reduce_only: Option<bool>,
+    keeper_fee: Coin,
+) -> ContractResult<Response> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let cm_address =
+        query_contract_addr(deps.as_ref(), &cfg.address_provider, MarsAddressType::CreditManager)?;
+
+    // Only the credit manager contract can place limit orders
+    ensure_eq!(info.sender, cm_address, ContractError::SenderIsNotCreditManager);
+
+    // The denom must exist and have been enabled
+    let ms = MARKET_STATES.may_load(deps.storage, &denom)?.ok_or_else(|| {
+        ContractError::DenomNotFound {
+            denom: denom.clone(),
+        }
+    })?;
+    if !ms.enabled {
+        return Err(ContractError::DenomNotEnabled {
+            denom,
+        });
+    }
+
+    ensure!(
+        !size.is_zero(),
+        ContractError::InvalidLimitOrder {
+            reason: "size can't be zero".to_string()
+        }
+    );
+    ensure!(
+        !limit_price.is_zero(),
+        ContractError::InvalidLimitOrder {
+            reason: "limit price can't be zero".to_string()
+        }
+    );
+
+    // Number of resting limit orders per account is limited
+    let account_orders = ACCOUNT_LIMIT_ORDERS
+        .prefix(&account_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    if account_orders >= MAX_LIMIT_ORDERS_PER_ACCOUNT as usize {
+        return Err(ContractError::MaxLimitOrdersReached {
+            account_id,
+            max_limit_orders: MAX_LIMIT_ORDERS_PER_ACCOUNT,
+        });
+    }
+
+    let order_id = NEXT_LIMIT_ORDER_ID.may_load(deps.storage)?.unwrap_or(1);
+    NEXT_LIMIT_ORDER_ID.save(deps.storage, &(order_id + 1))?;
+
+    let order = LimitOrder {
+        order_id,
+        account_id,
+        denom,
+        size,
+        limit_price,
+        reduce_only,
+        created_at: env.block.time.seconds(),
+        keeper_fee,
+    };
+    save_limit_order(deps.storage, &order)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "place_limit_order")
+        .add_attribute("account_id", order.account_id)
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("denom", order.denom)
+        .add_attribute("size", order.size.to_string())
+        .add_attribute("limit_price", order.limit_price.to_string())
+        .add_attribute("keeper_fee", order.keeper_fee.to_string()))
+}
+
+/// Removes a resting limit order from the book. The keeper fee is refunded to the account.
+pub fn cancel_limit_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    account_id: String,
+    order_id: u64,
+) -> ContractResult<Response> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let cm_address =
+        query_contract_addr(deps.as_ref(), &cfg.address_provider, MarsAddressType::CreditManager)?;
+
+    // Only the credit manager contract can cancel limit orders
+    ensure_eq!(info.sender, cm_address, ContractError::SenderIsNotCreditManager);
+
+    let order = LIMIT_ORDERS
+        .may_load(deps.storage, order_id)?
+        .filter(|order| order.account_id == account_id)
+        .ok_or_else(|| ContractError::LimitOrderNotFound {
+            account_id: account_id.clone(),
+            order_id,
+        })?;
+    remove_limit_order(deps.storage, &order);
+
+    Ok(Response::new()
+        .add_messages(refund_keeper_fee_msg(&cm_address, &order)?)
+        .add_attribute("action", "cancel_limit_order")
+        .add_attribute("account_id", account_id)
+        .add_attribute("order_id", order_id.to_string()))
+}
+
+/// Fills the resting limit orders of a market whose limit price is satisfied by the current
+/// oracle price.
+///
+/// Buy orders are matched from the highest limit price down, sell orders from the lowest limit
+/// price up. A matched order keeps resting if its execution price at the current skew would be
+/// worse than its limit price. The market impact of the other orders of the batch is not simulated
+/// here, as their fills are not confirmed yet; the credit manager checks the limit price against
+/// the actual skew at execution time.
+///
+/// Filled orders are removed from the book and executed through the credit manager, so that the
+/// opening fee, the market state accumulators and the account's health are handled exactly as
+/// for a market order. The limit price is passed along as the worst acceptable execution price.
+/// The credit manager pays the keeper fee of every executed order to the sender.
+pub fn fill_limit_orders(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> ContractResult<Response> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let addresses = query_contract_addrs(
+        deps.as_ref(),
+        &cfg.address_provider,
+        vec![MarsAddressType::CreditManager, MarsAddressType::Oracle],
+    )?;
+
+    // The denom must exist and have been enabled
+    let ms = MARKET_STATES.may_load(deps.storage, &denom)?.ok_or_else(|| {
+        ContractError::DenomNotFound {
+            denom: denom.clone(),
+        }
+    })?;
+    if !ms.enabled {
+        return Err(ContractError::DenomNotEnabled {
+            denom,
+        });
+    }
+
+    let oracle = get_oracle_adapter(&addresses[&MarsAddressType::Oracle]);
+    let denom_price = oracle.query_price(&deps.querier, &denom, ActionKind::Default)?.price;
+    let price_key = denom_price.atomics().u128();
+
+    // Buy orders with a limit price at or above the oracle price
+    let buy_order_ids = BUY_LIMIT_ORDERS
+        .sub_prefix(&denom)
+        .keys(deps.storage, Some(Bound::inclusive((price_key, 0))), None, Order::Descending)
+        .take(MAX_FILLS_PER_SIDE)
+        .map(|key| key.map(|(_, order_id)| order_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Sell orders with a limit price at or below the oracle price
+    let sell_order_ids = SELL_LIMIT_ORDERS
+        .sub_prefix(&denom)
+        .keys(deps.storage, None, Some(Bound::inclusive((price_key, u64::MAX))), Order::Ascending)
+        .take(MAX_FILLS_PER_SIDE)
+        .map(|key| key.map(|(_, order_id)| order_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let skew = ms.skew()?;
+
+    let mut submsgs = vec![];
+    let mut filled_orders = vec![];
+
+    for order_id in buy_order_ids.into_iter().chain(sell_order_ids) {
+        let order = LIMIT_ORDERS.load(deps.storage, order_id)?;
+
+        let exec_price =
+            opening_execution_price(skew, ms.funding.skew_scale, order.size, denom_price)?;
+        if ensure_limit_price(order.size, exec_price, Some(order.limit_price)).is_err() {
+            continue;
+        }
+
+        remove_limit_order(deps.storage, &order);
+
+        let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: addresses[&MarsAddressType::CreditManager].to_string(),
+            msg: to_json_binary(&credit_manager::ExecuteMsg::FillPerpLimitOrder {
+                account_id: order.account_id.clone(),
+                denom: order.denom.clone(),
+                size: order.size,
+                limit_price: order.limit_price,
+                reduce_only: order.reduce_only,
+                keeper: info.sender.to_string(),
+                keeper_fee: order.keeper_fee.clone(),
+            })?,
+            funds: vec![],
+        });
+
+        // A failing order (e.g. the account would become unhealthy) must not block the others.
+        // Every fill is replied to, so that the reply can be matched with its order.
+        submsgs.push(SubMsg::reply_always(msg, LIMIT_ORDER_FILL_REPLY_ID));
+        filled_orders.push(order);
+    }
+
+    if submsgs.is_empty() {
+        return Err(ContractError::NoLimitOrdersToFill {
+            denom,
+        });
+    }
+
+    let order_ids =
+        filled_orders.iter().map(|order| order.order_id.to_string()).collect::<Vec<_>>();
+    LIMIT_ORDER_FILL_TEMP_STORAGE.save(deps.storage, &filled_orders)?;
+
+    Ok(Response::new()
+        .add_submessages(submsgs)
+        .add_attribute("action", "fill_limit_orders")
+        .add_attribute("denom", denom)
+        .add_attribute("current_price", denom_price.to_string())
+        .add_attribute("order_ids", order_ids.join(",")))
+}
+
+/// Handles the result of a limit order execution in the credit manager.
+///
+/// Replies arrive in the order of the submessages, so the first order in the temporary storage is
+/// the one replied to. A failed order has already been removed from the book. If its limit price
+/// can't be met at the current skew (e.g. moved by the other fills of the batch), it failed on the
+/// limit price only and is put back in the book. Otherwise it is dropped and its keeper fee is
+/// refunded to the account.
+pub fn handle_limit_order_fill_reply(deps: DepsMut, reply: Reply) -> ContractResult<Response> {
+    let mut orders = LIMIT_ORDER_FILL_TEMP_STORAGE.load(deps.storage)?;
+    let order = orders.remove(0);
+    if orders.is_empty() {
+        LIMIT_ORDER_FILL_TEMP_STORAGE.remove(deps.storage);
+    } else {
+        LIMIT_ORDER_FILL_TEMP_STORAGE.save(deps.storage, &orders)?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "fill_limit_order/handle_reply")
+        .add_attribute("account_id", order.account_id.clone())
+        .add_attribute("order_id", order.order_id.to_string());
+
+    match reply.result {
+        SubMsgResult::Err(err) => {
+            let cfg = CONFIG.load(deps.storage)?;
+            let addresses = query_contract_addrs(
+                deps.as_ref(),
+                &cfg.address_provider,
+                vec![MarsAddressType::CreditManager, MarsAddressType::Oracle],
+            )?;
+
+            let ms = MARKET_STATES.load(deps.storage, &order.denom)?;
+            let denom_price = get_oracle_adapter(&addresses[&MarsAddressType::Oracle])
+                .query_price(&deps.querier, &order.denom, ActionKind::Default)?
+                .price;
+            let exec_price = opening_execution_price(
+                ms.skew()?,
+                ms.funding.skew_scale,
+                order.size,
+                denom_price,
+            )?;
+            if ensure_limit_price(order.size, exec_price, Some(order.limit_price)).is_err() {
+                save_limit_order(deps.storage, &order)?;
+                return Ok(res.add_attribute("status", "restored").add_attribute("error", err));
+            }
+
+            Ok(res
+                .add_messages(refund_keeper_fee_msg(
+                    &addresses[&MarsAddressType::CreditManager],
+                    &order,
+                )?)
+                .add_attribute("status", "failed")
+                .add_attribute("error", err))
+        }
+        SubMsgResult::Ok(_) => Ok(res.add_attribute("status", "filled")),
+    }
+}
+
+/// Removes the resting reduce-only limit orders of an account for the given denom, once its
+/// position has been closed or liquidated, as there is nothing left for them to reduce. Entry
+/// orders stay in the book. The keeper fees are refunded to the account.
+pub fn remove_limit_orders_of_position(
+    store: &mut dyn Storage,
+    cm_address: &Addr,
+    account_id: &str,
+    denom: &str,
+) -> ContractResult<Vec<CosmosMsg>> {
+    let order_ids = ACCOUNT_LIMIT_ORDERS
+        .prefix(account_id)
+        .keys(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs = vec![];
+    for order_id in order_ids {
+        let order = LIMIT_ORDERS.load(store, order_id)?;
+        if order.denom != denom || order.reduce_only != Some(true) {
+            continue;
+        }
+
+        remove_limit_order(store, &order);
+        msgs.extend(refund_keeper_fee_msg(cm_address, &order)?);
+    }
+
+    Ok(msgs)
+}
+
+/// Message refunding the escrowed keeper fee of a removed order to the account. Nothing is
+/// refunded for orders without a fee.
+fn refund_keeper_fee_msg(cm_address: &Addr, order: &LimitOrder) -> StdResult<Option<CosmosMsg>> {
+    if order.keeper_fee.amount.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: cm_address.to_string(),
+        msg: to_json_binary(&credit_manager::ExecuteMsg::RefundPerpLimitOrderFee {
+            account_id: order.account_id.clone(),
+            keeper_fee: order.keeper_fee.clone(),
+        })?,
+        funds: vec![],
+    })))
+}
+
+fn save_limit_order(store: &mut dyn Storage, order: &LimitOrder) -> StdResult<()> {
+    LIMIT_ORDERS.save(store, order.order_id, order)?;
+    ACCOUNT_LIMIT_ORDERS.save(store, (&order.account_id, order.order_id), &Empty {})?;
+
+    let book_key = (order.denom.as_str(), order.limit_price.atomics().u128(), order.order_id);
+    if order.size.is_negative() {
+        SELL_LIMIT_ORDERS.save(store, book_key, &Empty {})
+    } else {
+        BUY_LIMIT_ORDERS.save(store, book_key, &Empty {})
+    }
+}
+
+fn remove_limit_order(store: &mut dyn Storage, order: &LimitOrder) {
+    LIMIT_ORDERS.remove(store, order.order_id);
+    ACCOUNT_LIMIT_ORDERS.remove(store, (&order.account_id, order.order_id));
+
+    let book_key = (order.denom.as_str(), order.limit_price.atomics().u128(), order.order_id);
+    if order.size.is_negative() {
+        SELL_LIMIT_ORDERS.remove(store, book_key);
+    } else {
+        BUY_LIMIT_ORDERS.remove(store, book_key);
+    }
+}
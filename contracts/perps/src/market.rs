@@ -941,6 +941,7 @@ mod tests {
                     .unwrap(),
                 initial_skew: Int128::from_str("-12000").unwrap(),
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             },
         )
         .unwrap();
@@ -9,6 +9,7 @@ use mars_types::{
 
 use crate::{
     error::{ContractError, ContractResult},
+    funding::record_funding_snapshot,
     market::MarketStateExt,
     state::{CONFIG, MARKET_STATES},
     utils::get_oracle_adapter,
@@ -44,6 +45,7 @@ pub fn update_market(
 
     // Save the updated market state to storage
     MARKET_STATES.save(deps.storage, &params.denom, &market_state)?;
+    record_funding_snapshot(deps.storage, &params.denom, &market_state)?;
 
     // Return a response indicating the success of the update, with relevant attributes
     Ok(Response::new()
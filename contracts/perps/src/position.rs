@@ -276,7 +276,8 @@ mod tests {
             entry_exec_price: Decimal::from_str("4200.966").unwrap(),
             entry_accrued_funding_per_unit_in_base_denom: SignedDecimal::from_str("-14").unwrap(),
             initial_skew: Int128::from_str("180").unwrap(),
-            realized_pnl: PnlAmounts::default()
+            realized_pnl: PnlAmounts::default(),
+            isolated_margin: None,
         },
         Decimal::from_str("4200").unwrap(),
         Decimal::zero(),
@@ -296,7 +297,8 @@ mod tests {
             entry_exec_price: Decimal::from_str("4201.134").unwrap(),
             entry_accrued_funding_per_unit_in_base_denom: SignedDecimal::from_str("-12.826").unwrap(),
             initial_skew: Int128::from_str("220").unwrap(),
-            realized_pnl: PnlAmounts::default()
+            realized_pnl: PnlAmounts::default(),
+            isolated_margin: None,
         },
         Decimal::from_str("4400").unwrap(),
         Decimal::from_str("0.02").unwrap(),
@@ -316,7 +318,8 @@ mod tests {
             entry_exec_price: Decimal::from_str("4201.134").unwrap(),
             entry_accrued_funding_per_unit_in_base_denom: SignedDecimal::from_str("-12.826").unwrap(),
             initial_skew: Int128::from_str("220").unwrap(),
-            realized_pnl: PnlAmounts::default()
+            realized_pnl: PnlAmounts::default(),
+            isolated_margin: None,
         },
         Decimal::from_str("4000").unwrap(),
         Decimal::from_str("0.02").unwrap(),
@@ -336,7 +339,8 @@ mod tests {
             entry_exec_price: Decimal::from_str("4201.386").unwrap(),
             entry_accrued_funding_per_unit_in_base_denom: SignedDecimal::from_str("-14").unwrap(),
             initial_skew: Int128::from_str("380").unwrap(),
-            realized_pnl: PnlAmounts::default()
+            realized_pnl: PnlAmounts::default(),
+            isolated_margin: None,
         },
         Decimal::from_str("4200").unwrap(),
         Decimal::zero(),
@@ -356,7 +360,8 @@ mod tests {
             entry_exec_price: Decimal::from_str("4200.714").unwrap(),
             entry_accrued_funding_per_unit_in_base_denom: SignedDecimal::from_str("-12.826").unwrap(),
             initial_skew: Int128::from_str("220").unwrap(),
-            realized_pnl: PnlAmounts::default()
+            realized_pnl: PnlAmounts::default(),
+            isolated_margin: None,
         },
         Decimal::from_str("4400").unwrap(),
         Decimal::from_str("0.02").unwrap(),
@@ -376,7 +381,8 @@ mod tests {
             entry_exec_price: Decimal::from_str("4200.714").unwrap(),
             entry_accrued_funding_per_unit_in_base_denom: SignedDecimal::from_str("-12.826").unwrap(),
             initial_skew: Int128::from_str("220").unwrap(),
-            realized_pnl: PnlAmounts::default()
+            realized_pnl: PnlAmounts::default(),
+            isolated_margin: None,
         },
         Decimal::from_str("4000").unwrap(),
         Decimal::from_str("0.02").unwrap(),
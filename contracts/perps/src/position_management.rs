@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use cosmwasm_std::{
     coins, ensure_eq, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, Int128,
-    MessageInfo, Order, Response, StdError, Uint128,
+    MessageInfo, Order, Response, StdError, Storage, Uint128,
 };
 use cw_utils::may_pay;
 use mars_perps_common::pricing::opening_execution_price;
@@ -16,12 +16,16 @@ use mars_types::{
 use crate::{
     accounting::CashFlowExt,
     error::{ContractError, ContractResult},
+    funding::record_funding_snapshot,
+    insurance_fund::{absorb_bad_debt, increase_insurance_fund},
+    isolated_margin::settle_isolated_margin,
+    limit_order::remove_limit_orders_of_position,
     market::MarketStateExt,
     position::{calculate_new_size, PositionExt, PositionModification},
     state::{CONFIG, MARKET_STATES, POSITIONS, REALIZED_PNL, TOTAL_CASH_FLOW},
     utils::{
-        ensure_max_position, ensure_min_position, get_oracle_adapter, get_params_adapter,
-        update_position_attributes,
+        ensure_isolated_margin, ensure_limit_price, ensure_max_position, ensure_min_position,
+        get_oracle_adapter, get_params_adapter, update_position_attributes,
     },
 };
 
@@ -45,6 +49,15 @@ pub fn compute_discounted_fee_rates(
 ///
 /// Depending on whether a position exists and the reduce_only flag, this function either opens a new
 /// position, modifies an existing one, or returns an error if the operation is illegal.
+///
+/// If a limit price is provided, the order is rejected when its execution price is worse than the limit.
+///
+/// If an isolated margin is provided, the new position is backed only by that margin instead of the
+/// whole credit account.
+///
+/// The order is priced with the given action. Liquidation pricing can only be used by reduce-only
+/// orders, so that partial liquidations go through when default prices are unavailable.
+#[allow(clippy::too_many_arguments)]
 pub fn execute_order(
     deps: DepsMut,
     env: Env,
@@ -54,18 +67,52 @@ pub fn execute_order(
     size: Int128,
     reduce_only: Option<bool>,
     discount_pct: Option<Decimal>,
+    limit_price: Option<Decimal>,
+    isolated_margin: Option<Uint128>,
+    action: ActionKind,
 ) -> ContractResult<Response> {
     let position = POSITIONS.may_load(deps.storage, (&account_id, &denom))?;
     let reduce_only_checked = reduce_only.unwrap_or(false);
 
+    // Liquidation pricing may only be used to reduce positions
+    if action == ActionKind::Liquidation && !reduce_only_checked {
+        return Err(ContractError::IllegalPositionModification {
+            reason: "Liquidation pricing is only allowed for reduce_only orders".to_string(),
+        });
+    }
+
     match position {
         None if reduce_only_checked => Err(ContractError::IllegalPositionModification {
             reason: "Cannot open position if reduce_only = true".to_string(),
         }),
-        None => open_position(deps, env, info, account_id, denom, size, discount_pct),
+        None => open_position(
+            deps,
+            env,
+            info,
+            account_id,
+            denom,
+            size,
+            discount_pct,
+            limit_price,
+            isolated_margin,
+        ),
+        Some(_) if isolated_margin.is_some() => Err(ContractError::IllegalPositionModification {
+            reason: "Isolated margin can only be set when opening a position".to_string(),
+        }),
         Some(position) => {
             let new_size = calculate_new_size(position.size, size, reduce_only_checked)?;
-            modify_position(deps, env, info, position, account_id, denom, new_size, discount_pct)
+            modify_position(
+                deps,
+                env,
+                info,
+                position,
+                account_id,
+                denom,
+                new_size,
+                discount_pct,
+                limit_price,
+                action,
+            )
         }
     }
 }
@@ -73,6 +120,7 @@ pub fn execute_order(
 ///
 /// This function checks if the account can open a new position, validates the position parameters,
 /// and then creates the new position, updating the necessary states and applying any opening fees.
+#[allow(clippy::too_many_arguments)]
 fn open_position(
     deps: DepsMut,
     env: Env,
@@ -81,6 +129,8 @@ fn open_position(
     denom: String,
     size: Int128,
     discount_pct: Option<Decimal>,
+    limit_price: Option<Decimal>,
+    isolated_margin: Option<Uint128>,
 ) -> ContractResult<Response> {
     let cfg = CONFIG.load(deps.storage)?;
 
@@ -141,7 +191,7 @@ fn open_position(
     let (opening_fee_rate, closing_fee_rate) =
         compute_discounted_fee_rates(&perp_params, discount_pct)?;
 
-    let opening_fee_amt = may_pay(&info, &cfg.base_denom)?;
+    let paid_amount = may_pay(&info, &cfg.base_denom)?;
 
     // Query the asset's price.
     //
@@ -167,23 +217,43 @@ fn open_position(
         perp_params.skew_scale,
     )?;
 
-    // Ensure the opening fee amount sent is correct
+    // Ensure the amount sent is correct. For isolated-margin positions the margin is sent along
+    // with the opening fee.
+    let opening_fee_amt = fees.opening_fee.unsigned_abs();
+    let required_amt = opening_fee_amt.checked_add(isolated_margin.unwrap_or_default())?;
     ensure_eq!(
-        opening_fee_amt,
-        fees.opening_fee.unsigned_abs(),
+        paid_amount,
+        required_amt,
         ContractError::InvalidPayment {
             denom,
-            required: fees.opening_fee.unsigned_abs(),
-            received: opening_fee_amt,
+            required: required_amt,
+            received: paid_amount,
         }
     );
 
+    // The margin of an isolated-margin position must cover the position on its own
+    if let Some(margin) = isolated_margin {
+        ensure_isolated_margin(
+            margin,
+            size,
+            denom_price,
+            base_denom_price,
+            closing_fee_rate,
+            &perp_params,
+        )?;
+    }
+
     // Validate the position's size against OI limits
     ms.validate_open_interest(size, Int128::zero(), denom_price, &perp_params)?;
 
     // Skew _before_ modification
     let initial_skew = ms.skew()?;
 
+    // The order is executed at the opening execution price of the new position
+    let entry_exec_price =
+        opening_execution_price(initial_skew, ms.funding.skew_scale, size, denom_price)?;
+    ensure_limit_price(size, entry_exec_price, limit_price)?;
+
     // Update the denom's accumulators.
     // Funding rates and index is updated to the current block time (using old size).
     ms.open_position(env.block.time.seconds(), size, denom_price, base_denom_price)?;
@@ -201,6 +271,8 @@ fn open_position(
         let unrealized_pnl = PnlAmounts::from_opening_fee(opening_fee_amt)?;
 
         apply_pnl_and_fees(
+            deps.storage,
+            &denom,
             &cfg,
             &rewards_collector_addr,
             &mut ms,
@@ -220,10 +292,9 @@ fn open_position(
 
     let entry_accrued_funding_per_unit_in_base_denom =
         ms.funding.last_funding_accrued_per_unit_in_base_denom;
-    let entry_exec_price =
-        opening_execution_price(initial_skew, ms.funding.skew_scale, size, denom_price)?;
 
     MARKET_STATES.save(deps.storage, &denom, &ms)?;
+    record_funding_snapshot(deps.storage, &denom, &ms)?;
 
     // Save the user's new position with updated funding
     POSITIONS.save(
@@ -236,9 +307,14 @@ fn open_position(
             entry_accrued_funding_per_unit_in_base_denom,
             initial_skew,
             realized_pnl: position_realized_pnl,
+            isolated_margin,
         },
     )?;
 
+    if let Some(margin) = isolated_margin {
+        attrs.push(Attribute::new("isolated_margin", margin.to_string()));
+    }
+
     Ok(Response::new()
         .add_messages(msgs)
         .add_attribute("action", "open_position")
@@ -268,6 +344,8 @@ fn modify_position(
     denom: String,
     new_size: Int128,
     discount_pct: Option<Decimal>,
+    limit_price: Option<Decimal>,
+    action: ActionKind,
 ) -> ContractResult<Response> {
     // Load the contract's configuration
     let cfg = CONFIG.load(deps.storage)?;
@@ -307,9 +385,8 @@ fn modify_position(
     let entry_size = position.size;
 
     // Query the current prices for the denom and the base denom
-    let denom_price = oracle.query_price(&deps.querier, &denom, ActionKind::Default)?.price;
-    let base_denom_price =
-        oracle.query_price(&deps.querier, &cfg.base_denom, ActionKind::Default)?.price;
+    let denom_price = oracle.query_price(&deps.querier, &denom, action.clone())?.price;
+    let base_denom_price = oracle.query_price(&deps.querier, &cfg.base_denom, action)?.price;
 
     // When modifying a position, we must realise all PnL. The credit manager
     // may send no coin (in case the position is winning or breaking even) or
@@ -319,6 +396,13 @@ fn modify_position(
     // skew _before_ modification
     let initial_skew = ms.skew()?;
 
+    // The market impact is linear in the skew, so the execution price of the order is the same
+    // no matter if it increases, reduces or flips the position.
+    let order_size = new_size.checked_sub(entry_size)?;
+    let order_exec_price =
+        opening_execution_price(initial_skew, ms.funding.skew_scale, order_size, denom_price)?;
+    ensure_limit_price(order_size, order_exec_price, limit_price)?;
+
     // Determine the type of modification to the position based on the new size
     let modification = if new_size.is_zero() {
         // Close the position
@@ -352,6 +436,7 @@ fn modify_position(
 
         modification
     };
+    let position_increasing = !matches!(modification, PositionModification::Decrease(..));
 
     // Compute the position's unrealized PnL
     let pnl_amounts = position.compute_pnl(
@@ -369,8 +454,50 @@ fn modify_position(
 
     let mut msgs = vec![];
 
-    // Apply the payment to the credit manager if necessary
-    apply_payment_to_cm_if_needed(&cfg, cm_address, &mut msgs, paid_amount, &pnl)?;
+    let isolated_margin = match position.isolated_margin {
+        // Isolated-margin positions settle their PnL against the margin. Nothing is paid by the
+        // credit manager, and the margin left is only sent back once the position is closed.
+        Some(margin) => {
+            ensure_eq!(
+                paid_amount,
+                Uint128::zero(),
+                ContractError::InvalidPayment {
+                    denom: cfg.base_denom.clone(),
+                    required: Uint128::zero(),
+                    received: paid_amount,
+                }
+            );
+
+            let margin_after = Int128::try_from(margin)?.checked_add(pnl_amounts.pnl)?;
+            if !new_size.is_zero() {
+                if margin_after <= Int128::zero() {
+                    return Err(ContractError::IllegalPositionModification {
+                        reason:
+                            "Losses exceed the isolated margin, the position can only be closed"
+                                .to_string(),
+                    });
+                }
+
+                if position_increasing {
+                    ensure_isolated_margin(
+                        margin_after.unsigned_abs(),
+                        new_size,
+                        denom_price,
+                        base_denom_price,
+                        closing_fee_rate,
+                        &perp_params,
+                    )?;
+                }
+            }
+
+            Some(margin)
+        }
+        None => {
+            // Apply the payment to the credit manager if necessary
+            apply_payment_to_cm_if_needed(&cfg, cm_address, &mut msgs, paid_amount, &pnl)?;
+            None
+        }
+    };
 
     // Reduce the initial skew by the old position size. It is new "initial skew".
     let initial_skew = initial_skew.checked_sub(position.size)?;
@@ -392,6 +519,8 @@ fn modify_position(
 
     // Update the realized PnL, market state, and total cash flow based on the new amounts
     apply_pnl_and_fees(
+        deps.storage,
+        &denom,
         &cfg,
         &addresses[&MarsAddressType::RewardsCollector],
         &mut ms,
@@ -402,10 +531,42 @@ fn modify_position(
         &mut msgs,
     )?;
 
+    let isolated_margin = isolated_margin
+        .map(|margin| {
+            settle_isolated_margin(
+                deps.storage,
+                &denom,
+                &account_id,
+                env.block.time.seconds(),
+                margin,
+                pnl_amounts.pnl,
+                &mut ms,
+                &mut tcf,
+            )
+        })
+        .transpose()?;
+    if let Some(margin) = isolated_margin {
+        attrs.push(Attribute::new("isolated_margin", margin.to_string()));
+    }
+
     // Modify or delete the position state based on the new size
     let method = if new_size.is_zero() {
         // Delete the position if the new size is zero
         POSITIONS.remove(deps.storage, (&account_id, &denom));
+        msgs.extend(remove_limit_orders_of_position(
+            deps.storage,
+            cm_address,
+            &account_id,
+            &denom,
+        )?);
+
+        // Send the margin left to the credit manager
+        if let Some(margin) = isolated_margin.filter(|margin| !margin.is_zero()) {
+            msgs.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: cm_address.into(),
+                amount: coins(margin.u128(), &cfg.base_denom),
+            }));
+        }
 
         "close_position"
     } else {
@@ -426,6 +587,7 @@ fn modify_position(
                 entry_accrued_funding_per_unit_in_base_denom,
                 initial_skew,
                 realized_pnl,
+                isolated_margin,
             },
         )?;
 
@@ -435,6 +597,7 @@ fn modify_position(
     // Save the updated state variables
     REALIZED_PNL.save(deps.storage, (&account_id, &denom), &realized_pnl)?;
     MARKET_STATES.save(deps.storage, &denom, &ms)?;
+    record_funding_snapshot(deps.storage, &denom, &ms)?;
     TOTAL_CASH_FLOW.save(deps.storage, &tcf)?;
 
     // Return the response with the appropriate attributes
@@ -445,7 +608,14 @@ fn modify_position(
         .add_attributes(attrs))
 }
 
-/// Closes all positions for a given account.
+/// Closes all cross-margin positions for a given account.
+///
+/// Isolated-margin positions are backed by their own margin, not by the credit account, so they
+/// are left open.
+///
+/// The bad debt given by the credit manager is the part of the loss the account can't back with
+/// its collateral. Instead of being paid, it is absorbed by the insurance funds of the markets the
+/// account lost in, and then by the vault.
 pub fn close_all_positions(
     deps: DepsMut,
     env: Env,
@@ -453,6 +623,7 @@ pub fn close_all_positions(
     account_id: String,
     action: ActionKind,
     discount_pct: Option<Decimal>,
+    bad_debt: Uint128,
 ) -> ContractResult<Response> {
     let cfg = CONFIG.load(deps.storage)?;
 
@@ -477,8 +648,8 @@ pub fn close_all_positions(
     let oracle = get_oracle_adapter(&addresses[&MarsAddressType::Oracle]);
     let params = get_params_adapter(&addresses[&MarsAddressType::Params]);
 
-    // Read all positions for the account
-    let account_positions: Vec<_> = {
+    // Read all cross-margin positions for the account
+    let mut account_positions: Vec<_> = {
         // Collect all positions for the account to avoid problems with mutable/immutable borrows in the same scope
         POSITIONS
             .prefix(&account_id)
@@ -489,6 +660,7 @@ pub fn close_all_positions(
             })
             .collect::<ContractResult<Vec<_>>>()?
     };
+    account_positions.retain(|(_, position)| position.isolated_margin.is_none());
 
     // Read total cash flow
     let mut tcf = TOTAL_CASH_FLOW.may_load(deps.storage)?.unwrap_or_default();
@@ -504,6 +676,7 @@ pub fn close_all_positions(
     let mut attrs = vec![];
     let mut msgs = vec![];
     let mut pnl_amounts_accumulator = PnlAmounts::default();
+    let mut bad_debt_left = bad_debt;
     for (denom, position) in account_positions {
         let mut realized_pnl =
             REALIZED_PNL.may_load(deps.storage, (&account_id, &denom))?.unwrap_or_default();
@@ -552,6 +725,8 @@ pub fn close_all_positions(
         pnl_amounts_accumulator.add(&pnl_amounts)?;
 
         apply_pnl_and_fees(
+            deps.storage,
+            &denom,
             &cfg,
             &addresses[&MarsAddressType::RewardsCollector],
             &mut ms,
@@ -562,17 +737,42 @@ pub fn close_all_positions(
             &mut msgs,
         )?;
 
+        // The bad debt of the account is absorbed by the markets it lost in, up to their loss
+        if pnl_amounts.pnl.is_negative() && !bad_debt_left.is_zero() {
+            let market_bad_debt = bad_debt_left.min(pnl_amounts.pnl.unsigned_abs());
+            absorb_bad_debt(
+                deps.storage,
+                &denom,
+                &account_id,
+                market_bad_debt,
+                env.block.time.seconds(),
+                &mut ms,
+                &mut tcf,
+            )?;
+            bad_debt_left = bad_debt_left.checked_sub(market_bad_debt)?;
+        }
+
         // Remove the position
         POSITIONS.remove(deps.storage, (&account_id, &denom));
+        msgs.extend(remove_limit_orders_of_position(
+            deps.storage,
+            &addresses[&MarsAddressType::CreditManager],
+            &account_id,
+            &denom,
+        )?);
 
         // Save updated states
         REALIZED_PNL.save(deps.storage, (&account_id, &denom), &realized_pnl)?;
         MARKET_STATES.save(deps.storage, &denom, &ms)?;
+        record_funding_snapshot(deps.storage, &denom, &ms)?;
     }
 
     // Convert PnL amounts to coins
     let pnl = pnl_amounts_accumulator.to_coins(&cfg.base_denom).pnl;
 
+    // The credit manager only pays the part of the loss that isn't bad debt
+    let pnl = deduct_bad_debt(pnl, bad_debt)?;
+
     apply_payment_to_cm_if_needed(
         &cfg,
         &addresses[&MarsAddressType::CreditManager],
@@ -588,6 +788,7 @@ pub fn close_all_positions(
         .add_attribute("action", "close_all_positions")
         .add_attribute("account_id", account_id)
         .add_attribute("total_realized_pnl_change", pnl_amounts_accumulator.pnl.to_string())
+        .add_attribute("bad_debt", bad_debt.to_string())
         .add_attributes(attrs))
 }
 
@@ -635,9 +836,14 @@ fn adjust_position_with_validation(
 /// 1. **Update Realized PnL**: Adds unrealized PnL to realized PnL.
 /// 2. **Calculate and Send Protocol Fees**: Computes protocol fees as a percentage of unrealized opening and closing fees,
 ///    then creates and adds a message to send these fees to the rewards collector if applicable.
-/// 3. **Adjust PnL for Protocol Fees**: Calculates the PnL after accounting for protocol fees and updates the market and total cash flows accordingly.
-/// 4. **Update Response**: Adds attributes to the response indicating the protocol opening and closing fees.
+/// 3. **Fund the Insurance Fund**: Computes the insurance fund slice of the opening and closing fees and adds it to
+///    the insurance fund of the market. The coins stay in the contract.
+/// 4. **Adjust PnL for Protocol and Insurance Fees**: Calculates the PnL after accounting for protocol and insurance fees
+///    and updates the market and total cash flows accordingly.
+/// 5. **Update Response**: Adds attributes to the response indicating the protocol and insurance fees.
 pub fn apply_pnl_and_fees(
+    store: &mut dyn Storage,
+    denom: &str,
     cfg: &Config<Addr>,
     rewards_collector: &Addr,
     ms: &mut MarketState,
@@ -671,6 +877,18 @@ pub fn apply_pnl_and_fees(
         msgs.push(msg);
     }
 
+    // Insurance fund fee is calculated the same way, but rounded down in favour of the vault
+    let insurance_opening_fee =
+        unrealized_pnl.opening_fee.unsigned_abs().checked_mul_floor(cfg.insurance_fund_fee_rate)?;
+    let insurance_closing_fee =
+        unrealized_pnl.closing_fee.unsigned_abs().checked_mul_floor(cfg.insurance_fund_fee_rate)?;
+
+    let total_insurance_fee = insurance_opening_fee + insurance_closing_fee;
+
+    if !total_insurance_fee.is_zero() {
+        increase_insurance_fund(store, denom, total_insurance_fee)?;
+    }
+
     // Example calculation for pnl without protocol fee:
     // opening_fee = -2
     // closing_fee = -4
@@ -690,30 +908,63 @@ pub fn apply_pnl_and_fees(
     // pnl_without_protocol = -1 + (-2) + (-1) + 10 = 6
     //
     // pnl + protocol_opening_fee + protocol_closing_fee = 3 + 1 + 2 = 6 which is equal to pnl_without_protocol
-
-    let pnl_without_protocol_fee = PnlAmounts {
-        opening_fee: unrealized_pnl.opening_fee.checked_add(protocol_opening_fee.try_into()?)?,
-        closing_fee: unrealized_pnl.closing_fee.checked_add(protocol_closing_fee.try_into()?)?,
+    //
+    // The insurance fund fee is excluded from the vault's cash flow the same way.
+    let non_vault_opening_fee = protocol_opening_fee.checked_add(insurance_opening_fee)?;
+    let non_vault_closing_fee = protocol_closing_fee.checked_add(insurance_closing_fee)?;
+    let pnl_without_protocol_and_insurance_fees = PnlAmounts {
+        opening_fee: unrealized_pnl.opening_fee.checked_add(non_vault_opening_fee.try_into()?)?,
+        closing_fee: unrealized_pnl.closing_fee.checked_add(non_vault_closing_fee.try_into()?)?,
         pnl: unrealized_pnl
             .pnl
-            .checked_add(protocol_opening_fee.try_into()?)?
-            .checked_add(protocol_closing_fee.try_into()?)?,
+            .checked_add(non_vault_opening_fee.try_into()?)?
+            .checked_add(non_vault_closing_fee.try_into()?)?,
         ..unrealized_pnl.clone()
     };
 
-    // Apply pnl to denom cash flow (without protocol fee)
-    ms.cash_flow.add(&pnl_without_protocol_fee, total_protocol_fee)?;
+    // Apply pnl to denom cash flow (without protocol and insurance fees)
+    ms.cash_flow.add(&pnl_without_protocol_and_insurance_fees, total_protocol_fee)?;
 
-    // Apply pnl to total cash flow (without protocol fee)
-    tcf.add(&pnl_without_protocol_fee, total_protocol_fee)?;
+    // Apply pnl to total cash flow (without protocol and insurance fees)
+    tcf.add(&pnl_without_protocol_and_insurance_fees, total_protocol_fee)?;
 
-    // Add attributes for protocol fees
+    // Add attributes for protocol and insurance fees
     attrs.push(Attribute::new("protocol_opening_fee", protocol_opening_fee.to_string()));
     attrs.push(Attribute::new("protocol_closing_fee", protocol_closing_fee.to_string()));
+    attrs.push(Attribute::new("insurance_opening_fee", insurance_opening_fee.to_string()));
+    attrs.push(Attribute::new("insurance_closing_fee", insurance_closing_fee.to_string()));
 
     Ok(total_protocol_fee)
 }
 
+/// Deducts the bad debt of an account from the loss of its closed positions.
+fn deduct_bad_debt(pnl: PnL, bad_debt: Uint128) -> ContractResult<PnL> {
+    if bad_debt.is_zero() {
+        return Ok(pnl);
+    }
+
+    match pnl {
+        PnL::Loss(Coin {
+            denom,
+            amount,
+        }) if amount >= bad_debt => Ok(PnL::Loss(Coin {
+            denom,
+            amount: amount.checked_sub(bad_debt)?,
+        })),
+        PnL::Loss(Coin {
+            amount,
+            ..
+        }) => Err(ContractError::BadDebtExceedsLoss {
+            bad_debt,
+            loss: amount,
+        }),
+        _ => Err(ContractError::BadDebtExceedsLoss {
+            bad_debt,
+            loss: Uint128::zero(),
+        }),
+    }
+}
+
 /// Applies payments to the credit manager if necessary based on the PnL and paid amount.
 fn apply_payment_to_cm_if_needed(
     cfg: &Config<Addr>,
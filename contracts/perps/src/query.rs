@@ -1,6 +1,8 @@
 use std::{cmp::max, collections::HashMap};
 
-use cosmwasm_std::{coin, Addr, Decimal, Deps, Int128, Order, StdResult, Storage};
+use cosmwasm_std::{
+    coin, ensure, Addr, Decimal, Deps, Int128, Order, SignedDecimal, StdResult, Storage,
+};
 use cw_paginate::{paginate_map_query, PaginationResponse};
 use cw_storage_plus::Bound;
 use mars_perps_common::pricing::{closing_execution_price, opening_execution_price};
@@ -12,26 +14,30 @@ use mars_types::{
     oracle::ActionKind,
     params::PerpParams,
     perps::{
-        AccountingResponse, Config, MarketResponse, MarketState, MarketStateResponse, PerpPosition,
-        PnlAmounts, PositionFeesResponse, PositionResponse, PositionsByAccountResponse, TradingFee,
-        VaultDeposit, VaultPositionResponse, VaultResponse, VaultUnlock,
+        AccountingResponse, AverageFundingRateResponse, Config, FundingSnapshot, InsuranceFundDraw,
+        InsuranceFundResponse, LimitOrder, MarketResponse, MarketState, MarketStateResponse,
+        PerpPosition, PnlAmounts, PositionFeesResponse, PositionResponse,
+        PositionsByAccountResponse, TradingFee, VaultDeposit, VaultPositionResponse, VaultResponse,
+        VaultUnlock,
     },
 };
 
 use crate::{
     accounting::AccountingExt,
-    error::ContractResult,
-    market::{compute_total_accounting_data, MarketStateExt},
+    error::{ContractError, ContractResult},
+    funding::cumulative_funding_rate_at,
+    market::{compute_total_accounting_data, MarketStateExt, SECONDS_IN_DAY},
     position::{PositionExt, PositionModification},
     position_management::compute_discounted_fee_rates,
     state::{
-        CONFIG, DEPOSIT_SHARES, MARKET_STATES, POSITIONS, REALIZED_PNL,
+        ACCOUNT_LIMIT_ORDERS, CONFIG, DEPOSIT_SHARES, FUNDING_SNAPSHOTS, INSURANCE_FUNDS,
+        INSURANCE_FUND_DRAWS, LIMIT_ORDERS, MARKET_STATES, POSITIONS, REALIZED_PNL,
         TOTAL_UNLOCKING_OR_UNLOCKED_SHARES, UNLOCKS, VAULT_STATE,
     },
     utils::{
         create_user_id_key, get_credit_manager_adapter, get_oracle_adapter, get_params_adapter,
     },
-    vault::shares_to_amount,
+    vault::{compute_vault_collateral_value, shares_to_amount},
 };
 
 const DEFAULT_LIMIT: u32 = 10;
@@ -71,22 +77,30 @@ pub fn query_vault(
         &params,
         current_time,
         &cfg.base_denom,
-        action,
+        action.clone(),
     )?;
 
     // Calculate total withdrawal balance
     let total_withdrawal_balance = acc_data.total_withdrawal_balance(&vault_state)?;
 
+    // Calculate value of the collateral held by the vault. Collateral without an available price is
+    // valued at zero, so a stale collateral feed doesn't block withdrawals of the base denom.
+    let (collaterals, total_collateral_value) =
+        compute_vault_collateral_value(&deps, &oracle, &params, &cfg.base_denom, action, true)?;
+    let total_vault_value = total_withdrawal_balance.checked_add(total_collateral_value)?;
+
     // Calculate share price if total shares are non-zero
     let share_price = if vault_state.total_shares.is_zero() {
         None
     } else {
-        Some(Decimal::checked_from_ratio(total_withdrawal_balance, vault_state.total_shares)?)
+        Some(Decimal::checked_from_ratio(total_vault_value, vault_state.total_shares)?)
     };
 
-    // Calculate total cash flow
+    // Calculate total liquidity. The collateral can be swapped to the base denom to pay the PnL of
+    // traders, so its value counts towards the liquidity.
     let total_cash_flow = acc_data.cash_flow.total()?.checked_add(vault_state.total_balance)?;
     let total_cash_flow = max(total_cash_flow, Int128::zero()).unsigned_abs();
+    let total_liquidity = total_cash_flow.checked_add(total_collateral_value)?;
 
     // A positive total unrealized PnL indicates profit for traders, which is treated as a liability for the vault.
     // Thus, the vault's debt is equal to the positive unrealized PnL amount, or zero if there is no profit.
@@ -96,18 +110,15 @@ pub fn query_vault(
     let collateralization_ratio = if total_debt.is_zero() {
         None
     } else {
-        Some(Decimal::checked_from_ratio(total_cash_flow, total_debt)?)
+        Some(Decimal::checked_from_ratio(total_liquidity, total_debt)?)
     };
 
     // Calculate total unlocking/unlocked shares and amount
     let total_unlocking_or_unlocked_shares =
         TOTAL_UNLOCKING_OR_UNLOCKED_SHARES.may_load(deps.storage)?.unwrap_or_default();
-    let total_unlocking_or_unlocked_amount = shares_to_amount(
-        &vault_state,
-        total_unlocking_or_unlocked_shares,
-        total_withdrawal_balance,
-    )
-    .unwrap_or_default();
+    let total_unlocking_or_unlocked_amount =
+        shares_to_amount(&vault_state, total_unlocking_or_unlocked_shares, total_vault_value)
+            .unwrap_or_default();
 
     // Construct and return the VaultResponse
     Ok(VaultResponse {
@@ -116,8 +127,10 @@ pub fn query_vault(
         total_unlocking_or_unlocked_shares,
         total_unlocking_or_unlocked_amount,
         total_withdrawal_balance,
+        collaterals,
+        total_collateral_value,
         share_price,
-        total_liquidity: total_cash_flow,
+        total_liquidity,
         total_debt,
         collateralization_ratio,
     })
@@ -235,12 +248,21 @@ pub fn query_vault_position(
         &cfg.base_denom,
         ActionKind::Default,
     )?;
-    let total_withdrawal_balance = global_acc_data.total_withdrawal_balance(&vs)?;
+    let (_, total_collateral_value) = compute_vault_collateral_value(
+        &deps,
+        &oracle,
+        &params,
+        &cfg.base_denom,
+        ActionKind::Default,
+        true,
+    )?;
+    let total_vault_value =
+        global_acc_data.total_withdrawal_balance(&vs)?.checked_add(total_collateral_value)?;
 
     let shares = shares.unwrap_or_default();
     let perp_vault_deposit = VaultDeposit {
         shares,
-        amount: shares_to_amount(&vs, shares, total_withdrawal_balance).unwrap_or_default(),
+        amount: shares_to_amount(&vs, shares, total_vault_value).unwrap_or_default(),
     };
 
     let unlocks = unlocks.unwrap_or_default();
@@ -251,8 +273,7 @@ pub fn query_vault_position(
                 created_at: unlock.created_at,
                 cooldown_end: unlock.cooldown_end,
                 shares: unlock.shares,
-                amount: shares_to_amount(&vs, unlock.shares, total_withdrawal_balance)
-                    .unwrap_or_default(),
+                amount: shares_to_amount(&vs, unlock.shares, total_vault_value).unwrap_or_default(),
             })
         })
         .collect();
@@ -275,6 +296,7 @@ pub fn query_position(
     denom: String,
     order_size: Option<Int128>,
     reduce_only: Option<bool>,
+    action: ActionKind,
 ) -> ContractResult<PositionResponse> {
     let cfg = CONFIG.load(deps.storage)?;
     let addresses = query_contract_addrs(
@@ -288,9 +310,8 @@ pub fn query_position(
     let credit_manager = addresses[&MarsAddressType::CreditManager].clone();
     let credit_manager_adapter = get_credit_manager_adapter(&credit_manager);
 
-    let denom_price = oracle.query_price(&deps.querier, &denom, ActionKind::Default)?.price;
-    let base_denom_price =
-        oracle.query_price(&deps.querier, &cfg.base_denom, ActionKind::Default)?.price;
+    let denom_price = oracle.query_price(&deps.querier, &denom, action.clone())?.price;
+    let base_denom_price = oracle.query_price(&deps.querier, &cfg.base_denom, action)?.price;
     let perp_params = params.query_perp_params(&deps.querier, &denom)?;
 
     let ms = MARKET_STATES.load(deps.storage, &denom)?;
@@ -341,6 +362,7 @@ pub fn query_position(
             current_exec_price: exit_exec_price,
             unrealized_pnl: pnl_amounts,
             realized_pnl: position.realized_pnl,
+            isolated_margin: position.isolated_margin,
         }),
     })
 }
@@ -437,6 +459,7 @@ pub fn query_positions(
                     current_exec_price: exit_exec_price,
                     unrealized_pnl: pnl_amounts,
                     realized_pnl: position.realized_pnl,
+                    isolated_margin: position.isolated_margin,
                 }),
             })
         })
@@ -527,6 +550,7 @@ pub fn query_positions_by_account(
                 current_exec_price: exit_exec_price,
                 unrealized_pnl: pnl_amounts,
                 realized_pnl: position.realized_pnl,
+                isolated_margin: position.isolated_margin,
             })
         })
         .collect::<ContractResult<Vec<_>>>()?;
@@ -766,3 +790,133 @@ pub fn query_market_state(store: &dyn Storage, denom: String) -> StdResult<Marke
         market_state: ms,
     })
 }
+
+/// Retrieves a single resting limit order.
+pub fn query_limit_order(store: &dyn Storage, order_id: u64) -> StdResult<Option<LimitOrder>> {
+    LIMIT_ORDERS.may_load(store, order_id)
+}
+
+/// Lists resting limit orders of all accounts, ordered by order id.
+pub fn query_limit_orders(
+    store: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LimitOrder>> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    LIMIT_ORDERS
+        .range(store, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, order)| order))
+        .collect()
+}
+
+/// Lists resting limit orders of a specific credit account, ordered by order id.
+pub fn query_limit_orders_by_account(
+    store: &dyn Storage,
+    account_id: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LimitOrder>> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    ACCOUNT_LIMIT_ORDERS
+        .prefix(account_id)
+        .keys(store, start, None, Order::Ascending)
+        .take(limit)
+        .map(|order_id| LIMIT_ORDERS.load(store, order_id?))
+        .collect()
+}
+
+/// Lists funding snapshots of a market, ordered by timestamp.
+pub fn query_funding_history(
+    store: &dyn Storage,
+    denom: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<FundingSnapshot>> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    FUNDING_SNAPSHOTS
+        .prefix(denom)
+        .range(store, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, snapshot)| snapshot))
+        .collect()
+}
+
+/// Computes the time-weighted average funding rate of a market over the window [start, end].
+///
+/// It is the difference of the cumulative funding rates at the window bounds divided by the
+/// length of the window in days.
+pub fn query_average_funding_rate(
+    store: &dyn Storage,
+    denom: String,
+    start: u64,
+    end: Option<u64>,
+    current_time: u64,
+) -> ContractResult<AverageFundingRateResponse> {
+    let end = end.unwrap_or(current_time);
+    ensure!(
+        start < end,
+        ContractError::InvalidFundingWindow {
+            reason: "start must be before end".to_string()
+        }
+    );
+    ensure!(
+        end <= current_time,
+        ContractError::InvalidFundingWindow {
+            reason: "end can't be in the future".to_string()
+        }
+    );
+
+    let ms = MARKET_STATES.load(store, &denom)?;
+
+    let start_cumulative = cumulative_funding_rate_at(store, &denom, &ms, start)?;
+    let end_cumulative = cumulative_funding_rate_at(store, &denom, &ms, end)?;
+
+    let window_days: SignedDecimal = Decimal::from_ratio(end - start, SECONDS_IN_DAY).try_into()?;
+    let average_funding_rate =
+        end_cumulative.checked_sub(start_cumulative)?.checked_div(window_days)?;
+
+    Ok(AverageFundingRateResponse {
+        denom,
+        start,
+        end,
+        average_funding_rate,
+    })
+}
+
+/// Retrieves the insurance fund balance of a market.
+pub fn query_insurance_fund(
+    store: &dyn Storage,
+    denom: String,
+) -> StdResult<InsuranceFundResponse> {
+    let balance = INSURANCE_FUNDS.may_load(store, &denom)?.unwrap_or_default();
+    Ok(InsuranceFundResponse {
+        denom,
+        balance,
+    })
+}
+
+/// Lists the bad debt realized in a market and the amounts drawn from its insurance fund, ordered
+/// by draw id.
+pub fn query_insurance_fund_draws(
+    store: &dyn Storage,
+    denom: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<InsuranceFundDraw>> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    INSURANCE_FUND_DRAWS
+        .prefix(denom)
+        .range(store, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, draw)| draw))
+        .collect()
+}
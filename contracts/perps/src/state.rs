@@ -1,10 +1,13 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, Empty, StdError, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 use mars_owner::Owner;
 use mars_types::{
     keys::UserIdKey,
-    perps::{CashFlow, Config, MarketState, PnlAmounts, Position, UnlockState, VaultState},
+    perps::{
+        CashFlow, Config, FundingSnapshot, InsuranceFundDraw, LimitOrder, MarketState, PnlAmounts,
+        Position, UnlockState, VaultState,
+    },
 };
 
 #[cw_serde]
@@ -17,6 +20,12 @@ pub struct DeleverageRequestTempStorage {
 
     /// Requested amount of the denom from Credit Manager contract (to cover PnL loss)
     pub requested_amount: Uint128,
+
+    /// Account whose position was deleveraged
+    pub account_id: String,
+
+    /// Market of the deleveraged position
+    pub market: String,
 }
 
 pub const OWNER: Owner = Owner::new("owner");
@@ -25,6 +34,9 @@ pub const CONFIG: Item<Config<Addr>> = Item::new("cfg");
 
 pub const VAULT_STATE: Item<VaultState> = Item::new("vault");
 
+// denom => amount of non-base collateral deposited to the vault
+pub const VAULT_COLLATERALS: Map<&str, Uint128> = Map::new("vault_collaterals");
+
 // denom => market state
 pub const MARKET_STATES: Map<&str, MarketState> = Map::new("markets");
 
@@ -50,10 +62,49 @@ pub const TOTAL_CASH_FLOW: Item<CashFlow> = Item::new("total_cf");
 pub const DELEVERAGE_REQUEST_TEMP_STORAGE: Item<DeleverageRequestTempStorage> =
     Item::new("deleverage_req_temp_var");
 
+// Temporary state to save the base denom balance of the contract before swapping vault collateral,
+// to be used on reply handling
+pub const VAULT_COLLATERAL_SWAP_TEMP_STORAGE: Item<Uint128> =
+    Item::new("vault_collateral_swap_temp_var");
+
 // Total unlocking shares across all users
 pub const TOTAL_UNLOCKING_OR_UNLOCKED_SHARES: Item<Uint128> =
     Item::new("total_unlocking_or_unlocked_shares");
 
+// order id => limit order
+pub const LIMIT_ORDERS: Map<u64, LimitOrder> = Map::new("limit_orders");
+
+// (denom, limit price atomics, order id) => empty, for buy orders
+pub const BUY_LIMIT_ORDERS: Map<(&str, u128, u64), Empty> = Map::new("buy_limit_orders");
+
+// (denom, limit price atomics, order id) => empty, for sell orders
+pub const SELL_LIMIT_ORDERS: Map<(&str, u128, u64), Empty> = Map::new("sell_limit_orders");
+
+// (account id, order id) => empty
+pub const ACCOUNT_LIMIT_ORDERS: Map<(&str, u64), Empty> = Map::new("account_limit_orders");
+
+pub const NEXT_LIMIT_ORDER_ID: Item<u64> = Item::new("next_limit_order_id");
+
+// Temporary state to save the orders being filled, in the order of their submessages, to be used
+// on reply handling
+pub const LIMIT_ORDER_FILL_TEMP_STORAGE: Item<Vec<LimitOrder>> =
+    Item::new("limit_order_fill_temp_var");
+
+// (denom, timestamp) => funding snapshot
+pub const FUNDING_SNAPSHOTS: Map<(&str, u64), FundingSnapshot> = Map::new("funding_snapshots");
+
+// denom => number of stored funding snapshots
+pub const FUNDING_SNAPSHOT_COUNTS: Map<&str, u32> = Map::new("funding_snapshot_counts");
+
+// denom => insurance fund balance in the base denom
+pub const INSURANCE_FUNDS: Map<&str, Uint128> = Map::new("insurance_funds");
+
+// (denom, draw id) => insurance fund draw
+pub const INSURANCE_FUND_DRAWS: Map<(&str, u64), InsuranceFundDraw> =
+    Map::new("insurance_fund_draws");
+
+pub const NEXT_INSURANCE_FUND_DRAW_ID: Item<u64> = Item::new("next_insurance_fund_draw_id");
+
 /// Increase the deposit shares of a depositor by the given amount.
 /// Return the updated deposit shares.
 pub fn increase_deposit_shares(
@@ -8,6 +8,7 @@ use mars_types::{
 use crate::{
     error::{ContractError, ContractResult},
     state::{CONFIG, OWNER},
+    utils::ensure_fee_rates,
 };
 
 pub fn update_config(
@@ -41,6 +42,12 @@ pub fn update_config(
         existing_cfg.protocol_fee_rate = protocol_fee_rate;
     }
 
+    if let Some(insurance_fund_fee_rate) = updates.insurance_fund_fee_rate {
+        response =
+            response.add_attribute("insurance_fund_fee_rate", insurance_fund_fee_rate.to_string());
+        existing_cfg.insurance_fund_fee_rate = insurance_fund_fee_rate;
+    }
+
     if let Some(tcr) = updates.target_vault_collateralization_ratio {
         response = response.add_attribute("target_vault_collateralization_ratio", tcr.to_string());
         existing_cfg.target_vault_collateralization_ratio = tcr
@@ -61,6 +68,8 @@ pub fn update_config(
         existing_cfg.max_unlocks = max_unlocks;
     }
 
+    ensure_fee_rates(&existing_cfg)?;
+
     CONFIG.save(deps.storage, &existing_cfg)?;
 
     Ok(response)
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use cosmwasm_std::{Addr, Attribute, Decimal, Deps, Int128, Order, SignedDecimal, Uint128};
+use mars_perps_common::margin::isolated_health_factor;
 use mars_types::{
     adapters::{
         credit_manager::CreditManagerBase,
@@ -10,7 +11,7 @@ use mars_types::{
     keys::{UserId, UserIdKey},
     oracle::ActionKind,
     params::PerpParams,
-    perps::{PnlAmounts, Position},
+    perps::{Config, PnlAmounts, Position},
 };
 
 use crate::{
@@ -48,6 +49,73 @@ pub fn ensure_max_position(
     Ok(())
 }
 
+/// Ensures the execution price of an order is not worse than the (optional) limit price.
+/// Orders increasing the size (buy) can't be executed above the limit price, orders
+/// decreasing the size (sell) can't be executed below it.
+pub fn ensure_limit_price(
+    order_size: Int128,
+    exec_price: Decimal,
+    limit_price: Option<Decimal>,
+) -> ContractResult<()> {
+    if let Some(limit_price) = limit_price {
+        let limit_exceeded = if order_size.is_negative() {
+            exec_price < limit_price
+        } else {
+            exec_price > limit_price
+        };
+
+        if limit_exceeded {
+            return Err(ContractError::LimitPriceExceeded {
+                exec_price,
+                limit_price,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Ensures the margin of an isolated-margin position covers its max LTV requirement. The closing
+/// fee of the position is deducted from the margin upfront, as it will be charged on closing.
+pub fn ensure_isolated_margin(
+    margin: Uint128,
+    size: Int128,
+    denom_price: Decimal,
+    base_denom_price: Decimal,
+    closing_fee_rate: Decimal,
+    perp_params: &PerpParams,
+) -> ContractResult<()> {
+    let position_value = size.unsigned_abs().checked_mul_floor(denom_price)?;
+    let closing_fee =
+        position_value.checked_mul_ceil(closing_fee_rate)?.checked_div_ceil(base_denom_price)?;
+
+    let health_factor = isolated_health_factor(
+        margin,
+        Int128::zero().checked_sub(closing_fee.try_into()?)?,
+        base_denom_price,
+        position_value,
+        perp_params.max_loan_to_value,
+    )?;
+    if let Some(health_factor) = health_factor {
+        if health_factor < Decimal::one() {
+            return Err(ContractError::IsolatedMarginTooLow {
+                health_factor,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Ensures the protocol and the insurance fund together don't take more than the fees charged.
+pub fn ensure_fee_rates<T>(cfg: &Config<T>) -> ContractResult<()> {
+    if cfg.protocol_fee_rate.checked_add(cfg.insurance_fund_fee_rate)? > Decimal::one() {
+        return Err(ContractError::InvalidParam {
+            reason: "protocol_fee_rate + insurance_fund_fee_rate can't be greater than 1"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
 pub fn create_user_id_key(
     user_addr: &Addr,
     account_id: Option<String>,
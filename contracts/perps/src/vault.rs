@@ -1,9 +1,10 @@
 use cosmwasm_std::{
-    coins, ensure, to_json_binary, Addr, BankMsg, CosmosMsg, Deps, DepsMut, MessageInfo, Response,
-    Uint128, WasmMsg,
+    coin, ensure, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
-use cw_utils::must_pay;
+use cw_utils::one_coin;
 use mars_types::{
+    adapters::{oracle::Oracle, params::Params, swapper::Swapper},
     address_provider::{
         helpers::{query_contract_addr, query_contract_addrs},
         MarsAddressType,
@@ -12,6 +13,7 @@ use mars_types::{
     keys::UserIdKey,
     oracle::ActionKind,
     perps::{UnlockState, VaultState},
+    swapper::SwapperRoute,
 };
 
 use crate::{
@@ -22,17 +24,22 @@ use crate::{
     state::{
         decrease_deposit_shares, decrease_total_unlocking_or_unlocked_shares,
         increase_deposit_shares, increase_total_unlocking_or_unlocked_shares, CONFIG,
-        DEPOSIT_SHARES, UNLOCKS, VAULT_STATE,
+        DEPOSIT_SHARES, OWNER, UNLOCKS, VAULT_COLLATERALS, VAULT_COLLATERAL_SWAP_TEMP_STORAGE,
+        VAULT_STATE,
     },
     utils::{create_user_id_key, get_oracle_adapter, get_params_adapter},
 };
 
 pub const DEFAULT_SHARES_PER_AMOUNT: u128 = 1_000_000;
 
+pub const VAULT_COLLATERAL_SWAP_REPLY_ID: u64 = 10_003;
+
 /// Handles the logic for a user depositing funds into the vault.
 /// The function verifies the sender's permission to deposit with an optional account id,
-/// then calculates the number of shares to mint based on the deposit amount.
-/// It updates the total vault balance, the user's deposit shares, and triggers an incentive message.
+/// then calculates the number of shares to mint based on the deposit value.
+/// Apart from the base denom, the vault accepts collateral whitelisted with a haircut in the asset params.
+/// Collateral is valued with the oracle price reduced by the haircut.
+/// It updates the total vault balance (or the collateral held by the vault), the user's deposit shares, and triggers an incentive message.
 /// Returns a `Response` with details about the deposit, including the amount deposited and the number of shares minted.
 pub fn deposit(
     deps: DepsMut,
@@ -80,12 +87,29 @@ pub fn deposit(
         total_vault_shares_before,
     )?;
 
-    // Find the deposit amount
-    let amount = must_pay(&info, &cfg.base_denom)?;
+    // Find the deposited coin
+    let deposited = one_coin(&info)?;
+    let amount = deposited.amount;
 
     let oracle = get_oracle_adapter(&addresses[&MarsAddressType::Oracle]);
     let params = get_params_adapter(&addresses[&MarsAddressType::Params]);
 
+    // Value the deposit in the base denom. Collateral is valued after applying its haircut.
+    let value = if deposited.denom == cfg.base_denom {
+        amount
+    } else {
+        let haircut = params
+            .query_asset_params(&deps.querier, &deposited.denom)?
+            .and_then(|asset_params| asset_params.perps_vault_haircut)
+            .ok_or_else(|| ContractError::VaultCollateralNotWhitelisted {
+                denom: deposited.denom.clone(),
+            })?;
+        let price = oracle.query_price(&deps.querier, &deposited.denom, ActionKind::Default)?.price;
+        let base_denom_price =
+            oracle.query_price(&deps.querier, &cfg.base_denom, ActionKind::Default)?.price;
+        compute_collateral_value(&deposited, price, haircut, base_denom_price)?
+    };
+
     // Compute the new shares to be minted to the depositor
     let (global_acc_data, _) = compute_total_accounting_data(
         &deps.as_ref(),
@@ -95,7 +119,19 @@ pub fn deposit(
         &cfg.base_denom,
         ActionKind::Default,
     )?;
-    let shares = amount_to_shares(&vs, amount, global_acc_data.total_withdrawal_balance(&vs)?)?;
+    // Collateral without an available price can't be valued at zero here, as that would dilute the
+    // existing depositors
+    let (_, total_collateral_value) = compute_vault_collateral_value(
+        &deps.as_ref(),
+        &oracle,
+        &params,
+        &cfg.base_denom,
+        ActionKind::Default,
+        false,
+    )?;
+    let total_vault_value =
+        global_acc_data.total_withdrawal_balance(&vs)?.checked_add(total_collateral_value)?;
+    let shares = amount_to_shares(&vs, value, total_vault_value)?;
 
     if let Some(msr) = max_shares_receivable {
         if shares >= msr {
@@ -106,8 +142,14 @@ pub fn deposit(
         }
     }
 
-    // Increment total liquidity and deposit shares
-    vs.total_balance = vs.total_balance.checked_add(amount.try_into()?)?;
+    // Increment total liquidity (or the collateral held by the vault) and deposit shares
+    if deposited.denom == cfg.base_denom {
+        vs.total_balance = vs.total_balance.checked_add(amount.try_into()?)?;
+    } else {
+        VAULT_COLLATERALS.update(deps.storage, &deposited.denom, |collateral| {
+            Ok::<Uint128, ContractError>(collateral.unwrap_or_default().checked_add(amount)?)
+        })?;
+    }
     vs.total_shares = vs.total_shares.checked_add(shares)?;
     VAULT_STATE.save(deps.storage, &vs)?;
 
@@ -117,8 +159,9 @@ pub fn deposit(
     Ok(Response::new()
         .add_message(msg)
         .add_attribute("action", "deposit")
-        .add_attribute("denom", cfg.base_denom)
+        .add_attribute("denom", deposited.denom)
         .add_attribute("amount", amount)
+        .add_attribute("value", value)
         .add_attribute("shares", shares)
         .add_attribute("user_shares_before", user_shares_before))
 }
@@ -193,6 +236,7 @@ pub fn unlock(
 }
 
 /// Handles the withdrawal of unlocked shares from the vault, converting them to the corresponding amount of the base denomination.
+/// Collateral held by the vault is withdrawn in kind, proportionally to the withdrawn shares.
 /// The function verifies permissions, checks that there are unlocked shares available for withdrawal, and ensures the vault
 /// remains collateralized after the withdrawal. It then updates the vault's state and sends the withdrawn amount to the user.
 /// Returns a `Response` with details about the withdrawal, including the shares and amount withdrawn.
@@ -272,11 +316,19 @@ pub fn withdraw(
         &cfg.base_denom,
         ActionKind::Default,
     )?;
-    let unlocked_user_amount = shares_to_amount(
-        &vs,
-        user_vault_shares.unlocked_amount,
-        global_acc_data.total_withdrawal_balance(&vs)?,
-    )?;
+    let total_withdrawal_balance = global_acc_data.total_withdrawal_balance(&vs)?;
+
+    // Withdraw the user's share of the collateral held by the vault
+    let withdrawn_collaterals =
+        withdraw_vault_collaterals(deps.storage, &vs, user_vault_shares.unlocked_amount)?;
+
+    // The vault can consist of collateral only, in which case there is no base denom to withdraw
+    let unlocked_user_amount =
+        if total_withdrawal_balance.is_zero() && !withdrawn_collaterals.is_empty() {
+            Uint128::zero()
+        } else {
+            shares_to_amount(&vs, user_vault_shares.unlocked_amount, total_withdrawal_balance)?
+        };
 
     // Ensure slippage checks (if provided by user)
     if let Some(min) = min_recieve {
@@ -308,9 +360,14 @@ pub fn withdraw(
         });
     }
 
+    let mut withdrawn_coins = withdrawn_collaterals.clone();
+    if !unlocked_user_amount.is_zero() {
+        withdrawn_coins.insert(0, coin(unlocked_user_amount.u128(), &cfg.base_denom));
+    }
+
     msgs.push(CosmosMsg::from(BankMsg::Send {
         to_address: info.sender.into(),
-        amount: coins(unlocked_user_amount.u128(), &cfg.base_denom),
+        amount: withdrawn_coins,
     }));
 
     Ok(Response::new()
@@ -319,6 +376,10 @@ pub fn withdraw(
         .add_attribute("denom", &cfg.base_denom)
         .add_attribute("unlocked_user_shares", user_vault_shares.unlocked_amount)
         .add_attribute("amount", unlocked_user_amount)
+        .add_attribute(
+            "collaterals",
+            withdrawn_collaterals.iter().map(Coin::to_string).collect::<Vec<_>>().join(","),
+        )
         .add_attribute("unlocking_user_shares", user_vault_shares.unlocking_amount)
         .add_attribute("total_user_shares", total_user_shares)
         .add_attribute(
@@ -327,6 +388,103 @@ pub fn withdraw(
         ))
 }
 
+/// Swaps non-base collateral held by the vault to the base denom, so it can be used to pay the PnL of traders.
+/// Only the owner can swap the collateral. The swap has to return at least the value the vault attributes to the
+/// swapped collateral (its haircut value), so the value of the vault shares doesn't decrease.
+/// The received base denom is added to the vault balance when handling the reply.
+pub fn swap_vault_collateral(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+    route: Option<SwapperRoute>,
+) -> ContractResult<Response> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let held = VAULT_COLLATERALS.may_load(deps.storage, &denom)?.unwrap_or_default();
+    if held < amount {
+        return Err(ContractError::InsufficientVaultCollateral {
+            denom,
+            held,
+            requested: amount,
+        });
+    }
+
+    let remaining = held.checked_sub(amount)?;
+    if remaining.is_zero() {
+        VAULT_COLLATERALS.remove(deps.storage, &denom);
+    } else {
+        VAULT_COLLATERALS.save(deps.storage, &denom, &remaining)?;
+    }
+
+    let addresses = query_contract_addrs(
+        deps.as_ref(),
+        &cfg.address_provider,
+        vec![MarsAddressType::Oracle, MarsAddressType::Params, MarsAddressType::Swapper],
+    )?;
+
+    let oracle = get_oracle_adapter(&addresses[&MarsAddressType::Oracle]);
+    let params = get_params_adapter(&addresses[&MarsAddressType::Params]);
+
+    // Collateral that is no longer whitelisted for the vault is valued at zero
+    let collateral = coin(amount.u128(), &denom);
+    let haircut = params
+        .query_asset_params(&deps.querier, &denom)?
+        .and_then(|asset_params| asset_params.perps_vault_haircut);
+    let min_receive = match haircut {
+        Some(haircut) => {
+            let price = oracle.query_price(&deps.querier, &denom, ActionKind::Default)?.price;
+            let base_denom_price =
+                oracle.query_price(&deps.querier, &cfg.base_denom, ActionKind::Default)?.price;
+            compute_collateral_value(&collateral, price, haircut, base_denom_price)?
+        }
+        None => Uint128::zero(),
+    };
+
+    let base_denom_balance =
+        deps.querier.query_balance(&env.contract.address, &cfg.base_denom)?.amount;
+    VAULT_COLLATERAL_SWAP_TEMP_STORAGE.save(deps.storage, &base_denom_balance)?;
+
+    let swapper = Swapper::new(addresses[&MarsAddressType::Swapper].clone());
+    let swap_msg = swapper.swap_exact_in_msg(&collateral, &cfg.base_denom, min_receive, route)?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(swap_msg, VAULT_COLLATERAL_SWAP_REPLY_ID))
+        .add_attribute("action", "swap_vault_collateral")
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount)
+        .add_attribute("min_receive", min_receive))
+}
+
+/// Adds the base denom received from the swap of the vault collateral to the vault balance.
+pub fn handle_vault_collateral_swap_reply(
+    deps: DepsMut,
+    env: Env,
+    reply: Reply,
+) -> ContractResult<Response> {
+    reply.result.into_result().map_err(StdError::generic_err)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let base_denom_balance_before = VAULT_COLLATERAL_SWAP_TEMP_STORAGE.load(deps.storage)?;
+    VAULT_COLLATERAL_SWAP_TEMP_STORAGE.remove(deps.storage);
+
+    let base_denom_balance =
+        deps.querier.query_balance(&env.contract.address, &cfg.base_denom)?.amount;
+    let received = base_denom_balance.checked_sub(base_denom_balance_before)?;
+
+    let mut vs = VAULT_STATE.load(deps.storage)?;
+    vs.total_balance = vs.total_balance.checked_add(received.try_into()?)?;
+    VAULT_STATE.save(deps.storage, &vs)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "swap_vault_collateral/handle_reply")
+        .add_attribute("received", received))
+}
+
 /// Convert a deposit amount to shares, given the current total amount and
 /// shares.
 ///
@@ -370,6 +528,101 @@ pub fn shares_to_amount(
     total_withdrawal_balance.checked_multiply_ratio(shares, vs.total_shares).map_err(Into::into)
 }
 
+/// Computes the value of the collateral held by the vault, denominated in the base denom.
+///
+/// Every collateral is valued with its oracle price reduced by the haircut from its asset params.
+/// Collateral that is no longer whitelisted for the vault is valued at zero. If
+/// `skip_unavailable_prices` is set, collateral without an available price is valued at zero as
+/// well, instead of failing.
+///
+/// Returns the collateral held by the vault and its total value.
+pub fn compute_vault_collateral_value(
+    deps: &Deps,
+    oracle: &Oracle,
+    params: &Params,
+    base_denom: &str,
+    action: ActionKind,
+    skip_unavailable_prices: bool,
+) -> ContractResult<(Vec<Coin>, Uint128)> {
+    let collaterals = VAULT_COLLATERALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(coin(amount.u128(), denom))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    if collaterals.is_empty() {
+        return Ok((collaterals, Uint128::zero()));
+    }
+
+    let base_denom_price = oracle.query_price(&deps.querier, base_denom, action.clone())?.price;
+
+    let mut total_value = Uint128::zero();
+    for collateral in collaterals.iter() {
+        let Some(haircut) = params
+            .query_asset_params(&deps.querier, &collateral.denom)?
+            .and_then(|asset_params| asset_params.perps_vault_haircut)
+        else {
+            continue;
+        };
+        let price = match oracle.query_price(&deps.querier, &collateral.denom, action.clone()) {
+            Ok(res) => res.price,
+            Err(_) if skip_unavailable_prices => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let value = compute_collateral_value(collateral, price, haircut, base_denom_price)?;
+        total_value = total_value.checked_add(value)?;
+    }
+
+    Ok((collaterals, total_value))
+}
+
+/// Values the collateral in the base denom, after applying the haircut.
+fn compute_collateral_value(
+    collateral: &Coin,
+    price: Decimal,
+    haircut: Decimal,
+    base_denom_price: Decimal,
+) -> ContractResult<Uint128> {
+    let price_in_base_denom =
+        price.checked_mul(Decimal::one() - haircut)?.checked_div(base_denom_price)?;
+    Ok(collateral.amount.checked_mul_floor(price_in_base_denom)?)
+}
+
+/// Decreases the collateral held by the vault by the share of the withdrawn shares.
+/// Amounts are rounded down in favor of the vault.
+///
+/// Returns the withdrawn collateral, skipping denoms rounded down to zero.
+fn withdraw_vault_collaterals(
+    store: &mut dyn Storage,
+    vs: &VaultState,
+    shares: Uint128,
+) -> ContractResult<Vec<Coin>> {
+    let collaterals = VAULT_COLLATERALS
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut withdrawn = vec![];
+    for (denom, amount) in collaterals {
+        let withdrawn_amount = amount.checked_multiply_ratio(shares, vs.total_shares)?;
+        if withdrawn_amount.is_zero() {
+            continue;
+        }
+
+        let remaining = amount.checked_sub(withdrawn_amount)?;
+        if remaining.is_zero() {
+            VAULT_COLLATERALS.remove(store, &denom);
+        } else {
+            VAULT_COLLATERALS.save(store, &denom, &remaining)?;
+        }
+
+        withdrawn.push(coin(withdrawn_amount.u128(), denom));
+    }
+
+    Ok(withdrawn)
+}
+
 /// For internal use by the struct only.
 ///
 /// Create an execute message to inform the incentive contract to update the user's index upon a
@@ -2,7 +2,7 @@
 use std::mem::take;
 
 use anyhow::Result as AnyResult;
-use cosmwasm_std::{coin, Addr, Coin, Decimal, Empty, Int128, Timestamp, Uint128};
+use cosmwasm_std::{coin, Addr, Coin, Decimal, Empty, Int128, StdResult, Timestamp, Uint128};
 use cw_multi_test::{App, AppResponse, BankSudo, BasicApp, Executor, SudoMsg};
 use cw_paginate::PaginationResponse;
 use mars_oracle_osmosis::OsmosisPriceSourceUnchecked;
@@ -18,9 +18,10 @@ use mars_types::{
         PerpParams, PerpParamsUpdate,
     },
     perps::{
-        self, AccountingResponse, Config, ConfigUpdates, MarketResponse, MarketStateResponse,
-        PnlAmounts, PositionFeesResponse, PositionResponse, PositionsByAccountResponse, TradingFee,
-        VaultPositionResponse, VaultResponse,
+        self, AccountingResponse, AverageFundingRateResponse, Config, ConfigUpdates,
+        FundingSnapshot, InsuranceFundDraw, InsuranceFundResponse, LimitOrder, MarketResponse,
+        MarketStateResponse, PnlAmounts, PositionFeesResponse, PositionResponse,
+        PositionsByAccountResponse, TradingFee, VaultPositionResponse, VaultResponse,
     },
     rewards_collector::{self, RewardConfig, TransferType},
 };
@@ -52,6 +53,7 @@ pub struct MockEnvBuilder {
     cooldown_period: u64,
     max_positions: u8,
     protocol_fee_rate: Decimal,
+    insurance_fund_fee_rate: Decimal,
     pub address_provider: Option<Addr>,
     target_vault_collateralization_ratio: Decimal,
     pub emergency_owner: Option<String>,
@@ -72,6 +74,7 @@ impl MockEnv {
             cooldown_period: 3600,
             max_positions: 4,
             protocol_fee_rate: Decimal::percent(0),
+            insurance_fund_fee_rate: Decimal::percent(0),
             address_provider: None,
             target_vault_collateralization_ratio: Decimal::percent(125),
             emergency_owner: None,
@@ -216,6 +219,27 @@ impl MockEnv {
         size: Int128,
         reduce_only: Option<bool>,
         funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.execute_perp_order_with_limit_price(
+            sender,
+            account_id,
+            denom,
+            size,
+            reduce_only,
+            None,
+            funds,
+        )
+    }
+
+    pub fn execute_perp_order_with_limit_price(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        denom: &str,
+        size: Int128,
+        reduce_only: Option<bool>,
+        limit_price: Option<Decimal>,
+        funds: &[Coin],
     ) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             sender.clone(),
@@ -226,11 +250,93 @@ impl MockEnv {
                 size,
                 reduce_only,
                 discount_pct: None,
+                limit_price,
+                isolated_margin: None,
+                action: None,
             },
             funds,
         )
     }
 
+    pub fn execute_perp_order_with_action(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        denom: &str,
+        size: Int128,
+        reduce_only: Option<bool>,
+        action: ActionKind,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.clone(),
+            &perps::ExecuteMsg::ExecuteOrder {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+                size,
+                reduce_only,
+                discount_pct: None,
+                limit_price: None,
+                isolated_margin: None,
+                action: Some(action),
+            },
+            funds,
+        )
+    }
+
+    pub fn place_limit_order(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        denom: &str,
+        size: Int128,
+        limit_price: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.clone(),
+            &perps::ExecuteMsg::PlaceLimitOrder {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+                size,
+                limit_price,
+                reduce_only: None,
+                // The fee is escrowed by the credit manager, the mocked one doesn't handle it
+                keeper_fee: coin(0, "uusdc"),
+            },
+            &[],
+        )
+    }
+
+    pub fn cancel_limit_order(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        order_id: u64,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.clone(),
+            &perps::ExecuteMsg::CancelLimitOrder {
+                account_id: account_id.to_string(),
+                order_id,
+            },
+            &[],
+        )
+    }
+
+    pub fn fill_limit_orders(&mut self, sender: &Addr, denom: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.clone(),
+            &perps::ExecuteMsg::FillLimitOrders {
+                denom: denom.to_string(),
+            },
+            &[],
+        )
+    }
+
     pub fn close_all_positions(
         &mut self,
         sender: &Addr,
@@ -244,6 +350,7 @@ impl MockEnv {
                 account_id: account_id.to_string(),
                 action: None,
                 discount_pct: None,
+                bad_debt: None,
             },
             funds,
         )
@@ -389,6 +496,98 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_limit_orders(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<LimitOrder> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.clone(),
+                &perps::QueryMsg::LimitOrders {
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_limit_orders_by_account(&self, account_id: &str) -> Vec<LimitOrder> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.clone(),
+                &perps::QueryMsg::LimitOrdersByAccount {
+                    account_id: account_id.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_funding_history(
+        &self,
+        denom: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<FundingSnapshot> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.clone(),
+                &perps::QueryMsg::FundingHistory {
+                    denom: denom.to_string(),
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_average_funding_rate(
+        &self,
+        denom: &str,
+        start: u64,
+        end: Option<u64>,
+    ) -> StdResult<AverageFundingRateResponse> {
+        self.app.wrap().query_wasm_smart(
+            self.perps.clone(),
+            &perps::QueryMsg::AverageFundingRate {
+                denom: denom.to_string(),
+                start,
+                end,
+            },
+        )
+    }
+
+    pub fn query_insurance_fund(&self, denom: &str) -> InsuranceFundResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.clone(),
+                &perps::QueryMsg::InsuranceFund {
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_insurance_fund_draws(&self, denom: &str) -> Vec<InsuranceFundDraw> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.clone(),
+                &perps::QueryMsg::InsuranceFundDraws {
+                    denom: denom.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_position(&self, account_id: &str, denom: &str) -> PositionResponse {
         self.query_position_with_order_size(account_id, denom, None)
     }
@@ -408,6 +607,7 @@ impl MockEnv {
                     denom: denom.to_string(),
                     order_size,
                     reduce_only: None,
+                    action: None,
                 },
             )
             .unwrap()
@@ -698,6 +898,7 @@ impl MockEnvBuilder {
                     cooldown_period: self.cooldown_period,
                     max_positions: self.max_positions,
                     protocol_fee_rate: self.protocol_fee_rate,
+                    insurance_fund_fee_rate: self.insurance_fund_fee_rate,
                     target_vault_collateralization_ratio: self.target_vault_collateralization_ratio,
                     deleverage_enabled: self.deleverage_enabled,
                     vault_withdraw_enabled: self.withdraw_enabled,
@@ -858,6 +1059,11 @@ impl MockEnvBuilder {
         self
     }
 
+    pub fn insurance_fund_fee_rate(&mut self, rate: Decimal) -> &mut Self {
+        self.insurance_fund_fee_rate = rate;
+        self
+    }
+
     pub fn target_vault_collaterization_ratio(&mut self, ratio: Decimal) -> &mut Self {
         self.target_vault_collateralization_ratio = ratio;
         self
@@ -2,7 +2,10 @@ mod helpers;
 
 mod test_accounting;
 mod test_accounting_with_discount;
+mod test_funding_history;
 mod test_instantiate;
+mod test_limit_orders;
+mod test_limit_price;
 mod test_managing_markets;
 mod test_migration_v2;
 mod test_migration_v2_3_0;
@@ -371,7 +371,9 @@ fn assert_vault(mock: &MockEnv, vault_before: &VaultResponse) {
             )),
             total_liquidity,
             total_debt,
-            collateralization_ratio
+            collateralization_ratio,
+            collaterals: vec![],
+            total_collateral_value: Uint128::zero(),
         }
     );
 }
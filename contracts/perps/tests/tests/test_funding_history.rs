@@ -0,0 +1,185 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, Decimal, Int128, SignedDecimal};
+use mars_types::params::{PerpParams, PerpParamsUpdate};
+
+use super::helpers::MockEnv;
+use crate::tests::helpers::default_perp_params;
+
+const ONE_DAY_SEC: u64 = 86400;
+
+fn setup() -> MockEnv {
+    let mut mock = MockEnv::new().build().unwrap();
+
+    let owner = mock.owner.clone();
+    let credit_manager = mock.credit_manager.clone();
+
+    mock.set_price(&owner, "uusdc", Decimal::one()).unwrap();
+    mock.set_price(&owner, "uatom", Decimal::from_str("10").unwrap()).unwrap();
+
+    mock.fund_accounts(&[&credit_manager], 1_000_000_000_000u128, &["uusdc"]);
+    mock.deposit_to_vault(&credit_manager, Some("jake"), None, &[coin(1_000_000_000u128, "uusdc")])
+        .unwrap();
+
+    // max funding velocity 3, skew scale 1000000
+    mock.update_perp_params(
+        &owner,
+        PerpParamsUpdate::AddOrUpdate {
+            params: default_perp_params("uatom"),
+        },
+    );
+
+    mock
+}
+
+fn signed(value: &str) -> SignedDecimal {
+    SignedDecimal::from_str(value).unwrap()
+}
+
+#[test]
+fn funding_snapshots_recorded_on_market_updates() {
+    let mut mock = setup();
+    let owner = mock.owner.clone();
+    let credit_manager = mock.credit_manager.clone();
+    let t0 = mock.query_block_time();
+
+    // Opening a position in the same block as the market initialization doesn't add a snapshot
+    mock.execute_perp_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100000").unwrap(),
+        None,
+        &[],
+    )
+    .unwrap();
+    let history = mock.query_funding_history("uatom", None, None);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].timestamp, t0);
+    assert_eq!(history[0].funding_rate, SignedDecimal::zero());
+    assert_eq!(history[0].cumulative_funding_rate, SignedDecimal::zero());
+
+    // Skew 0.1 => funding rate velocity 0.3 per day
+    mock.increment_by_time(ONE_DAY_SEC);
+    mock.execute_perp_order(
+        &credit_manager,
+        "2",
+        "uatom",
+        Int128::from_str("100000").unwrap(),
+        None,
+        &[],
+    )
+    .unwrap();
+
+    // Skew 0.2 => funding rate velocity 0.6 per day. Updating the market params records a
+    // snapshot too.
+    mock.increment_by_time(ONE_DAY_SEC);
+    mock.update_perp_params(
+        &owner,
+        PerpParamsUpdate::AddOrUpdate {
+            params: PerpParams {
+                opening_fee_rate: Decimal::percent(1),
+                ..default_perp_params("uatom")
+            },
+        },
+    );
+
+    let ms = mock.query_market_state("uatom").market_state;
+
+    let history = mock.query_funding_history("uatom", None, None);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[1].timestamp, t0 + ONE_DAY_SEC);
+    assert_eq!(history[1].funding_rate, signed("0.3"));
+    assert_eq!(history[1].cumulative_funding_rate, signed("0.15"));
+    assert_eq!(history[2].timestamp, t0 + 2 * ONE_DAY_SEC);
+    assert_eq!(history[2].funding_rate, signed("0.9"));
+    assert_eq!(history[2].funding_rate, ms.funding.last_funding_rate);
+    assert_eq!(
+        history[2].funding_accrued_per_unit_in_base_denom,
+        ms.funding.last_funding_accrued_per_unit_in_base_denom
+    );
+    assert_eq!(history[2].cumulative_funding_rate, signed("0.75"));
+
+    // Pagination by timestamp
+    let page = mock.query_funding_history("uatom", Some(t0), Some(1));
+    assert_eq!(page, vec![history[1].clone()]);
+}
+
+#[test]
+fn average_funding_rate_over_window() {
+    let mut mock = setup();
+    let credit_manager = mock.credit_manager.clone();
+    let t0 = mock.query_block_time();
+
+    mock.execute_perp_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100000").unwrap(),
+        None,
+        &[],
+    )
+    .unwrap();
+    mock.increment_by_time(ONE_DAY_SEC);
+    mock.execute_perp_order(
+        &credit_manager,
+        "2",
+        "uatom",
+        Int128::from_str("100000").unwrap(),
+        None,
+        &[],
+    )
+    .unwrap();
+    mock.increment_by_time(ONE_DAY_SEC);
+    mock.execute_perp_order(
+        &credit_manager,
+        "3",
+        "uatom",
+        Int128::from_str("-200000").unwrap(),
+        None,
+        &[],
+    )
+    .unwrap();
+
+    // Window matching the snapshots: rate goes 0 -> 0.3 -> 0.9
+    let res = mock.query_average_funding_rate("uatom", t0, Some(t0 + 2 * ONE_DAY_SEC)).unwrap();
+    assert_eq!(res.start, t0);
+    assert_eq!(res.end, t0 + 2 * ONE_DAY_SEC);
+    assert_eq!(res.average_funding_rate, signed("0.375"));
+
+    // Window between the snapshots: rate goes 0.15 -> 0.3 -> 0.6
+    let res = mock
+        .query_average_funding_rate("uatom", t0 + ONE_DAY_SEC / 2, Some(t0 + 3 * ONE_DAY_SEC / 2))
+        .unwrap();
+    assert_eq!(res.average_funding_rate, signed("0.3375"));
+
+    // Window after the last snapshot uses the current market state. There is no skew left, so
+    // the funding rate stays at 0.9.
+    mock.increment_by_time(ONE_DAY_SEC / 2);
+    let current_time = mock.query_block_time();
+    let res = mock.query_average_funding_rate("uatom", t0 + 2 * ONE_DAY_SEC, None).unwrap();
+    assert_eq!(res.end, current_time);
+    assert_eq!(res.average_funding_rate, signed("0.9"));
+}
+
+#[test]
+fn cannot_query_average_funding_rate_for_invalid_window() {
+    let mut mock = setup();
+    let t0 = mock.query_block_time();
+    mock.increment_by_time(ONE_DAY_SEC);
+
+    // Before the funding history of the market
+    let res = mock.query_average_funding_rate("uatom", t0 - 1, None);
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("Funding history of denom uatom is not available"));
+
+    // Start not before end
+    let res = mock.query_average_funding_rate("uatom", t0 + 10, Some(t0 + 10));
+    assert!(res.unwrap_err().to_string().contains("Invalid funding window"));
+
+    // End in the future
+    let res = mock.query_average_funding_rate("uatom", t0, Some(t0 + 2 * ONE_DAY_SEC));
+    assert!(res.unwrap_err().to_string().contains("Invalid funding window"));
+}
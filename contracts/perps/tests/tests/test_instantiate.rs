@@ -12,6 +12,7 @@ fn proper_initialization() {
         .max_positions(9)
         .max_unlocks(102)
         .protocol_fee_rate(Decimal::percent(25))
+        .insurance_fund_fee_rate(Decimal::percent(10))
         .build()
         .unwrap();
 
@@ -39,6 +40,7 @@ fn proper_initialization() {
             cooldown_period: 3688,
             max_positions: 9,
             protocol_fee_rate: Decimal::percent(25),
+            insurance_fund_fee_rate: Decimal::percent(10),
             target_vault_collateralization_ratio: Decimal::percent(125),
             deleverage_enabled: true,
             vault_withdraw_enabled: true,
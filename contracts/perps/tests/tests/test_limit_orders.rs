@@ -0,0 +1,223 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Decimal, Int128};
+use mars_perps::{error::ContractError, limit_order::MAX_LIMIT_ORDERS_PER_ACCOUNT};
+use mars_types::params::{PerpParams, PerpParamsUpdate};
+
+use super::helpers::{assert_err, MockEnv};
+use crate::tests::helpers::default_perp_params;
+
+fn setup(enabled: bool) -> MockEnv {
+    let mut mock = MockEnv::new().build().unwrap();
+
+    let owner = mock.owner.clone();
+
+    // set prices
+    mock.set_price(&owner, "uusdc", Decimal::from_str("1").unwrap()).unwrap();
+    mock.set_price(&owner, "uatom", Decimal::from_str("10").unwrap()).unwrap();
+
+    // init denoms
+    mock.update_perp_params(
+        &owner,
+        PerpParamsUpdate::AddOrUpdate {
+            params: PerpParams {
+                enabled,
+                ..default_perp_params("uatom")
+            },
+        },
+    );
+
+    mock
+}
+
+#[test]
+fn only_credit_manager_can_place_and_cancel_orders() {
+    let mut mock = setup(true);
+    let credit_manager = mock.credit_manager.clone();
+    let random_user = Addr::unchecked("random-user-123");
+
+    let res = mock.place_limit_order(
+        &random_user,
+        "1",
+        "uatom",
+        Int128::from_str("100").unwrap(),
+        Decimal::from_str("9").unwrap(),
+    );
+    assert_err(res, ContractError::SenderIsNotCreditManager);
+
+    mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100").unwrap(),
+        Decimal::from_str("9").unwrap(),
+    )
+    .unwrap();
+
+    let res = mock.cancel_limit_order(&random_user, "1", 1);
+    assert_err(res, ContractError::SenderIsNotCreditManager);
+
+    // Orders can only be cancelled for the account they belong to
+    let res = mock.cancel_limit_order(&credit_manager, "2", 1);
+    assert_err(
+        res,
+        ContractError::LimitOrderNotFound {
+            account_id: "2".to_string(),
+            order_id: 1,
+        },
+    );
+
+    mock.cancel_limit_order(&credit_manager, "1", 1).unwrap();
+    assert!(mock.query_limit_orders_by_account("1").is_empty());
+}
+
+#[test]
+fn cannot_place_invalid_orders() {
+    let mut mock = setup(true);
+    let credit_manager = mock.credit_manager.clone();
+
+    let res = mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::zero(),
+        Decimal::from_str("9").unwrap(),
+    );
+    assert_err(
+        res,
+        ContractError::InvalidLimitOrder {
+            reason: "size can't be zero".to_string(),
+        },
+    );
+
+    let res = mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100").unwrap(),
+        Decimal::zero(),
+    );
+    assert_err(
+        res,
+        ContractError::InvalidLimitOrder {
+            reason: "limit price can't be zero".to_string(),
+        },
+    );
+
+    let res = mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uosmo",
+        Int128::from_str("100").unwrap(),
+        Decimal::from_str("9").unwrap(),
+    );
+    assert_err(
+        res,
+        ContractError::DenomNotFound {
+            denom: "uosmo".to_string(),
+        },
+    );
+}
+
+#[test]
+fn cannot_place_orders_for_disabled_denom() {
+    let mut mock = setup(false);
+    let credit_manager = mock.credit_manager.clone();
+
+    let res = mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100").unwrap(),
+        Decimal::from_str("9").unwrap(),
+    );
+    assert_err(
+        res,
+        ContractError::DenomNotEnabled {
+            denom: "uatom".to_string(),
+        },
+    );
+}
+
+#[test]
+fn number_of_orders_per_account_is_limited() {
+    let mut mock = setup(true);
+    let credit_manager = mock.credit_manager.clone();
+
+    for i in 0..MAX_LIMIT_ORDERS_PER_ACCOUNT {
+        mock.place_limit_order(
+            &credit_manager,
+            "1",
+            "uatom",
+            Int128::from_str("100").unwrap(),
+            Decimal::from_str("9").unwrap() - Decimal::percent(i as u64),
+        )
+        .unwrap();
+    }
+
+    let res = mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100").unwrap(),
+        Decimal::from_str("9").unwrap(),
+    );
+    assert_err(
+        res,
+        ContractError::MaxLimitOrdersReached {
+            account_id: "1".to_string(),
+            max_limit_orders: MAX_LIMIT_ORDERS_PER_ACCOUNT,
+        },
+    );
+
+    // Other accounts are not affected
+    mock.place_limit_order(
+        &credit_manager,
+        "2",
+        "uatom",
+        Int128::from_str("-100").unwrap(),
+        Decimal::from_str("11").unwrap(),
+    )
+    .unwrap();
+
+    let orders = mock.query_limit_orders_by_account("2");
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].order_id, MAX_LIMIT_ORDERS_PER_ACCOUNT as u64 + 1);
+    assert_eq!(orders[0].size, Int128::from_str("-100").unwrap());
+
+    let orders = mock.query_limit_orders(Some(5), Some(3));
+    assert_eq!(orders.iter().map(|o| o.order_id).collect::<Vec<_>>(), vec![6, 7, 8]);
+}
+
+#[test]
+fn cannot_fill_when_no_orders_match() {
+    let mut mock = setup(true);
+    let credit_manager = mock.credit_manager.clone();
+
+    // Buy below and sell above the current price of 10
+    mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("100").unwrap(),
+        Decimal::from_str("9").unwrap(),
+    )
+    .unwrap();
+    mock.place_limit_order(
+        &credit_manager,
+        "1",
+        "uatom",
+        Int128::from_str("-100").unwrap(),
+        Decimal::from_str("11").unwrap(),
+    )
+    .unwrap();
+
+    let res = mock.fill_limit_orders(&Addr::unchecked("keeper"), "uatom");
+    assert_err(
+        res,
+        ContractError::NoLimitOrdersToFill {
+            denom: "uatom".to_string(),
+        },
+    );
+    assert_eq!(mock.query_limit_orders(None, None).len(), 2);
+}
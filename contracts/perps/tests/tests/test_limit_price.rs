@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, Decimal, Int128};
+use mars_perps::error::ContractError;
+use mars_perps_common::pricing::opening_execution_price;
+use mars_types::params::PerpParamsUpdate;
+use test_case::test_case;
+
+use super::helpers::{assert_err, MockEnv};
+use crate::tests::helpers::default_perp_params;
+
+fn setup() -> MockEnv {
+    let mut mock = MockEnv::new().build().unwrap();
+
+    let owner = mock.owner.clone();
+    let credit_manager = mock.credit_manager.clone();
+
+    // credit manager is calling the perps contract, so we need to fund it (funds will be used for closing losing position)
+    mock.fund_accounts(&[&credit_manager], 1_000_000_000_000_000u128, &["uosmo", "uatom", "uusdc"]);
+
+    // set prices
+    mock.set_price(&owner, "uusdc", Decimal::from_str("1").unwrap()).unwrap();
+    mock.set_price(&owner, "uatom", Decimal::from_str("10").unwrap()).unwrap();
+
+    // deposit some big number of uusdc to vault
+    mock.deposit_to_vault(
+        &credit_manager,
+        Some("jake"),
+        None,
+        &[coin(1_000_000_000_000u128, "uusdc")],
+    )
+    .unwrap();
+
+    // init denoms
+    mock.update_perp_params(
+        &owner,
+        PerpParamsUpdate::AddOrUpdate {
+            params: default_perp_params("uatom"),
+        },
+    );
+
+    mock
+}
+
+fn exec_price(mock: &MockEnv, order_size: Int128) -> Decimal {
+    let ms = mock.query_market_state("uatom").market_state;
+    let skew = Int128::try_from(ms.long_oi)
+        .unwrap()
+        .checked_sub(Int128::try_from(ms.short_oi).unwrap())
+        .unwrap();
+    opening_execution_price(
+        skew,
+        default_perp_params("uatom").skew_scale,
+        order_size,
+        Decimal::from_str("10").unwrap(),
+    )
+    .unwrap()
+}
+
+#[test_case(
+    Int128::from_str("100000").unwrap();
+    "open long"
+)]
+#[test_case(
+    Int128::from_str("-100000").unwrap();
+    "open short"
+)]
+fn open_position_respects_limit_price(size: Int128) {
+    let mut mock = setup();
+    let credit_manager = mock.credit_manager.clone();
+
+    let expected_exec_price = exec_price(&mock, size);
+    let worse_limit_price = if size.is_negative() {
+        expected_exec_price + Decimal::percent(1)
+    } else {
+        expected_exec_price - Decimal::percent(1)
+    };
+
+    let res = mock.execute_perp_order_with_limit_price(
+        &credit_manager,
+        "1",
+        "uatom",
+        size,
+        None,
+        Some(worse_limit_price),
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::LimitPriceExceeded {
+            exec_price: expected_exec_price,
+            limit_price: worse_limit_price,
+        },
+    );
+
+    // limit price equal to the execution price is accepted
+    mock.execute_perp_order_with_limit_price(
+        &credit_manager,
+        "1",
+        "uatom",
+        size,
+        None,
+        Some(expected_exec_price),
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_position("1", "uatom").position.unwrap();
+    assert_eq!(position.size, size);
+}
+
+#[test_case(
+    Int128::from_str("-50000").unwrap();
+    "reduce long"
+)]
+#[test_case(
+    Int128::from_str("50000").unwrap();
+    "increase long"
+)]
+#[test_case(
+    Int128::from_str("-150000").unwrap();
+    "flip long to short"
+)]
+fn modify_position_respects_limit_price(order_size: Int128) {
+    let mut mock = setup();
+    let credit_manager = mock.credit_manager.clone();
+
+    let entry_size = Int128::from_str("100000").unwrap();
+    mock.execute_perp_order(&credit_manager, "1", "uatom", entry_size, None, &[]).unwrap();
+
+    let expected_exec_price = exec_price(&mock, order_size);
+    let worse_limit_price = if order_size.is_negative() {
+        expected_exec_price + Decimal::percent(1)
+    } else {
+        expected_exec_price - Decimal::percent(1)
+    };
+
+    let res = mock.execute_perp_order_with_limit_price(
+        &credit_manager,
+        "1",
+        "uatom",
+        order_size,
+        None,
+        Some(worse_limit_price),
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::LimitPriceExceeded {
+            exec_price: expected_exec_price,
+            limit_price: worse_limit_price,
+        },
+    );
+
+    // limit price equal to the execution price is accepted
+    mock.execute_perp_order_with_limit_price(
+        &credit_manager,
+        "1",
+        "uatom",
+        order_size,
+        None,
+        Some(expected_exec_price),
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_position("1", "uatom").position.unwrap();
+    assert_eq!(position.size, entry_size.checked_add(order_size).unwrap());
+}
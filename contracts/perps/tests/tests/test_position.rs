@@ -2480,3 +2480,73 @@ fn global_realized_pnl_matches_positions_realized_pnl() {
     let global_realized_pnl = mock.query_realized_pnl_by_account_and_market("1", "uatom");
     assert_eq!(global_realized_pnl, realized_pnl);
 }
+
+#[test]
+fn liquidation_pricing_only_allowed_for_reduce_only_orders() {
+    let mut mock = MockEnv::new().build().unwrap();
+
+    let owner = mock.owner.clone();
+    let credit_manager = mock.credit_manager.clone();
+    let user = "jake";
+
+    // Credit manager is calling the perps contract, so we need to fund it (funds will be used for closing losing position)
+    mock.fund_accounts(&[&credit_manager], 1_000_000_000_000_000u128, &["uatom", "uusdc"]);
+
+    // Set prices
+    mock.set_price(&owner, "uusdc", Decimal::from_str("1").unwrap()).unwrap();
+    mock.set_price(&owner, "uatom", Decimal::from_str("10").unwrap()).unwrap();
+
+    // Deposit some big number of uusdc to vault
+    mock.deposit_to_vault(
+        &credit_manager,
+        Some(user),
+        None,
+        &[coin(1_000_000_000_000u128, "uusdc")],
+    )
+    .unwrap();
+
+    // Init perp
+    mock.update_perp_params(
+        &owner,
+        PerpParamsUpdate::AddOrUpdate {
+            params: default_perp_params("uatom"),
+        },
+    );
+
+    // Open a LONG position
+    let size = Int128::from_str("300000").unwrap();
+    mock.execute_perp_order(&credit_manager, "1", "uatom", size, None, &[]).unwrap();
+
+    // Increasing the position with liquidation pricing is not allowed
+    let res = mock.execute_perp_order_with_action(
+        &credit_manager,
+        "1",
+        "uatom",
+        size,
+        None,
+        ActionKind::Liquidation,
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::IllegalPositionModification {
+            reason: "Liquidation pricing is only allowed for reduce_only orders".to_string(),
+        },
+    );
+
+    // Reducing the position with liquidation pricing is allowed
+    let closing_size = Int128::from_str("-100000").unwrap();
+    mock.execute_perp_order_with_action(
+        &credit_manager,
+        "1",
+        "uatom",
+        closing_size,
+        Some(true),
+        ActionKind::Liquidation,
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_position("1", "uatom").position.unwrap();
+    assert_eq!(position.size, Int128::from_str("200000").unwrap());
+}
@@ -63,6 +63,7 @@ fn update_total_config() {
         deleverage_enabled: false,
         max_positions: 100,
         protocol_fee_rate: Decimal::from_ratio(2u128, 100u128),
+        insurance_fund_fee_rate: Decimal::from_ratio(5u128, 100u128),
         target_vault_collateralization_ratio: Decimal::from_ratio(150u128, 100u128),
         vault_withdraw_enabled: false,
         max_unlocks: 14,
@@ -76,6 +77,7 @@ fn update_total_config() {
             deleverage_enabled: Some(new_config.deleverage_enabled),
             max_positions: Some(new_config.max_positions),
             protocol_fee_rate: Some(new_config.protocol_fee_rate),
+            insurance_fund_fee_rate: Some(new_config.insurance_fund_fee_rate),
             target_vault_collateralization_ratio: Some(
                 new_config.target_vault_collateralization_ratio,
             ),
@@ -89,3 +91,25 @@ fn update_total_config() {
     assert_eq!(new_config, new_config_loaded);
     assert!(res.is_ok());
 }
+
+#[test]
+fn fee_rates_cannot_exceed_one() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = mock.query_owner();
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            protocol_fee_rate: Some(Decimal::percent(60)),
+            insurance_fund_fee_rate: Some(Decimal::percent(41)),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidParam {
+            reason: "protocol_fee_rate + insurance_fund_fee_rate can't be greater than 1"
+                .to_string(),
+        },
+    );
+}
@@ -98,7 +98,9 @@ fn unlock_few_times() {
             share_price: Some(Decimal::from_ratio(deposit.amount, deposit.shares)),
             total_liquidity: deposit.amount,
             total_debt: Uint128::zero(),
-            collateralization_ratio: None
+            collateralization_ratio: None,
+            collaterals: vec![],
+            total_collateral_value: Uint128::zero(),
         }
     );
 
@@ -423,7 +425,9 @@ fn withdraw_unlocked_shares() {
             share_price: Some(Decimal::from_ratio(total_deposits, total_shares)),
             total_liquidity: total_deposits,
             total_debt: Uint128::zero(),
-            collateralization_ratio: None
+            collateralization_ratio: None,
+            collaterals: vec![],
+            total_collateral_value: Uint128::zero(),
         }
     );
 
@@ -939,7 +943,9 @@ fn withdraw_profits_for_depositors() {
             share_price: Some(Decimal::from_ratio(total_liquidity, total_shares)),
             total_liquidity,
             total_debt: Uint128::zero(),
-            collateralization_ratio: None
+            collateralization_ratio: None,
+            collaterals: vec![],
+            total_collateral_value: Uint128::zero(),
         }
     );
 
@@ -976,7 +982,9 @@ fn withdraw_profits_for_depositors() {
             share_price: None,
             total_liquidity: Uint128::zero(),
             total_debt: Uint128::zero(),
-            collateralization_ratio: None
+            collateralization_ratio: None,
+            collaterals: vec![],
+            total_collateral_value: Uint128::zero(),
         }
     );
 }
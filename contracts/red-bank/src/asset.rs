@@ -55,6 +55,7 @@ pub fn create_market(block_time: u64, params: MarketParams) -> Result<Market, Co
         denom,
         reserve_factor,
         interest_rate_model,
+        flash_loan_fee_rate,
     } = params;
 
     // All fields should be available
@@ -74,7 +75,11 @@ pub fn create_market(block_time: u64, params: MarketParams) -> Result<Market, Co
         indexes_last_updated: block_time,
         collateral_total_scaled: Uint128::zero(),
         debt_total_scaled: Uint128::zero(),
+        term_debt_total: Uint128::zero(),
+        term_interest_per_year: Uint128::zero(),
         interest_rate_model: interest_rate_model.unwrap(),
+        flash_loan_fee_rate: flash_loan_fee_rate.unwrap_or_default(),
+        adaptive_rate_state: None,
     };
 
     new_market.validate()?;
@@ -95,6 +100,7 @@ pub fn update_asset(
         denom,
         reserve_factor,
         interest_rate_model,
+        flash_loan_fee_rate,
     } = params;
 
     // If reserve factor or interest rates are updated we update indexes with
@@ -130,6 +136,7 @@ pub fn update_asset(
     let mut updated_market = Market {
         reserve_factor: reserve_factor.unwrap_or(market.reserve_factor),
         interest_rate_model: interest_rate_model.unwrap_or(market.interest_rate_model),
+        flash_loan_fee_rate: flash_loan_fee_rate.unwrap_or(market.flash_loan_fee_rate),
         ..market
     };
 
@@ -0,0 +1,252 @@
+use std::cmp::min;
+
+use cosmwasm_std::{
+    Addr, Decimal, DepsMut, Env, Event, MessageInfo, Order, Response, Storage, Uint128,
+};
+use mars_interest_rate::{
+    get_scaled_debt_amount, get_scaled_liquidity_amount, get_underlying_debt_amount,
+    get_underlying_liquidity_amount,
+};
+use mars_types::{
+    address_provider::{self, MarsAddressType},
+    error::MarsError,
+    keys::{UserId, UserIdKey},
+    red_bank::Market,
+};
+
+use crate::{
+    error::ContractError,
+    interest_rates::{apply_accumulated_interests, update_interest_rates},
+    isolation::update_isolated_debts,
+    state::{COLLATERALS, CONFIG, DEBTS, MARKETS},
+    term_loan::convert_term_loans_of_denom,
+    user::User,
+};
+
+/// Write off the debt of a user left without any collateral, so that it stops counting towards
+/// the utilization of the market. The loss is covered by the reserves first, then socialized
+/// among the depositors.
+pub fn write_off_bad_debt(
+    mut deps: DepsMut,
+    env: Env,
+    user_addr: Addr,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![
+            MarsAddressType::Oracle,
+            MarsAddressType::Incentives,
+            MarsAddressType::RewardsCollector,
+            MarsAddressType::Params,
+            MarsAddressType::CreditManager,
+        ],
+    )?;
+    let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
+    let incentives_addr = &addresses[&MarsAddressType::Incentives];
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+    let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
+
+    // Debt of credit manager accounts is written off through the credit manager
+    if user_addr == credit_manager_addr {
+        return Err(ContractError::CannotWriteOffCreditManagerDebt {});
+    }
+
+    let user_id = UserId::credit_manager(user_addr.clone(), "".to_string());
+    let user_id_key: UserIdKey = user_id.try_into()?;
+    let has_collateral = COLLATERALS
+        .prefix(&user_id_key)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if has_collateral {
+        return Err(ContractError::CannotWriteOffDebtWithCollateral {});
+    }
+
+    let mut response = convert_term_loans_of_denom(
+        deps.storage,
+        &env,
+        &user_addr,
+        &denom,
+        rewards_collector_addr,
+        incentives_addr,
+        Response::new(),
+    )?;
+
+    let debt = DEBTS
+        .may_load(deps.storage, (&user_addr, &denom))?
+        .ok_or(ContractError::NoDebtToWriteOff {})?;
+
+    let mut market = MARKETS.load(deps.storage, &denom)?;
+
+    response = apply_accumulated_interests(
+        deps.storage,
+        &env,
+        &mut market,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    let loss = get_underlying_debt_amount(debt.amount_scaled, &market, env.block.time.seconds())?;
+
+    User(&user_addr).decrease_debt(deps.storage, &denom, debt.amount_scaled)?;
+    market.decrease_debt(debt.amount_scaled)?;
+
+    response = realize_loss(
+        deps.storage,
+        &env,
+        &mut market,
+        loss,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    response = update_interest_rates(&env, &mut market, response)?;
+    MARKETS.save(deps.storage, &denom, &market)?;
+
+    update_isolated_debts(deps.branch(), &env, &user_addr, oracle_addr, params_addr, false)?;
+
+    Ok(response
+        .add_attribute("action", "write_off_bad_debt")
+        .add_attribute("user", user_addr)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", loss))
+}
+
+/// Write off debt of the credit manager, after it removed the debt of one of its accounts left
+/// without any collateral
+pub fn write_off_credit_manager_bad_debt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![
+            MarsAddressType::Incentives,
+            MarsAddressType::RewardsCollector,
+            MarsAddressType::CreditManager,
+        ],
+    )?;
+    let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
+    let incentives_addr = &addresses[&MarsAddressType::Incentives];
+    let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
+
+    if info.sender != credit_manager_addr {
+        return Err(MarsError::Unauthorized {}.into());
+    }
+
+    let debt = DEBTS
+        .may_load(deps.storage, (credit_manager_addr, &denom))?
+        .ok_or(ContractError::NoDebtToWriteOff {})?;
+
+    let mut market = MARKETS.load(deps.storage, &denom)?;
+
+    let mut response = apply_accumulated_interests(
+        deps.storage,
+        &env,
+        &mut market,
+        rewards_collector_addr,
+        incentives_addr,
+        Response::new(),
+    )?;
+
+    // Can't write off more than the debt of the credit manager
+    let current_timestamp = env.block.time.seconds();
+    let amount_scaled =
+        min(get_scaled_debt_amount(amount, &market, current_timestamp)?, debt.amount_scaled);
+    let loss = get_underlying_debt_amount(amount_scaled, &market, current_timestamp)?;
+
+    User(credit_manager_addr).decrease_debt(deps.storage, &denom, amount_scaled)?;
+    market.decrease_debt(amount_scaled)?;
+
+    response = realize_loss(
+        deps.storage,
+        &env,
+        &mut market,
+        loss,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    response = update_interest_rates(&env, &mut market, response)?;
+    MARKETS.save(deps.storage, &denom, &market)?;
+
+    Ok(response
+        .add_attribute("action", "write_off_credit_manager_bad_debt")
+        .add_attribute("denom", denom)
+        .add_attribute("amount", loss))
+}
+
+/// Cover a loss of the market with the reserves (collateral of the rewards collector) and reduce
+/// the liquidity index by the remaining amount
+fn realize_loss(
+    store: &mut dyn Storage,
+    env: &Env,
+    market: &mut Market,
+    loss: Uint128,
+    rewards_collector_addr: &Addr,
+    incentives_addr: &Addr,
+    mut response: Response,
+) -> Result<Response, ContractError> {
+    let current_timestamp = env.block.time.seconds();
+
+    let rewards_collector_id =
+        UserId::credit_manager(rewards_collector_addr.clone(), "".to_string());
+    let rewards_collector_id_key: UserIdKey = rewards_collector_id.try_into()?;
+    let reserves_scaled = COLLATERALS
+        .may_load(store, (&rewards_collector_id_key, &market.denom))?
+        .map(|collateral| collateral.amount_scaled)
+        .unwrap_or_default();
+    let reserves = get_underlying_liquidity_amount(reserves_scaled, market, current_timestamp)?;
+
+    let covered_by_reserves = min(loss, reserves);
+    if !covered_by_reserves.is_zero() {
+        let covered_scaled = if covered_by_reserves == reserves {
+            reserves_scaled
+        } else {
+            get_scaled_liquidity_amount(covered_by_reserves, market, current_timestamp)?
+        };
+        response = User(rewards_collector_addr).decrease_collateral(
+            store,
+            market,
+            covered_scaled,
+            incentives_addr,
+            response,
+            None,
+        )?;
+        market.decrease_collateral(covered_scaled)?;
+    }
+
+    let socialized = loss.checked_sub(covered_by_reserves)?;
+    if !socialized.is_zero() {
+        let total_liquidity = get_underlying_liquidity_amount(
+            market.collateral_total_scaled,
+            market,
+            current_timestamp,
+        )?;
+        let remaining_ratio =
+            Decimal::checked_from_ratio(total_liquidity.checked_sub(socialized)?, total_liquidity)?;
+        market.liquidity_index = market.liquidity_index.checked_mul(remaining_ratio)?;
+    }
+
+    Ok(response.add_event(
+        Event::new("bad_debt_written_off")
+            .add_attribute("denom", market.denom.clone())
+            .add_attribute("loss", loss)
+            .add_attribute("covered_by_reserves", covered_by_reserves)
+            .add_attribute("socialized", socialized),
+    ))
+}
@@ -6,24 +6,30 @@ use mars_types::{address_provider, address_provider::MarsAddressType};
 use mars_utils::helpers::build_send_asset_msg;
 
 use crate::{
+    credit_delegation::use_credit_delegation,
     error::ContractError,
     health::assert_below_max_ltv_after_borrow,
     helpers::query_asset_params,
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    isolation::update_isolated_debts,
     state::{CONFIG, MARKETS},
     user::User,
 };
 
 /// Add debt for the borrower and send the borrowed funds
 pub fn borrow(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     denom: String,
     borrow_amount: Uint128,
     recipient: Option<String>,
+    on_behalf_of: Option<String>,
 ) -> Result<Response, ContractError> {
-    let borrower = User(&info.sender);
+    // With a credit delegation, the debt is charged to the delegator
+    let delegator_addr = on_behalf_of.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let borrower_addr = delegator_addr.clone().unwrap_or_else(|| info.sender.clone());
+    let borrower = User(&borrower_addr);
 
     let config = CONFIG.load(deps.storage)?;
 
@@ -64,7 +70,8 @@ pub fn borrow(
         borrow_market.debt_total_scaled,
         &borrow_market,
         env.block.time.seconds(),
-    )?;
+    )?
+    .checked_add(borrow_market.term_debt_total)?;
 
     // Cannot borrow zero amount or more than available liquidity
     let available_liquidity = collateral_balance_before.checked_sub(debt_balance_before)?;
@@ -74,9 +81,27 @@ pub fn borrow(
         });
     }
 
+    // Cannot borrow more than the borrow cap of the market
+    if debt_balance_before.checked_add(borrow_amount)? > asset_params.borrow_cap {
+        return Err(ContractError::BorrowCapExceeded {
+            denom,
+        });
+    }
+
+    if let Some(delegator_addr) = &delegator_addr {
+        use_credit_delegation(
+            deps.storage,
+            &env,
+            delegator_addr,
+            &info.sender,
+            &denom,
+            borrow_amount,
+        )?;
+    }
+
     // Check if user can borrow specified amount
     let mut uncollateralized_debt = false;
-    if info.sender != credit_manager_addr {
+    if borrower.address() != credit_manager_addr {
         if !assert_below_max_ltv_after_borrow(
             &deps.as_ref(),
             &env,
@@ -114,17 +139,32 @@ pub fn borrow(
     response = update_interest_rates(&env, &mut borrow_market, response)?;
     MARKETS.save(deps.storage, &denom, &borrow_market)?;
 
-    // Send borrow amount to borrower or another recipient
+    if !uncollateralized_debt {
+        update_isolated_debts(
+            deps.branch(),
+            &env,
+            borrower.address(),
+            oracle_addr,
+            params_addr,
+            true,
+        )?;
+    }
+
+    // Send borrow amount to the sender or another recipient
     let recipient_addr = if let Some(recipient) = recipient {
         deps.api.addr_validate(&recipient)?
     } else {
-        borrower.address().clone()
+        info.sender.clone()
     };
 
+    if let Some(delegator_addr) = delegator_addr {
+        response = response.add_attribute("on_behalf_of", delegator_addr);
+    }
+
     Ok(response
         .add_message(build_send_asset_msg(&recipient_addr, &denom, borrow_amount))
         .add_attribute("action", "borrow")
-        .add_attribute("sender", borrower)
+        .add_attribute("sender", info.sender)
         .add_attribute("recipient", recipient_addr)
         .add_attribute("denom", denom)
         .add_attribute("amount", borrow_amount)
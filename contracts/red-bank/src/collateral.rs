@@ -8,13 +8,15 @@ use mars_types::{
 use crate::{
     error::ContractError,
     health::get_health_and_positions,
-    state::{COLLATERALS, CONFIG},
+    helpers::query_emode_category,
+    isolation::update_isolated_debts,
+    state::{COLLATERALS, CONFIG, USER_EMODES},
     user::User,
 };
 
 /// Update (enable / disable) collateral asset for specific user
 pub fn update_asset_collateral_status(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     denom: String,
@@ -38,19 +40,19 @@ pub fn update_asset_collateral_status(
     collateral.enabled = enable;
     COLLATERALS.save(deps.storage, (&user_id_key, &denom), &collateral)?;
 
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![MarsAddressType::Oracle, MarsAddressType::Params],
+    )?;
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+
     // if the collateral was previously enabled, but is not disabled, it is necessary to ensure the
     // user is not liquidatable after disabling
     if previously_enabled && !enable {
-        let config = CONFIG.load(deps.storage)?;
-
-        let addresses = address_provider::helpers::query_contract_addrs(
-            deps.as_ref(),
-            &config.address_provider,
-            vec![MarsAddressType::Oracle, MarsAddressType::Params],
-        )?;
-        let oracle_addr = &addresses[&MarsAddressType::Oracle];
-        let params_addr = &addresses[&MarsAddressType::Params];
-
         let (health, _) = get_health_and_positions(
             &deps.as_ref(),
             &env,
@@ -66,9 +68,65 @@ pub fn update_asset_collateral_status(
         }
     }
 
+    update_isolated_debts(deps.branch(), &env, user.address(), oracle_addr, params_addr, true)?;
+
     Ok(Response::new()
         .add_attribute("action", "update_asset_collateral_status")
         .add_attribute("user", user)
         .add_attribute("denom", denom)
         .add_attribute("enable", enable.to_string()))
 }
+
+/// Opt the user into an e-mode category, or out of the current one.
+///
+/// The user must not end up above max LTV after the change, which can happen when leaving a
+/// category or switching to one with lower params.
+pub fn set_emode(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    category: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![MarsAddressType::Oracle, MarsAddressType::Params],
+    )?;
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+
+    match &category {
+        Some(name) => {
+            if query_emode_category(&deps.querier, params_addr, name)?.is_none() {
+                return Err(ContractError::EModeCategoryNotFound {
+                    name: name.clone(),
+                });
+            }
+            USER_EMODES.save(deps.storage, &info.sender, name)?;
+        }
+        None => USER_EMODES.remove(deps.storage, &info.sender),
+    }
+
+    let (health, _) = get_health_and_positions(
+        &deps.as_ref(),
+        &env,
+        &info.sender,
+        "",
+        oracle_addr,
+        params_addr,
+        false,
+    )?;
+
+    if health.is_above_max_ltv() {
+        return Err(ContractError::InvalidHealthFactorAfterEModeChange {});
+    }
+
+    update_isolated_debts(deps.branch(), &env, &info.sender, oracle_addr, params_addr, true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_emode")
+        .add_attribute("user", info.sender)
+        .add_attribute("category", category.unwrap_or_default()))
+}
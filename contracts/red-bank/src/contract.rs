@@ -4,8 +4,8 @@ use cosmwasm_std::{
 use mars_types::red_bank::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 use crate::{
-    asset, borrow, collateral, config, deposit, error::ContractError, instantiate, liquidate,
-    migrations, query, repay, withdraw,
+    asset, bad_debt, borrow, collateral, config, credit_delegation, deposit, error::ContractError,
+    flash_loan, instantiate, liquidate, migrations, query, repay, term_loan, withdraw,
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -74,9 +74,48 @@ pub fn execute(
             denom,
             amount,
             recipient,
+            on_behalf_of,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            borrow::borrow(deps, env, info, denom, amount, recipient, on_behalf_of)
+        }
+        ExecuteMsg::ApproveCreditDelegation {
+            delegatee,
+            denom,
+            amount,
+            expires_at,
         } => {
             cw_utils::nonpayable(&info)?;
-            borrow::borrow(deps, env, info, denom, amount, recipient)
+            credit_delegation::approve_credit_delegation(
+                deps, env, info, delegatee, denom, amount, expires_at,
+            )
+        }
+        ExecuteMsg::RevokeCreditDelegation {
+            delegatee,
+            denom,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            credit_delegation::revoke_credit_delegation(deps, info, delegatee, denom)
+        }
+        ExecuteMsg::BorrowTermLoan {
+            denom,
+            amount,
+            duration,
+            recipient,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            term_loan::borrow_term_loan(deps, env, info, denom, amount, duration, recipient)
+        }
+        ExecuteMsg::RepayTermLoan {
+            loan_id,
+        } => term_loan::repay_term_loan(deps, env, info, loan_id),
+        ExecuteMsg::ConvertTermLoan {
+            user,
+            loan_id,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            let user_addr = deps.api.addr_validate(&user)?;
+            term_loan::convert_term_loan(deps, env, user_addr, loan_id)
         }
         ExecuteMsg::Repay {
             on_behalf_of,
@@ -102,6 +141,21 @@ pub fn execute(
                 recipient,
             )
         }
+        ExecuteMsg::WriteOffBadDebt {
+            user,
+            denom,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            let user_addr = deps.api.addr_validate(&user)?;
+            bad_debt::write_off_bad_debt(deps, env, user_addr, denom)
+        }
+        ExecuteMsg::WriteOffCreditManagerBadDebt {
+            denom,
+            amount,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            bad_debt::write_off_credit_manager_bad_debt(deps, env, info, denom, amount)
+        }
         ExecuteMsg::UpdateAssetCollateralStatus {
             denom,
             enable,
@@ -109,6 +163,24 @@ pub fn execute(
             cw_utils::nonpayable(&info)?;
             collateral::update_asset_collateral_status(deps, env, info, denom, enable)
         }
+        ExecuteMsg::SetEMode {
+            category,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            collateral::set_emode(deps, env, info, category)
+        }
+        ExecuteMsg::FlashLoan {
+            coins,
+            callback,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            flash_loan::flash_loan(deps, env, info, coins, callback)
+        }
+        ExecuteMsg::RepayFlashLoan {} => flash_loan::repay_flash_loan(deps, info),
+        ExecuteMsg::FinalizeFlashLoan {} => {
+            cw_utils::nonpayable(&info)?;
+            flash_loan::finalize_flash_loan(deps, env, info)
+        }
     }
 }
 
@@ -151,6 +223,46 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
                 limit,
             )?)
         }
+        QueryMsg::CreditDelegationsGranted {
+            delegator,
+            start_after,
+            limit,
+        } => {
+            let delegator_addr = deps.api.addr_validate(&delegator)?;
+            to_json_binary(&query::query_credit_delegations_granted(
+                deps,
+                delegator_addr,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::CreditDelegationsReceived {
+            delegatee,
+            start_after,
+            limit,
+        } => {
+            let delegatee_addr = deps.api.addr_validate(&delegatee)?;
+            to_json_binary(&query::query_credit_delegations_received(
+                deps,
+                delegatee_addr,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::UserTermLoans {
+            user,
+            start_after,
+            limit,
+        } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            to_json_binary(&query::query_user_term_loans(
+                deps,
+                &env.block,
+                user_addr,
+                start_after,
+                limit,
+            )?)
+        }
         QueryMsg::UserCollateral {
             user,
             account_id,
@@ -193,6 +305,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
                 limit,
             )?)
         }
+        QueryMsg::UserEMode {
+            user,
+        } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            to_json_binary(&query::query_user_emode(deps, user_addr)?)
+        }
         QueryMsg::UserPosition {
             user,
             account_id,
@@ -228,6 +346,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             denom,
             amount_scaled,
         } => to_json_binary(&query::query_underlying_debt_amount(deps, env, denom, amount_scaled)?),
+        QueryMsg::IsolatedDebts {
+            denom,
+        } => to_json_binary(&query::query_isolated_debts(deps, env, denom)?),
     };
     res.map_err(Into::into)
 }
@@ -242,5 +363,6 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
             market,
         } => migrations::v2_3_3::migrate(deps, haircut, &market),
         MigrateMsg::V2_3_1ToV2_3_2 {} => migrations::v2_3_2::migrate(deps),
+        MigrateMsg::V2_3_3ToV2_4_0 {} => migrations::v2_4_0::migrate(deps),
     }
 }
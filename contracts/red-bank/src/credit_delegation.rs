@@ -0,0 +1,134 @@
+use cosmwasm_std::{Addr, DepsMut, Empty, Env, MessageInfo, Response, Storage, Uint128};
+use mars_types::red_bank::CreditDelegation;
+
+use crate::{
+    error::ContractError,
+    state::{CREDIT_DELEGATIONS, MARKETS, RECEIVED_CREDIT_DELEGATIONS},
+};
+
+/// Allow the delegatee to borrow an asset against the collateral of the sender
+pub fn approve_credit_delegation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegatee: String,
+    denom: String,
+    amount: Uint128,
+    expires_at: Option<u64>,
+) -> Result<Response, ContractError> {
+    let delegatee_addr = deps.api.addr_validate(&delegatee)?;
+
+    if delegatee_addr == info.sender {
+        return Err(ContractError::InvalidCreditDelegation {
+            reason: "cannot delegate credit to self".to_string(),
+        });
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidCreditDelegation {
+            reason: "amount must be greater than zero".to_string(),
+        });
+    }
+
+    if expires_at.map_or(false, |expires_at| expires_at <= env.block.time.seconds()) {
+        return Err(ContractError::InvalidCreditDelegation {
+            reason: "expiration must be in the future".to_string(),
+        });
+    }
+
+    if !MARKETS.has(deps.storage, &denom) {
+        return Err(ContractError::InvalidCreditDelegation {
+            reason: format!("no market for {denom}"),
+        });
+    }
+
+    CREDIT_DELEGATIONS.save(
+        deps.storage,
+        (&info.sender, &delegatee_addr, &denom),
+        &CreditDelegation {
+            amount,
+            expires_at,
+        },
+    )?;
+    RECEIVED_CREDIT_DELEGATIONS.save(
+        deps.storage,
+        (&delegatee_addr, &info.sender, &denom),
+        &Empty {},
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_credit_delegation")
+        .add_attribute("delegator", info.sender)
+        .add_attribute("delegatee", delegatee_addr)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount)
+        .add_attribute("expires_at", expires_at.map(|t| t.to_string()).unwrap_or_default()))
+}
+
+/// Revoke a credit delegation granted by the sender
+pub fn revoke_credit_delegation(
+    deps: DepsMut,
+    info: MessageInfo,
+    delegatee: String,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let delegatee_addr = deps.api.addr_validate(&delegatee)?;
+
+    remove_credit_delegation(deps.storage, &info.sender, &delegatee_addr, &denom);
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_credit_delegation")
+        .add_attribute("delegator", info.sender)
+        .add_attribute("delegatee", delegatee_addr)
+        .add_attribute("denom", denom))
+}
+
+/// Consume the allowance of a credit delegation for a borrow by the delegatee
+pub fn use_credit_delegation(
+    store: &mut dyn Storage,
+    env: &Env,
+    delegator_addr: &Addr,
+    delegatee_addr: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let mut delegation = CREDIT_DELEGATIONS
+        .may_load(store, (delegator_addr, delegatee_addr, denom))?
+        .ok_or_else(|| ContractError::CreditDelegationNotFound {
+            delegator: delegator_addr.to_string(),
+            denom: denom.to_string(),
+        })?;
+
+    if delegation.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::CreditDelegationExpired {
+            delegator: delegator_addr.to_string(),
+            denom: denom.to_string(),
+        });
+    }
+
+    if amount > delegation.amount {
+        return Err(ContractError::CreditDelegationExceeded {
+            allowance: delegation.amount,
+            amount,
+        });
+    }
+
+    delegation.amount -= amount;
+    if delegation.amount.is_zero() {
+        remove_credit_delegation(store, delegator_addr, delegatee_addr, denom);
+    } else {
+        CREDIT_DELEGATIONS.save(store, (delegator_addr, delegatee_addr, denom), &delegation)?;
+    }
+
+    Ok(())
+}
+
+fn remove_credit_delegation(
+    store: &mut dyn Storage,
+    delegator_addr: &Addr,
+    delegatee_addr: &Addr,
+    denom: &str,
+) {
+    CREDIT_DELEGATIONS.remove(store, (delegator_addr, delegatee_addr, denom));
+    RECEIVED_CREDIT_DELEGATIONS.remove(store, (delegatee_addr, delegator_addr, denom));
+}
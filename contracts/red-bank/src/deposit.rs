@@ -9,12 +9,13 @@ use crate::{
     error::ContractError,
     helpers::{query_asset_params, query_total_deposit},
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    isolation::update_isolated_debts,
     state::{CONFIG, MARKETS},
     user::User,
 };
 
 pub fn deposit(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     on_behalf_of: Option<String>,
@@ -28,6 +29,7 @@ pub fn deposit(
         deps.as_ref(),
         &config.address_provider,
         vec![
+            MarsAddressType::Oracle,
             MarsAddressType::Incentives,
             MarsAddressType::RewardsCollector,
             MarsAddressType::Params,
@@ -36,6 +38,7 @@ pub fn deposit(
     )?;
     let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
     let incentives_addr = &addresses[&MarsAddressType::Incentives];
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
     let params_addr = &addresses[&MarsAddressType::Params];
     let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
 
@@ -104,7 +107,7 @@ pub fn deposit(
         deposit_amount_scaled,
         incentives_addr,
         response,
-        account_id,
+        account_id.clone(),
     )?;
 
     market.increase_collateral(deposit_amount_scaled)?;
@@ -113,6 +116,11 @@ pub fn deposit(
 
     MARKETS.save(deps.storage, &denom, &market)?;
 
+    // Credit manager accounts are accounted for in the credit manager
+    if account_id.is_none() {
+        update_isolated_debts(deps.branch(), &env, user.address(), oracle_addr, params_addr, true)?;
+    }
+
     Ok(response
         .add_attribute("action", "deposit")
         .add_attribute("sender", &info.sender)
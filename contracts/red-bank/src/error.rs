@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyFractionError, DivideByZeroError, OverflowError, StdError,
+    CheckedFromRatioError, CheckedMultiplyFractionError, DivideByZeroError, OverflowError,
+    StdError, Uint128,
 };
 use cw_utils::PaymentError;
 use mars_health::error::HealthError;
@@ -72,6 +73,11 @@ pub enum ContractError {
         denom: String,
     },
 
+    #[error("Borrow Cap exceeded for {denom:?}")]
+    BorrowCapExceeded {
+        denom: String,
+    },
+
     #[error("Cannot have 0 as liquidity index")]
     InvalidLiquidityIndex {},
 
@@ -142,6 +148,44 @@ pub enum ContractError {
     #[error("User's health factor can't be less than 1 after disabling collateral")]
     InvalidHealthFactorAfterDisablingCollateral {},
 
+    #[error("Term loans are not offered for {denom:?}")]
+    TermLoansNotEnabled {
+        denom: String,
+    },
+
+    #[error("No term of {duration} seconds offered for {denom:?}")]
+    InvalidLoanTerm {
+        denom: String,
+        duration: u64,
+    },
+
+    #[error("Credit manager cannot borrow term loans")]
+    CannotBorrowTermLoanAsCreditManager {},
+
+    #[error("Term loan {loan_id} not found")]
+    TermLoanNotFound {
+        loan_id: u64,
+    },
+
+    #[error("Term loan {loan_id} has not matured yet")]
+    TermLoanNotMatured {
+        loan_id: u64,
+    },
+
+    #[error("Term loan requires a repayment of {expected}, sent {sent}")]
+    TermLoanNotFullyRepaid {
+        expected: Uint128,
+        sent: Uint128,
+    },
+
+    #[error("E-mode category {name:?} not found")]
+    EModeCategoryNotFound {
+        name: String,
+    },
+
+    #[error("User's debt can't be above max LTV after changing e-mode")]
+    InvalidHealthFactorAfterEModeChange {},
+
     #[error("{0}")]
     Version(#[from] cw2::VersionError),
 
@@ -153,4 +197,63 @@ pub enum ContractError {
 
     #[error("Cannot liquidate credit manager (use credit-manager contract liquidate function)")]
     CannotLiquidateCreditManager {},
+
+    #[error("Invalid flash loan: {reason}")]
+    InvalidFlashLoan {
+        reason: String,
+    },
+
+    #[error("A flash loan is already in progress")]
+    FlashLoanInProgress {},
+
+    #[error("No flash loan in progress")]
+    NoFlashLoanInProgress {},
+
+    #[error("Flash loan of {denom:?} not repaid: expected {expected}, repaid {repaid}")]
+    FlashLoanNotRepaid {
+        denom: String,
+        expected: Uint128,
+        repaid: Uint128,
+    },
+
+    #[error("Invalid credit delegation: {reason}")]
+    InvalidCreditDelegation {
+        reason: String,
+    },
+
+    #[error("No credit delegation of {denom:?} from {delegator}")]
+    CreditDelegationNotFound {
+        delegator: String,
+        denom: String,
+    },
+
+    #[error("Credit delegation of {denom:?} from {delegator} has expired")]
+    CreditDelegationExpired {
+        delegator: String,
+        denom: String,
+    },
+
+    #[error("Credit delegation exceeded: allowance {allowance}, requested {amount}")]
+    CreditDelegationExceeded {
+        allowance: Uint128,
+        amount: Uint128,
+    },
+
+    #[error(
+        "Cannot write off debt of credit manager (use credit-manager contract write off function)"
+    )]
+    CannotWriteOffCreditManagerDebt {},
+
+    #[error("Cannot write off debt of a user with collateral")]
+    CannotWriteOffDebtWithCollateral {},
+
+    #[error("No debt to write off")]
+    NoDebtToWriteOff {},
+
+    #[error("Debt ceiling of isolated collateral {denom:?} exceeded: ceiling {ceiling}, new value {new_value}")]
+    IsolatedDebtCeilingExceeded {
+        denom: String,
+        ceiling: Uint128,
+        new_value: Uint128,
+    },
 }
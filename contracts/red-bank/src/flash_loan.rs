@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{
+    to_json_binary, Binary, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, WasmMsg,
+};
+use mars_interest_rate::{get_underlying_debt_amount, get_underlying_liquidity_amount};
+use mars_types::{
+    address_provider::{self, MarsAddressType},
+    error::MarsError,
+    red_bank::{ExecuteMsg, FlashLoanReceiverMsg},
+};
+
+use crate::{
+    error::ContractError,
+    helpers::query_asset_params,
+    interest_rates::{apply_accumulated_interests, distribute_fee, update_interest_rates},
+    state::{FlashLoanState, CONFIG, FLASH_LOAN, MARKETS},
+};
+
+/// Lend the requested coins to the sender without collateral and invoke its flash loan callback.
+///
+/// The receiver has to send the coins back, plus the flash loan fee of each market, via
+/// `RepayFlashLoan` before the callback returns. Repayment is asserted by `FinalizeFlashLoan`,
+/// which is dispatched by the contract to itself after the callback, so that the whole
+/// transaction reverts if the loan is not repaid.
+pub fn flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    coins: Vec<Coin>,
+    callback: Binary,
+) -> Result<Response, ContractError> {
+    if FLASH_LOAN.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::FlashLoanInProgress {});
+    }
+
+    if coins.is_empty() {
+        return Err(ContractError::InvalidFlashLoan {
+            reason: "no coins requested".to_string(),
+        });
+    }
+
+    let mut denoms = HashSet::new();
+    if !coins.iter().all(|coin| denoms.insert(coin.denom.as_str())) {
+        return Err(ContractError::InvalidFlashLoan {
+            reason: "duplicate denoms".to_string(),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let params_addr = address_provider::helpers::query_contract_addr(
+        deps.as_ref(),
+        &config.address_provider,
+        MarsAddressType::Params,
+    )?;
+
+    let mut fees = vec![];
+    for coin in &coins {
+        let asset_params = query_asset_params(&deps.querier, &params_addr, &coin.denom)?;
+        if !asset_params.red_bank.borrow_enabled {
+            return Err(ContractError::BorrowNotEnabled {
+                denom: coin.denom.clone(),
+            });
+        }
+
+        let market = MARKETS.load(deps.storage, &coin.denom)?;
+
+        // Cannot flash loan zero amount or more than available liquidity
+        let collateral_balance = get_underlying_liquidity_amount(
+            market.collateral_total_scaled,
+            &market,
+            env.block.time.seconds(),
+        )?;
+        let debt_balance = get_underlying_debt_amount(
+            market.debt_total_scaled,
+            &market,
+            env.block.time.seconds(),
+        )?
+        .checked_add(market.term_debt_total)?;
+        let available_liquidity = collateral_balance.checked_sub(debt_balance)?;
+        if coin.amount.is_zero() || coin.amount > available_liquidity {
+            return Err(ContractError::InvalidBorrowAmount {
+                denom: coin.denom.clone(),
+            });
+        }
+
+        let fee = coin.amount.checked_mul_ceil(market.flash_loan_fee_rate)?;
+        fees.push(Coin::new(fee.u128(), coin.denom.clone()));
+    }
+
+    FLASH_LOAN.save(
+        deps.storage,
+        &FlashLoanState {
+            receiver: info.sender.clone(),
+            coins: coins.clone(),
+            fees: fees.clone(),
+            repaid: vec![],
+        },
+    )?;
+
+    let callback_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: info.sender.to_string(),
+        msg: to_json_binary(&FlashLoanReceiverMsg::FlashLoanCallback {
+            coins: coins.clone(),
+            fees,
+            callback,
+        })?,
+        funds: coins,
+    });
+    let finalize_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_json_binary(&ExecuteMsg::FinalizeFlashLoan {})?,
+        funds: vec![],
+    });
+
+    Ok(Response::new()
+        .add_message(callback_msg)
+        .add_message(finalize_msg)
+        .add_attribute("action", "flash_loan")
+        .add_attribute("receiver", info.sender))
+}
+
+/// Record coins sent back for the flash loan in progress
+pub fn repay_flash_loan(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut flash_loan =
+        FLASH_LOAN.may_load(deps.storage)?.ok_or(ContractError::NoFlashLoanInProgress {})?;
+
+    if info.funds.is_empty() {
+        return Err(ContractError::InvalidFlashLoan {
+            reason: "no coins sent".to_string(),
+        });
+    }
+
+    for coin in &info.funds {
+        if !flash_loan.coins.iter().any(|c| c.denom == coin.denom) {
+            return Err(ContractError::InvalidFlashLoan {
+                reason: format!("{} was not flash loaned", coin.denom),
+            });
+        }
+
+        match flash_loan.repaid.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(repaid) => repaid.amount = repaid.amount.checked_add(coin.amount)?,
+            None => flash_loan.repaid.push(coin.clone()),
+        }
+    }
+
+    FLASH_LOAN.save(deps.storage, &flash_loan)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "repay_flash_loan")
+        .add_attribute("sender", info.sender)
+        .add_attribute("coins", coins_to_string(&info.funds)))
+}
+
+/// Assert that the flash loan in progress has been repaid together with its fees, and distribute
+/// the fees of each market between the protocol and the depositors
+pub fn finalize_flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(MarsError::Unauthorized {}.into());
+    }
+
+    let flash_loan =
+        FLASH_LOAN.may_load(deps.storage)?.ok_or(ContractError::NoFlashLoanInProgress {})?;
+    FLASH_LOAN.remove(deps.storage);
+
+    let config = CONFIG.load(deps.storage)?;
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![MarsAddressType::Incentives, MarsAddressType::RewardsCollector],
+    )?;
+    let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
+    let incentives_addr = &addresses[&MarsAddressType::Incentives];
+
+    let mut response = Response::new();
+    let mut collected_fees = vec![];
+
+    for (coin, fee) in flash_loan.coins.iter().zip(flash_loan.fees.iter()) {
+        let expected = coin.amount.checked_add(fee.amount)?;
+        let repaid = flash_loan
+            .repaid
+            .iter()
+            .find(|c| c.denom == coin.denom)
+            .map(|c| c.amount)
+            .unwrap_or_default();
+        if repaid < expected {
+            return Err(ContractError::FlashLoanNotRepaid {
+                denom: coin.denom.clone(),
+                expected,
+                repaid,
+            });
+        }
+
+        // Anything repaid on top of the borrowed amount is treated as fee
+        let fee_amount = repaid - coin.amount;
+        if fee_amount.is_zero() {
+            continue;
+        }
+
+        let mut market = MARKETS.load(deps.storage, &coin.denom)?;
+
+        response = apply_accumulated_interests(
+            deps.storage,
+            &env,
+            &mut market,
+            rewards_collector_addr,
+            incentives_addr,
+            response,
+        )?;
+
+        response = distribute_fee(
+            deps.storage,
+            &env,
+            &mut market,
+            fee_amount,
+            rewards_collector_addr,
+            incentives_addr,
+            response,
+        )?;
+
+        response = update_interest_rates(&env, &mut market, response)?;
+        MARKETS.save(deps.storage, &coin.denom, &market)?;
+
+        collected_fees.push(Coin::new(fee_amount.u128(), coin.denom.clone()));
+    }
+
+    Ok(response
+        .add_attribute("action", "finalize_flash_loan")
+        .add_attribute("receiver", flash_loan.receiver)
+        .add_attribute("coins", coins_to_string(&flash_loan.coins))
+        .add_attribute("fees", coins_to_string(&collected_fees)))
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins.iter().map(|coin| coin.to_string()).collect::<Vec<_>>().join(",")
+}
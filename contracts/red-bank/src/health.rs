@@ -1,18 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
-use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, Decimal, Deps, Env, Order, StdError, StdResult, Uint128};
 use mars_health::health::{Health, Position as HealthPosition};
 use mars_interest_rate::{get_underlying_debt_amount, get_underlying_liquidity_amount};
 use mars_types::{
     keys::{UserId, UserIdKey},
     oracle,
+    params::EModeCategory,
     red_bank::Position,
 };
 
 use crate::{
     error::ContractError,
-    helpers::query_asset_params,
-    state::{COLLATERALS, DEBTS, MARKETS},
+    helpers::{query_asset_params, query_emode_category},
+    state::{COLLATERALS, DEBTS, MARKETS, TERM_LOANS, USER_EMODES},
 };
 
 /// Get health and positions for a given user
@@ -34,7 +35,8 @@ pub fn get_health_and_positions(
         params_addr,
         is_liquidation,
     )?;
-    let health = compute_position_health(&positions)?;
+    let emode = query_user_emode_category(deps, user_addr, params_addr)?;
+    let health = compute_position_health(&positions, emode.as_ref())?;
 
     Ok((health, positions))
 }
@@ -68,7 +70,8 @@ pub fn assert_below_liq_threshold_after_withdraw(
         None => return Err(StdError::generic_err("No User Balance").into()),
     }
 
-    let health = compute_position_health(&positions)?;
+    let emode = query_user_emode_category(deps, user_addr, params_addr)?;
+    let health = compute_position_health(&positions, emode.as_ref())?;
     Ok(!health.is_liquidatable())
 }
 
@@ -97,15 +100,102 @@ pub fn assert_below_max_ltv_after_borrow(
         })
         .debt_amount += borrow_amount;
 
-    let health = compute_position_health(&positions)?;
+    let emode = query_user_emode_category(deps, user_addr, params_addr)?;
+    let health = compute_position_health(&positions, emode.as_ref())?;
     Ok(!health.is_above_max_ltv())
 }
 
-/// Compute Health of a given User Position
+/// Get the e-mode category the user opted into, if it still exists in the params contract
+pub fn query_user_emode_category(
+    deps: &Deps,
+    user_addr: &Addr,
+    params_addr: &Addr,
+) -> Result<Option<EModeCategory>, ContractError> {
+    let Some(name) = USER_EMODES.may_load(deps.storage, user_addr)? else {
+        return Ok(None);
+    };
+    Ok(query_emode_category(&deps.querier, params_addr, name)?)
+}
+
+/// Compute Health of a given User Position.
+///
+/// If the user opted into an e-mode category and every debt of the position belongs to it, the
+/// collaterals belonging to the category use its max LTV and liquidation threshold.
 pub fn compute_position_health(
     positions: &HashMap<String, Position>,
+    emode: Option<&EModeCategory>,
 ) -> Result<Health, ContractError> {
-    let positions = positions
+    let positions = health_positions(positions, emode);
+    Health::compute_health(&positions).map_err(Into::into)
+}
+
+/// Portion of the user's debts backed by each of its collaterals in isolation mode, which counts
+/// towards the debt ceiling of the collateral.
+///
+/// Debts are backed by all collaterals in proportion to their max LTV adjusted value. An isolated
+/// collateral backs none of them if a debt denom is outside of its allow-list.
+pub fn isolated_debt_portions(
+    positions: &HashMap<String, Position>,
+    emode: Option<&EModeCategory>,
+) -> Result<HashMap<String, Decimal>, ContractError> {
+    let debt_denoms = collateralized_debt_denoms(positions);
+    if debt_denoms.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut max_ltv_adjusted_values = HashMap::new();
+    let mut max_ltv_adjusted_collateral = Uint128::zero();
+    for p in health_positions(positions, emode) {
+        let value = p.collateral_amount.checked_mul_floor(p.price)?.checked_mul_floor(p.max_ltv)?;
+        max_ltv_adjusted_collateral = max_ltv_adjusted_collateral.checked_add(value)?;
+        max_ltv_adjusted_values.insert(p.denom, value);
+    }
+    if max_ltv_adjusted_collateral.is_zero() {
+        return Ok(HashMap::new());
+    }
+
+    let mut portions = HashMap::new();
+    for (denom, value) in max_ltv_adjusted_values {
+        let isolated = positions.get(&denom).is_some_and(|p| p.isolation.is_some());
+        if !isolated || value.is_zero() {
+            continue;
+        }
+
+        let portion = Decimal::checked_from_ratio(value, max_ltv_adjusted_collateral)?;
+        portions.insert(denom, portion);
+    }
+
+    Ok(portions)
+}
+
+/// The e-mode category of the user, if every debt of the position belongs to it
+pub fn applicable_emode<'a>(
+    positions: &HashMap<String, Position>,
+    emode: Option<&'a EModeCategory>,
+) -> Option<&'a EModeCategory> {
+    let debt_denoms = collateralized_debt_denoms(positions);
+    emode.filter(|category| debt_denoms.iter().all(|denom| category.contains(denom)))
+}
+
+/// Denoms of the debts backed by the collaterals of the position
+fn collateralized_debt_denoms(positions: &HashMap<String, Position>) -> Vec<&str> {
+    positions
+        .values()
+        .filter(|p| !p.debt_amount.is_zero() && !p.uncollateralized_debt)
+        .map(|p| p.denom.as_str())
+        .collect()
+}
+
+/// Positions with the max LTV and liquidation threshold of the e-mode category and of the
+/// isolation mode applied
+fn health_positions(
+    positions: &HashMap<String, Position>,
+    emode: Option<&EModeCategory>,
+) -> Vec<HealthPosition> {
+    let debt_denoms = collateralized_debt_denoms(positions);
+    let emode = applicable_emode(positions, emode);
+
+    positions
         .values()
         .map(|p| {
             // if it is an "uncollateralized" debt, then it won't count towards their health factor
@@ -115,18 +205,41 @@ pub fn compute_position_health(
                 p.debt_amount
             };
 
+            let (max_ltv, liquidation_threshold) = match emode {
+                Some(category) if category.contains(&p.denom) => {
+                    (category.max_loan_to_value, category.liquidation_threshold)
+                }
+                _ => (p.max_ltv, p.liquidation_threshold),
+            };
+            let (max_ltv, liquidation_threshold) =
+                isolated_ltvs(p, max_ltv, liquidation_threshold, &debt_denoms);
+
             HealthPosition {
                 denom: p.denom.clone(),
                 collateral_amount: p.collateral_amount,
                 debt_amount,
                 price: p.asset_price,
-                max_ltv: p.max_ltv,
-                liquidation_threshold: p.liquidation_threshold,
+                max_ltv,
+                liquidation_threshold,
             }
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    Health::compute_health(&positions).map_err(Into::into)
+/// Max LTV and liquidation threshold of a collateral in isolation mode.
+///
+/// An isolated collateral doesn't back any debt if the position borrows a denom outside of its
+/// allow-list. Its debt ceiling is a global limit on the debts it backs across all positions.
+fn isolated_ltvs(
+    position: &Position,
+    max_ltv: Decimal,
+    liquidation_threshold: Decimal,
+    debt_denoms: &[&str],
+) -> (Decimal, Decimal) {
+    match &position.isolation {
+        Some(isolation) if !isolation.can_back(debt_denoms) => (Decimal::zero(), Decimal::zero()),
+        _ => (max_ltv, liquidation_threshold),
+    }
 }
 
 /// Goes through assets user has a position in and returns a HashMap mapping the asset denoms to the
@@ -155,10 +268,20 @@ pub fn get_user_positions_map(
         .keys(deps.storage, None, None, Order::Ascending)
         .collect::<StdResult<Vec<_>>>()?;
 
+    // Term loans are owed at their fixed rate, on top of the variable debt
+    let mut term_debts: HashMap<String, Uint128> = HashMap::new();
+    for item in TERM_LOANS.prefix(user_addr).range(deps.storage, None, None, Order::Ascending) {
+        let (_, loan) = item?;
+        let amount_owed = loan.amount_owed(block_time)?;
+        let term_debt = term_debts.entry(loan.denom).or_default();
+        *term_debt = term_debt.checked_add(amount_owed)?;
+    }
+
     // Collect the denoms into a hashset so that there are no dups
     let mut denoms = HashSet::new();
     denoms.extend(collateral_denoms);
     denoms.extend(debt_denoms);
+    denoms.extend(term_debts.keys().cloned());
 
     // Enumerate the denoms, compute underlying debt and collateral amount, and query the prices.
     // Finally, collect the results into a hashmap indexed by the denoms.
@@ -186,6 +309,8 @@ pub fn get_user_positions_map(
                     }
                     None => (Uint128::zero(), false),
                 };
+            let debt_amount =
+                debt_amount.checked_add(term_debts.get(&denom).copied().unwrap_or_default())?;
 
             let asset_price = if is_liquidation {
                 oracle::helpers::query_price_for_liquidate(&deps.querier, oracle_addr, &denom)?
@@ -201,6 +326,7 @@ pub fn get_user_positions_map(
                 max_ltv: params.max_loan_to_value,
                 liquidation_threshold: params.liquidation_threshold,
                 asset_price,
+                isolation: params.isolation,
             };
 
             Ok((denom, position))
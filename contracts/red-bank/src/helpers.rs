@@ -1,5 +1,5 @@
 use cosmwasm_std::{Coin, QuerierWrapper, StdResult};
-use mars_types::params::{AssetParams, QueryMsg};
+use mars_types::params::{AssetParams, EModeCategory, QueryMsg, TotalIsolatedDebtResponse};
 
 pub fn query_asset_params(
     querier: &QuerierWrapper,
@@ -14,6 +14,19 @@ pub fn query_asset_params(
     )
 }
 
+pub fn query_emode_category(
+    querier: &QuerierWrapper,
+    params: impl Into<String>,
+    name: impl Into<String>,
+) -> StdResult<Option<EModeCategory>> {
+    querier.query_wasm_smart(
+        params.into(),
+        &QueryMsg::EModeCategory {
+            name: name.into(),
+        },
+    )
+}
+
 pub fn query_total_deposit(
     querier: &QuerierWrapper,
     params: impl Into<String>,
@@ -26,3 +39,16 @@ pub fn query_total_deposit(
         },
     )
 }
+
+pub fn query_total_isolated_debt(
+    querier: &QuerierWrapper,
+    params: impl Into<String>,
+    denom: impl Into<String>,
+) -> StdResult<TotalIsolatedDebtResponse> {
+    querier.query_wasm_smart(
+        params.into(),
+        &QueryMsg::TotalIsolatedDebt {
+            denom: denom.into(),
+        },
+    )
+}
@@ -30,11 +30,16 @@ pub fn apply_accumulated_interests(
 ) -> Result<Response, ContractError> {
     let current_timestamp = env.block.time.seconds();
     let previous_borrow_index = market.borrow_index;
+    let mut term_interest_accrued = Uint128::zero();
 
     // Update market indices
     if market.indexes_last_updated < current_timestamp {
         let time_elapsed = current_timestamp - market.indexes_last_updated;
 
+        // Term loans accrue their fixed interest to the term debt, and to the depositors through
+        // the liquidity rate
+        term_interest_accrued = market.accrue_term_interest(time_elapsed)?;
+
         if !market.borrow_rate.is_zero() {
             market.borrow_index = calculate_applied_linear_interest_rate(
                 market.borrow_index,
@@ -64,7 +69,7 @@ pub fn apply_accumulated_interests(
         ScalingOperation::Ceil,
     )?;
 
-    let borrow_interest_accrued = if new_debt_total > previous_debt_total {
+    let variable_interest_accrued = if new_debt_total > previous_debt_total {
         // debt stays constant between the application of the interest rate
         // so the difference between debt at the start and the end is the
         // total borrow interest accrued
@@ -72,6 +77,7 @@ pub fn apply_accumulated_interests(
     } else {
         Uint128::zero()
     };
+    let borrow_interest_accrued = variable_interest_accrued.checked_add(term_interest_accrued)?;
 
     let accrued_protocol_rewards = borrow_interest_accrued * market.reserve_factor;
 
@@ -100,6 +106,61 @@ pub fn apply_accumulated_interests(
     Ok(response)
 }
 
+/// Split a fee paid to the market (flash loan fee, term loan penalty) the same way as borrow
+/// interest: the reserve factor portion is minted as collateral to the rewards collector and the
+/// rest accrues to the depositors by increasing the liquidity index.
+///
+/// NOTE: it does not save the market to store
+pub fn distribute_fee(
+    store: &mut dyn Storage,
+    env: &Env,
+    market: &mut Market,
+    fee_amount: Uint128,
+    rewards_collector_addr: &Addr,
+    incentives_addr: &Addr,
+    mut response: Response,
+) -> Result<Response, ContractError> {
+    let protocol_fee = fee_amount.checked_mul_floor(market.reserve_factor)?;
+    let depositors_fee = fee_amount.checked_sub(protocol_fee)?;
+
+    if !depositors_fee.is_zero() {
+        let total_collateral = get_underlying_liquidity_amount(
+            market.collateral_total_scaled,
+            market,
+            env.block.time.seconds(),
+        )?;
+        let index_growth = Decimal::one()
+            .checked_add(Decimal::checked_from_ratio(depositors_fee, total_collateral)?)?;
+        market.liquidity_index = market.liquidity_index.checked_mul(index_growth)?;
+    }
+
+    if !protocol_fee.is_zero() {
+        let protocol_fee_scaled = compute_scaled_amount(
+            protocol_fee,
+            market.liquidity_index,
+            ScalingOperation::Truncate,
+        )?;
+        response = User(rewards_collector_addr).increase_collateral(
+            store,
+            market,
+            protocol_fee_scaled,
+            incentives_addr,
+            response,
+            None,
+        )?;
+        market.increase_collateral(protocol_fee_scaled)?;
+
+        response = response
+            .add_attribute("rewards_collector", rewards_collector_addr.to_string())
+            .add_attribute(
+                "rewards_collector_fee",
+                Coin::new(protocol_fee.u128(), market.denom.clone()).to_string(),
+            );
+    }
+
+    Ok(response)
+}
+
 /// Update interest rates for current liquidity and debt levels
 /// Note it does not save the market to the store (that is left to the caller)
 /// Returns response with appended interest rates updated event
@@ -114,21 +175,41 @@ pub fn update_interest_rates(
 
     let total_collateral =
         get_underlying_liquidity_amount(market.collateral_total_scaled, market, current_timestamp)?;
-    let total_debt =
+    let variable_debt =
         get_underlying_debt_amount(market.debt_total_scaled, market, current_timestamp)?;
-
-    let mut current_utilization_rate = if !total_collateral.is_zero() {
-        Decimal::from_ratio(total_debt, total_collateral)
-    } else {
-        Decimal::zero()
+    let total_debt = variable_debt.checked_add(market.term_debt_total)?;
+
+    let utilization_rate = |debt: Uint128| {
+        let utilization_rate = if !total_collateral.is_zero() {
+            Decimal::from_ratio(debt, total_collateral)
+        } else {
+            Decimal::zero()
+        };
+
+        // Limit utilization_rate to 100%.
+        // With the current code it should hopefully never happen that it gets calculated to more than 100%,
+        // but better be safe than sorry.
+        utilization_rate.min(Decimal::one())
     };
 
-    // Limit utilization_rate to 100%.
-    // With the current code it should hopefully never happen that it gets calculated to more than 100%,
-    // but better be safe than sorry.
-    current_utilization_rate = current_utilization_rate.min(Decimal::one());
+    market.update_interest_rates(utilization_rate(total_debt), current_timestamp)?;
 
-    market.update_interest_rates(current_utilization_rate)?;
+    // Depositors earn the variable rate on the variable debt and the fixed rates of the term loans
+    // on the term debt
+    if !market.term_debt_total.is_zero() {
+        let variable_liquidity_rate = market.interest_rate_model.get_liquidity_rate(
+            market.borrow_rate,
+            utilization_rate(variable_debt),
+            market.reserve_factor,
+        )?;
+        let term_liquidity_rate = if !total_collateral.is_zero() {
+            Decimal::checked_from_ratio(market.term_interest_per_year, total_collateral)?
+                .checked_mul(Decimal::one() - market.reserve_factor)?
+        } else {
+            Decimal::zero()
+        };
+        market.liquidity_rate = variable_liquidity_rate.checked_add(term_liquidity_rate)?;
+    }
 
     Ok(response.add_event(build_interests_updated_event(&market.denom, market)))
 }
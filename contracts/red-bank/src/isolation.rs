@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, Order, StdResult, Storage, Uint128};
+use mars_interest_rate::{get_scaled_debt_amount, get_underlying_debt_amount};
+use mars_types::oracle;
+
+use crate::{
+    error::ContractError,
+    health::{get_user_positions_map, isolated_debt_portions, query_user_emode_category},
+    helpers::{query_asset_params, query_total_isolated_debt},
+    state::{DEBTS, ISOLATED_DEBTS, MARKETS, TERM_LOANS, USER_ISOLATED_DEBTS},
+};
+
+/// Re-record the debts of the user backed by each of its collaterals in isolation mode.
+///
+/// Each isolated collateral backs the portion of every debt that its max LTV adjusted value makes
+/// up of the user's total. The portions are recorded as scaled debt amounts, so the interest
+/// accrued on them counts towards the debt ceilings. Term loans are scaled by the variable borrow
+/// index of their market as well.
+///
+/// With `enforce_ceiling`, assert that the total debt backed by any isolated collateral whose
+/// portion of the user's debts went up stays within its debt ceiling.
+pub fn update_isolated_debts(
+    deps: DepsMut,
+    env: &Env,
+    user_addr: &Addr,
+    oracle_addr: &Addr,
+    params_addr: &Addr,
+    enforce_ceiling: bool,
+) -> Result<(), ContractError> {
+    let old_records = USER_ISOLATED_DEBTS
+        .sub_prefix(user_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Nothing to record for a user without debts
+    if old_records.is_empty() && !has_debts(deps.storage, user_addr) {
+        return Ok(());
+    }
+
+    // Liquidation pricing when the ceilings aren't enforced, so that liquidations and repayments
+    // go through when the default prices are unavailable
+    let block_time = env.block.time.seconds();
+    let positions = get_user_positions_map(
+        &deps.as_ref(),
+        env,
+        user_addr,
+        "",
+        oracle_addr,
+        params_addr,
+        !enforce_ceiling,
+    )?;
+    let emode = query_user_emode_category(&deps.as_ref(), user_addr, params_addr)?;
+    let portions = isolated_debt_portions(&positions, emode.as_ref())?;
+
+    let debts = positions
+        .values()
+        .filter(|p| !p.debt_amount.is_zero() && !p.uncollateralized_debt)
+        .collect::<Vec<_>>();
+
+    let mut new_records = vec![];
+    for (collateral_denom, portion) in &portions {
+        for debt in &debts {
+            let market = MARKETS.load(deps.storage, &debt.denom)?;
+            let amount = debt.debt_amount.checked_mul_floor(*portion)?;
+            let amount_scaled = get_scaled_debt_amount(amount, &market, block_time)?;
+            if !amount_scaled.is_zero() {
+                new_records.push(((collateral_denom.clone(), debt.denom.clone()), amount_scaled));
+            }
+        }
+    }
+
+    if enforce_ceiling {
+        let mut old_values: HashMap<&str, Uint128> = HashMap::new();
+        for ((collateral_denom, debt_denom), amount_scaled) in &old_records {
+            let value =
+                isolated_debt_value(deps.as_ref(), env, oracle_addr, debt_denom, *amount_scaled)?;
+            let old_value = old_values.entry(collateral_denom.as_str()).or_default();
+            *old_value = old_value.checked_add(value)?;
+        }
+
+        for (collateral_denom, portion) in &portions {
+            let mut new_value = Uint128::zero();
+            for debt in &debts {
+                let value = debt.debt_amount.checked_mul_ceil(debt.asset_price * *portion)?;
+                new_value = new_value.checked_add(value)?;
+            }
+
+            let old_value = old_values.get(collateral_denom.as_str()).copied().unwrap_or_default();
+            if new_value <= old_value {
+                continue;
+            }
+
+            let Some(isolation) =
+                query_asset_params(&deps.querier, params_addr, collateral_denom)?.isolation
+            else {
+                continue;
+            };
+            let total = query_total_isolated_debt(&deps.querier, params_addr, collateral_denom)?;
+            let total_value = total.value.saturating_sub(old_value).checked_add(new_value)?;
+            if total_value > isolation.debt_ceiling {
+                return Err(ContractError::IsolatedDebtCeilingExceeded {
+                    denom: collateral_denom.clone(),
+                    ceiling: isolation.debt_ceiling,
+                    new_value: total_value,
+                });
+            }
+        }
+    }
+
+    for ((collateral_denom, debt_denom), amount_scaled) in &old_records {
+        USER_ISOLATED_DEBTS.remove(deps.storage, (user_addr, collateral_denom, debt_denom));
+        let total = ISOLATED_DEBTS
+            .load(deps.storage, (collateral_denom, debt_denom))?
+            .checked_sub(*amount_scaled)?;
+        save_total(deps.storage, collateral_denom, debt_denom, total)?;
+    }
+    for ((collateral_denom, debt_denom), amount_scaled) in &new_records {
+        USER_ISOLATED_DEBTS.save(
+            deps.storage,
+            (user_addr, collateral_denom, debt_denom),
+            amount_scaled,
+        )?;
+        let total = ISOLATED_DEBTS
+            .may_load(deps.storage, (collateral_denom, debt_denom))?
+            .unwrap_or_default()
+            .checked_add(*amount_scaled)?;
+        save_total(deps.storage, collateral_denom, debt_denom, total)?;
+    }
+
+    Ok(())
+}
+
+/// Current amount of a scaled isolated debt, with the interest accrued on it
+pub fn isolated_debt_amount(
+    deps: Deps,
+    env: &Env,
+    denom: &str,
+    amount_scaled: Uint128,
+) -> Result<Uint128, ContractError> {
+    let market = MARKETS.load(deps.storage, denom)?;
+    Ok(get_underlying_debt_amount(amount_scaled, &market, env.block.time.seconds())?)
+}
+
+/// Value of a scaled isolated debt, with the interest accrued on it
+fn isolated_debt_value(
+    deps: Deps,
+    env: &Env,
+    oracle_addr: &Addr,
+    denom: &str,
+    amount_scaled: Uint128,
+) -> Result<Uint128, ContractError> {
+    let amount = isolated_debt_amount(deps, env, denom, amount_scaled)?;
+    let price = oracle::helpers::query_price(&deps.querier, oracle_addr, denom)?;
+    Ok(amount.checked_mul_ceil(price)?)
+}
+
+fn has_debts(store: &dyn Storage, user_addr: &Addr) -> bool {
+    DEBTS.prefix(user_addr).keys(store, None, None, Order::Ascending).next().is_some()
+        || TERM_LOANS.prefix(user_addr).keys(store, None, None, Order::Ascending).next().is_some()
+}
+
+fn save_total(
+    store: &mut dyn Storage,
+    collateral_denom: &str,
+    debt_denom: &str,
+    total: Uint128,
+) -> StdResult<()> {
+    if total.is_zero() {
+        ISOLATED_DEBTS.remove(store, (collateral_denom, debt_denom));
+        Ok(())
+    } else {
+        ISOLATED_DEBTS.save(store, (collateral_denom, debt_denom), &total)
+    }
+}
@@ -1,19 +1,24 @@
 pub mod asset;
+pub mod bad_debt;
 pub mod borrow;
 pub mod collateral;
 pub mod config;
 #[cfg(not(feature = "library"))]
 pub mod contract;
+pub mod credit_delegation;
 pub mod deposit;
 pub mod error;
+pub mod flash_loan;
 pub mod health;
 pub mod instantiate;
 pub mod interest_rates;
+pub mod isolation;
 pub mod liquidate;
 pub mod migrations;
 pub mod query;
 pub mod repay;
 pub mod state;
+pub mod term_loan;
 pub mod user;
 pub mod withdraw;
 
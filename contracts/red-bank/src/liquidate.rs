@@ -12,16 +12,18 @@ use mars_utils::helpers::{build_send_asset_msg, option_string_to_addr};
 
 use crate::{
     error::ContractError,
-    health::get_health_and_positions,
+    health::{applicable_emode, get_health_and_positions, query_user_emode_category},
     helpers::query_asset_params,
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    isolation::update_isolated_debts,
     state::{COLLATERALS, CONFIG, DEBTS, MARKETS},
+    term_loan::convert_term_loans_of_denom,
     user::User,
 };
 
 /// Execute loan liquidations on under-collateralized loans
 pub fn liquidate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     collateral_denom: String,
@@ -82,6 +84,17 @@ pub fn liquidate(
         });
     }
 
+    // term loans in the debt asset become variable debt so that they can be repaid by the liquidator
+    let mut response = convert_term_loans_of_denom(
+        deps.storage,
+        &env,
+        &liquidatee_addr,
+        &debt_denom,
+        rewards_collector_addr,
+        incentives_addr,
+        Response::new(),
+    )?;
+
     // check if user has outstanding debt in the deposited asset that needs to be repayed
     let user_debt = DEBTS
         .may_load(deps.storage, (&liquidatee_addr, &debt_denom))?
@@ -121,13 +134,22 @@ pub fn liquidate(
         .ok_or(ContractError::CannotLiquidateWhenNoDebtBalance {})?
         .asset_price;
 
-    let mut response = Response::new();
-
     let user_debt_amount =
         get_underlying_debt_amount(user_debt.amount_scaled, &debt_market, block_time)?;
 
-    let collateral_params = query_asset_params(&deps.querier, params_addr, &collateral_denom)?;
-    let debt_params = query_asset_params(&deps.querier, params_addr, &debt_denom)?;
+    let mut collateral_params = query_asset_params(&deps.querier, params_addr, &collateral_denom)?;
+    let mut debt_params = query_asset_params(&deps.querier, params_addr, &debt_denom)?;
+
+    // the liquidation amounts are computed with the same e-mode params as the health factor
+    let emode = query_user_emode_category(&deps.as_ref(), &liquidatee_addr, params_addr)?;
+    if let Some(category) = applicable_emode(&assets_positions, emode.as_ref()) {
+        for params in [&mut collateral_params, &mut debt_params] {
+            if category.contains(&params.denom) {
+                params.max_loan_to_value = category.max_loan_to_value;
+                params.liquidation_threshold = category.liquidation_threshold;
+            }
+        }
+    }
 
     let user_collateral_amount = get_underlying_liquidity_amount(
         user_collateral.amount_scaled,
@@ -224,6 +246,15 @@ pub fn liquidate(
     response = update_interest_rates(&env, &mut debt_market_after, response)?;
     MARKETS.save(deps.storage, &debt_denom, &debt_market_after)?;
 
+    // Re-record the isolated debts of the liquidatee and, as it received collateral, of the
+    // recipient, without enforcing the ceilings so that a liquidation is never blocked by them
+    for user_addr in [liquidatee.address(), recipient.address()] {
+        if user_addr == credit_manager_addr {
+            continue;
+        }
+        update_isolated_debts(deps.branch(), &env, user_addr, oracle_addr, params_addr, false)?;
+    }
+
     // 7. Build response
     // refund sent amount in excess of actual debt amount to liquidate
     if !refund_amount.is_zero() {
@@ -2,3 +2,4 @@ pub mod v2_3_0;
 pub mod v2_3_1;
 pub mod v2_3_2;
 pub mod v2_3_3;
+pub mod v2_4_0;
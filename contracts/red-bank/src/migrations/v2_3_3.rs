@@ -3,12 +3,13 @@ use cw2::{assert_contract_version, set_contract_version};
 use mars_types::keys::{UserId, UserIdKey};
 
 use crate::{
-    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    contract::CONTRACT_NAME,
     error::ContractError,
     state::{COLLATERALS, MARKETS},
 };
 
 const FROM_VERSION: &str = "2.3.2";
+const TO_VERSION: &str = "2.3.3";
 
 pub fn migrate(deps: DepsMut, haircut: Decimal, denom: &str) -> Result<Response, ContractError> {
     // Make sure we're migrating the correct contract and from the correct version
@@ -35,13 +36,13 @@ pub fn migrate(deps: DepsMut, haircut: Decimal, denom: &str) -> Result<Response,
 
     COLLATERALS.remove(deps.storage, (&user_id_key, denom));
 
-    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), CONTRACT_VERSION)?;
+    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), TO_VERSION)?;
 
     Ok(Response::new()
         .add_attribute("action", "migrate")
         .add_attribute("from_version", FROM_VERSION)
-        .add_attribute("to_version", CONTRACT_VERSION)
-        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attribute("to_version", TO_VERSION)
+        .add_attribute("to_version", TO_VERSION)
         .add_attribute("haircut_percent", haircut.to_string())
         .add_attribute("haircut_market", denom))
 }
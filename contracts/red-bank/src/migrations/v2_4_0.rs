@@ -0,0 +1,87 @@
+use cosmwasm_std::{Decimal, DepsMut, Order, Response, StdResult, Uint128};
+use cw2::{assert_contract_version, set_contract_version};
+use mars_types::red_bank::{InterestRateModel, Market};
+
+use crate::{
+    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    error::ContractError,
+    state::MARKETS,
+};
+
+const FROM_VERSION: &str = "2.3.3";
+
+/// Copy paste of the state structs from the v2.3.3 of the contract, where the interest rate model
+/// was a two-slope model stored without an enum tag.
+pub mod v2_3_3_state {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Decimal, Uint128};
+    use cw_storage_plus::Map;
+
+    #[cw_serde]
+    pub struct InterestRateModel {
+        pub optimal_utilization_rate: Decimal,
+        pub base: Decimal,
+        pub slope_1: Decimal,
+        pub slope_2: Decimal,
+    }
+
+    #[cw_serde]
+    pub struct Market {
+        pub denom: String,
+        pub reserve_factor: Decimal,
+        pub interest_rate_model: InterestRateModel,
+        pub borrow_index: Decimal,
+        pub liquidity_index: Decimal,
+        pub borrow_rate: Decimal,
+        pub liquidity_rate: Decimal,
+        pub indexes_last_updated: u64,
+        pub collateral_total_scaled: Uint128,
+        pub debt_total_scaled: Uint128,
+    }
+
+    pub const MARKETS: Map<&str, Market> = Map::new("markets");
+}
+
+pub fn migrate(deps: DepsMut) -> Result<Response, ContractError> {
+    // Make sure we're migrating the correct contract and from the correct version
+    assert_contract_version(deps.storage, &format!("crates.io:{CONTRACT_NAME}"), FROM_VERSION)?;
+
+    // Interest rate models are now stored as a tagged enum
+    let markets = v2_3_3_state::MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (denom, market) in markets {
+        MARKETS.save(deps.storage, &denom, &from_v2_3_3_to_v2_4_0_market(market))?;
+    }
+
+    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", FROM_VERSION)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+fn from_v2_3_3_to_v2_4_0_market(value: v2_3_3_state::Market) -> Market {
+    Market {
+        denom: value.denom,
+        reserve_factor: value.reserve_factor,
+        interest_rate_model: InterestRateModel::TwoSlope {
+            optimal_utilization_rate: value.interest_rate_model.optimal_utilization_rate,
+            base: value.interest_rate_model.base,
+            slope_1: value.interest_rate_model.slope_1,
+            slope_2: value.interest_rate_model.slope_2,
+        },
+        borrow_index: value.borrow_index,
+        liquidity_index: value.liquidity_index,
+        borrow_rate: value.borrow_rate,
+        liquidity_rate: value.liquidity_rate,
+        indexes_last_updated: value.indexes_last_updated,
+        collateral_total_scaled: value.collateral_total_scaled,
+        debt_total_scaled: value.debt_total_scaled,
+        term_debt_total: Uint128::zero(),
+        term_interest_per_year: Uint128::zero(),
+        flash_loan_fee_rate: Decimal::zero(),
+        adaptive_rate_state: None,
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, BlockInfo, Decimal, Deps, Env, Order, StdResult, Uint128};
+use cosmwasm_std::{coin, Addr, BlockInfo, Coin, Decimal, Deps, Env, Order, StdResult, Uint128};
 use cw_paginate::{paginate_map_query, paginate_prefix_query, PaginationResponse};
 use cw_storage_plus::Bound;
 use mars_interest_rate::{
@@ -9,16 +9,20 @@ use mars_types::{
     address_provider::{self, MarsAddressType},
     keys::{UserId, UserIdKey},
     red_bank::{
-        Collateral, ConfigResponse, Debt, Market, MarketV2Response,
-        PaginatedUserCollateralResponse, UserCollateralResponse, UserDebtResponse,
-        UserHealthStatus, UserPositionResponse,
+        Collateral, ConfigResponse, CreditDelegationResponse, Debt, Market, MarketV2Response,
+        PaginatedUserCollateralResponse, TermLoanResponse, UserCollateralResponse,
+        UserDebtResponse, UserHealthStatus, UserPositionResponse,
     },
 };
 
 use crate::{
     error::{ContractError, ContractResult},
     health,
-    state::{COLLATERALS, CONFIG, DEBTS, MARKETS, OWNER},
+    isolation::isolated_debt_amount,
+    state::{
+        COLLATERALS, CONFIG, CREDIT_DELEGATIONS, DEBTS, ISOLATED_DEBTS, MARKETS, OWNER,
+        RECEIVED_CREDIT_DELEGATIONS, TERM_LOANS, USER_EMODES,
+    },
 };
 
 const DEFAULT_LIMIT: u32 = 10;
@@ -168,6 +172,97 @@ pub fn query_user_debts(
         .collect()
 }
 
+pub fn query_credit_delegations_granted(
+    deps: Deps,
+    delegator_addr: Addr,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CreditDelegationResponse>> {
+    let start_after = start_after.map(|(delegatee, denom)| (Addr::unchecked(delegatee), denom));
+    let start = start_after
+        .as_ref()
+        .map(|(delegatee_addr, denom)| Bound::exclusive((delegatee_addr, denom.as_str())));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    CREDIT_DELEGATIONS
+        .sub_prefix(&delegator_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let ((delegatee_addr, denom), delegation) = item?;
+            Ok(CreditDelegationResponse {
+                delegator: delegator_addr.to_string(),
+                delegatee: delegatee_addr.to_string(),
+                denom,
+                amount: delegation.amount,
+                expires_at: delegation.expires_at,
+            })
+        })
+        .collect()
+}
+
+pub fn query_credit_delegations_received(
+    deps: Deps,
+    delegatee_addr: Addr,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CreditDelegationResponse>> {
+    let start_after = start_after.map(|(delegator, denom)| (Addr::unchecked(delegator), denom));
+    let start = start_after
+        .as_ref()
+        .map(|(delegator_addr, denom)| Bound::exclusive((delegator_addr, denom.as_str())));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    RECEIVED_CREDIT_DELEGATIONS
+        .sub_prefix(&delegatee_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (delegator_addr, denom) = item?;
+            let delegation = CREDIT_DELEGATIONS
+                .load(deps.storage, (&delegator_addr, &delegatee_addr, &denom))?;
+            Ok(CreditDelegationResponse {
+                delegator: delegator_addr.to_string(),
+                delegatee: delegatee_addr.to_string(),
+                denom,
+                amount: delegation.amount,
+                expires_at: delegation.expires_at,
+            })
+        })
+        .collect()
+}
+
+pub fn query_user_term_loans(
+    deps: Deps,
+    block: &BlockInfo,
+    user_addr: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<TermLoanResponse>, ContractError> {
+    let block_time = block.time.seconds();
+
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    TERM_LOANS
+        .prefix(&user_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, loan) = item?;
+
+            let amount_owed = loan.amount_owed(block_time)?;
+            let matured = block_time >= loan.maturity;
+
+            Ok(TermLoanResponse {
+                loan,
+                amount_owed,
+                matured,
+            })
+        })
+        .collect()
+}
+
 pub fn query_user_collateral(
     deps: Deps,
     block: &BlockInfo,
@@ -290,6 +385,10 @@ pub fn query_underlying_debt_amount(
     Ok(get_underlying_debt_amount(amount_scaled, &market, env.block.time.seconds())?)
 }
 
+pub fn query_user_emode(deps: Deps, user_addr: Addr) -> StdResult<Option<String>> {
+    USER_EMODES.may_load(deps.storage, &user_addr)
+}
+
 pub fn query_user_position(
     deps: Deps,
     env: Env,
@@ -317,7 +416,8 @@ pub fn query_user_position(
         params_addr,
         liquidation_pricing,
     )?;
-    let health = health::compute_position_health(&positions)?;
+    let emode = health::query_user_emode_category(&deps, &user_addr, params_addr)?;
+    let health = health::compute_position_health(&positions, emode.as_ref())?;
 
     let health_status = if let (Some(max_ltv_hf), Some(liq_threshold_hf)) =
         (health.max_ltv_health_factor, health.liquidation_health_factor)
@@ -338,3 +438,16 @@ pub fn query_user_position(
         health_status,
     })
 }
+
+/// Debts backed by the given isolated collateral, with the interest accrued on them
+pub fn query_isolated_debts(deps: Deps, env: Env, denom: String) -> ContractResult<Vec<Coin>> {
+    ISOLATED_DEBTS
+        .prefix(&denom)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (debt_denom, amount_scaled) = item?;
+            let amount = isolated_debt_amount(deps, &env, &debt_denom, amount_scaled)?;
+            Ok(coin(amount.u128(), debt_denom))
+        })
+        .collect()
+}
@@ -6,12 +6,13 @@ use mars_utils::helpers::build_send_asset_msg;
 use crate::{
     error::ContractError,
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    isolation::update_isolated_debts,
     state::{CONFIG, DEBTS, MARKETS},
     user::User,
 };
 
 pub fn repay(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     on_behalf_of: Option<String>,
@@ -24,13 +25,17 @@ pub fn repay(
         deps.as_ref(),
         &config.address_provider,
         vec![
+            MarsAddressType::Oracle,
             MarsAddressType::Incentives,
             MarsAddressType::RewardsCollector,
+            MarsAddressType::Params,
             MarsAddressType::CreditManager,
         ],
     )?;
     let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
     let incentives_addr = &addresses[&MarsAddressType::Incentives];
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
     let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
 
     let user_addr: Addr;
@@ -91,6 +96,17 @@ pub fn repay(
     response = update_interest_rates(&env, &mut market, response)?;
     MARKETS.save(deps.storage, &denom, &market)?;
 
+    if user.address() != credit_manager_addr {
+        update_isolated_debts(
+            deps.branch(),
+            &env,
+            user.address(),
+            oracle_addr,
+            params_addr,
+            false,
+        )?;
+    }
+
     Ok(response
         .add_attribute("action", "repay")
         .add_attribute("sender", &info.sender)
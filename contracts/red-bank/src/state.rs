@@ -1,9 +1,10 @@
-use cosmwasm_std::Addr;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 use mars_owner::Owner;
 use mars_types::{
     keys::UserIdKey,
-    red_bank::{Collateral, Config, Debt, Market},
+    red_bank::{Collateral, Config, CreditDelegation, Debt, Market, TermLoan},
 };
 
 pub const OWNER: Owner = Owner::new("owner");
@@ -11,3 +12,29 @@ pub const CONFIG: Item<Config<Addr>> = Item::new("config");
 pub const MARKETS: Map<&str, Market> = Map::new("markets");
 pub const COLLATERALS: Map<(&UserIdKey, &str), Collateral> = Map::new("colls");
 pub const DEBTS: Map<(&Addr, &str), Debt> = Map::new("debts");
+pub const TERM_LOANS: Map<(&Addr, u64), TermLoan> = Map::new("term_loans");
+pub const NEXT_TERM_LOAN_ID: Item<u64> = Item::new("next_term_loan_id");
+/// Credit delegations by (delegator, delegatee, denom)
+pub const CREDIT_DELEGATIONS: Map<(&Addr, &Addr, &str), CreditDelegation> =
+    Map::new("credit_delegations");
+/// Reverse lookup of the credit delegations by (delegatee, delegator, denom)
+pub const RECEIVED_CREDIT_DELEGATIONS: Map<(&Addr, &Addr, &str), Empty> =
+    Map::new("received_credit_delegations");
+/// Name of the e-mode category each user opted into
+pub const USER_EMODES: Map<&Addr, String> = Map::new("user_emodes");
+/// Scaled debt amounts backed by isolated collaterals by (isolated collateral denom, debt denom)
+pub const ISOLATED_DEBTS: Map<(&str, &str), Uint128> = Map::new("isolated_debts");
+/// Scaled debt amounts of each user backed by isolated collaterals by (user, isolated collateral
+/// denom, debt denom)
+pub const USER_ISOLATED_DEBTS: Map<(&Addr, &str, &str), Uint128> = Map::new("user_isolated_debts");
+
+/// Flash loan in progress, from sending the coins to the receiver until its repayment is asserted
+#[cw_serde]
+pub struct FlashLoanState {
+    pub receiver: Addr,
+    pub coins: Vec<Coin>,
+    pub fees: Vec<Coin>,
+    pub repaid: Vec<Coin>,
+}
+
+pub const FLASH_LOAN: Item<FlashLoanState> = Item::new("flash_loan");
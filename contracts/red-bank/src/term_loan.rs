@@ -0,0 +1,401 @@
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use mars_interest_rate::{
+    get_scaled_debt_amount, get_underlying_debt_amount, get_underlying_liquidity_amount,
+};
+use mars_types::{
+    address_provider::{self, MarsAddressType},
+    red_bank::{Market, TermLoan},
+};
+use mars_utils::helpers::build_send_asset_msg;
+
+use crate::{
+    error::ContractError,
+    health::assert_below_max_ltv_after_borrow,
+    helpers::query_asset_params,
+    interest_rates::{apply_accumulated_interests, distribute_fee, update_interest_rates},
+    isolation::update_isolated_debts,
+    state::{CONFIG, MARKETS, NEXT_TERM_LOAN_ID, TERM_LOANS},
+    user::User,
+};
+
+/// Borrow at a fixed rate for one of the terms offered by the market and send the borrowed funds
+pub fn borrow_term_loan(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+    duration: u64,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![
+            MarsAddressType::Oracle,
+            MarsAddressType::Incentives,
+            MarsAddressType::RewardsCollector,
+            MarsAddressType::Params,
+            MarsAddressType::CreditManager,
+        ],
+    )?;
+    let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
+    let incentives_addr = &addresses[&MarsAddressType::Incentives];
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+    let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
+
+    // Credit manager debt is uncollateralized in the Red Bank, so it can't be fixed to a term
+    if info.sender == credit_manager_addr {
+        return Err(ContractError::CannotBorrowTermLoanAsCreditManager {});
+    }
+
+    let asset_params = query_asset_params(&deps.querier, params_addr, &denom)?;
+
+    if !asset_params.red_bank.borrow_enabled {
+        return Err(ContractError::BorrowNotEnabled {
+            denom,
+        });
+    }
+
+    let term = asset_params
+        .term_loans
+        .as_ref()
+        .ok_or_else(|| ContractError::TermLoansNotEnabled {
+            denom: denom.clone(),
+        })?
+        .term(duration)
+        .ok_or_else(|| ContractError::InvalidLoanTerm {
+            denom: denom.clone(),
+            duration,
+        })?;
+
+    let mut market = MARKETS.load(deps.storage, &denom)?;
+
+    let current_timestamp = env.block.time.seconds();
+    let collateral_balance_before = get_underlying_liquidity_amount(
+        market.collateral_total_scaled,
+        &market,
+        current_timestamp,
+    )?;
+    let debt_balance_before =
+        get_underlying_debt_amount(market.debt_total_scaled, &market, current_timestamp)?
+            .checked_add(market.term_debt_total)?;
+
+    // Cannot borrow zero amount or more than available liquidity
+    let available_liquidity = collateral_balance_before.checked_sub(debt_balance_before)?;
+    if amount.is_zero() || amount > available_liquidity {
+        return Err(ContractError::InvalidBorrowAmount {
+            denom,
+        });
+    }
+
+    // Cannot borrow more than the borrow cap of the market
+    if debt_balance_before.checked_add(amount)? > asset_params.borrow_cap {
+        return Err(ContractError::BorrowCapExceeded {
+            denom,
+        });
+    }
+
+    if !assert_below_max_ltv_after_borrow(
+        &deps.as_ref(),
+        &env,
+        &info.sender,
+        "",
+        oracle_addr,
+        params_addr,
+        &denom,
+        amount,
+    )? {
+        return Err(ContractError::BorrowAmountExceedsGivenCollateral {});
+    }
+
+    let mut response = Response::new();
+
+    response = apply_accumulated_interests(
+        deps.storage,
+        &env,
+        &mut market,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    // The rate is fixed from the borrow rate including the loan, so that splitting a loan in
+    // several parts doesn't get a lower rate
+    market.term_debt_total = market.term_debt_total.checked_add(amount)?;
+    update_interest_rates(&env, &mut market, Response::new())?;
+
+    let loan_id = NEXT_TERM_LOAN_ID.may_load(deps.storage)?.unwrap_or(1);
+    NEXT_TERM_LOAN_ID.save(deps.storage, &(loan_id + 1))?;
+    let loan = TermLoan {
+        id: loan_id,
+        denom: denom.clone(),
+        principal: amount,
+        rate: market.borrow_rate.checked_add(term.rate_premium)?,
+        opened_at: current_timestamp,
+        maturity: current_timestamp + duration,
+    };
+    TERM_LOANS.save(deps.storage, (&info.sender, loan_id), &loan)?;
+
+    // Depositors earn the fixed interest of the loan from now on
+    market.term_interest_per_year =
+        market.term_interest_per_year.checked_add(loan.yearly_interest()?)?;
+    response = update_interest_rates(&env, &mut market, response)?;
+    MARKETS.save(deps.storage, &denom, &market)?;
+
+    update_isolated_debts(deps.branch(), &env, &info.sender, oracle_addr, params_addr, true)?;
+
+    let recipient_addr = if let Some(recipient) = recipient {
+        deps.api.addr_validate(&recipient)?
+    } else {
+        info.sender.clone()
+    };
+
+    Ok(response
+        .add_message(build_send_asset_msg(&recipient_addr, &denom, amount))
+        .add_attribute("action", "borrow_term_loan")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient_addr)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount)
+        .add_attribute("loan_id", loan_id.to_string())
+        .add_attribute("rate", loan.rate.to_string())
+        .add_attribute("maturity", loan.maturity.to_string()))
+}
+
+/// Repay a term loan of the sender in full, with the early repay penalty if it hasn't matured
+pub fn repay_term_loan(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    loan_id: u64,
+) -> Result<Response, ContractError> {
+    let loan = TERM_LOANS.may_load(deps.storage, (&info.sender, loan_id))?.ok_or(
+        ContractError::TermLoanNotFound {
+            loan_id,
+        },
+    )?;
+
+    let sent_amount = cw_utils::must_pay(&info, &loan.denom)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![
+            MarsAddressType::Oracle,
+            MarsAddressType::Incentives,
+            MarsAddressType::RewardsCollector,
+            MarsAddressType::Params,
+        ],
+    )?;
+    let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
+    let incentives_addr = &addresses[&MarsAddressType::Incentives];
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+
+    let current_timestamp = env.block.time.seconds();
+    let amount_owed = loan.amount_owed(current_timestamp)?;
+    let penalty = if current_timestamp < loan.maturity {
+        let early_repay_penalty = query_asset_params(&deps.querier, params_addr, &loan.denom)?
+            .term_loans
+            .map(|term_loans| term_loans.early_repay_penalty)
+            .unwrap_or_default();
+        amount_owed.checked_mul_ceil(early_repay_penalty)?
+    } else {
+        Uint128::zero()
+    };
+
+    let expected = amount_owed.checked_add(penalty)?;
+    if sent_amount < expected {
+        return Err(ContractError::TermLoanNotFullyRepaid {
+            expected,
+            sent: sent_amount,
+        });
+    }
+
+    let mut response = Response::new();
+
+    // Refund any excess amount
+    let refund_amount = sent_amount - expected;
+    if !refund_amount.is_zero() {
+        response =
+            response.add_message(build_send_asset_msg(&info.sender, &loan.denom, refund_amount));
+    }
+
+    let mut market = MARKETS.load(deps.storage, &loan.denom)?;
+
+    response = apply_accumulated_interests(
+        deps.storage,
+        &env,
+        &mut market,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    remove_from_term_debt(&mut market, &loan, amount_owed)?;
+
+    // The interest has already accrued to the depositors, the penalty is paid to the market like
+    // flash loan fees
+    response = distribute_fee(
+        deps.storage,
+        &env,
+        &mut market,
+        penalty,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    response = update_interest_rates(&env, &mut market, response)?;
+    MARKETS.save(deps.storage, &loan.denom, &market)?;
+
+    TERM_LOANS.remove(deps.storage, (&info.sender, loan_id));
+
+    update_isolated_debts(deps.branch(), &env, &info.sender, oracle_addr, params_addr, false)?;
+
+    Ok(response
+        .add_attribute("action", "repay_term_loan")
+        .add_attribute("sender", info.sender)
+        .add_attribute("loan_id", loan_id.to_string())
+        .add_attribute("denom", loan.denom)
+        .add_attribute("amount", expected)
+        .add_attribute("penalty", penalty))
+}
+
+/// Convert a matured term loan of the user into variable debt
+pub fn convert_term_loan(
+    deps: DepsMut,
+    env: Env,
+    user_addr: Addr,
+    loan_id: u64,
+) -> Result<Response, ContractError> {
+    let loan = TERM_LOANS.may_load(deps.storage, (&user_addr, loan_id))?.ok_or(
+        ContractError::TermLoanNotFound {
+            loan_id,
+        },
+    )?;
+
+    if env.block.time.seconds() < loan.maturity {
+        return Err(ContractError::TermLoanNotMatured {
+            loan_id,
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![MarsAddressType::Incentives, MarsAddressType::RewardsCollector],
+    )?;
+    let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
+    let incentives_addr = &addresses[&MarsAddressType::Incentives];
+
+    let response = convert_to_variable_debt(
+        deps.storage,
+        &env,
+        &user_addr,
+        &loan,
+        rewards_collector_addr,
+        incentives_addr,
+        Response::new(),
+    )?;
+
+    Ok(response
+        .add_attribute("action", "convert_term_loan")
+        .add_attribute("user", user_addr)
+        .add_attribute("loan_id", loan_id.to_string()))
+}
+
+/// Convert all term loans of the user in the given denom into variable debt, regardless of their
+/// maturity. Used before liquidating the debt of the user.
+pub fn convert_term_loans_of_denom(
+    store: &mut dyn Storage,
+    env: &Env,
+    user_addr: &Addr,
+    denom: &str,
+    rewards_collector_addr: &Addr,
+    incentives_addr: &Addr,
+    mut response: Response,
+) -> Result<Response, ContractError> {
+    let loans = TERM_LOANS
+        .prefix(user_addr)
+        .range(store, None, None, cosmwasm_std::Order::Ascending)
+        .map(|res| Ok(res?.1))
+        .filter(|res: &Result<TermLoan, ContractError>| {
+            res.as_ref().map_or(true, |loan| loan.denom == denom)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    for loan in loans {
+        response = convert_to_variable_debt(
+            store,
+            env,
+            user_addr,
+            &loan,
+            rewards_collector_addr,
+            incentives_addr,
+            response,
+        )?;
+    }
+
+    Ok(response)
+}
+
+/// Replace a term loan by variable debt of the amount owed. The accrued interest has already
+/// accrued to the depositors, it is now owed as variable debt.
+fn convert_to_variable_debt(
+    store: &mut dyn Storage,
+    env: &Env,
+    user_addr: &Addr,
+    loan: &TermLoan,
+    rewards_collector_addr: &Addr,
+    incentives_addr: &Addr,
+    mut response: Response,
+) -> Result<Response, ContractError> {
+    let current_timestamp = env.block.time.seconds();
+
+    let mut market = MARKETS.load(store, &loan.denom)?;
+
+    response = apply_accumulated_interests(
+        store,
+        env,
+        &mut market,
+        rewards_collector_addr,
+        incentives_addr,
+        response,
+    )?;
+
+    let amount_owed = loan.amount_owed(current_timestamp)?;
+    let debt_amount_scaled = get_scaled_debt_amount(amount_owed, &market, current_timestamp)?;
+    remove_from_term_debt(&mut market, loan, amount_owed)?;
+    market.increase_debt(debt_amount_scaled)?;
+    User(user_addr).increase_debt(store, &loan.denom, debt_amount_scaled, false)?;
+
+    response = update_interest_rates(env, &mut market, response)?;
+    MARKETS.save(store, &loan.denom, &market)?;
+
+    TERM_LOANS.remove(store, (user_addr, loan.id));
+
+    Ok(response.add_attribute("converted_loan_id", loan.id.to_string()))
+}
+
+/// Remove a repaid or converted loan from the term debt of the market. The interest accrued by
+/// the market is rounded down while the one owed by each loan is rounded up, so the term debt
+/// is saturated at zero instead of underflowing.
+fn remove_from_term_debt(
+    market: &mut Market,
+    loan: &TermLoan,
+    amount_owed: Uint128,
+) -> Result<(), ContractError> {
+    market.term_interest_per_year =
+        market.term_interest_per_year.checked_sub(loan.yearly_interest()?)?;
+    market.term_debt_total = market.term_debt_total.saturating_sub(amount_owed);
+    Ok(())
+}
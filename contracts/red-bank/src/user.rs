@@ -8,7 +8,7 @@ use mars_types::{
     red_bank::{Collateral, Debt, Market},
 };
 
-use crate::state::{COLLATERALS, DEBTS};
+use crate::state::{COLLATERALS, DEBTS, TERM_LOANS};
 
 /// A helper class providing an intuitive API for managing user positions in the contract store.
 ///
@@ -79,9 +79,10 @@ impl<'a> User<'a> {
     /// the user is not borrowing any asset.
     ///
     /// The user is borrowing if, in the `DEBTS` map, there is at least one denom stored under the
-    /// user address prefix.
+    /// user address prefix, or if the user has a term loan.
     pub fn is_borrowing(&self, store: &dyn Storage) -> bool {
         DEBTS.prefix(self.0).range(store, None, None, Order::Ascending).next().is_some()
+            || TERM_LOANS.prefix(self.0).range(store, None, None, Order::Ascending).next().is_some()
     }
 
     /// Increase a user's collateral shares by the specified amount. Returns a message to inform the
@@ -8,12 +8,13 @@ use crate::{
     health::assert_below_liq_threshold_after_withdraw,
     helpers::query_asset_params,
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    isolation::update_isolated_debts,
     state::{CONFIG, MARKETS},
     user::User,
 };
 
 pub fn withdraw(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     denom: String,
@@ -138,7 +139,7 @@ pub fn withdraw(
         withdraw_amount_scaled,
         incentives_addr,
         response,
-        account_id,
+        account_id.clone(),
     )?;
 
     market.decrease_collateral(withdraw_amount_scaled)?;
@@ -147,6 +148,18 @@ pub fn withdraw(
 
     MARKETS.save(deps.storage, &denom, &market)?;
 
+    // Credit manager accounts are accounted for in the credit manager
+    if account_id.is_none() {
+        update_isolated_debts(
+            deps.branch(),
+            &env,
+            withdrawer.address(),
+            oracle_addr,
+            params_addr,
+            true,
+        )?;
+    }
+
     // send underlying asset to user or another recipient
     let recipient_addr = if let Some(recipient) = recipient {
         deps.api.addr_validate(&recipient)?
@@ -144,14 +144,19 @@ pub fn th_default_asset_params() -> AssetParams {
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         deposit_cap: Uint128::MAX,
+        borrow_cap: Uint128::MAX,
         close_factor: Decimal::percent(80u64),
         reserve_factor: Decimal::percent(10u64),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(80u64),
             base: Decimal::zero(),
             slope_1: Decimal::percent(7u64),
             slope_2: Decimal::percent(45u64),
         },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
 
@@ -247,7 +252,7 @@ pub fn th_get_expected_indices_and_rates(
 
     // interest rates (make a copy and update those values to get the expeted irs)
     let mut market_copy = market.clone();
-    market_copy.update_interest_rates(expected_utilization_rate).unwrap();
+    market_copy.update_interest_rates(expected_utilization_rate, block_time).unwrap();
 
     TestInterestResults {
         borrow_index: expected_indices.borrow,
@@ -1,9 +1,12 @@
 mod helpers;
 
 mod test_admin;
+mod test_bad_debt;
 mod test_borrow;
 mod test_credit_accounts;
+mod test_credit_delegation;
 mod test_deposit;
+mod test_flash_loan;
 mod test_health;
 mod test_inflated_collateral;
 mod test_liquidate;
@@ -11,5 +14,6 @@ mod test_migration_v2;
 mod test_misc;
 mod test_payment;
 mod test_query;
+mod test_term_loan;
 mod test_update_owner;
 mod test_withdraw;
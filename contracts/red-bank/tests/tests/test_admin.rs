@@ -134,7 +134,7 @@ fn init_asset() {
     let info = mock_info("owner", &[]);
     instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    let ir_model = InterestRateModel {
+    let ir_model = InterestRateModel::TwoSlope {
         optimal_utilization_rate: Decimal::one(),
         base: Decimal::percent(5),
         slope_1: Decimal::zero(),
@@ -145,6 +145,7 @@ fn init_asset() {
         denom: "someasset".to_string(),
         reserve_factor: Some(Decimal::from_ratio(1u128, 100u128)),
         interest_rate_model: Some(ir_model.clone()),
+        flash_loan_fee_rate: None,
     };
 
     // non owner is not authorized
@@ -221,6 +222,7 @@ fn init_asset() {
             denom: "someasset".to_string(),
             reserve_factor: None,
             interest_rate_model: None,
+            flash_loan_fee_rate: None,
         };
         let msg = ExecuteMsg::UpdateMarketParams(MarketParamsUpdate::AddOrUpdate {
             params: empty_asset_params,
@@ -257,7 +259,7 @@ fn init_asset() {
     {
         let invalid_asset_params = MarketParams {
             denom: "someasset".to_string(),
-            interest_rate_model: Some(InterestRateModel {
+            interest_rate_model: Some(InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(110),
                 ..ir_model
             }),
@@ -282,7 +284,7 @@ fn init_asset() {
     // init asset where slope_1 >= slope_2
     {
         let invalid_asset_params = MarketParams {
-            interest_rate_model: Some(InterestRateModel {
+            interest_rate_model: Some(InterestRateModel::TwoSlope {
                 slope_1: Decimal::percent(10),
                 slope_2: Decimal::percent(10),
                 ..ir_model
@@ -354,7 +356,7 @@ fn update_asset() {
     let info = mock_info("owner", &[]);
     instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    let ir_model = InterestRateModel {
+    let ir_model = InterestRateModel::TwoSlope {
         optimal_utilization_rate: Decimal::one(),
         base: Decimal::percent(5),
         slope_1: Decimal::zero(),
@@ -365,6 +367,7 @@ fn update_asset() {
         denom: "someasset".to_string(),
         reserve_factor: Some(Decimal::from_ratio(1u128, 100u128)),
         interest_rate_model: Some(ir_model.clone()),
+        flash_loan_fee_rate: None,
     };
 
     // owner of the contract is not authorized
@@ -400,7 +403,7 @@ fn update_asset() {
     {
         let invalid_asset_params = MarketParams {
             denom: "someasset".to_string(),
-            interest_rate_model: Some(InterestRateModel {
+            interest_rate_model: Some(InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(110),
                 ..ir_model
             }),
@@ -428,6 +431,7 @@ fn update_asset() {
             denom: "someasset".to_string(),
             reserve_factor: Some(Decimal::from_ratio(10u128, 100u128)),
             interest_rate_model: Some(ir_model),
+            flash_loan_fee_rate: None,
         };
         let msg = ExecuteMsg::UpdateMarketParams(MarketParamsUpdate::AddOrUpdate {
             params: params.clone(),
@@ -454,6 +458,7 @@ fn update_asset() {
             denom: "someasset".to_string(),
             reserve_factor: None,
             interest_rate_model: None,
+            flash_loan_fee_rate: None,
         };
         let msg = ExecuteMsg::UpdateMarketParams(MarketParamsUpdate::AddOrUpdate {
             params: empty_asset_params,
@@ -487,7 +492,7 @@ fn update_asset_with_new_interest_rate_model_params() {
     let env = mock_env(MockEnvParams::default());
     instantiate(deps.as_mut(), env, info, msg).unwrap();
 
-    let ir_model = InterestRateModel {
+    let ir_model = InterestRateModel::TwoSlope {
         optimal_utilization_rate: Decimal::one(),
         base: Decimal::percent(5),
         slope_1: Decimal::zero(),
@@ -498,6 +503,7 @@ fn update_asset_with_new_interest_rate_model_params() {
         denom: "someasset".to_string(),
         reserve_factor: Some(Decimal::from_ratio(2u128, 100u128)),
         interest_rate_model: Some(ir_model.clone()),
+        flash_loan_fee_rate: None,
     };
 
     let msg = ExecuteMsg::UpdateMarketParams(MarketParamsUpdate::AddOrUpdate {
@@ -512,7 +518,7 @@ fn update_asset_with_new_interest_rate_model_params() {
     assert_eq!(market_before.interest_rate_model, ir_model);
 
     // new IR model has a fixed borrow rate of 69%
-    let new_ir_model = InterestRateModel {
+    let new_ir_model = InterestRateModel::TwoSlope {
         base: Decimal::percent(69),
         ..ir_model
     };
@@ -568,7 +574,7 @@ fn update_asset_new_reserve_factor_accrues_interest_rate() {
 
     let reserve_factor = Decimal::from_ratio(1_u128, 10_u128);
 
-    let ir_model = InterestRateModel {
+    let ir_model = InterestRateModel::TwoSlope {
         optimal_utilization_rate: Decimal::from_ratio(80u128, 100u128),
         base: Decimal::zero(),
         slope_1: Decimal::from_ratio(1_u128, 2_u128),
@@ -610,6 +616,7 @@ fn update_asset_new_reserve_factor_accrues_interest_rate() {
         denom: "somecoin".to_string(),
         reserve_factor: Some(Decimal::from_ratio(2_u128, 10_u128)),
         interest_rate_model: None,
+        flash_loan_fee_rate: None,
     };
     let msg = ExecuteMsg::UpdateMarketParams(MarketParamsUpdate::AddOrUpdate {
         params,
@@ -0,0 +1,141 @@
+use cosmwasm_std::{
+    coin,
+    testing::{mock_info, MockApi, MockStorage},
+    Addr, Decimal, Event, OwnedDeps, Uint128,
+};
+use mars_interest_rate::SCALING_FACTOR;
+use mars_red_bank::{
+    contract::execute,
+    error::ContractError,
+    state::{DEBTS, MARKETS},
+};
+use mars_testing::{mock_env_at_block_time, MarsMockQuerier};
+use mars_types::{
+    error::MarsError,
+    red_bank::{ExecuteMsg, Market},
+};
+
+use super::helpers::{
+    has_collateral_position, set_collateral, set_debt, th_default_asset_params, th_init_market,
+    th_setup,
+};
+
+const BLOCK_TIME: u64 = 10_000_000;
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
+    let mut deps = th_setup(&[coin(1_000_000, "uosmo")]);
+
+    let market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+        debt_total_scaled: Uint128::new(100_000) * SCALING_FACTOR,
+        indexes_last_updated: BLOCK_TIME,
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uosmo", &market);
+    deps.querier.set_redbank_params("uosmo", th_default_asset_params());
+
+    // reserves accumulated by the rewards collector
+    set_collateral(
+        deps.as_mut(),
+        &Addr::unchecked("rewards_collector"),
+        "uosmo",
+        Uint128::new(30_000) * SCALING_FACTOR,
+        false,
+    );
+
+    set_debt(
+        deps.as_mut(),
+        &Addr::unchecked("user"),
+        "uosmo",
+        Uint128::new(100_000) * SCALING_FACTOR,
+        false,
+    );
+
+    deps
+}
+
+fn write_off_msg() -> ExecuteMsg {
+    ExecuteMsg::WriteOffBadDebt {
+        user: "user".to_string(),
+        denom: "uosmo".to_string(),
+    }
+}
+
+#[test]
+fn write_off_bad_debt_with_reserves_then_depositors() {
+    let mut deps = setup();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("keeper", &[]),
+        write_off_msg(),
+    )
+    .unwrap();
+    assert!(res.events.contains(
+        &Event::new("bad_debt_written_off")
+            .add_attribute("denom", "uosmo")
+            .add_attribute("loss", "100000")
+            .add_attribute("covered_by_reserves", "30000")
+            .add_attribute("socialized", "70000")
+    ));
+
+    assert!(!DEBTS.has(deps.as_ref().storage, (&Addr::unchecked("user"), "uosmo")));
+    assert!(!has_collateral_position(
+        deps.as_ref(),
+        &Addr::unchecked("rewards_collector"),
+        "uosmo"
+    ));
+
+    // the remaining loss is taken by the depositors
+    let market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    assert!(market.debt_total_scaled.is_zero());
+    assert_eq!(market.collateral_total_scaled, Uint128::new(970_000) * SCALING_FACTOR);
+    assert_eq!(market.liquidity_index, Decimal::from_ratio(900_000u128, 970_000u128));
+}
+
+#[test]
+fn cannot_write_off_debt_of_user_with_collateral() {
+    let mut deps = setup();
+    set_collateral(deps.as_mut(), &Addr::unchecked("user"), "uosmo", Uint128::one(), false);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("keeper", &[]),
+        write_off_msg(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotWriteOffDebtWithCollateral {});
+}
+
+#[test]
+fn only_credit_manager_can_write_off_its_debt() {
+    let mut deps = setup();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("keeper", &[]),
+        ExecuteMsg::WriteOffBadDebt {
+            user: "credit_manager".to_string(),
+            denom: "uosmo".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotWriteOffCreditManagerDebt {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("keeper", &[]),
+        ExecuteMsg::WriteOffCreditManagerBadDebt {
+            denom: "uosmo".to_string(),
+            amount: Uint128::new(100),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Mars(MarsError::Unauthorized {}));
+}
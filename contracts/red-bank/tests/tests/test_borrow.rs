@@ -106,6 +106,7 @@ fn borrow_and_repay() {
         denom: "uosmo".to_string(),
         amount: borrow_amount,
         recipient: None,
+        on_behalf_of: None,
     };
 
     let env = mock_env_at_block_time(block_time);
@@ -173,6 +174,7 @@ fn borrow_and_repay() {
         denom: "uosmo".to_string(),
         amount: borrow_amount,
         recipient: None,
+        on_behalf_of: None,
     };
 
     let env = mock_env_at_block_time(block_time);
@@ -223,6 +225,7 @@ fn borrow_and_repay() {
         denom: String::from("uusd"),
         amount: borrow_amount,
         recipient: None,
+        on_behalf_of: None,
     };
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -285,6 +288,7 @@ fn borrow_and_repay() {
         denom: String::from("uusd"),
         amount: Uint128::from(83968_u128),
         recipient: None,
+        on_behalf_of: None,
     };
     let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
     assert_eq!(error_res, ContractError::BorrowAmountExceedsGivenCollateral {});
@@ -538,6 +542,7 @@ fn repay_without_refund_on_behalf_of() {
         denom: String::from("borrowedcoinnative"),
         amount: Uint128::from(borrow_amount),
         recipient: None,
+        on_behalf_of: None,
     };
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -633,6 +638,7 @@ fn repay_with_refund_on_behalf_of() {
         denom: String::from("borrowedcoinnative"),
         amount: Uint128::from(borrow_amount),
         recipient: None,
+        on_behalf_of: None,
     };
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -750,6 +756,7 @@ fn borrow_uusd() {
         denom: "uusd".to_string(),
         amount: max_to_borrow + Uint128::from(1u128),
         recipient: None,
+        on_behalf_of: None,
     };
     let env = mock_env_at_block_time(new_block_time);
     let info = mock_info("borrower", &[]);
@@ -761,6 +768,7 @@ fn borrow_uusd() {
         denom: "uusd".to_string(),
         amount: valid_amount,
         recipient: None,
+        on_behalf_of: None,
     };
     let env = mock_env_at_block_time(block_time);
     let info = mock_info("borrower", &[]);
@@ -830,6 +838,7 @@ fn borrow_full_liquidity_and_then_repay() {
             denom: "uusd".to_string(),
             amount: initial_liquidity.into(),
             recipient: None,
+            on_behalf_of: None,
         };
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -972,6 +981,7 @@ fn borrow_collateral_check() {
         denom: "uosmo".to_string(),
         amount: exceeding_borrow_amount,
         recipient: None,
+        on_behalf_of: None,
     };
     let env = mock_env(MockEnvParams::default());
     let info = mock_info("borrower", &[]);
@@ -983,6 +993,7 @@ fn borrow_collateral_check() {
         denom: "uosmo".to_string(),
         amount: permissible_borrow_amount,
         recipient: None,
+        on_behalf_of: None,
     };
     execute(deps.as_mut(), env, info, borrow_msg).unwrap();
 }
@@ -1017,6 +1028,7 @@ fn cannot_borrow_if_market_not_enabled() {
         denom: "somecoin".to_string(),
         amount: Uint128::new(1000),
         recipient: None,
+        on_behalf_of: None,
     };
     let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
     assert_eq!(
@@ -1027,6 +1039,54 @@ fn cannot_borrow_if_market_not_enabled() {
     );
 }
 
+#[test]
+fn cannot_borrow_above_borrow_cap() {
+    let mut deps = th_setup(&[coin(1_000_000, "somecoin")]);
+
+    let market = Market {
+        borrow_index: Decimal::one(),
+        liquidity_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+        debt_total_scaled: Uint128::new(900) * SCALING_FACTOR,
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "somecoin", &market);
+
+    deps.querier.set_redbank_params(
+        "somecoin",
+        AssetParams {
+            borrow_cap: Uint128::new(1000),
+            ..th_default_asset_params()
+        },
+    );
+
+    let env = mock_env(MockEnvParams::default());
+    let info = cosmwasm_std::testing::mock_info("borrower", &[]);
+    let msg = ExecuteMsg::Borrow {
+        denom: "somecoin".to_string(),
+        amount: Uint128::new(101),
+        recipient: None,
+        on_behalf_of: None,
+    };
+    let error_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        error_res,
+        ContractError::BorrowCapExceeded {
+            denom: "somecoin".to_string()
+        }
+    );
+
+    // Borrowing up to the cap passes the cap check and fails on the missing collateral
+    let msg = ExecuteMsg::Borrow {
+        denom: "somecoin".to_string(),
+        amount: Uint128::new(100),
+        recipient: None,
+        on_behalf_of: None,
+    };
+    let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(error_res, ContractError::BorrowAmountExceedsGivenCollateral {});
+}
+
 #[test]
 fn borrow_and_send_funds_to_another_user() {
     let initial_liquidity = 10000000;
@@ -1061,6 +1121,7 @@ fn borrow_and_send_funds_to_another_user() {
         denom: "uusd".to_string(),
         amount: borrow_amount,
         recipient: Some(another_user_addr.to_string()),
+        on_behalf_of: None,
     };
     let env = mock_env(MockEnvParams::default());
     let info = mock_info("borrower", &[]);
@@ -0,0 +1,261 @@
+use cosmwasm_std::{
+    coin, coins, from_json,
+    testing::{mock_info, MockApi, MockStorage},
+    Addr, BankMsg, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128,
+};
+use mars_interest_rate::SCALING_FACTOR;
+use mars_red_bank::{
+    contract::{execute, query},
+    error::ContractError,
+    state::DEBTS,
+};
+use mars_testing::{mock_env_at_block_time, MarsMockQuerier};
+use mars_types::{
+    params::AssetParams,
+    red_bank::{CreditDelegationResponse, ExecuteMsg, Market, QueryMsg},
+};
+
+use super::helpers::{set_collateral, th_default_asset_params, th_init_market, th_setup};
+
+const BLOCK_TIME: u64 = 10_000_000;
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
+    let mut deps = th_setup(&[coin(1_000_000, "uosmo")]);
+
+    deps.querier.set_oracle_price("uatom", Decimal::one());
+    deps.querier.set_oracle_price("uosmo", Decimal::one());
+
+    let market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+        indexes_last_updated: BLOCK_TIME,
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uosmo", &market);
+    th_init_market(deps.as_mut(), "uatom", &market);
+
+    deps.querier.set_redbank_params("uosmo", th_default_asset_params());
+    deps.querier.set_redbank_params(
+        "uatom",
+        AssetParams {
+            max_loan_to_value: Decimal::percent(50),
+            ..th_default_asset_params()
+        },
+    );
+
+    // only the treasury has collateral, the desk borrows against it
+    set_collateral(
+        deps.as_mut(),
+        &Addr::unchecked("treasury"),
+        "uatom",
+        Uint128::new(1_000_000) * SCALING_FACTOR,
+        true,
+    );
+
+    deps
+}
+
+fn approve(deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>, expires_at: Option<u64>) {
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("treasury", &[]),
+        ExecuteMsg::ApproveCreditDelegation {
+            delegatee: "desk".to_string(),
+            denom: "uosmo".to_string(),
+            amount: Uint128::new(100_000),
+            expires_at,
+        },
+    )
+    .unwrap();
+}
+
+fn borrow_on_behalf_of_msg(amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Borrow {
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(amount),
+        recipient: None,
+        on_behalf_of: Some("treasury".to_string()),
+    }
+}
+
+fn query_granted(
+    deps: &OwnedDeps<MockStorage, MockApi, MarsMockQuerier>,
+) -> Vec<CreditDelegationResponse> {
+    let res = query(
+        deps.as_ref(),
+        mock_env_at_block_time(BLOCK_TIME),
+        QueryMsg::CreditDelegationsGranted {
+            delegator: "treasury".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    from_json(res).unwrap()
+}
+
+#[test]
+fn borrow_with_credit_delegation() {
+    let mut deps = setup();
+    approve(&mut deps, None);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("desk", &[]),
+        borrow_on_behalf_of_msg(40_000),
+    )
+    .unwrap();
+
+    // funds are sent to the delegatee, debt is charged to the delegator
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "desk".to_string(),
+            amount: coins(40_000, "uosmo"),
+        }))]
+    );
+    assert!(DEBTS.has(deps.as_ref().storage, (&Addr::unchecked("treasury"), "uosmo")));
+    assert!(!DEBTS.has(deps.as_ref().storage, (&Addr::unchecked("desk"), "uosmo")));
+
+    let delegation = CreditDelegationResponse {
+        delegator: "treasury".to_string(),
+        delegatee: "desk".to_string(),
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(60_000),
+        expires_at: None,
+    };
+    assert_eq!(query_granted(&deps), vec![delegation.clone()]);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env_at_block_time(BLOCK_TIME),
+        QueryMsg::CreditDelegationsReceived {
+            delegatee: "desk".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let received: Vec<CreditDelegationResponse> = from_json(res).unwrap();
+    assert_eq!(received, vec![delegation]);
+
+    // cannot borrow more than the remaining allowance
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("desk", &[]),
+        borrow_on_behalf_of_msg(60_001),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CreditDelegationExceeded {
+            allowance: Uint128::new(60_000),
+            amount: Uint128::new(60_001),
+        }
+    );
+
+    // using the whole allowance removes the delegation
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("desk", &[]),
+        borrow_on_behalf_of_msg(60_000),
+    )
+    .unwrap();
+    assert!(query_granted(&deps).is_empty());
+}
+
+#[test]
+fn cannot_borrow_with_expired_or_revoked_credit_delegation() {
+    let mut deps = setup();
+    approve(&mut deps, Some(BLOCK_TIME + 100));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME + 100),
+        mock_info("desk", &[]),
+        borrow_on_behalf_of_msg(1_000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CreditDelegationExpired {
+            delegator: "treasury".to_string(),
+            denom: "uosmo".to_string(),
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("treasury", &[]),
+        ExecuteMsg::RevokeCreditDelegation {
+            delegatee: "desk".to_string(),
+            denom: "uosmo".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(query_granted(&deps).is_empty());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("desk", &[]),
+        borrow_on_behalf_of_msg(1_000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CreditDelegationNotFound {
+            delegator: "treasury".to_string(),
+            denom: "uosmo".to_string(),
+        }
+    );
+}
+
+#[test]
+fn cannot_approve_invalid_credit_delegation() {
+    let mut deps = setup();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("treasury", &[]),
+        ExecuteMsg::ApproveCreditDelegation {
+            delegatee: "treasury".to_string(),
+            denom: "uosmo".to_string(),
+            amount: Uint128::new(100),
+            expires_at: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidCreditDelegation {
+            reason: "cannot delegate credit to self".to_string()
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("treasury", &[]),
+        ExecuteMsg::ApproveCreditDelegation {
+            delegatee: "desk".to_string(),
+            denom: "uosmo".to_string(),
+            amount: Uint128::new(100),
+            expires_at: Some(BLOCK_TIME),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidCreditDelegation {
+            reason: "expiration must be in the future".to_string()
+        }
+    );
+}
@@ -80,14 +80,19 @@ fn setup_test() -> TestSuite {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Uint128::new(12_000_000),
+            borrow_cap: Uint128::MAX,
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     );
 
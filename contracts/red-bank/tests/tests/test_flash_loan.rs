@@ -0,0 +1,255 @@
+use cosmwasm_std::{
+    coin,
+    testing::{mock_info, MockApi, MockStorage},
+    to_json_binary, Addr, Binary, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+use mars_interest_rate::{compute_scaled_amount, ScalingOperation, SCALING_FACTOR};
+use mars_red_bank::{
+    contract::execute,
+    error::ContractError,
+    state::{COLLATERALS, FLASH_LOAN, MARKETS},
+};
+use mars_testing::{mock_env_at_block_time, MarsMockQuerier};
+use mars_types::{
+    error::MarsError,
+    keys::{UserId, UserIdKey},
+    params::{AssetParams, RedBankSettings},
+    red_bank::{ExecuteMsg, FlashLoanReceiverMsg, Market},
+};
+
+use super::helpers::{th_default_asset_params, th_init_market, th_setup};
+
+const BLOCK_TIME: u64 = 10_000_000;
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
+    let mut deps = th_setup(&[coin(1_000_000, "uosmo")]);
+
+    let market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        reserve_factor: Decimal::percent(10),
+        flash_loan_fee_rate: Decimal::percent(1),
+        collateral_total_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+        indexes_last_updated: BLOCK_TIME,
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uosmo", &market);
+    deps.querier.set_redbank_params("uosmo", th_default_asset_params());
+
+    deps
+}
+
+fn flash_loan_msg(amount: u128) -> ExecuteMsg {
+    ExecuteMsg::FlashLoan {
+        coins: vec![coin(amount, "uosmo")],
+        callback: Binary::from(b"liquidate".to_vec()),
+    }
+}
+
+#[test]
+fn flash_loan_calls_receiver_and_finalizes() {
+    let mut deps = setup();
+    let env = mock_env_at_block_time(BLOCK_TIME);
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("bot", &[]), flash_loan_msg(100_000))
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "bot".to_string(),
+                msg: to_json_binary(&FlashLoanReceiverMsg::FlashLoanCallback {
+                    coins: vec![coin(100_000, "uosmo")],
+                    fees: vec![coin(1_000, "uosmo")],
+                    callback: Binary::from(b"liquidate".to_vec()),
+                })
+                .unwrap(),
+                funds: vec![coin(100_000, "uosmo")],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::FinalizeFlashLoan {}).unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let flash_loan = FLASH_LOAN.load(deps.as_ref().storage).unwrap();
+    assert_eq!(flash_loan.receiver, Addr::unchecked("bot"));
+    assert!(flash_loan.repaid.is_empty());
+
+    // Flash loans can't be nested
+    let err = execute(deps.as_mut(), env, mock_info("bot", &[]), flash_loan_msg(100)).unwrap_err();
+    assert_eq!(err, ContractError::FlashLoanInProgress {});
+}
+
+#[test]
+fn cannot_flash_loan_invalid_amounts() {
+    let mut deps = setup();
+    let env = mock_env_at_block_time(BLOCK_TIME);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        ExecuteMsg::FlashLoan {
+            coins: vec![],
+            callback: Binary::default(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFlashLoan {
+            reason: "no coins requested".to_string()
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        ExecuteMsg::FlashLoan {
+            coins: vec![coin(100, "uosmo"), coin(200, "uosmo")],
+            callback: Binary::default(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFlashLoan {
+            reason: "duplicate denoms".to_string()
+        }
+    );
+
+    let err = execute(deps.as_mut(), env.clone(), mock_info("bot", &[]), flash_loan_msg(1_000_001))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBorrowAmount {
+            denom: "uosmo".to_string()
+        }
+    );
+
+    deps.querier.set_redbank_params(
+        "uosmo",
+        AssetParams {
+            red_bank: RedBankSettings {
+                deposit_enabled: true,
+                borrow_enabled: false,
+                withdraw_enabled: true,
+            },
+            ..th_default_asset_params()
+        },
+    );
+    let err =
+        execute(deps.as_mut(), env, mock_info("bot", &[]), flash_loan_msg(100_000)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BorrowNotEnabled {
+            denom: "uosmo".to_string()
+        }
+    );
+}
+
+#[test]
+fn finalize_fails_if_fee_not_repaid() {
+    let mut deps = setup();
+    let env = mock_env_at_block_time(BLOCK_TIME);
+    let contract_info = mock_info(env.contract.address.as_str(), &[]);
+
+    execute(deps.as_mut(), env.clone(), mock_info("bot", &[]), flash_loan_msg(100_000)).unwrap();
+
+    // Only the contract itself can finalize the flash loan
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[]),
+        ExecuteMsg::FinalizeFlashLoan {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Mars(MarsError::Unauthorized {}));
+
+    // Coins that weren't flash loaned can't be repaid
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[coin(100, "uatom")]),
+        ExecuteMsg::RepayFlashLoan {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFlashLoan {
+            reason: "uatom was not flash loaned".to_string()
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[coin(100_000, "uosmo")]),
+        ExecuteMsg::RepayFlashLoan {},
+    )
+    .unwrap();
+
+    let err =
+        execute(deps.as_mut(), env, contract_info, ExecuteMsg::FinalizeFlashLoan {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FlashLoanNotRepaid {
+            denom: "uosmo".to_string(),
+            expected: Uint128::new(101_000),
+            repaid: Uint128::new(100_000),
+        }
+    );
+}
+
+#[test]
+fn fee_is_split_between_protocol_and_depositors() {
+    let mut deps = setup();
+    let env = mock_env_at_block_time(BLOCK_TIME);
+    let contract_info = mock_info(env.contract.address.as_str(), &[]);
+
+    execute(deps.as_mut(), env.clone(), mock_info("bot", &[]), flash_loan_msg(100_000)).unwrap();
+
+    // Repayment can be split into several messages
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[coin(60_000, "uosmo")]),
+        ExecuteMsg::RepayFlashLoan {},
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bot", &[coin(41_000, "uosmo")]),
+        ExecuteMsg::RepayFlashLoan {},
+    )
+    .unwrap();
+
+    execute(deps.as_mut(), env, contract_info, ExecuteMsg::FinalizeFlashLoan {}).unwrap();
+    assert!(FLASH_LOAN.may_load(deps.as_ref().storage).unwrap().is_none());
+
+    // 90% of the 1000 fee accrues to the 1_000_000 deposited
+    let market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    let expected_liquidity_index = Decimal::from_ratio(1_000_900u128, 1_000_000u128);
+    assert_eq!(market.liquidity_index, expected_liquidity_index);
+
+    // 10% of the fee is minted to the rewards collector
+    let expected_rc_amount_scaled = compute_scaled_amount(
+        Uint128::new(100),
+        expected_liquidity_index,
+        ScalingOperation::Truncate,
+    )
+    .unwrap();
+    let user_id = UserId::credit_manager(Addr::unchecked("rewards_collector"), "".to_string());
+    let user_id_key: UserIdKey = user_id.try_into().unwrap();
+    let rc_collateral = COLLATERALS.load(deps.as_ref().storage, (&user_id_key, "uosmo")).unwrap();
+    assert_eq!(rc_collateral.amount_scaled, expected_rc_amount_scaled);
+    assert_eq!(
+        market.collateral_total_scaled,
+        Uint128::new(1_000_000) * SCALING_FACTOR + expected_rc_amount_scaled
+    );
+}
@@ -2,14 +2,20 @@ use std::collections::HashMap;
 
 use cosmwasm_std::{CheckedMultiplyRatioError, Decimal, Uint128};
 use mars_health::error::HealthError;
-use mars_red_bank::{error::ContractError, health::compute_position_health};
-use mars_types::red_bank::Position;
+use mars_red_bank::{
+    error::ContractError,
+    health::{compute_position_health, isolated_debt_portions},
+};
+use mars_types::{
+    params::{EModeCategory, IsolationParams},
+    red_bank::Position,
+};
 
 #[test]
 fn health_position() {
     // No Debt No Collateral
     let positions = HashMap::new();
-    let health = compute_position_health(&positions).unwrap();
+    let health = compute_position_health(&positions, None).unwrap();
 
     assert_eq!(health.total_collateral_value, Uint128::zero());
     assert_eq!(health.total_debt_value, Uint128::zero());
@@ -25,7 +31,7 @@ fn health_position() {
     osmo_position.collateral_amount = Uint128::from(500u128);
     let positions = HashMap::from([("osmo".to_string(), osmo_position)]);
 
-    let health = compute_position_health(&positions).unwrap();
+    let health = compute_position_health(&positions, None).unwrap();
 
     assert_eq!(health.total_collateral_value, Uint128::from(1182u128));
     assert_eq!(health.total_debt_value, Uint128::zero());
@@ -45,7 +51,7 @@ fn health_position() {
 
     let positions =
         HashMap::from([("osmo".to_string(), osmo_position), ("atom".to_string(), atom_position)]);
-    let health = compute_position_health(&positions).unwrap();
+    let health = compute_position_health(&positions, None).unwrap();
 
     assert_eq!(health.total_collateral_value, Uint128::from(5913u128));
     assert_eq!(health.total_debt_value, Uint128::from(2040u128));
@@ -62,7 +68,7 @@ fn health_position() {
 
     let positions =
         HashMap::from([("osmo".to_string(), osmo_position), ("atom".to_string(), atom_position)]);
-    let health = compute_position_health(&positions).unwrap();
+    let health = compute_position_health(&positions, None).unwrap();
 
     assert_eq!(health.total_collateral_value, Uint128::from(5913u128));
     assert_eq!(health.total_debt_value, Uint128::from(3222u128));
@@ -70,13 +76,102 @@ fn health_position() {
     assert!(health.is_above_max_ltv());
 }
 
+#[test]
+fn health_position_in_isolation_mode() {
+    // 1000 osmo worth 2365 with a debt ceiling of 500
+    let isolated_osmo_position = || Position {
+        collateral_amount: Uint128::from(1000u128),
+        isolation: Some(IsolationParams {
+            borrowable_denoms: vec!["atom".to_string()],
+            debt_ceiling: Uint128::from(500u128),
+        }),
+        ..default_osmo_position()
+    };
+
+    // The debt ceiling is a global limit and doesn't cap the max LTV of the position
+    let mut atom_position = default_atom_position();
+    atom_position.debt_amount = Uint128::from(50u128);
+    let mut positions = HashMap::from([
+        ("osmo".to_string(), isolated_osmo_position()),
+        ("atom".to_string(), atom_position),
+    ]);
+    let health = compute_position_health(&positions, None).unwrap();
+    assert_eq!(health.total_debt_value, Uint128::from(510u128));
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::from(1182u128));
+    assert!(!health.is_above_max_ltv());
+
+    // The isolated collateral backs all of the debt as the only collateral
+    let portions = isolated_debt_portions(&positions, None).unwrap();
+    assert_eq!(portions, HashMap::from([("osmo".to_string(), Decimal::one())]));
+
+    // and its share of the max LTV adjusted collateral with 100 atom worth 1020 added
+    positions.get_mut("atom").unwrap().collateral_amount = Uint128::from(100u128);
+    let portions = isolated_debt_portions(&positions, None).unwrap();
+    assert_eq!(
+        portions,
+        HashMap::from([("osmo".to_string(), Decimal::from_ratio(1182u128, 1896u128))])
+    );
+
+    // Debt outside of the allow-list isn't backed by the isolated collateral
+    let mut osmo_position = isolated_osmo_position();
+    osmo_position.isolation = Some(IsolationParams {
+        borrowable_denoms: vec!["usdc".to_string()],
+        debt_ceiling: Uint128::from(500u128),
+    });
+    let mut atom_position = default_atom_position();
+    atom_position.debt_amount = Uint128::from(1u128);
+    let positions =
+        HashMap::from([("osmo".to_string(), osmo_position), ("atom".to_string(), atom_position)]);
+    let health = compute_position_health(&positions, None).unwrap();
+    assert!(health.is_above_max_ltv());
+    assert!(health.is_liquidatable());
+    assert!(isolated_debt_portions(&positions, None).unwrap().is_empty());
+}
+
+#[test]
+fn health_position_in_emode() {
+    let category = EModeCategory {
+        name: "osmo-atom".to_string(),
+        denoms: vec!["osmo".to_string(), "atom".to_string()],
+        max_loan_to_value: Decimal::percent(90),
+        liquidation_threshold: Decimal::percent(93),
+    };
+
+    // 1000 osmo worth 2365 backing 150 atom worth 1530
+    let mut osmo_position = default_osmo_position();
+    osmo_position.collateral_amount = Uint128::from(1000u128);
+    let mut atom_position = default_atom_position();
+    atom_position.debt_amount = Uint128::from(150u128);
+    let mut positions =
+        HashMap::from([("osmo".to_string(), osmo_position), ("atom".to_string(), atom_position)]);
+
+    let health = compute_position_health(&positions, None).unwrap();
+    assert!(health.is_above_max_ltv());
+    assert!(health.is_liquidatable());
+
+    // Category params apply when every debt belongs to the category
+    let health = compute_position_health(&positions, Some(&category)).unwrap();
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::from(2128u128));
+    assert_eq!(health.liquidation_threshold_adjusted_collateral, Uint128::from(2199u128));
+    assert!(!health.is_above_max_ltv());
+
+    // A debt outside of the category disables them for the whole position
+    let mut usdc_position = default_atom_position();
+    usdc_position.denom = "usdc".to_string();
+    usdc_position.debt_amount = Uint128::from(1u128);
+    positions.insert("usdc".to_string(), usdc_position);
+    let health = compute_position_health(&positions, Some(&category)).unwrap();
+    assert!(health.is_above_max_ltv());
+    assert!(health.is_liquidatable());
+}
+
 #[test]
 fn health_error_if_overflow() {
     let mut osmo_position = default_osmo_position();
     osmo_position.collateral_amount = Uint128::MAX;
     osmo_position.asset_price = Decimal::MAX;
     let positions = HashMap::from([("osmo".to_string(), osmo_position)]);
-    let res_err = compute_position_health(&positions).unwrap_err();
+    let res_err = compute_position_health(&positions, None).unwrap_err();
     assert_eq!(
         res_err,
         ContractError::Health(HealthError::CheckedMultiplyRatio(
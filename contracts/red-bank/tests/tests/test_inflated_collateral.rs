@@ -153,14 +153,19 @@ fn atom_asset_params(denom: &str) -> AssetParams {
         },
         protocol_liquidation_fee: Decimal::percent(25),
         deposit_cap: Uint128::from(700000000000u128),
+        borrow_cap: Uint128::MAX,
         close_factor: Decimal::percent(90),
         reserve_factor: Decimal::percent(10),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(10),
             base: Decimal::percent(0),
             slope_1: Decimal::percent(20),
             slope_2: Decimal::percent(300),
         },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
 
@@ -187,13 +192,18 @@ fn osmo_asset_params(denom: &str) -> AssetParams {
         },
         protocol_liquidation_fee: Decimal::percent(25),
         deposit_cap: Uint128::from(10000000000000u128),
+        borrow_cap: Uint128::MAX,
         close_factor: Decimal::percent(90),
         reserve_factor: Decimal::percent(10),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(80),
             base: Decimal::percent(0),
             slope_1: Decimal::percent(15),
             slope_2: Decimal::percent(300),
         },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
@@ -14,7 +14,7 @@ use mars_testing::{
 use mars_types::{
     address_provider::MarsAddressType,
     incentives::{self, IncentiveKind},
-    params::{AssetParams, CmSettings, LiquidationBonus, RedBankSettings},
+    params::{AssetParams, CmSettings, EModeCategory, LiquidationBonus, RedBankSettings},
     red_bank::{
         ExecuteMsg, InterestRateModel, Market, MarketParamsUpdate, QueryMsg,
         UserCollateralResponse, UserDebtResponse,
@@ -871,6 +871,7 @@ fn response_verification() {
             denom: "uusdc".to_string(),
             amount: Uint128::from(3000u128),
             recipient: None,
+            on_behalf_of: None,
         },
     )
     .unwrap();
@@ -882,6 +883,7 @@ fn response_verification() {
             denom: "untrn".to_string(),
             amount: Uint128::from(1200u128),
             recipient: None,
+            on_behalf_of: None,
         },
     )
     .unwrap();
@@ -1037,6 +1039,84 @@ fn liquidation_uses_correct_price_kind() {
     red_bank.borrow(&mut mock_env, &provider, "uusdc", 300).unwrap_err();
 }
 
+#[test]
+fn liquidate_emode_position() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner"))
+        .target_health_factor(Decimal::from_ratio(12u128, 10u128))
+        .build();
+
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+    let params = mock_env.params.clone();
+    let rewards_collector = mock_env.rewards_collector.clone();
+
+    let provider = Addr::unchecked("provider");
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+
+    for denom in ["uatom", "ustatom"] {
+        oracle.set_price_source_fixed(&mut mock_env, denom, Decimal::one());
+        params.init_params(
+            &mut mock_env,
+            default_asset_params_with(
+                denom,
+                Decimal::percent(50),
+                Decimal::percent(55),
+                Decimal::percent(80),
+            ),
+        );
+    }
+    params.update_emode_category(
+        &mut mock_env,
+        EModeCategory {
+            name: "atom".to_string(),
+            denoms: vec!["uatom".to_string(), "ustatom".to_string()],
+            max_loan_to_value: Decimal::percent(85),
+            liquidation_threshold: Decimal::percent(90),
+        },
+    );
+    mock_env.fund_accounts(
+        &[&provider, &liquidatee, &liquidator],
+        1_000_000_000_000u128,
+        &["uatom", "ustatom"],
+    );
+
+    red_bank.deposit(&mut mock_env, &provider, coin(1000000, "uatom")).unwrap();
+    red_bank.deposit(&mut mock_env, &liquidatee, coin(10000, "ustatom")).unwrap();
+    red_bank.set_emode(&mut mock_env, &liquidatee, Some("atom")).unwrap();
+    red_bank.borrow(&mut mock_env, &liquidatee, "uatom", 8000).unwrap();
+
+    // healthy with the e-mode liquidation threshold, even though it is above the default one
+    let error_res = red_bank.liquidate(
+        &mut mock_env,
+        &liquidator,
+        &liquidatee,
+        "ustatom",
+        &[coin(2000, "uatom")],
+    );
+    assert_err(error_res, ContractError::CannotLiquidateHealthyPosition {});
+
+    oracle.set_price_source_fixed(&mut mock_env, "ustatom", Decimal::percent(88));
+    let liquidatee_position = red_bank.query_user_position(&mut mock_env, &liquidatee);
+    assert_eq!(liq_threshold_hf(&liquidatee_position), Decimal::percent(99));
+
+    red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "ustatom", &[coin(2000, "uatom")])
+        .unwrap();
+
+    // the liquidation bonus of 3% follows from the e-mode health factor of 0.99:
+    // 2000 uatom repaid for 2000 * 1.03 / 0.88 = 2340 ustatom, 2 of which go to the protocol
+    let liquidatee_collaterals = red_bank.query_user_collaterals(&mut mock_env, &liquidatee);
+    assert_eq!(liquidatee_collaterals.get("ustatom").unwrap().amount.u128(), 7660);
+    let liquidatee_debts = red_bank.query_user_debts(&mut mock_env, &liquidatee);
+    assert_eq!(liquidatee_debts.get("uatom").unwrap().amount.u128(), 6000);
+    let liquidator_collaterals = red_bank.query_user_collaterals(&mut mock_env, &liquidator);
+    assert_eq!(liquidator_collaterals.get("ustatom").unwrap().amount.u128(), 2338);
+    let rc_collaterals =
+        red_bank.query_user_collaterals(&mut mock_env, &rewards_collector.contract_addr);
+    assert_eq!(rc_collaterals.get("ustatom").unwrap().amount.u128(), 2);
+}
+
 // recipient - can be liquidator or another address which can receive collateral
 fn expected_messages(
     user_addr: &Addr,
@@ -1266,14 +1346,19 @@ fn _default_asset_params_with(
         liquidation_bonus,
         protocol_liquidation_fee: Decimal::percent(2),
         deposit_cap: Uint128::MAX,
+        borrow_cap: Uint128::MAX,
         close_factor,
         reserve_factor: Decimal::percent(20),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(10),
             base: Decimal::percent(30),
             slope_1: Decimal::percent(25),
             slope_2: Decimal::percent(30),
         },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
 
@@ -3,17 +3,19 @@ use cw2::{ContractVersion, VersionError};
 use mars_red_bank::{
     contract::{migrate, CONTRACT_VERSION},
     error::ContractError,
+    migrations::v2_4_0::v2_3_3_state,
     state::{COLLATERALS, MARKETS},
 };
 use mars_testing::mock_dependencies;
 use mars_types::{
     keys::{UserId, UserIdKey},
-    red_bank::{Collateral, Market, MigrateMsg},
+    red_bank::{Collateral, InterestRateModel, Market, MigrateMsg},
 };
 
 const CONTRACT_NAME: &str = "crates.io:mars-red-bank";
 
 const FROM_VERSION_V2_3_2: &str = "2.3.2";
+const TO_VERSION_V2_3_3: &str = "2.3.3";
 
 #[test]
 fn v2_2_0_to_v2_3_0_wrong_contract_name() {
@@ -213,8 +215,8 @@ fn v2_3_2_to_v2_3_3_successful_migration() {
         vec![
             attr("action", "migrate"),
             attr("from_version", FROM_VERSION_V2_3_2),
-            attr("to_version", CONTRACT_VERSION),
-            attr("to_version", CONTRACT_VERSION),
+            attr("to_version", TO_VERSION_V2_3_3),
+            attr("to_version", TO_VERSION_V2_3_3),
             attr("haircut_percent", haircut.to_string()),
             attr("haircut_market", denom),
         ]
@@ -225,6 +227,89 @@ fn v2_3_2_to_v2_3_3_successful_migration() {
 
     assert!(COLLATERALS.may_load(deps.as_ref().storage, (&user_id_key, denom)).unwrap().is_none());
 
+    let new_contract_version = ContractVersion {
+        contract: CONTRACT_NAME.to_string(),
+        version: TO_VERSION_V2_3_3.to_string(),
+    };
+    assert_eq!(cw2::get_contract_version(deps.as_ref().storage).unwrap(), new_contract_version);
+}
+
+#[test]
+fn v2_3_3_to_v2_4_0_wrong_contract_version() {
+    let mut deps = mock_dependencies(&[]);
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, FROM_VERSION_V2_3_2).unwrap();
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::V2_3_3ToV2_4_0 {}).unwrap_err();
+
+    assert_eq!(
+        err,
+        ContractError::Version(VersionError::WrongVersion {
+            expected: TO_VERSION_V2_3_3.to_string(),
+            found: FROM_VERSION_V2_3_2.to_string()
+        })
+    );
+}
+
+#[test]
+fn v2_3_3_to_v2_4_0_successful_migration() {
+    let mut deps = mock_dependencies(&[]);
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, TO_VERSION_V2_3_3).unwrap();
+
+    let model = v2_3_3_state::InterestRateModel {
+        optimal_utilization_rate: Decimal::percent(80),
+        base: Decimal::zero(),
+        slope_1: Decimal::percent(7),
+        slope_2: Decimal::percent(45),
+    };
+    let market = v2_3_3_state::Market {
+        denom: "umars".to_string(),
+        reserve_factor: Decimal::percent(10),
+        interest_rate_model: model.clone(),
+        borrow_index: Decimal::percent(120),
+        liquidity_index: Decimal::percent(110),
+        borrow_rate: Decimal::percent(5),
+        liquidity_rate: Decimal::percent(3),
+        indexes_last_updated: 1_700_000_000,
+        collateral_total_scaled: Uint128::new(1_000_000),
+        debt_total_scaled: Uint128::new(500_000),
+    };
+    v2_3_3_state::MARKETS.save(deps.as_mut().storage, "umars", &market).unwrap();
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::V2_3_3ToV2_4_0 {}).unwrap();
+
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("from_version", TO_VERSION_V2_3_3),
+            attr("to_version", CONTRACT_VERSION),
+        ]
+    );
+
+    let new_market = MARKETS.load(deps.as_ref().storage, "umars").unwrap();
+    assert_eq!(
+        new_market,
+        Market {
+            denom: "umars".to_string(),
+            reserve_factor: Decimal::percent(10),
+            interest_rate_model: InterestRateModel::TwoSlope {
+                optimal_utilization_rate: model.optimal_utilization_rate,
+                base: model.base,
+                slope_1: model.slope_1,
+                slope_2: model.slope_2,
+            },
+            borrow_index: Decimal::percent(120),
+            liquidity_index: Decimal::percent(110),
+            borrow_rate: Decimal::percent(5),
+            liquidity_rate: Decimal::percent(3),
+            indexes_last_updated: 1_700_000_000,
+            collateral_total_scaled: Uint128::new(1_000_000),
+            debt_total_scaled: Uint128::new(500_000),
+            ..Market::default()
+        }
+    );
+
     let new_contract_version = ContractVersion {
         contract: CONTRACT_NAME.to_string(),
         version: CONTRACT_VERSION.to_string(),
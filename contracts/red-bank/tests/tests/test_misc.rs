@@ -3,10 +3,15 @@ use mars_interest_rate::{
     compute_underlying_amount, get_scaled_debt_amount, get_updated_liquidity_index,
     ScalingOperation, SCALING_FACTOR,
 };
-use mars_red_bank::{contract::execute, error::ContractError, health, state::DEBTS};
+use mars_red_bank::{
+    contract::execute,
+    error::ContractError,
+    health,
+    state::{DEBTS, USER_EMODES},
+};
 use mars_testing::{mock_env, MockEnvParams};
 use mars_types::{
-    params::AssetParams,
+    params::{AssetParams, EModeCategory},
     red_bank::{Debt, ExecuteMsg, Market},
 };
 
@@ -179,7 +184,7 @@ fn update_asset_collateral() {
             false,
         )
         .unwrap();
-        let health = health::compute_position_health(&positions).unwrap();
+        let health = health::compute_position_health(&positions, None).unwrap();
 
         // Should have valid health factor
         assert_eq!(health.liquidation_health_factor.unwrap(), Decimal::one());
@@ -193,3 +198,73 @@ fn update_asset_collateral() {
         assert_eq!(res_error, ContractError::InvalidHealthFactorAfterDisablingCollateral {})
     }
 }
+
+#[test]
+fn set_emode() {
+    let mut deps = th_setup(&[]);
+
+    let user_addr = Addr::unchecked("user");
+    let env = mock_env(MockEnvParams::default());
+    let info = mock_info(user_addr.as_str(), &[]);
+
+    let market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uosmo", &market);
+    th_init_market(deps.as_mut(), "uatom", &market);
+    for denom in ["uosmo", "uatom"] {
+        deps.querier.set_redbank_params(
+            denom,
+            AssetParams {
+                max_loan_to_value: Decimal::percent(50),
+                liquidation_threshold: Decimal::percent(55),
+                ..th_default_asset_params()
+            },
+        );
+        deps.querier.set_oracle_price(denom, Decimal::one());
+    }
+
+    // 1000 uosmo backing 600 uatom is above max LTV outside of e-mode
+    set_collateral(deps.as_mut(), &user_addr, "uosmo", Uint128::new(1000) * SCALING_FACTOR, true);
+    DEBTS
+        .save(
+            deps.as_mut().storage,
+            (&user_addr, "uatom"),
+            &Debt {
+                amount_scaled: Uint128::new(600) * SCALING_FACTOR,
+                uncollateralized: false,
+            },
+        )
+        .unwrap();
+
+    let set_emode_msg = |category: Option<&str>| ExecuteMsg::SetEMode {
+        category: category.map(ToString::to_string),
+    };
+
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), set_emode_msg(Some("osmo-atom")))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::EModeCategoryNotFound {
+            name: "osmo-atom".to_string()
+        }
+    );
+
+    deps.querier.set_emode_category(EModeCategory {
+        name: "osmo-atom".to_string(),
+        denoms: vec!["uosmo".to_string(), "uatom".to_string()],
+        max_loan_to_value: Decimal::percent(80),
+        liquidation_threshold: Decimal::percent(85),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), set_emode_msg(Some("osmo-atom"))).unwrap();
+    assert_eq!(
+        USER_EMODES.load(deps.as_ref().storage, &user_addr).unwrap(),
+        "osmo-atom".to_string()
+    );
+
+    // Leaving the category would put the user above max LTV
+    let err = execute(deps.as_mut(), env, info, set_emode_msg(None)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidHealthFactorAfterEModeChange {});
+}
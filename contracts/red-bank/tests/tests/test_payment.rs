@@ -44,6 +44,7 @@ fn rejecting_unexpected_payments() {
             denom: "".into(),
             amount: Uint128::zero(),
             recipient: None,
+            on_behalf_of: None,
         },
     )
     .unwrap_err();
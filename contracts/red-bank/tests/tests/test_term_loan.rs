@@ -0,0 +1,307 @@
+use cosmwasm_std::{
+    coin, coins,
+    testing::{mock_info, MockApi, MockStorage},
+    Addr, BankMsg, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128,
+};
+use mars_interest_rate::{calculate_applied_linear_interest_rate, SCALING_FACTOR};
+use mars_red_bank::{
+    contract::execute,
+    error::ContractError,
+    state::{DEBTS, MARKETS, TERM_LOANS},
+};
+use mars_testing::{mock_env_at_block_time, MarsMockQuerier};
+use mars_types::{
+    params::{AssetParams, LoanTerm, TermLoanParams},
+    red_bank::{ExecuteMsg, Market, TermLoan},
+};
+
+use super::helpers::{set_collateral, th_default_asset_params, th_init_market, th_setup};
+
+const BLOCK_TIME: u64 = 10_000_000;
+const TERM: u64 = 30 * 24 * 3600;
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
+    let mut deps = th_setup(&[coin(1_000_000, "uosmo")]);
+
+    deps.querier.set_oracle_price("uatom", Decimal::one());
+    deps.querier.set_oracle_price("uosmo", Decimal::one());
+
+    let market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        reserve_factor: Decimal::percent(10),
+        collateral_total_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+        indexes_last_updated: BLOCK_TIME,
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uosmo", &market);
+    th_init_market(deps.as_mut(), "uatom", &market);
+
+    deps.querier.set_redbank_params(
+        "uosmo",
+        AssetParams {
+            term_loans: Some(TermLoanParams {
+                terms: vec![LoanTerm {
+                    duration: TERM,
+                    rate_premium: Decimal::percent(2),
+                }],
+                early_repay_penalty: Decimal::percent(1),
+            }),
+            ..th_default_asset_params()
+        },
+    );
+    deps.querier.set_redbank_params(
+        "uatom",
+        AssetParams {
+            max_loan_to_value: Decimal::percent(50),
+            ..th_default_asset_params()
+        },
+    );
+
+    let borrower = Addr::unchecked("borrower");
+    set_collateral(
+        deps.as_mut(),
+        &borrower,
+        "uatom",
+        Uint128::new(1_000_000) * SCALING_FACTOR,
+        true,
+    );
+
+    deps
+}
+
+fn borrow_term_loan_msg(amount: u128, duration: u64) -> ExecuteMsg {
+    ExecuteMsg::BorrowTermLoan {
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(amount),
+        duration,
+        recipient: None,
+    }
+}
+
+#[test]
+fn borrow_term_loan() {
+    let mut deps = setup();
+    let env = mock_env_at_block_time(BLOCK_TIME);
+    let info = mock_info("borrower", &[]);
+
+    // Only the terms offered by the market can be borrowed
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), borrow_term_loan_msg(1_000, 3600))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidLoanTerm {
+            denom: "uosmo".to_string(),
+            duration: 3600
+        }
+    );
+
+    // Cannot borrow more than allowed by the collateral
+    let err =
+        execute(deps.as_mut(), env.clone(), info.clone(), borrow_term_loan_msg(600_000, TERM))
+            .unwrap_err();
+    assert_eq!(err, ContractError::BorrowAmountExceedsGivenCollateral {});
+
+    let res = execute(deps.as_mut(), env, info, borrow_term_loan_msg(100_000, TERM)).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "borrower".to_string(),
+            amount: coins(100_000, "uosmo"),
+        }))]
+    );
+
+    let market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    assert_eq!(market.term_debt_total, Uint128::new(100_000));
+    assert!(market.debt_total_scaled.is_zero());
+    assert!(!market.borrow_rate.is_zero());
+
+    let loan = TERM_LOANS.load(deps.as_ref().storage, (&Addr::unchecked("borrower"), 1)).unwrap();
+    assert_eq!(
+        loan,
+        TermLoan {
+            id: 1,
+            denom: "uosmo".to_string(),
+            principal: Uint128::new(100_000),
+            rate: market.borrow_rate + Decimal::percent(2),
+            opened_at: BLOCK_TIME,
+            maturity: BLOCK_TIME + TERM,
+        }
+    );
+}
+
+#[test]
+fn cannot_borrow_term_loan_if_not_enabled() {
+    let mut deps = setup();
+    deps.querier.set_redbank_params("uosmo", th_default_asset_params());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("borrower", &[]),
+        borrow_term_loan_msg(1_000, TERM),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TermLoansNotEnabled {
+            denom: "uosmo".to_string()
+        }
+    );
+}
+
+#[test]
+fn repay_term_loan_before_maturity_charges_penalty() {
+    let mut deps = setup();
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("borrower", &[]),
+        borrow_term_loan_msg(100_000, TERM),
+    )
+    .unwrap();
+
+    let loan = TERM_LOANS.load(deps.as_ref().storage, (&Addr::unchecked("borrower"), 1)).unwrap();
+    let repay_time = BLOCK_TIME + TERM / 2;
+    let amount_owed = loan.amount_owed(repay_time).unwrap();
+    let expected = amount_owed + amount_owed.mul_ceil(Decimal::percent(1));
+
+    // Repaying only the amount owed is not enough before maturity
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(repay_time),
+        mock_info("borrower", &coins(amount_owed.u128(), "uosmo")),
+        ExecuteMsg::RepayTermLoan {
+            loan_id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TermLoanNotFullyRepaid {
+            expected,
+            sent: amount_owed
+        }
+    );
+
+    // Excess amount is refunded
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(repay_time),
+        mock_info("borrower", &coins(expected.u128() + 10, "uosmo")),
+        ExecuteMsg::RepayTermLoan {
+            loan_id: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "borrower".to_string(),
+            amount: coins(10, "uosmo"),
+        }))
+    );
+
+    let market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    assert!(market.term_debt_total.is_zero());
+    assert!(TERM_LOANS
+        .may_load(deps.as_ref().storage, (&Addr::unchecked("borrower"), 1))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn convert_matured_term_loan_to_variable_debt() {
+    let mut deps = setup();
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("borrower", &[]),
+        borrow_term_loan_msg(100_000, TERM),
+    )
+    .unwrap();
+
+    let convert_msg = ExecuteMsg::ConvertTermLoan {
+        user: "borrower".to_string(),
+        loan_id: 1,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME + TERM - 1),
+        mock_info("keeper", &[]),
+        convert_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TermLoanNotMatured {
+            loan_id: 1
+        }
+    );
+
+    // Anyone can convert a loan once it has matured
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME + TERM),
+        mock_info("keeper", &[]),
+        convert_msg,
+    )
+    .unwrap();
+
+    let market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    assert!(market.term_debt_total.is_zero());
+
+    let debt = DEBTS.load(deps.as_ref().storage, (&Addr::unchecked("borrower"), "uosmo")).unwrap();
+    assert_eq!(debt.amount_scaled, market.debt_total_scaled);
+    assert!(!debt.uncollateralized);
+    assert!(TERM_LOANS
+        .may_load(deps.as_ref().storage, (&Addr::unchecked("borrower"), 1))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn term_loan_interest_accrues_to_depositors_over_the_loan_life() {
+    let mut deps = setup();
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(BLOCK_TIME),
+        mock_info("borrower", &[]),
+        borrow_term_loan_msg(100_000, TERM),
+    )
+    .unwrap();
+
+    // Depositors earn the fixed interest of the loan through the liquidity rate
+    let loan = TERM_LOANS.load(deps.as_ref().storage, (&Addr::unchecked("borrower"), 1)).unwrap();
+    let market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    let yearly_interest = loan.yearly_interest().unwrap();
+    assert_eq!(market.term_interest_per_year, yearly_interest);
+    assert_eq!(
+        market.liquidity_rate,
+        Decimal::from_ratio(yearly_interest, 1_000_000u128) * Decimal::percent(90)
+    );
+
+    // Repaying the loan doesn't make the liquidity index jump, the interest has already accrued
+    let repay_time = BLOCK_TIME + TERM;
+    let amount_owed = loan.amount_owed(repay_time).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env_at_block_time(repay_time),
+        mock_info("borrower", &coins(amount_owed.u128(), "uosmo")),
+        ExecuteMsg::RepayTermLoan {
+            loan_id: 1,
+        },
+    )
+    .unwrap();
+
+    let new_market = MARKETS.load(deps.as_ref().storage, "uosmo").unwrap();
+    assert_eq!(
+        new_market.liquidity_index,
+        calculate_applied_linear_interest_rate(Decimal::one(), market.liquidity_rate, TERM)
+            .unwrap()
+    );
+    assert!(new_market.term_debt_total.is_zero());
+    assert!(new_market.term_interest_per_year.is_zero());
+    assert!(new_market.liquidity_rate.is_zero());
+}
@@ -123,6 +123,7 @@ pub fn open_perp_position(
             order_size: size,
             reduce_only: None,
             order_type: None,
+            limit_price: None,
         }],
         &[],
     )
@@ -400,12 +400,18 @@ fn interest_rates_accured_based_on_internally_tracked_balances() {
     assert_eq!(usdc_market.liquidity_index, Decimal::one());
     assert_eq!(usdc_market.borrow_index, Decimal::one());
     assert_eq!(usdc_market.liquidity_rate, Decimal::zero());
-    assert_eq!(usdc_market.borrow_rate, usdc_market.interest_rate_model.base);
+    assert_eq!(
+        usdc_market.borrow_rate,
+        usdc_market.interest_rate_model.get_borrow_rate(Decimal::zero()).unwrap()
+    );
     let atom_market = red_bank.query_market(&mut mock_env, "uatom");
     assert_eq!(atom_market.liquidity_index, Decimal::one());
     assert_eq!(atom_market.borrow_index, Decimal::one());
     assert_eq!(atom_market.liquidity_rate, Decimal::zero());
-    assert_eq!(atom_market.borrow_rate, atom_market.interest_rate_model.base);
+    assert_eq!(
+        atom_market.borrow_rate,
+        atom_market.interest_rate_model.get_borrow_rate(Decimal::zero()).unwrap()
+    );
 
     // move few blocks
     mock_env.increment_by_blocks(10);
@@ -456,7 +462,10 @@ fn interest_rates_accured_based_on_internally_tracked_balances() {
     assert_eq!(atom_market.liquidity_index, Decimal::one());
     assert_eq!(atom_market.borrow_index, Decimal::from_str("1.000001141552511415").unwrap());
     assert_eq!(atom_market.liquidity_rate, Decimal::zero());
-    assert_eq!(atom_market.borrow_rate, atom_market.interest_rate_model.base);
+    assert_eq!(
+        atom_market.borrow_rate,
+        atom_market.interest_rate_model.get_borrow_rate(Decimal::zero()).unwrap()
+    );
 
     // move few blocks
     mock_env.increment_by_blocks(10);
@@ -484,5 +493,8 @@ fn interest_rates_accured_based_on_internally_tracked_balances() {
     assert_eq!(usdc_market.liquidity_index, Decimal::from_str("1.000000009893455098").unwrap());
     assert_eq!(usdc_market.borrow_index, Decimal::from_str("1.000001807458848941").unwrap());
     assert_eq!(usdc_market.liquidity_rate, Decimal::zero());
-    assert_eq!(usdc_market.borrow_rate, usdc_market.interest_rate_model.base);
+    assert_eq!(
+        usdc_market.borrow_rate,
+        usdc_market.interest_rate_model.get_borrow_rate(Decimal::zero()).unwrap()
+    );
 }
@@ -2,8 +2,8 @@ use std::str::FromStr;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coin, from_json, to_json_binary, Decimal, Empty, QuerierWrapper, QueryRequest, StdError,
-    StdResult, Uint128, WasmQuery,
+    coin, from_json, to_json_binary, Decimal, Decimal256, Empty, QuerierWrapper, QueryRequest,
+    StdError, StdResult, Uint128, Uint256, WasmQuery,
 };
 use osmosis_std::{
     shim::{Duration, Timestamp},
@@ -27,6 +27,18 @@ use osmosis_std::{
 };
 use prost::Message;
 
+/// Exponent of the additive increment between ticks at price one in concentrated liquidity pools
+const EXPONENT_AT_PRICE_ONE: i64 = -6;
+
+/// Number of ticks after which the additive increment between ticks is multiplied by 10
+const GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS: i64 = 9_000_000;
+
+/// Lowest tick a concentrated liquidity position can be created at (price 10^-12)
+pub const MIN_INITIALIZED_TICK: i64 = -108_000_000;
+
+/// Highest tick of a concentrated liquidity pool (price 10^38)
+pub const MAX_TICK: i64 = 342_000_000;
+
 #[derive(Debug, PartialEq)]
 pub struct CosmWasmPool {
     pub id: u64,
@@ -324,6 +336,99 @@ pub fn query_cosmwasm_pool_out_amount(
     Uint128::from_str(&amount_str)
 }
 
+/// Convert a concentrated liquidity tick to the price (token1 per token0) it represents.
+///
+/// Ticks are spaced additively within a power of ten of the price, and the increment is multiplied
+/// by 10 every 9_000_000 ticks: https://github.com/osmosis-labs/osmosis/tree/main/x/concentrated-liquidity#tick-spacing-example-tick-to-price
+pub fn tick_to_price(tick: i64) -> StdResult<Decimal256> {
+    if !(MIN_INITIALIZED_TICK..=MAX_TICK).contains(&tick) {
+        return Err(StdError::generic_err(format!(
+            "tick {tick} is out of range [{MIN_INITIALIZED_TICK}, {MAX_TICK}]"
+        )));
+    }
+
+    // Number of powers of ten the price is away from one
+    let geometric_exponent_delta = tick / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS;
+    let mut exponent_at_current_tick = EXPONENT_AT_PRICE_ONE + geometric_exponent_delta;
+    if tick < 0 {
+        // Below price one the increment is ten times smaller
+        exponent_at_current_tick -= 1;
+    }
+    let num_additive_ticks =
+        tick - geometric_exponent_delta * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS;
+
+    let geometric_price = pow10(geometric_exponent_delta)?;
+    let additive_price =
+        additive_increment(num_additive_ticks.unsigned_abs(), exponent_at_current_tick)?;
+
+    if num_additive_ticks < 0 {
+        Ok(geometric_price.checked_sub(additive_price)?)
+    } else {
+        Ok(geometric_price.checked_add(additive_price)?)
+    }
+}
+
+/// 10^exponent
+fn pow10(exponent: i64) -> StdResult<Decimal256> {
+    additive_increment(1, exponent)
+}
+
+/// num_ticks * 10^exponent
+fn additive_increment(num_ticks: u64, exponent: i64) -> StdResult<Decimal256> {
+    if exponent >= 0 {
+        let factor = Uint256::from(10u8).checked_pow(exponent as u32)?;
+        Ok(Decimal256::from_ratio(Uint256::from(num_ticks).checked_mul(factor)?, 1u8))
+    } else {
+        // Digits beyond the 18 decimal places of Decimal256 are truncated
+        Decimal256::from_atomics(num_ticks, exponent.unsigned_abs() as u32)
+            .map_err(|e| StdError::generic_err(e.to_string()))
+    }
+}
+
+/// Value of `liquidity` of a concentrated liquidity position in the range [lower_tick, upper_tick].
+///
+/// The amounts of the two tokens the liquidity corresponds to are calculated at the price implied
+/// by `token0_price` and `token1_price` (price of token0 in token1 = token0_price / token1_price)
+/// instead of the current price of the pool. If these prices come from a manipulation resistant
+/// source (e.g. TWAP or Pyth), so does the value of the position, similarly to the fair LP token
+/// pricing of XYK pools.
+///
+/// For sqrt prices sa < sb of the range and sp (clamped to the range):
+/// amount0 = liquidity * (sb - sp) / (sp * sb)
+/// amount1 = liquidity * (sp - sa)
+pub fn concentrated_liquidity_position_value(
+    liquidity: Decimal256,
+    lower_tick: i64,
+    upper_tick: i64,
+    token0_price: Decimal256,
+    token1_price: Decimal256,
+) -> StdResult<Decimal256> {
+    if lower_tick >= upper_tick {
+        return Err(StdError::generic_err("lower tick must be less than upper tick"));
+    }
+
+    if token0_price.is_zero() || token1_price.is_zero() {
+        return Err(StdError::generic_err("token prices must be greater than zero"));
+    }
+
+    let sqrt_price_lower = tick_to_price(lower_tick)?.sqrt();
+    let sqrt_price_upper = tick_to_price(upper_tick)?.sqrt();
+    let sqrt_price = Decimal256::checked_from_ratio(token0_price.atomics(), token1_price.atomics())
+        .map_err(|e| StdError::generic_err(e.to_string()))?
+        .sqrt()
+        .clamp(sqrt_price_lower, sqrt_price_upper);
+
+    // Multiply by the token price before dividing to limit the rounding error
+    let value0 = liquidity
+        .checked_mul(sqrt_price_upper - sqrt_price)?
+        .checked_mul(token0_price)?
+        .checked_div(sqrt_price.checked_mul(sqrt_price_upper)?)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let value1 = liquidity.checked_mul(sqrt_price - sqrt_price_lower)?.checked_mul(token1_price)?;
+
+    Ok(value0.checked_add(value1)?)
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::to_json_vec;
@@ -577,4 +682,76 @@ mod tests {
         assert_eq!(cosmwasm_pool.pool_id, pool.get_pool_id());
         assert_eq!(Vec::<String>::new(), pool.get_pool_denoms());
     }
+
+    #[test]
+    fn converting_tick_to_price() {
+        assert_eq!(tick_to_price(0).unwrap(), Decimal256::one());
+        assert_eq!(tick_to_price(8_000_000).unwrap(), Decimal256::from_ratio(9u8, 1u8));
+        assert_eq!(tick_to_price(9_000_000).unwrap(), Decimal256::from_ratio(10u8, 1u8));
+        assert_eq!(tick_to_price(9_100_000).unwrap(), Decimal256::from_ratio(11u8, 1u8));
+        assert_eq!(tick_to_price(-1).unwrap(), Decimal256::from_str("0.9999999").unwrap());
+        assert_eq!(tick_to_price(-9_000_000).unwrap(), Decimal256::from_ratio(1u8, 10u8));
+        assert_eq!(
+            tick_to_price(MIN_INITIALIZED_TICK).unwrap(),
+            Decimal256::from_str("0.000000000001").unwrap()
+        );
+
+        let err = tick_to_price(MIN_INITIALIZED_TICK - 1).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("tick -108000001 is out of range [-108000000, 342000000]")
+        );
+    }
+
+    #[test]
+    fn valuing_concentrated_liquidity_position() {
+        let lower_tick = 0; // price 1
+        let upper_tick = 8_000_000; // price 9
+
+        // price 4 is in range, sp = 2:
+        // value = 4 * (3 - 2) / (2 * 3) + 1 * (2 - 1)
+        let value = concentrated_liquidity_position_value(
+            Decimal256::one(),
+            lower_tick,
+            upper_tick,
+            Decimal256::from_ratio(4u8, 1u8),
+            Decimal256::one(),
+        )
+        .unwrap();
+        assert_eq!(value, Decimal256::from_str("1.666666666666666666").unwrap());
+
+        // price 16 is above the range, the position only holds token1:
+        // value = 1 * (3 - 1)
+        let value = concentrated_liquidity_position_value(
+            Decimal256::one(),
+            lower_tick,
+            upper_tick,
+            Decimal256::from_ratio(16u8, 1u8),
+            Decimal256::one(),
+        )
+        .unwrap();
+        assert_eq!(value, Decimal256::from_ratio(2u8, 1u8));
+
+        // price 0.25 is below the range, the position only holds token0:
+        // value = 3 * 0.25 * (3 - 1) / (1 * 3)
+        let value = concentrated_liquidity_position_value(
+            Decimal256::from_ratio(3u8, 1u8),
+            lower_tick,
+            upper_tick,
+            Decimal256::percent(25),
+            Decimal256::one(),
+        )
+        .unwrap();
+        assert_eq!(value, Decimal256::from_ratio(1u8, 2u8));
+
+        let err = concentrated_liquidity_position_value(
+            Decimal256::one(),
+            upper_tick,
+            lower_tick,
+            Decimal256::one(),
+            Decimal256::one(),
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err("lower tick must be less than upper tick"));
+    }
 }
@@ -8,7 +8,7 @@ use std::{
 use bigdecimal::{BigDecimal, One, RoundingMode, Zero};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Coin, Decimal, Fraction, Int128, SignedDecimal, Uint128};
-use mars_perps_common::pricing::closing_execution_price;
+use mars_perps_common::{margin::isolated_health_factor, pricing::closing_execution_price};
 use mars_types::{
     credit_manager::Positions,
     health::{
@@ -111,6 +111,8 @@ impl HealthComputer {
             perps_pnl_profit: perp_pnl_values.profit,
             perps_pnl_loss: perp_pnl_values.loss,
             has_perps: !self.positions.perps.is_empty(),
+            perps_liq_ltv_numerator: perp_hf_values.liq_ltv_numerator,
+            perps_liq_ltv_denominator: perp_hf_values.liq_ltv_denominator,
         })
     }
 
@@ -363,8 +365,12 @@ impl HealthComputer {
         borrow_denom: &str,
         target: &BorrowTarget,
     ) -> HealthResult<Uint128> {
+        // Isolated collaterals may not back the new debt
+        let mut debt_denoms =
+            self.positions.debts.iter().map(|d| d.denom.as_str()).collect::<Vec<_>>();
+        debt_denoms.push(borrow_denom);
         let total_max_ltv_adjusted_value =
-            self.total_collateral_value()?.max_ltv_adjusted_collateral;
+            self.collateral_value_backing(&debt_denoms)?.max_ltv_adjusted_collateral;
         let debt_value = self.debt_value()?;
 
         // We often add one to calcs for a margin of error, so rather than create it multiple times we just create it once here.
@@ -847,7 +853,9 @@ impl HealthComputer {
         let mut profit = Uint128::zero();
         let mut loss = Uint128::zero();
 
-        for position in perps.iter() {
+        // Isolated-margin positions are backed only by their own margin, which is no longer part
+        // of the account. They can't affect the health of the rest of the account.
+        for position in perps.iter().filter(|position| position.isolated_margin.is_none()) {
             let base_denom_price = self.get_price(&position.base_denom)?;
 
             match &position.unrealized_pnl.to_coins(&position.base_denom).pnl {
@@ -887,6 +895,39 @@ impl HealthComputer {
         ))
     }
 
+    /// Health factor of an isolated-margin perp position, evaluated only against its own margin
+    /// with the perp's liquidation threshold. The position can be liquidated on its own when it
+    /// drops below 1.
+    ///
+    /// Returns `None` for cross-margin positions, which are part of the account's health, and for
+    /// positions without value.
+    pub fn isolated_perp_health_factor(
+        &self,
+        position: &PerpPosition,
+    ) -> HealthResult<Option<Decimal>> {
+        let Some(margin) = position.isolated_margin else {
+            return Ok(None);
+        };
+
+        let base_denom_price = self.get_price(&position.base_denom)?;
+        let perp_params = self
+            .perps_data
+            .params
+            .get(&position.denom)
+            .ok_or(MissingPerpParams(position.denom.to_string()))?;
+
+        let position_value =
+            position.size.unsigned_abs().checked_mul_floor(position.current_price)?;
+
+        Ok(isolated_health_factor(
+            margin,
+            position.unrealized_pnl.pnl,
+            base_denom_price,
+            position_value,
+            perp_params.liquidation_threshold,
+        )?)
+    }
+
     fn perp_health_factor_values(
         &self,
         position: &PerpPosition,
@@ -983,10 +1024,18 @@ impl HealthComputer {
     }
 
     fn total_collateral_value(&self) -> HealthResult<CollateralValue> {
+        let debt_denoms = self.positions.debts.iter().map(|d| d.denom.as_str()).collect::<Vec<_>>();
+        self.collateral_value_backing(&debt_denoms)
+    }
+
+    /// Collateral value of the account when backing debts of the given denoms
+    fn collateral_value_backing(&self, debt_denoms: &[&str]) -> HealthResult<CollateralValue> {
         let deposits = self.coins_value(&self.positions.deposits)?;
         let lends = self.coins_value(&self.positions.lends)?;
         let vaults = self.vaults_value()?;
         let staked_lp = self.coins_value(&self.positions.staked_astro_lps)?;
+        let (isolated_max_ltv_excess, isolated_liq_ltv_excess) =
+            self.isolated_collateral_excess(debt_denoms)?;
 
         Ok(CollateralValue {
             total_collateral_value: deposits
@@ -998,12 +1047,14 @@ impl HealthComputer {
                 .max_ltv_adjusted_collateral
                 .checked_add(vaults.max_ltv_adjusted_collateral)?
                 .checked_add(lends.max_ltv_adjusted_collateral)?
-                .checked_add(staked_lp.max_ltv_adjusted_collateral)?,
+                .checked_add(staked_lp.max_ltv_adjusted_collateral)?
+                .checked_sub(isolated_max_ltv_excess)?,
             liq_ltv_adjusted_collateral: deposits
                 .liq_ltv_adjusted_collateral
                 .checked_add(vaults.liq_ltv_adjusted_collateral)?
                 .checked_add(lends.liq_ltv_adjusted_collateral)?
-                .checked_add(staked_lp.liq_ltv_adjusted_collateral)?,
+                .checked_add(staked_lp.liq_ltv_adjusted_collateral)?
+                .checked_sub(isolated_liq_ltv_excess)?,
         })
     }
 
@@ -1052,6 +1103,118 @@ impl HealthComputer {
         })
     }
 
+    /// Portion of the account's debts backed by each of its collaterals in isolation mode, which
+    /// counts towards the debt ceiling of the collateral.
+    ///
+    /// Debts are backed by all collaterals in proportion to their max LTV adjusted value. An
+    /// isolated collateral backs none of them if a debt denom is outside of its allow-list.
+    pub fn isolated_debt_portions(&self) -> HealthResult<HashMap<String, Decimal>> {
+        let debt_denoms = self.positions.debts.iter().map(|d| d.denom.as_str()).collect::<Vec<_>>();
+        if debt_denoms.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let max_ltv_adjusted_collateral =
+            self.collateral_value_backing(&debt_denoms)?.max_ltv_adjusted_collateral;
+        if max_ltv_adjusted_collateral.is_zero() {
+            return Ok(HashMap::new());
+        }
+
+        let mut portions = HashMap::new();
+        for (denom, value) in self.isolated_collateral_values()? {
+            let Some(isolation) =
+                self.asset_params.get(denom).and_then(|params| params.isolation.as_ref())
+            else {
+                continue;
+            };
+            if !isolation.can_back(&debt_denoms) || value.max_ltv_adjusted_collateral.is_zero() {
+                continue;
+            }
+
+            let portion = Decimal::checked_from_ratio(
+                value.max_ltv_adjusted_collateral,
+                max_ltv_adjusted_collateral,
+            )?;
+            portions.insert(denom.to_string(), portion);
+        }
+
+        Ok(portions)
+    }
+
+    /// Portion of the max LTV and liquidation threshold adjusted values of collaterals in
+    /// isolation mode that can't back the given debts. An isolated collateral backs nothing if a
+    /// debt denom is outside of its allow-list.
+    fn isolated_collateral_excess(&self, debt_denoms: &[&str]) -> HealthResult<(Uint128, Uint128)> {
+        let mut max_ltv_excess = Uint128::zero();
+        let mut liq_ltv_excess = Uint128::zero();
+        for (denom, value) in self.isolated_collateral_values()? {
+            let Some(isolation) =
+                self.asset_params.get(denom).and_then(|params| params.isolation.as_ref())
+            else {
+                continue;
+            };
+
+            if !isolation.can_back(debt_denoms) {
+                max_ltv_excess = max_ltv_excess.checked_add(value.max_ltv_adjusted_collateral)?;
+                liq_ltv_excess = liq_ltv_excess.checked_add(value.liq_ltv_adjusted_collateral)?;
+            }
+        }
+
+        Ok((max_ltv_excess, liq_ltv_excess))
+    }
+
+    /// Adjusted values of the collaterals in isolation mode, with the same rounding as in
+    /// `coins_value` so that they never exceed the adjusted values of the account
+    fn isolated_collateral_values(&self) -> HealthResult<HashMap<&str, CollateralValue>> {
+        let mut isolated_values: HashMap<&str, CollateralValue> = HashMap::new();
+
+        let coins = self
+            .positions
+            .deposits
+            .iter()
+            .chain(self.positions.lends.iter())
+            .chain(self.positions.staked_astro_lps.iter());
+        for c in coins {
+            let Some(AssetParams {
+                credit_manager:
+                    CmSettings {
+                        hls,
+                        ..
+                    },
+                liquidation_threshold,
+                isolation: Some(_),
+                ..
+            }) = self.coin_contribution_to_collateral(c)?
+            else {
+                continue;
+            };
+
+            let coin_value = c.amount.checked_mul_floor(self.get_price(&c.denom)?)?;
+            let max_ltv_adjusted =
+                coin_value.checked_mul_floor(self.get_coin_max_ltv(&c.denom)?)?;
+            let checked_liquidation_threshold = match self.kind {
+                AccountKind::HighLeveredStrategy => {
+                    hls.as_ref().ok_or(MissingHLSParams(c.denom.clone()))?.liquidation_threshold
+                }
+                _ => *liquidation_threshold,
+            };
+            let liq_adjusted = coin_value.checked_mul_floor(checked_liquidation_threshold)?;
+
+            let value = isolated_values.entry(c.denom.as_str()).or_insert(CollateralValue {
+                total_collateral_value: Uint128::zero(),
+                max_ltv_adjusted_collateral: Uint128::zero(),
+                liq_ltv_adjusted_collateral: Uint128::zero(),
+            });
+            value.total_collateral_value = value.total_collateral_value.checked_add(coin_value)?;
+            value.max_ltv_adjusted_collateral =
+                value.max_ltv_adjusted_collateral.checked_add(max_ltv_adjusted)?;
+            value.liq_ltv_adjusted_collateral =
+                value.liq_ltv_adjusted_collateral.checked_add(liq_adjusted)?;
+        }
+
+        Ok(isolated_values)
+    }
+
     fn coin_contribution_to_collateral(&self, coin: &Coin) -> HealthResult<Option<&AssetParams>> {
         let Some(asset_params) = self.asset_params.get(&coin.denom) else {
             // If the coin is not found (whitelisted), it is not considered for collateral
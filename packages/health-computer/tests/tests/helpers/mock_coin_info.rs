@@ -41,14 +41,19 @@ pub fn umars_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -80,14 +85,19 @@ pub fn udai_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -119,14 +129,19 @@ pub fn uluna_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -170,14 +185,19 @@ pub fn ustars_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -209,14 +229,19 @@ pub fn ujuno_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -254,14 +279,19 @@ pub fn uatom_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -299,14 +329,19 @@ pub fn uusdc_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
@@ -342,14 +377,19 @@ pub fn create_coin_info(
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            borrow_cap: Default::default(),
             close_factor: Decimal::percent(80u64),
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         },
     }
 }
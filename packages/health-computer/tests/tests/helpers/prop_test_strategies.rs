@@ -154,14 +154,19 @@ fn random_coin_info() -> impl Strategy<Value = AssetParams> {
                     },
                     protocol_liquidation_fee: Default::default(),
                     deposit_cap: Default::default(),
+                    borrow_cap: Default::default(),
                     close_factor,
                     reserve_factor,
-                    interest_rate_model: InterestRateModel {
+                    interest_rate_model: InterestRateModel::TwoSlope {
                         optimal_utilization_rate,
                         base,
                         slope_1,
                         slope_2,
                     },
+                    perps_vault_haircut: None,
+                    flash_loan_fee_rate: Decimal::zero(),
+                    isolation: None,
+                    term_loans: None,
                 }
             },
         )
@@ -489,6 +494,7 @@ fn random_perps(perp_denoms_data: PerpsData) -> impl Strategy<Value = Vec<PerpPo
                         current_exec_price: market_price,
                         unrealized_pnl: pnl_amounts,
                         realized_pnl: PnlAmounts::default(),
+                        isolated_margin: None,
                     }
                 },
             ),
@@ -3,6 +3,8 @@ mod helpers;
 mod test_health_scenarios;
 mod test_hls;
 mod test_input_validation;
+mod test_isolated_perps;
+mod test_isolation_mode;
 mod test_liquidation_price;
 mod test_max_borrow_deposit;
 mod test_max_borrow_prop;
@@ -1376,6 +1376,7 @@ fn long_one_negative_pnl_perp_no_spot_debt() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -1449,6 +1450,7 @@ fn test_missing_usdc_margin_params() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -1508,6 +1510,7 @@ fn long_one_positive_pnl_perp_no_spot_debt() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         asset_params,
@@ -1576,6 +1579,7 @@ fn one_short_negative_pnl_perp_no_spot_debt() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -1684,6 +1688,7 @@ fn one_short_negative_pnl_perp_vault_collateral_no_spot_debt() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -1759,6 +1764,7 @@ fn one_short_positive_pnl_perp_no_spot_debt() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -1857,6 +1863,7 @@ fn perps_one_short_negative_pnl_one_long_negative_pnl_no_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
                 PerpPosition {
                     denom: ethperp.denom,
@@ -1873,6 +1880,7 @@ fn perps_one_short_negative_pnl_one_long_negative_pnl_no_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
             ],
         },
@@ -1972,6 +1980,7 @@ fn perps_one_short_negative_pnl_one_long_positive_pnl_no_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
                 PerpPosition {
                     denom: ethperp.denom,
@@ -1988,6 +1997,7 @@ fn perps_one_short_negative_pnl_one_long_positive_pnl_no_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
             ],
         },
@@ -2074,6 +2084,7 @@ fn perp_short_delta_neutral_with_btc_collateral() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -2164,6 +2175,7 @@ fn spot_short_delta_neutral_with_leverage() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -2301,6 +2313,7 @@ fn perps_two_short_positive_pnl_one_long_negative_pnl_with_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
                 PerpPosition {
                     denom: ethperp.denom,
@@ -2316,6 +2329,7 @@ fn perps_two_short_positive_pnl_one_long_negative_pnl_with_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
                 PerpPosition {
                     denom: atomperp.denom,
@@ -2331,6 +2345,7 @@ fn perps_two_short_positive_pnl_one_long_negative_pnl_with_spot_debt() {
                         ..Default::default()
                     },
                     realized_pnl: PnlAmounts::default(),
+                    isolated_margin: None,
                 },
             ],
         },
@@ -2405,6 +2420,7 @@ fn single_perp_funding_greater_than_pnl() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
@@ -2499,6 +2515,7 @@ fn same_denomination_for_values() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices: oracle_prices.clone(),
@@ -2556,6 +2573,7 @@ fn same_denomination_for_values() {
                     ..Default::default()
                 },
                 realized_pnl: PnlAmounts::default(),
+                isolated_margin: None,
             }],
         },
         oracle_prices,
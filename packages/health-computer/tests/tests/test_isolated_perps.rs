@@ -0,0 +1,133 @@
+use std::{collections::HashMap, str::FromStr};
+
+use cosmwasm_std::{coin, Decimal, Int128, Uint128};
+use mars_rover_health_computer::{HealthComputer, PerpsData};
+use mars_types::{
+    credit_manager::Positions,
+    health::AccountKind,
+    perps::{PerpPosition, PnlAmounts},
+};
+
+use super::helpers::uusdc_info;
+use crate::tests::helpers::create_perp_info;
+
+fn perp_position(denom: &str, isolated_margin: Option<Uint128>) -> PerpPosition {
+    let entry_price = Decimal::from_str("100").unwrap();
+    let current_price = Decimal::from_str("92").unwrap();
+    PerpPosition {
+        denom: denom.to_string(),
+        base_denom: uusdc_info().denom,
+        current_price,
+        entry_price,
+        entry_exec_price: entry_price,
+        current_exec_price: current_price,
+        size: Int128::from_str("10000000").unwrap(),
+        unrealized_pnl: PnlAmounts {
+            accrued_funding: Int128::from_str("-25210000").unwrap(),
+            pnl: Int128::from_str("-24790000").unwrap(),
+            ..Default::default()
+        },
+        realized_pnl: PnlAmounts::default(),
+        isolated_margin,
+    }
+}
+
+fn health_computer(perps: Vec<PerpPosition>) -> HealthComputer {
+    let uusd = uusdc_info();
+    let max_ltv = Decimal::from_str("0.9").unwrap();
+    let liquidation_threshold = Decimal::from_str("0.95").unwrap();
+    let btcperp = create_perp_info(
+        "btc/usd/perp".to_string(),
+        Decimal::from_str("92").unwrap(),
+        max_ltv,
+        liquidation_threshold,
+    );
+    let ethperp = create_perp_info(
+        "eth/usd/perp".to_string(),
+        Decimal::from_str("92").unwrap(),
+        max_ltv,
+        liquidation_threshold,
+    );
+
+    HealthComputer {
+        kind: AccountKind::Default,
+        positions: Positions {
+            account_id: "123".to_string(),
+            account_kind: AccountKind::Default,
+            deposits: vec![coin(152000000, &uusd.denom)],
+            debts: vec![],
+            lends: vec![],
+            staked_astro_lps: vec![],
+            vaults: vec![],
+            perps,
+        },
+        oracle_prices: HashMap::from([
+            (uusd.denom.clone(), uusd.price),
+            (btcperp.denom.clone(), btcperp.price),
+            (ethperp.denom.clone(), ethperp.price),
+        ]),
+        asset_params: HashMap::from([(uusd.denom.clone(), uusd.params.clone())]),
+        vaults_data: Default::default(),
+        perps_data: PerpsData {
+            params: HashMap::from([
+                (btcperp.denom.clone(), btcperp.perp_params.clone()),
+                (ethperp.denom.clone(), ethperp.perp_params.clone()),
+            ]),
+        },
+    }
+}
+
+#[test]
+fn isolated_position_does_not_affect_account_health() {
+    let cross = perp_position("btc/usd/perp", None);
+    let isolated = perp_position("eth/usd/perp", Some(Uint128::new(50000000)));
+
+    let health_cross_only = health_computer(vec![cross.clone()]).compute_health().unwrap();
+    let health = health_computer(vec![cross, isolated]).compute_health().unwrap();
+
+    // Same values as for the cross-margin position alone
+    assert_eq!(health.total_collateral_value, health_cross_only.total_collateral_value);
+    assert_eq!(health.max_ltv_health_factor, health_cross_only.max_ltv_health_factor);
+    assert_eq!(health.liquidation_health_factor, health_cross_only.liquidation_health_factor);
+    assert_eq!(
+        health.liquidation_health_factor,
+        Some(Decimal::from_str("0.993177983047375659").unwrap())
+    );
+    assert_eq!(health.perps_pnl_loss, health_cross_only.perps_pnl_loss);
+    assert!(health.has_perps);
+
+    // With only isolated positions there is nothing the account has to cover
+    let isolated = perp_position("eth/usd/perp", Some(Uint128::new(50000000)));
+    let health = health_computer(vec![isolated]).compute_health().unwrap();
+    assert_eq!(health.max_ltv_health_factor, None);
+    assert_eq!(health.liquidation_health_factor, None);
+    assert!(!health.is_liquidatable());
+}
+
+#[test]
+fn isolated_position_health_factor() {
+    let h = health_computer(vec![]);
+
+    // Cross-margin positions don't have an isolated health factor
+    let cross = perp_position("btc/usd/perp", None);
+    assert_eq!(h.isolated_perp_health_factor(&cross).unwrap(), None);
+
+    // Margin requirement = 10000000 * 92 * (1 - 0.95) = 46000000
+    // Equity = 50000000 - 24790000 = 25210000
+    let isolated = perp_position("eth/usd/perp", Some(Uint128::new(50000000)));
+    assert_eq!(
+        h.isolated_perp_health_factor(&isolated).unwrap(),
+        Some(Decimal::from_str("0.548043478260869565").unwrap())
+    );
+
+    // Equity = 80000000 - 24790000 = 55210000
+    let isolated = perp_position("eth/usd/perp", Some(Uint128::new(80000000)));
+    assert_eq!(
+        h.isolated_perp_health_factor(&isolated).unwrap(),
+        Some(Decimal::from_str("1.200217391304347826").unwrap())
+    );
+
+    // Losses exceeding the margin
+    let isolated = perp_position("eth/usd/perp", Some(Uint128::new(20000000)));
+    assert_eq!(h.isolated_perp_health_factor(&isolated).unwrap(), Some(Decimal::zero()));
+}
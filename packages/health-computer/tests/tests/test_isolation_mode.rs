@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{coin, Decimal, Uint128};
+use mars_rover_health_computer::{HealthComputer, PerpsData, VaultsData};
+use mars_types::{
+    credit_manager::{DebtAmount, Positions},
+    health::{AccountKind, BorrowTarget},
+    params::IsolationParams,
+};
+
+use super::helpers::{uatom_info, umars_info, uusdc_info};
+
+/// 300 umars (price 1, max LTV 0.8) in isolation mode, only backing uusdc with a debt ceiling of 100
+fn isolated_computer(debts: Vec<DebtAmount>) -> HealthComputer {
+    let mut umars = umars_info();
+    umars.params.isolation = Some(IsolationParams {
+        borrowable_denoms: vec!["uusdc".to_string()],
+        debt_ceiling: Uint128::new(100),
+    });
+    let uusdc = uusdc_info();
+    let uatom = uatom_info();
+
+    HealthComputer {
+        kind: AccountKind::Default,
+        positions: Positions {
+            account_id: "123".to_string(),
+            account_kind: AccountKind::Default,
+            deposits: vec![coin(300, &umars.denom)],
+            debts,
+            lends: vec![],
+            vaults: vec![],
+            staked_astro_lps: vec![],
+            perps: vec![],
+        },
+        asset_params: HashMap::from([
+            (umars.denom.clone(), umars.params.clone()),
+            (uusdc.denom.clone(), uusdc.params.clone()),
+            (uatom.denom.clone(), uatom.params.clone()),
+        ]),
+        oracle_prices: HashMap::from([
+            (umars.denom.clone(), umars.price),
+            (uusdc.denom.clone(), uusdc.price),
+            (uatom.denom.clone(), uatom.price),
+        ]),
+        vaults_data: VaultsData {
+            vault_values: Default::default(),
+            vault_configs: Default::default(),
+        },
+        perps_data: PerpsData {
+            params: Default::default(),
+        },
+    }
+}
+
+fn debt(amount: u128, denom: &str) -> DebtAmount {
+    DebtAmount {
+        denom: denom.to_string(),
+        shares: Uint128::new(amount) * Uint128::new(1_000_000),
+        amount: Uint128::new(amount),
+    }
+}
+
+#[test]
+fn isolated_collateral_backs_allowed_debt() {
+    let h = isolated_computer(vec![debt(50, "uusdc")]);
+
+    let health = h.compute_health().unwrap();
+    assert_eq!(health.total_collateral_value, Uint128::new(300));
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::new(240));
+    assert_eq!(health.liquidation_threshold_adjusted_collateral, Uint128::new(252));
+    assert_eq!(health.max_ltv_health_factor, Some(Decimal::from_ratio(24u128, 5u128)));
+    assert!(!health.is_above_max_ltv());
+
+    // The debt ceiling is a global limit enforced on borrow, not a cap on the account's health
+    let h = isolated_computer(vec![debt(101, "uusdc")]);
+    let health = h.compute_health().unwrap();
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::new(240));
+    assert!(!health.is_above_max_ltv());
+}
+
+#[test]
+fn isolated_collateral_does_not_back_other_debts() {
+    let h = isolated_computer(vec![debt(50, "uusdc"), debt(10, "uatom")]);
+
+    let health = h.compute_health().unwrap();
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::zero());
+    assert_eq!(health.liquidation_threshold_adjusted_collateral, Uint128::zero());
+    assert!(health.is_above_max_ltv());
+    assert!(health.is_liquidatable());
+    assert!(h.isolated_debt_portions().unwrap().is_empty());
+}
+
+#[test]
+fn isolated_collateral_backs_its_portion_of_debts() {
+    // The isolated collateral is the only one backing the debts
+    let h = isolated_computer(vec![debt(50, "uusdc")]);
+    let portions = h.isolated_debt_portions().unwrap();
+    assert_eq!(portions, HashMap::from([("umars".to_string(), Decimal::one())]));
+
+    // 240 out of a max LTV adjusted collateral of 240 + 400 * 0.9
+    let mut h = isolated_computer(vec![debt(50, "uusdc")]);
+    h.positions.deposits.push(coin(400, "uusdc"));
+    let portions = h.isolated_debt_portions().unwrap();
+    assert_eq!(portions, HashMap::from([("umars".to_string(), Decimal::percent(40))]));
+
+    // Nothing is backed without debts
+    let h = isolated_computer(vec![]);
+    assert!(h.isolated_debt_portions().unwrap().is_empty());
+}
+
+#[test]
+fn max_borrow_in_isolation_mode() {
+    let h = isolated_computer(vec![]);
+
+    let max_borrow = h.max_borrow_amount_estimate("uusdc", &BorrowTarget::Wallet).unwrap();
+    assert_eq!(max_borrow, Uint128::new(239));
+
+    let max_borrow = h.max_borrow_amount_estimate("uatom", &BorrowTarget::Wallet).unwrap();
+    assert_eq!(max_borrow, Uint128::zero());
+}
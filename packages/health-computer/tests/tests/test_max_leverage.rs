@@ -270,6 +270,7 @@ fn asserting_health_factor(
                         entry_price: Decimal::from_str("2000").unwrap(),
                         realized_pnl: Default::default(),
                         unrealized_pnl: pnl_amounts,
+                        isolated_margin: None,
                     }
                 })
                 .collect(),
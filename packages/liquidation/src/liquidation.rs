@@ -212,6 +212,87 @@ pub fn calculate_liquidation_amounts(
     ))
 }
 
+/// Calculates the share of every cross-margin perp position to close in order to bring the
+/// account to the Target Health Factor (THF), instead of closing the whole perps book.
+///
+/// It is the same THF math as in `calculate_liquidation_amounts`, with the perps part of the HF
+/// playing the role of the repaid debt. Closing a share `f` of every position removes
+/// `f * perps_numerator` from the HF numerator and `f * perps_denominator` from the denominator.
+/// The realized PnL and the liquidation bonus are settled in the base denom (i.e. usdc), weighted
+/// by its liquidation threshold:
+///
+/// f = (THF * total_debt_value - liq_th_collateral_value) / (THF * perps_denominator - released_collateral)
+/// where:
+/// total_debt_value        - Value of debt, including the perps denominator
+/// liq_th_collateral_value - Value of collateral adjusted to liquidation threshold, including the perps numerator
+/// released_collateral     - perps_numerator + base_denom_liq_th * (pnl_loss - pnl_profit + perps_LB * perps_denominator)
+/// perps_LB                - Liquidation Bonus for perps: `perps_lb_ratio * LB`
+///
+/// The liquidator is rewarded proportionally to the size closed:
+/// `perps_lb_value = f * perps_denominator * perps_LB`, and the PLF is charged as a % of it.
+///
+/// A ratio of one means the THF can't be reached by closing perps alone, so all of them should be
+/// closed before liquidating the spot positions.
+///
+/// Returns -> (Close Ratio, Perps LB Value, Protocol Fee Value)
+pub fn calculate_perps_close_ratio(
+    health: &HealthValuesResponse,
+    target_health_factor: Decimal,
+    base_denom_params: &AssetParams,
+    perps_lb_ratio: Decimal,
+) -> Result<(Decimal, Uint128, Uint128), LiquidationError> {
+    let perps_numerator = health.perps_liq_ltv_numerator;
+    let perps_denominator = health.perps_liq_ltv_denominator;
+    let base_denom_liq_th = base_denom_params.liquidation_threshold;
+
+    let health_data: HealthData = health.clone().try_into()?;
+    let liquidation_bonus = calculate_liquidation_bonus(
+        health_data.liquidation_health_factor,
+        health_data.collateralization_ratio,
+        base_denom_params,
+    )?;
+    let perps_lb = perps_lb_ratio.checked_mul(liquidation_bonus)?;
+
+    let liq_th_collateral_value =
+        health.liquidation_threshold_adjusted_collateral.checked_add(perps_numerator)?;
+    let total_debt_value = health.total_debt_value.checked_add(perps_denominator)?;
+
+    let numerator = total_debt_value
+        .checked_mul_ceil(target_health_factor)?
+        .saturating_sub(liq_th_collateral_value);
+    if numerator.is_zero() {
+        return Ok((Decimal::zero(), Uint128::zero(), Uint128::zero()));
+    }
+
+    let denominator_positive = perps_denominator
+        .checked_mul_floor(target_health_factor)?
+        .checked_add(health.perps_pnl_profit.checked_mul_floor(base_denom_liq_th)?)?;
+    let denominator_negative = perps_numerator
+        .checked_add(health.perps_pnl_loss.checked_mul_ceil(base_denom_liq_th)?)?
+        .checked_add(
+            perps_denominator.checked_mul_ceil(perps_lb.checked_mul(base_denom_liq_th)?)?,
+        )?;
+
+    // Closing perps doesn't improve the HF enough
+    if denominator_positive <= denominator_negative {
+        return Ok((Decimal::one(), Uint128::zero(), Uint128::zero()));
+    }
+
+    let close_ratio =
+        Decimal::checked_from_ratio(numerator, denominator_positive - denominator_negative)?;
+    if close_ratio >= Decimal::one() {
+        return Ok((Decimal::one(), Uint128::zero(), Uint128::zero()));
+    }
+
+    let perps_lb_value = perps_denominator.checked_mul_floor(close_ratio.checked_mul(perps_lb)?)?;
+
+    // Use ceiling in favour of protocol
+    let protocol_fee_value =
+        perps_lb_value.checked_mul_ceil(base_denom_params.protocol_liquidation_fee)?;
+
+    Ok((close_ratio, perps_lb_value, protocol_fee_value))
+}
+
 /// The LB will depend on the Health Factor and a couple other parameters as follows:
 /// Liquidation Bonus = min(
 ///     starting_lb + (slope * (1 - HF)),
@@ -0,0 +1 @@
+mod test_perps_close_ratio;
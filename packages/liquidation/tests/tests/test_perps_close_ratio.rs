@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use mars_liquidation::liquidation::calculate_perps_close_ratio;
+use mars_types::{
+    health::HealthValuesResponse,
+    params::{AssetParams, CmSettings, LiquidationBonus, RedBankSettings},
+    red_bank::InterestRateModel,
+};
+
+fn base_denom_params() -> AssetParams {
+    AssetParams {
+        denom: "uusdc".to_string(),
+        credit_manager: CmSettings {
+            whitelisted: true,
+            withdraw_enabled: true,
+            hls: None,
+        },
+        red_bank: RedBankSettings {
+            deposit_enabled: true,
+            borrow_enabled: true,
+            withdraw_enabled: true,
+        },
+        max_loan_to_value: Decimal::percent(85),
+        liquidation_threshold: Decimal::percent(90),
+        liquidation_bonus: LiquidationBonus {
+            starting_lb: Decimal::percent(1),
+            slope: Decimal::from_str("2").unwrap(),
+            min_lb: Decimal::percent(2),
+            max_lb: Decimal::percent(10),
+        },
+        protocol_liquidation_fee: Decimal::percent(10),
+        deposit_cap: Uint128::MAX,
+        borrow_cap: Uint128::MAX,
+        close_factor: Decimal::percent(80),
+        reserve_factor: Decimal::percent(10),
+        interest_rate_model: InterestRateModel::default(),
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
+    }
+}
+
+/// Account with 1000 of debt, 1500 of collateral (1200 adjusted to the liquidation threshold) and
+/// perps contributing 100 to the numerator and 200 to the denominator of the liquidation HF.
+///
+/// With a CR of 1.5 and a HF of 0.95, the LB is capped by max LB: min(1% + 2 * 5%, 10%) = 10%.
+fn health() -> HealthValuesResponse {
+    HealthValuesResponse {
+        total_debt_value: Uint128::new(1_000_000_000),
+        total_collateral_value: Uint128::new(1_500_000_000),
+        max_ltv_adjusted_collateral: Uint128::new(1_100_000_000),
+        liquidation_threshold_adjusted_collateral: Uint128::new(1_200_000_000),
+        max_ltv_health_factor: Some(Decimal::percent(90)),
+        liquidation_health_factor: Some(Decimal::percent(95)),
+        perps_pnl_profit: Uint128::zero(),
+        perps_pnl_loss: Uint128::zero(),
+        liquidatable: true,
+        above_max_ltv: true,
+        has_perps: true,
+        perps_liq_ltv_numerator: Uint128::new(100_000_000),
+        perps_liq_ltv_denominator: Uint128::new(200_000_000),
+    }
+}
+
+#[test]
+fn partial_close_reaches_target_health_factor() {
+    let (close_ratio, perps_lb_value, protocol_fee_value) = calculate_perps_close_ratio(
+        &health(),
+        Decimal::from_str("1.1").unwrap(),
+        &base_denom_params(),
+        Decimal::percent(50),
+    )
+    .unwrap();
+
+    // perps_LB = 50% * 10% = 5%
+    // numerator = 1.1 * (1000 + 200) - (1200 + 100) = 20
+    // denominator = 200 * 1.1 - (100 + 200 * 5% * 0.9) = 111
+    assert_eq!(close_ratio, Decimal::from_str("0.18018018018018018").unwrap());
+    // 200 * close_ratio * 5%
+    assert_eq!(perps_lb_value, Uint128::new(1_801_801));
+    // 10% of the bonus, rounded up
+    assert_eq!(protocol_fee_value, Uint128::new(180_181));
+}
+
+#[test]
+fn already_at_target_health_factor() {
+    let health = HealthValuesResponse {
+        liquidation_threshold_adjusted_collateral: Uint128::new(1_220_000_000),
+        ..health()
+    };
+
+    // 1.1 * (1000 + 200) <= 1220 + 100, nothing to close
+    let res = calculate_perps_close_ratio(
+        &health,
+        Decimal::from_str("1.1").unwrap(),
+        &base_denom_params(),
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert_eq!(res, (Decimal::zero(), Uint128::zero(), Uint128::zero()));
+}
+
+#[test]
+fn full_close_if_target_health_factor_out_of_reach() {
+    let health = HealthValuesResponse {
+        liquidation_threshold_adjusted_collateral: Uint128::new(800_000_000),
+        ..health()
+    };
+
+    // numerator = 1.1 * 1200 - 900 = 420 exceeds the denominator of 111
+    let res = calculate_perps_close_ratio(
+        &health,
+        Decimal::from_str("1.1").unwrap(),
+        &base_denom_params(),
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert_eq!(res, (Decimal::one(), Uint128::zero(), Uint128::zero()));
+}
+
+#[test]
+fn full_close_if_denominator_is_zero() {
+    let health = HealthValuesResponse {
+        liquidation_threshold_adjusted_collateral: Uint128::new(900_000_000),
+        perps_liq_ltv_numerator: Uint128::zero(),
+        perps_liq_ltv_denominator: Uint128::zero(),
+        ..health()
+    };
+
+    // Closing perps doesn't change the HF
+    let res = calculate_perps_close_ratio(
+        &health,
+        Decimal::from_str("1.1").unwrap(),
+        &base_denom_params(),
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert_eq!(res, (Decimal::one(), Uint128::zero(), Uint128::zero()));
+}
+
+#[test]
+fn full_close_if_denominator_is_negative() {
+    let health = HealthValuesResponse {
+        perps_pnl_loss: Uint128::new(200_000_000),
+        ..health()
+    };
+
+    // Realizing the loss outweighs the released requirement:
+    // 200 * 1.1 < 100 + 200 * 0.9 + 200 * 5% * 0.9
+    let res = calculate_perps_close_ratio(
+        &health,
+        Decimal::from_str("1.1").unwrap(),
+        &base_denom_params(),
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert_eq!(res, (Decimal::one(), Uint128::zero(), Uint128::zero()));
+}
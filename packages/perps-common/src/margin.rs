@@ -0,0 +1,32 @@
+use cosmwasm_std::{Decimal, Int128, Uint128};
+use mars_types::perps::PerpsError;
+
+/// Health factor of an isolated-margin position, evaluated only against its own collateral.
+///
+/// HF = (margin + unrealized PnL) * base denom price / (position value * (1 - LTV))
+///
+/// where the position value is denominated in the oracle's base currency and the unrealized
+/// PnL (including the closing fee) in the base denom. Losses exceeding the margin result in a
+/// zero health factor.
+///
+/// Returns `None` if the position has no value, i.e. there is nothing to be liquidated.
+pub fn isolated_health_factor(
+    margin: Uint128,
+    unrealized_pnl: Int128,
+    base_denom_price: Decimal,
+    position_value: Uint128,
+    ltv: Decimal,
+) -> Result<Option<Decimal>, PerpsError> {
+    let margin_requirement = position_value.checked_mul_floor(Decimal::one().checked_sub(ltv)?)?;
+    if margin_requirement.is_zero() {
+        return Ok(None);
+    }
+
+    let equity = Int128::try_from(margin)?.checked_add(unrealized_pnl)?;
+    if equity.is_negative() {
+        return Ok(Some(Decimal::zero()));
+    }
+    let equity_value = equity.unsigned_abs().checked_mul_floor(base_denom_price)?;
+
+    Ok(Some(Decimal::checked_from_ratio(equity_value, margin_requirement)?))
+}
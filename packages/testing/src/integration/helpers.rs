@@ -41,13 +41,18 @@ pub fn default_asset_params_with(
         },
         protocol_liquidation_fee: Decimal::percent(2),
         deposit_cap: Uint128::MAX,
+        borrow_cap: Uint128::MAX,
         close_factor: Decimal::percent(80),
         reserve_factor: Decimal::percent(20),
-        interest_rate_model: InterestRateModel {
+        interest_rate_model: InterestRateModel::TwoSlope {
             optimal_utilization_rate: Decimal::percent(10),
             base: Decimal::percent(30),
             slope_1: Decimal::percent(25),
             slope_2: Decimal::percent(30),
         },
+        perps_vault_haircut: None,
+        flash_loan_fee_rate: Decimal::zero(),
+        isolation: None,
+        term_loans: None,
     }
 }
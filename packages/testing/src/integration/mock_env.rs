@@ -17,7 +17,9 @@ use mars_types::{
         ActionKind::{Default as ActionDefault, Liquidation},
         PriceResponse,
     },
-    params::{AssetParams, AssetParamsUpdate, TotalDepositResponse},
+    params::{
+        AssetParams, AssetParamsUpdate, EModeCategory, EModeCategoryUpdate, TotalDepositResponse,
+    },
     red_bank::{
         self, CreateOrUpdateConfig, Market, MarketV2Response, UserCollateralResponse,
         UserDebtResponse, UserPositionResponse,
@@ -474,6 +476,23 @@ impl RedBank {
                 denom: denom.to_string(),
                 amount: amount.into(),
                 recipient: None,
+                on_behalf_of: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_emode(
+        &self,
+        env: &mut MockEnv,
+        sender: &Addr,
+        category: Option<&str>,
+    ) -> AnyResult<AppResponse> {
+        env.app.execute_contract(
+            sender.clone(),
+            self.contract_addr.clone(),
+            &red_bank::ExecuteMsg::SetEMode {
+                category: category.map(ToString::to_string),
             },
             &[],
         )
@@ -788,6 +807,21 @@ impl Params {
             .unwrap();
     }
 
+    pub fn update_emode_category(&self, env: &mut MockEnv, category: EModeCategory) {
+        env.app
+            .execute_contract(
+                env.owner.clone(),
+                self.contract_addr.clone(),
+                &mars_types::params::ExecuteMsg::UpdateEModeCategory(
+                    EModeCategoryUpdate::AddOrUpdate {
+                        category,
+                    },
+                ),
+                &[],
+            )
+            .unwrap();
+    }
+
     pub fn query_params(&self, env: &mut MockEnv, denom: &str) -> AssetParams {
         env.app
             .wrap()
@@ -1133,6 +1167,7 @@ impl MockEnvBuilder {
                     cooldown_period: self.cooldown_period,
                     max_positions: self.max_positions,
                     protocol_fee_rate: Decimal::percent(25),
+                    insurance_fund_fee_rate: Decimal::zero(),
                     target_vault_collateralization_ratio: Decimal::percent(125),
                     deleverage_enabled: true,
                     vault_withdraw_enabled: true,
@@ -13,6 +13,7 @@ pub mod duality_swapper;
 /// cosmwasm_std::testing overrides and custom test helpers
 mod helpers;
 mod incentives_querier;
+mod managed_vault_querier;
 mod mars_mock_querier;
 mod mock_address_provider;
 mod mocks;
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{to_json_binary, Addr, Binary, ContractResult, QuerierResult};
+use cw_vault_standard::VaultStandardQueryMsg;
+use mars_vault::msg::{ExtensionQueryMsg, QueryMsg, VaultInfoResponseExt};
+
+#[derive(Default)]
+pub struct ManagedVaultQuerier {
+    pub vault_infos: HashMap<Addr, VaultInfoResponseExt>,
+}
+
+impl ManagedVaultQuerier {
+    pub fn handle_query(&self, contract_addr: &Addr, query: QueryMsg) -> QuerierResult {
+        let res: ContractResult<Binary> = match query {
+            VaultStandardQueryMsg::VaultExtension(ExtensionQueryMsg::VaultInfo {}) => {
+                match self.vault_infos.get(contract_addr) {
+                    Some(vault_info) => to_json_binary(vault_info).into(),
+                    None => {
+                        Err(format!("[mock]: could not find vault info for {contract_addr}")).into()
+                    }
+                }
+            }
+
+            _ => Err("[mock]: Unsupported managed vault query").into(),
+        };
+
+        Ok(res).into()
+    }
+}
@@ -10,10 +10,11 @@ use mars_oracle_osmosis::DowntimeDetector;
 use mars_oracle_wasm::slinky::CurrencyPairExt;
 use mars_types::{
     address_provider, incentives, oracle,
-    params::AssetParams,
+    params::{AssetParams, EModeCategory},
     perps::{VaultPositionResponse, VaultResponse},
     red_bank,
 };
+use mars_vault::msg::VaultInfoResponseExt;
 use neutron_sdk::bindings::{
     marketmap::types::Market,
     oracle::{query::GetPriceResponse, types::CurrencyPair},
@@ -31,6 +32,7 @@ use crate::{
     astroport_incentives_querier::AstroportIncentivesQuerier,
     cosmwasm_pool_querier::CosmWasmPoolQuerier,
     incentives_querier::IncentivesQuerier,
+    managed_vault_querier::ManagedVaultQuerier,
     mock_address_provider,
     oracle_querier::OracleQuerier,
     osmosis_querier::{OsmosisQuerier, PriceKey},
@@ -57,6 +59,7 @@ pub struct MarsMockQuerier {
     slinky_querier: SlinkyQuerier,
     perps_querier: PerpsQuerier,
     swapper_querier: SwapperQuerier,
+    managed_vault_querier: ManagedVaultQuerier,
 }
 
 impl Querier for MarsMockQuerier {
@@ -109,6 +112,7 @@ impl MarsMockQuerier {
             slinky_querier: SlinkyQuerier::default(),
             perps_querier: PerpsQuerier::default(),
             swapper_querier: SwapperQuerier::default(),
+            managed_vault_querier: ManagedVaultQuerier::default(),
         }
     }
 
@@ -268,6 +272,10 @@ impl MarsMockQuerier {
         self.params_querier.params.insert(denom.to_string(), params);
     }
 
+    pub fn set_emode_category(&mut self, category: EModeCategory) {
+        self.params_querier.emode_categories.insert(category.name.clone(), category);
+    }
+
     pub fn set_total_deposit(&mut self, denom: impl Into<String>, amount: impl Into<Uint128>) {
         self.params_querier.total_deposits.insert(denom.into(), amount.into());
     }
@@ -308,6 +316,10 @@ impl MarsMockQuerier {
         self.perps_querier.vault = vault_res;
     }
 
+    pub fn set_managed_vault_info(&mut self, vault_addr: &str, vault_info: VaultInfoResponseExt) {
+        self.managed_vault_querier.vault_infos.insert(Addr::unchecked(vault_addr), vault_info);
+    }
+
     fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
             QueryRequest::Wasm(WasmQuery::Smart {
@@ -381,6 +393,13 @@ impl MarsMockQuerier {
                     return self.swapper_querier.handle_query(&contract_addr, swapper_query);
                 }
 
+                // Managed Vault Queries
+                if let Ok(managed_vault_query) = from_json::<mars_vault::msg::QueryMsg>(msg) {
+                    return self
+                        .managed_vault_querier
+                        .handle_query(&contract_addr, managed_vault_query);
+                }
+
                 // CosmWasm pool Queries
                 if let Ok(cw_pool_query) = from_json::<CalcOutAmtGivenInRequest>(msg) {
                     return self.cosmwasm_pool_queries.handle_query(cw_pool_query);
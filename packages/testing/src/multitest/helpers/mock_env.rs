@@ -44,8 +44,8 @@ use mars_types::{
         Account, Action, CallbackMsg, CoinBalanceResponseItem, ConfigResponse, ConfigUpdates,
         DebtShares, ExecuteMsg, InstantiateMsg, KeeperFeeConfig, Positions,
         QueryMsg::{self, EstimateProvideLiquidity, VaultPositionValue},
-        SharesResponseItem, TriggerOrderResponse, VaultBinding, VaultPositionResponseItem,
-        VaultUtilizationResponse,
+        SharesResponseItem, TrailingMarkResponse, TriggerOrderResponse, VaultBinding,
+        VaultPositionResponseItem, VaultUtilizationResponse,
     },
     fee_tiers::{FeeTier, FeeTierConfig},
     health::{
@@ -66,11 +66,12 @@ use mars_types::{
             UpdateAssetParams, UpdateManagedVaultConfig, UpdatePerpParams, UpdateVaultConfig,
         },
         InstantiateMsg as ParamsInstantiateMsg, ManagedVaultConfigResponse,
-        ManagedVaultConfigUpdate, PerpParamsUpdate, QueryMsg as ParamsQueryMsg, VaultConfig,
-        VaultConfigUnchecked, VaultConfigUpdate,
+        ManagedVaultConfigUpdate, PerpParamsUpdate, QueryMsg as ParamsQueryMsg,
+        TotalIsolatedDebtResponse, VaultConfig, VaultConfigUnchecked, VaultConfigUpdate,
     },
     perps::{
-        self, Config, InstantiateMsg as PerpsInstantiateMsg, PnL, PositionResponse, TradingFee,
+        self, Config, InstantiateMsg as PerpsInstantiateMsg, InsuranceFundDraw,
+        InsuranceFundResponse, LimitOrder, PnL, PositionResponse, TradingFee,
         VaultPositionResponse, VaultResponse,
     },
     red_bank::{
@@ -138,7 +139,9 @@ pub struct MockEnvBuilder {
     pub withdraw_enabled: Option<bool>,
     pub keeper_fee_config: Option<KeeperFeeConfig>,
     pub perps_liquidation_bonus_ratio: Option<Decimal>,
+    pub perps_liquidation_target_health_factor: Option<Decimal>,
     pub perps_protocol_fee_ratio: Option<Decimal>,
+    pub perps_insurance_fund_fee_ratio: Option<Decimal>,
     pub swap_fee: Option<Decimal>,
     pub fee_tier_config: Option<mars_types::fee_tiers::FeeTierConfig>,
     pub governance_addr: Option<Addr>,
@@ -174,7 +177,9 @@ impl MockEnv {
             withdraw_enabled: None,
             keeper_fee_config: None,
             perps_liquidation_bonus_ratio: None,
+            perps_liquidation_target_health_factor: None,
             perps_protocol_fee_ratio: None,
+            perps_insurance_fund_fee_ratio: None,
             swap_fee: None,
             fee_tier_config: None,
             governance_addr: None,
@@ -290,6 +295,23 @@ impl MockEnv {
         )
     }
 
+    pub fn write_off_bad_debt(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        denom: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::WriteOffBadDebt {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+            },
+            &[],
+        )
+    }
+
     pub fn execute_trigger_order(
         &mut self,
         sender: &Addr,
@@ -307,6 +329,40 @@ impl MockEnv {
         )
     }
 
+    pub fn prune_expired_trigger_order(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        trigger_order_id: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::PruneExpiredTriggerOrder {
+                account_id: account_id.to_string(),
+                trigger_order_id: trigger_order_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn refresh_trailing_mark(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        trigger_order_id: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::RefreshTrailingMark {
+                account_id: account_id.to_string(),
+                trigger_order_id: trigger_order_id.to_string(),
+            },
+            &[],
+        )
+    }
+
     pub fn update_config(
         &mut self,
         sender: &Addr,
@@ -605,6 +661,52 @@ impl MockEnv {
         )
     }
 
+    pub fn swap_perp_vault_collateral(
+        &mut self,
+        sender: &Addr,
+        denom: &str,
+        amount: Uint128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.address().clone(),
+            &perps::ExecuteMsg::SwapVaultCollateral {
+                denom: denom.to_string(),
+                amount,
+                route: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn fill_perp_limit_orders(&mut self, sender: &Addr, denom: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.address().clone(),
+            &perps::ExecuteMsg::FillLimitOrders {
+                denom: denom.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn liquidate_isolated_perp_position(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        denom: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.perps.address().clone(),
+            &perps::ExecuteMsg::LiquidateIsolatedPosition {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+            },
+            &[],
+        )
+    }
+
     //--------------------------------------------------------------------------------------------------
     // Queries
     //--------------------------------------------------------------------------------------------------
@@ -800,6 +902,18 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_total_isolated_debt(&self, denom: &str) -> TotalIsolatedDebtResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.params.address(),
+                &ParamsQueryMsg::TotalIsolatedDebt {
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_managed_vault_config(&self) -> ManagedVaultConfigResponse {
         self.app
             .wrap()
@@ -1043,6 +1157,42 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_trailing_mark(
+        &self,
+        account_id: &str,
+        trigger_order_id: &str,
+    ) -> Option<TrailingMarkResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.rover.clone(),
+                &QueryMsg::TrailingMark {
+                    account_id: account_id.to_string(),
+                    trigger_order_id: trigger_order_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_trailing_marks_for_account(
+        &self,
+        account_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> PaginationResponse<TrailingMarkResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.rover.clone(),
+                &QueryMsg::AllAccountTrailingMarks {
+                    account_id,
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_swap_estimate(
         &self,
         coin_in: &Coin,
@@ -1144,6 +1294,7 @@ impl MockEnv {
                     denom: denom.to_string(),
                     order_size: modification_size,
                     reduce_only: None,
+                    action: None,
                 },
             )
             .unwrap()
@@ -1168,6 +1319,32 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_perp_limit_order(&self, order_id: u64) -> Option<LimitOrder> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.address(),
+                &perps::QueryMsg::LimitOrder {
+                    order_id,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_perp_limit_orders_by_account(&self, account_id: &str) -> Vec<LimitOrder> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.address(),
+                &perps::QueryMsg::LimitOrdersByAccount {
+                    account_id: account_id.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_perp_config(&self) -> Config<String> {
         self.app.wrap().query_wasm_smart(self.perps.address(), &perps::QueryMsg::Config {}).unwrap()
     }
@@ -1181,6 +1358,32 @@ impl MockEnv {
         )
     }
 
+    pub fn query_perp_insurance_fund(&self, denom: &str) -> InsuranceFundResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.address(),
+                &perps::QueryMsg::InsuranceFund {
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_perp_insurance_fund_draws(&self, denom: &str) -> Vec<InsuranceFundDraw> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.perps.address(),
+                &perps::QueryMsg::InsuranceFundDraws {
+                    denom: denom.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_perp_vault_position(&self, acc_id: &str) -> Option<VaultPositionResponse> {
         self.app
             .wrap()
@@ -1446,6 +1649,8 @@ impl MockEnvBuilder {
                     incentives,
                     keeper_fee_config,
                     perps_liquidation_bonus_ratio,
+                    perps_liquidation_target_health_factor: self
+                        .perps_liquidation_target_health_factor,
                     swap_fee,
                     fee_tier_config,
                     governance_address: governance,
@@ -1679,6 +1884,7 @@ impl MockEnvBuilder {
         let deleverage_enabled = self.get_delegerage_enabled();
         let vault_withdraw_enabled = self.get_withdraw_enabled();
         let perps_protocol_fee_ratio = self.get_perps_protocol_fee_ratio();
+        let perps_insurance_fund_fee_ratio = self.get_perps_insurance_fund_fee_ratio();
         let addr = self
             .app
             .instantiate_contract(
@@ -1690,6 +1896,7 @@ impl MockEnvBuilder {
                     cooldown_period: 360,
                     max_positions: 4,
                     protocol_fee_rate: perps_protocol_fee_ratio,
+                    insurance_fund_fee_rate: perps_insurance_fund_fee_ratio,
                     target_vault_collateralization_ratio,
                     deleverage_enabled,
                     vault_withdraw_enabled,
@@ -1947,6 +2154,9 @@ impl MockEnvBuilder {
                 amount: coins(1_000_000, "uosmo"),
             }))
             .unwrap();
+
+        self.set_address(MarsAddressType::Swapper, addr.clone());
+
         SwapperBase::new(addr)
     }
 
@@ -2034,6 +2244,7 @@ impl MockEnvBuilder {
     fn get_keeper_fee_config(&self) -> KeeperFeeConfig {
         self.keeper_fee_config.clone().unwrap_or(KeeperFeeConfig {
             min_fee: coin(1000000, "uusdc"),
+            pruner_fee_pct: Decimal::percent(50),
         })
     }
 
@@ -2073,6 +2284,10 @@ impl MockEnvBuilder {
         self.perps_protocol_fee_ratio.unwrap_or_else(|| Decimal::percent(0))
     }
 
+    fn get_perps_insurance_fund_fee_ratio(&self) -> Decimal {
+        self.perps_insurance_fund_fee_ratio.unwrap_or_else(|| Decimal::percent(0))
+    }
+
     fn get_swap_fee(&self) -> Decimal {
         self.swap_fee.unwrap_or_else(|| Decimal::percent(0))
     }
@@ -2171,6 +2386,11 @@ impl MockEnvBuilder {
         self
     }
 
+    pub fn perps_liquidation_target_health_factor(mut self, target_hf: Decimal) -> Self {
+        self.perps_liquidation_target_health_factor = Some(target_hf);
+        self
+    }
+
     pub fn evil_vault(mut self, credit_account: &str) -> Self {
         self.evil_vault = Some(credit_account.to_string());
         self
@@ -2191,6 +2411,11 @@ impl MockEnvBuilder {
         self
     }
 
+    pub fn perps_insurance_fund_fee_ratio(mut self, ratio: Decimal) -> Self {
+        self.perps_insurance_fund_fee_ratio = Some(ratio);
+        self
+    }
+
     pub fn swap_fee(mut self, ratio: Decimal) -> Self {
         self.swap_fee = Some(ratio);
         self
@@ -89,14 +89,19 @@ impl From<CoinInfo> for AssetParamsUnchecked {
             liquidation_bonus: c.liquidation_bonus,
             protocol_liquidation_fee: c.protocol_liquidation_fee,
             deposit_cap: Uint128::MAX,
+            borrow_cap: Uint128::MAX,
             close_factor: c.close_factor,
             reserve_factor: Decimal::percent(10u64),
-            interest_rate_model: InterestRateModel {
+            interest_rate_model: InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(80u64),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7u64),
                 slope_2: Decimal::percent(45u64),
             },
+            perps_vault_haircut: None,
+            flash_loan_fee_rate: Decimal::zero(),
+            isolation: None,
+            term_loans: None,
         }
     }
 }
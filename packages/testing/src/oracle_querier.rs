@@ -21,6 +21,7 @@ impl OracleQuerier {
                     to_json_binary(&PriceResponse {
                         denom,
                         price: *price,
+                        fallback_used: false,
                     })
                     .into()
                 } else {
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
 use cosmwasm_std::{to_json_binary, Binary, Coin, ContractResult, QuerierResult, Uint128};
-use mars_types::params::{AssetParams, QueryMsg};
+use mars_types::params::{AssetParams, EModeCategory, QueryMsg};
 
 #[derive(Default)]
 pub struct ParamsQuerier {
     pub params: HashMap<String, AssetParams>,
     pub total_deposits: HashMap<String, Uint128>,
+    pub emode_categories: HashMap<String, EModeCategory>,
 }
 
 impl ParamsQuerier {
@@ -28,6 +29,9 @@ impl ParamsQuerier {
                 .into(),
                 None => Err(format!("[mock]: could not find total deposit for {denom}")).into(),
             },
+            QueryMsg::EModeCategory {
+                name,
+            } => to_json_binary(&self.emode_categories.get(&name)).into(),
             _ => Err("[mock]: Unsupported params query".to_string()).into(),
         };
         Ok(ret).into()
@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Api, Decimal, QuerierWrapper, StdResult};
 
-use crate::credit_manager::{AccountTierAndDiscountResponse, QueryMsg};
+use crate::credit_manager::{AccountTierAndDiscountResponse, ConfigResponse, QueryMsg};
 
 #[cw_serde]
 pub struct CreditManagerBase<T>(T);
@@ -53,4 +53,12 @@ impl CreditManager {
     ) -> StdResult<Decimal> {
         Ok(self.query_account_tier_and_discount(querier, account_id)?.discount_pct)
     }
+
+    pub fn query_perps_liquidation_bonus_ratio(
+        &self,
+        querier: &QuerierWrapper,
+    ) -> StdResult<Decimal> {
+        let res: ConfigResponse = querier.query_wasm_smart(self.address(), &QueryMsg::Config {})?;
+        Ok(res.perps_liquidation_bonus_ratio)
+    }
 }
@@ -5,8 +5,8 @@ use cosmwasm_std::{Addr, Api, QuerierWrapper, StdResult};
 use cw_paginate::{PaginationResponse, MAX_LIMIT};
 
 use crate::params::{
-    AssetParams, ManagedVaultConfigResponse, PerpParams, QueryMsg, TotalDepositResponse,
-    VaultConfig,
+    AssetParams, ManagedVaultConfigResponse, PerpParams, QueryMsg, TotalBorrowResponse,
+    TotalDepositResponse, TotalIsolatedDebtResponse, VaultConfig,
 };
 
 #[cw_serde]
@@ -77,6 +77,32 @@ impl Params {
         )
     }
 
+    pub fn query_total_borrow(
+        &self,
+        querier: &QuerierWrapper,
+        denom: &str,
+    ) -> StdResult<TotalBorrowResponse> {
+        querier.query_wasm_smart(
+            self.address().to_string(),
+            &QueryMsg::TotalBorrow {
+                denom: denom.to_string(),
+            },
+        )
+    }
+
+    pub fn query_total_isolated_debt(
+        &self,
+        querier: &QuerierWrapper,
+        denom: &str,
+    ) -> StdResult<TotalIsolatedDebtResponse> {
+        querier.query_wasm_smart(
+            self.address().to_string(),
+            &QueryMsg::TotalIsolatedDebt {
+                denom: denom.to_string(),
+            },
+        )
+    }
+
     pub fn query_vault_config(
         &self,
         querier: &QuerierWrapper,
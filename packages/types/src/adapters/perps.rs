@@ -8,7 +8,7 @@ use crate::{
     oracle::ActionKind,
     perps::{
         Config, ExecuteMsg, MarketResponse, PerpPosition, PositionResponse,
-        PositionsByAccountResponse, QueryMsg, TradingFee, VaultPositionResponse,
+        PositionsByAccountResponse, QueryMsg, TradingFee, VaultPositionResponse, VaultResponse,
     },
 };
 
@@ -98,6 +98,9 @@ impl Perps {
         reduce_only: Option<bool>,
         funds: Vec<Coin>,
         discount_pct: Option<Decimal>,
+        limit_price: Option<Decimal>,
+        isolated_margin: Option<Uint128>,
+        action: Option<ActionKind>,
     ) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: self.address().into(),
@@ -107,11 +110,71 @@ impl Perps {
                 size,
                 reduce_only,
                 discount_pct,
+                limit_price,
+                isolated_margin,
+                action,
             })?,
             funds,
         }))
     }
 
+    /// Generate message for adding margin to an isolated position
+    pub fn add_isolated_margin_msg(
+        &self,
+        account_id: impl Into<String>,
+        denom: impl Into<String>,
+        margin: &Coin,
+    ) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.address().into(),
+            msg: to_json_binary(&ExecuteMsg::AddIsolatedMargin {
+                account_id: account_id.into(),
+                denom: denom.into(),
+            })?,
+            funds: vec![margin.clone()],
+        }))
+    }
+
+    /// Generate message for placing a limit order
+    pub fn place_limit_order_msg(
+        &self,
+        account_id: impl Into<String>,
+        denom: impl Into<String>,
+        size: Int128,
+        limit_price: Decimal,
+        reduce_only: Option<bool>,
+        keeper_fee: Coin,
+    ) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.address().into(),
+            msg: to_json_binary(&ExecuteMsg::PlaceLimitOrder {
+                account_id: account_id.into(),
+                denom: denom.into(),
+                size,
+                limit_price,
+                reduce_only,
+                keeper_fee,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    /// Generate message for cancelling a limit order
+    pub fn cancel_limit_order_msg(
+        &self,
+        account_id: impl Into<String>,
+        order_id: u64,
+    ) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.address().into(),
+            msg: to_json_binary(&ExecuteMsg::CancelLimitOrder {
+                account_id: account_id.into(),
+                order_id,
+            })?,
+            funds: vec![],
+        }))
+    }
+
     /// Generate message for closing all perp positions
     pub fn close_all_msg(
         &self,
@@ -119,6 +182,7 @@ impl Perps {
         funds: Vec<Coin>,
         action: ActionKind,
         discount_pct: Option<Decimal>,
+        bad_debt: Option<Uint128>,
     ) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: self.address().into(),
@@ -126,6 +190,7 @@ impl Perps {
                 account_id: account_id.into(),
                 action: Some(action),
                 discount_pct,
+                bad_debt,
             })?,
             funds,
         }))
@@ -138,6 +203,7 @@ impl Perps {
         denom: impl Into<String>,
         order_size: Option<Int128>,
         reduce_only: Option<bool>,
+        action: ActionKind,
     ) -> StdResult<Option<PerpPosition>> {
         let res: PositionResponse = querier.query_wasm_smart(
             self.address(),
@@ -146,6 +212,7 @@ impl Perps {
                 denom: denom.into(),
                 order_size,
                 reduce_only,
+                action: Some(action),
             },
         )?;
         Ok(res.position)
@@ -204,6 +271,16 @@ impl Perps {
         Ok(res)
     }
 
+    pub fn query_vault(&self, querier: &QuerierWrapper) -> StdResult<VaultResponse> {
+        let res: VaultResponse = querier.query_wasm_smart(
+            self.address(),
+            &QueryMsg::Vault {
+                action: None,
+            },
+        )?;
+        Ok(res)
+    }
+
     pub fn query_vault_position(
         &self,
         querier: &QuerierWrapper,
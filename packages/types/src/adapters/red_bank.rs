@@ -54,6 +54,7 @@ impl RedBank {
                 denom: coin.denom.to_string(),
                 amount: coin.amount,
                 recipient: None,
+                on_behalf_of: None,
             })?,
             funds: vec![],
         }))
@@ -70,6 +71,18 @@ impl RedBank {
         }))
     }
 
+    /// Generate message for writing off a specified amount of bad debt
+    pub fn write_off_bad_debt_msg(&self, coin: &Coin) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.addr.to_string(),
+            msg: to_json_binary(&red_bank::ExecuteMsg::WriteOffCreditManagerBadDebt {
+                denom: coin.denom.to_string(),
+                amount: coin.amount,
+            })?,
+            funds: vec![],
+        }))
+    }
+
     /// Generate message for lending a specified amount of coin
     pub fn lend_msg(&self, coin: &Coin, account_id: &str) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -40,6 +40,27 @@ pub enum ExecuteMsg {
         trigger_order_id: String,
     },
 
+    /// Moves the mark of a trigger order with a `TrailingOraclePrice` condition to the current
+    /// oracle price, if the price moved in favour of the order. Callable by anyone (keepers).
+    RefreshTrailingMark {
+        account_id: String,
+        trigger_order_id: String,
+    },
+
+    /// Removes a trigger order whose `expires_at` has passed. Callable by anyone. The keeper fee
+    /// is split between the caller (`pruner_fee_pct` from the keeper fee config) and the account.
+    PruneExpiredTriggerOrder {
+        account_id: String,
+        trigger_order_id: String,
+    },
+
+    /// Writes off the debt of an account left without any collateral after its liquidation.
+    /// Callable by anyone. The loss is realized in Red Bank.
+    WriteOffBadDebt {
+        account_id: String,
+        denom: String,
+    },
+
     //--------------------------------------------------------------------------------------------------
     // Privileged messages
     //--------------------------------------------------------------------------------------------------
@@ -69,6 +90,34 @@ pub enum ExecuteMsg {
         account_id: String,
         pnl: PnL,
     },
+
+    /// Credits the account with the margin left of an isolated-margin perp position closed by the
+    /// perps contract (liquidated or deleveraged). The margin is sent along with the message.
+    ReturnIsolatedMargin {
+        account_id: String,
+    },
+
+    /// This is part of filling perp limit orders by the perps contract.
+    ///
+    /// Executes the filled order like `Action::ExecutePerpOrder` on behalf of the account, with the
+    /// limit price as the worst acceptable execution price. The account's health is checked the
+    /// same way as for any other account update. The escrowed keeper fee is paid to the keeper.
+    FillPerpLimitOrder {
+        account_id: String,
+        denom: String,
+        size: Int128,
+        limit_price: Decimal,
+        reduce_only: Option<bool>,
+        keeper: String,
+        keeper_fee: Coin,
+    },
+
+    /// Credits the account with the escrowed keeper fee of a perp limit order removed by the perps
+    /// contract without being filled (cancelled, failed to fill or its position was closed).
+    RefundPerpLimitOrderFee {
+        account_id: String,
+        keeper_fee: Coin,
+    },
 }
 
 #[cw_serde]
@@ -145,7 +194,7 @@ pub enum Comparison {
 }
 
 impl Comparison {
-    pub fn is_met(&self, lhs: Decimal, rhs: Decimal) -> bool {
+    pub fn is_met<T: PartialOrd>(&self, lhs: T, rhs: T) -> bool {
         match self {
             Comparison::GreaterThan => lhs > rhs,
             Comparison::LessThan => lhs < rhs,
@@ -176,6 +225,25 @@ pub enum Condition {
         threshold: Decimal,
         comparison: Comparison,
     },
+    /// Trailing version of `OraclePrice`. On order creation the current oracle price is stored as
+    /// the mark, which is moved along with the price by `RefreshTrailingMark` (highest price seen
+    /// for `LessThan`, lowest price seen for `GreaterThan`). The condition is met once the price
+    /// retraces by more than `trailing_pct` from the mark:
+    /// - `LessThan`: price < mark * (1 - trailing_pct), e.g. trailing stop loss for a long
+    /// - `GreaterThan`: price > mark * (1 + trailing_pct), e.g. trailing stop loss for a short
+    ///
+    /// Only one trailing condition is allowed per trigger order.
+    TrailingOraclePrice {
+        denom: String,
+        trailing_pct: Decimal,
+        comparison: Comparison,
+    },
+    /// If the block time (in seconds) is above or below the specified timestamp, depending
+    /// on the comparison, the condition is met.
+    BlockTime {
+        timestamp: u64,
+        comparison: Comparison,
+    },
     /// If the other trigger_order is successfully executed, the condition is met.
     TriggerOrderExecuted {
         // When empty string is provided, a base order should be provided in the same tx.
@@ -233,11 +301,17 @@ pub enum Action {
     /// Note that size is signed
     ///     - to increase short or reduce long, use a negative value
     ///     - to reduce short or increase long, use a positive value
+    ///
+    /// If `limit_price` is set, the order reverts when the execution price (oracle price with
+    /// market impact applied) is worse than the limit: above it when increasing the size (buy),
+    /// below it when decreasing the size (sell). This is mostly useful for trigger orders, where
+    /// the execution happens at a later point in time.
     ExecutePerpOrder {
         denom: String,
         order_size: Int128,
         reduce_only: Option<bool>,
         order_type: Option<ExecutePerpOrderType>,
+        limit_price: Option<Decimal>,
     },
 
     /// Executes a perp order against the given market for the current position size to close the
@@ -246,12 +320,56 @@ pub enum Action {
         denom: String,
     },
 
+    /// Opens a perp position in isolated-margin mode. `margin` of the perps base denom (i.e. usdc)
+    /// is moved from the account to the position as its dedicated collateral, together with the
+    /// opening fee.
+    ///
+    /// The position is not part of the account's health. It is backed only by its margin and
+    /// liquidated on its own, so its losses can never exceed the margin. It is modified and closed
+    /// with `ExecutePerpOrder` and `ClosePerpPosition`, settling the PnL against the margin. When
+    /// closed, what is left of the margin is returned to the account.
+    OpenIsolatedPerpPosition {
+        denom: String,
+        order_size: Int128,
+        margin: Uint128,
+        limit_price: Option<Decimal>,
+    },
+
+    /// Moves `amount` of the perps base denom (i.e. usdc) from the account to the margin of an
+    /// isolated-margin perp position.
+    AddPerpIsolatedMargin {
+        denom: String,
+        amount: Uint128,
+    },
+
+    /// Place a limit order resting in the perps contract. Once the oracle price reaches
+    /// `limit_price` (at or below for buy orders, at or above for sell orders), anyone can fill
+    /// it, executing it like `ExecutePerpOrder` with `limit_price` as the worst acceptable
+    /// execution price. The opening fee is charged at execution.
+    /// The `keeper_fee` is escrowed and paid to whoever fills the order. It is refunded if the
+    /// order is cancelled, fails to fill or the position of the order's denom is closed.
+    PlacePerpLimitOrder {
+        denom: String,
+        order_size: Int128,
+        limit_price: Decimal,
+        reduce_only: Option<bool>,
+        keeper_fee: Coin,
+    },
+
+    /// Cancel a limit order resting in the perps contract. The keeper fee is refunded.
+    CancelPerpLimitOrder {
+        order_id: u64,
+    },
+
     /// Dispatch orders to be triggered under specified conditions.
+    /// If `expires_at` (block time in seconds) is set, the order can't be executed from that
+    /// moment on and can be pruned by anyone with `PruneExpiredTriggerOrder`.
     CreateTriggerOrder {
         actions: Vec<Action>,
         conditions: Vec<Condition>,
         keeper_fee: Coin,
         order_type: Option<CreateTriggerOrderType>,
+        expires_at: Option<u64>,
     },
 
     DeleteTriggerOrder {
@@ -280,6 +398,9 @@ pub enum Action {
         vault: VaultUnchecked,
     },
     /// Pay back debt of a liquidatable rover account for a via liquidating a specific type of the position.
+    /// If the account has perp positions and a perps liquidation target health factor is
+    /// configured, only the share of the perp positions needed to reach it is closed, and the debt
+    /// is not repaid unless closing all perps is not enough.
     Liquidate {
         /// The credit account id of the one with a liquidation threshold health factor 1 or below
         liquidatee_account_id: String,
@@ -290,6 +411,8 @@ pub enum Action {
     },
     /// Perform a swapper with an exact-in amount. Requires slippage allowance %.
     /// If `coin_in.amount: AccountBalance`, the accounts entire balance of `coin_in.denom` will be used.
+    /// When executed as part of a trigger order, `min_receive` is raised to the amount implied by
+    /// the oracle prices minus the max slippage, if that is higher.
     SwapExactIn {
         coin_in: ActionCoin,
         denom_out: String,
@@ -356,6 +479,18 @@ impl Action {
             Action::ClosePerpPosition {
                 ..
             } => true,
+            Action::OpenIsolatedPerpPosition {
+                ..
+            } => true,
+            Action::AddPerpIsolatedMargin {
+                ..
+            } => true,
+            Action::PlacePerpLimitOrder {
+                ..
+            } => true,
+            Action::CancelPerpLimitOrder {
+                ..
+            } => true,
             Action::RefundAllCoinBalances {} => true,
 
             // Forbidden actions
@@ -457,6 +592,12 @@ pub enum CallbackMsg {
         account_id: String,
         prev_health_state: HealthState,
     },
+    /// Re-record the debts of the account backed by its collaterals in isolation mode.
+    /// If `enforce_ceiling`, assert that the debt ceilings of those collaterals are not exceeded.
+    UpdateIsolatedDebts {
+        account_id: String,
+        enforce_ceiling: bool,
+    },
     /// Assert that the total deposit amounts of the given denoms across Red
     /// Bank and Rover do not exceed their respective deposit caps.
     AssertDepositCaps {
@@ -484,6 +625,7 @@ pub enum CallbackMsg {
         actions: Vec<Action>,
         conditions: Vec<Condition>,
         keeper_fee: Coin,
+        expires_at: Option<u64>,
     },
     // Deletes an accounts trigger order
     DeleteTriggerOrder {
@@ -516,12 +658,41 @@ pub enum CallbackMsg {
         denom: String,
         size: Int128,
         reduce_only: Option<bool>,
+        limit_price: Option<Decimal>,
     },
     /// Executes a perp order against the given market for the current position size.
     ClosePerpPosition {
         account_id: String,
         denom: String,
     },
+    /// Opens an isolated-margin perp position backed by the given margin.
+    OpenIsolatedPerpPosition {
+        account_id: String,
+        denom: String,
+        size: Int128,
+        margin: Uint128,
+        limit_price: Option<Decimal>,
+    },
+    /// Adds margin to an isolated-margin perp position.
+    AddPerpIsolatedMargin {
+        account_id: String,
+        denom: String,
+        amount: Uint128,
+    },
+    /// Places a limit order in the perps contract.
+    PlacePerpLimitOrder {
+        account_id: String,
+        denom: String,
+        size: Int128,
+        limit_price: Decimal,
+        reduce_only: Option<bool>,
+        keeper_fee: Coin,
+    },
+    /// Cancels a limit order in the perps contract.
+    CancelPerpLimitOrder {
+        account_id: String,
+        order_id: u64,
+    },
     /// Requests unlocking of shares for a vault with a lock period
     RequestVaultUnlock {
         account_id: String,
@@ -538,13 +709,24 @@ pub enum CallbackMsg {
     CloseAllPerps {
         account_id: String,
     },
+    /// Close a share of every cross-margin perp position of a liquidatable account, paying the
+    /// liquidator a bonus from the liquidatee's base denom balance
+    LiquidatePerps {
+        liquidator_account_id: String,
+        liquidatee_account_id: String,
+        close_ratio: Decimal,
+        liquidator_bonus: Coin,
+        protocol_fee: Coin,
+    },
     /// Pay back debts of a liquidatable rover account for a bonus
     Liquidate {
         liquidator_account_id: String,
         liquidatee_account_id: String,
         debt_coin: Coin,
         request: LiquidateRequest<Vault>,
-        prev_health: HealthValuesResponse,
+        /// Health of the liquidatee before the liquidation. `None` if a share of its perp
+        /// positions was liquidated first, in which case it is queried again.
+        prev_health: Option<HealthValuesResponse>,
     },
     /// Perform a swapper with an exact-in amount. Requires slippage allowance %.
     /// If `coin_in.amount: AccountBalance`, the accounts entire balance of `coin_in.denom` will be used.
@@ -554,6 +736,9 @@ pub enum CallbackMsg {
         denom_out: String,
         min_receive: Uint128,
         route: Option<SwapperRoute>,
+        /// Raise `min_receive` to the amount implied by the oracle prices minus the max slippage,
+        /// computed on the amount actually swapped (used for trigger orders)
+        enforce_oracle_min_receive: bool,
     },
     /// Used to update the coin balance of account after an async action
     UpdateCoinBalance {
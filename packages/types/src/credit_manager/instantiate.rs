@@ -51,6 +51,13 @@ pub struct InstantiateMsg {
     /// PnL loss as follows:
     /// `bonus applied to liquidation = perps_liquidation_bonus_ratio * original LB * PnL loss`
     pub perps_liquidation_bonus_ratio: Decimal,
+    /// The Health Factor (HF) a liquidated account with perps positions should be left at.
+    /// If set, liquidation closes only the share of every perp position needed to bring the
+    /// account to this HF, with a liquidator bonus proportional to the size closed. The spot
+    /// positions are liquidated only if closing all perps is not enough.
+    /// If not set, all perps positions are closed before liquidating the spot positions.
+    #[serde(default)]
+    pub perps_liquidation_target_health_factor: Option<Decimal>,
     /// The swap fee applied to each swap. This is a percentage of the swap amount.
     /// For example, if set to 0.0001, 0.01% of the swap amount will be taken as a fee.
     /// This fee is applied once, no matter how many hops in the route
@@ -82,6 +89,7 @@ pub struct ConfigUpdates {
     pub perps: Option<PerpsUnchecked>,
     pub keeper_fee_config: Option<KeeperFeeConfig>,
     pub perps_liquidation_bonus_ratio: Option<Decimal>,
+    pub perps_liquidation_target_health_factor: Option<Decimal>,
     pub swap_fee: Option<Decimal>,
     // Staking-based fee tiers
     pub fee_tier_config: Option<FeeTierConfig>,
@@ -108,6 +108,19 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Return the mark of a trigger order with a `TrailingOraclePrice` condition.
+    #[returns(Option<TrailingMarkResponse>)]
+    TrailingMark {
+        account_id: String,
+        trigger_order_id: String,
+    },
+    /// Return the marks of all trigger orders with a `TrailingOraclePrice` condition for an account.
+    #[returns(cw_paginate::PaginationResponse<TrailingMarkResponse>)]
+    AllAccountTrailingMarks {
+        account_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     /// Enumerate all vault bindings; start_after accepts account_id
     #[returns(Vec<VaultBinding>)]
@@ -134,6 +147,12 @@ pub enum QueryMsg {
 
     #[returns(FeeTierConfigResponse)]
     FeeTierConfig {},
+
+    /// Debts of the accounts backed by the given isolated collateral, interest included
+    #[returns(Vec<Coin>)]
+    IsolatedDebts {
+        denom: String,
+    },
 }
 
 #[cw_serde]
@@ -205,6 +224,10 @@ pub struct DebtAmount {
 #[derive(Default)]
 pub struct KeeperFeeConfig {
     pub min_fee: Coin,
+    /// Share of the keeper fee paid to whoever prunes an expired trigger order.
+    /// The rest is refunded to the account.
+    #[serde(default)]
+    pub pruner_fee_pct: Decimal,
 }
 
 #[cw_serde]
@@ -213,6 +236,8 @@ pub struct TriggerOrder {
     pub actions: Vec<Action>,
     pub conditions: Vec<Condition>,
     pub keeper_fee: Coin,
+    /// Block time (in seconds) from which the order can't be executed anymore
+    pub expires_at: Option<u64>,
 }
 
 impl Coins for Vec<DebtAmount> {
@@ -250,6 +275,17 @@ pub struct TriggerOrderResponse {
     pub order: TriggerOrder,
 }
 
+#[cw_serde]
+pub struct TrailingMarkResponse {
+    pub account_id: String,
+    pub order_id: String,
+    pub denom: String,
+    /// Highest (`LessThan`) or lowest (`GreaterThan`) oracle price seen since the order creation
+    pub mark: Decimal,
+    /// Price at which the `TrailingOraclePrice` condition is met
+    pub trigger_price: Decimal,
+}
+
 #[cw_serde]
 pub struct VaultWithBalance {
     pub vault: Vault,
@@ -273,6 +309,7 @@ pub struct ConfigResponse {
     pub rewards_collector: Option<RewardsCollector>,
     pub keeper_fee_config: KeeperFeeConfig,
     pub perps_liquidation_bonus_ratio: Decimal,
+    pub perps_liquidation_target_health_factor: Option<Decimal>,
 }
 
 #[cw_serde]
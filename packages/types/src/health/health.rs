@@ -32,6 +32,12 @@ pub struct Health {
     /// If the account has perps positions.
     /// `perps_pnl_profit` and `perps_pnl_loss` could be zero even with perps (`BreakEven` case).
     pub has_perps: bool,
+    /// The part of the liquidation health factor numerator coming from cross-margin perp positions
+    #[serde(default)]
+    pub perps_liq_ltv_numerator: Uint128,
+    /// The part of the liquidation health factor denominator coming from cross-margin perp positions
+    #[serde(default)]
+    pub perps_liq_ltv_denominator: Uint128,
 }
 
 impl fmt::Display for Health {
@@ -83,6 +89,10 @@ pub struct HealthValuesResponse {
     pub liquidatable: bool,
     pub above_max_ltv: bool,
     pub has_perps: bool,
+    #[serde(default)]
+    pub perps_liq_ltv_numerator: Uint128,
+    #[serde(default)]
+    pub perps_liq_ltv_denominator: Uint128,
 }
 
 impl From<Health> for HealthValuesResponse {
@@ -99,6 +109,8 @@ impl From<Health> for HealthValuesResponse {
             liquidatable: h.is_liquidatable(),
             above_max_ltv: h.is_above_max_ltv(),
             has_perps: h.has_perps,
+            perps_liq_ltv_numerator: h.perps_liq_ltv_numerator,
+            perps_liq_ltv_denominator: h.perps_liq_ltv_denominator,
         }
     }
 }
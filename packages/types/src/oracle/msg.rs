@@ -18,6 +18,39 @@ pub struct Config {
     pub base_denom: String,
 }
 
+/// Protection against a price moving too much in a short period of time, e.g. because of a
+/// manipulated or glitching price source
+#[cw_serde]
+pub struct CircuitBreaker {
+    /// The maximum change (percentage) of the price relative to the last accepted price
+    pub max_price_change: Decimal,
+    /// The period in seconds over which the price may change by at most `max_price_change`
+    pub window: u64,
+}
+
+/// Price accepted by a circuit breaker, used as the reference for the following prices
+#[cw_serde]
+pub struct AcceptedPrice {
+    pub price: Decimal,
+    pub timestamp: u64,
+}
+
+/// Configuration of the price history of a coin
+#[cw_serde]
+pub struct PriceHistoryConfig {
+    /// The maximum number of prices kept. Once reached, the oldest price is overwritten.
+    pub capacity: u32,
+    /// The minimum number of seconds between two recorded prices
+    pub min_interval: u64,
+}
+
+/// Price recorded in the price history of a coin
+#[cw_serde]
+pub struct PriceSample {
+    pub price: Decimal,
+    pub timestamp: u64,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg<T, C = Empty> {
     /// Specify the price source to be used for a coin
@@ -37,6 +70,47 @@ pub enum ExecuteMsg<T, C = Empty> {
     UpdateConfig {
         base_denom: Option<String>,
     },
+    /// Set the keeper allowed to record prices alongside the owner, or remove it if `None` (only
+    /// callable by owner)
+    SetKeeper {
+        keeper: Option<String>,
+    },
+    /// Set the circuit breaker of a coin, or remove it if `None` (only callable by owner)
+    SetCircuitBreaker {
+        denom: String,
+        circuit_breaker: Option<CircuitBreaker>,
+    },
+    /// Record the current prices of coins as the reference prices of their circuit breakers
+    /// (only callable by owner or keeper). The reference price of a coin moves at most once per
+    /// window. Coins whose price moved more than allowed since the last accepted price are skipped.
+    RecordCircuitBreakerPrices {
+        denoms: Vec<String>,
+    },
+    /// Accept the current price of a coin as the reference price of its circuit breaker, even if
+    /// it moved more than allowed (only callable by owner)
+    ResetCircuitBreaker {
+        denom: String,
+    },
+    /// Enable or reconfigure the price history of a coin, or disable it and delete the recorded
+    /// prices if `None` (only callable by owner). Reconfiguring keeps the most recent prices that
+    /// fit in the new capacity.
+    SetPriceHistory {
+        denom: String,
+        config: Option<PriceHistoryConfig>,
+    },
+    /// Record the current prices of coins in their price history (only callable by owner or
+    /// keeper). Coins without price history, whose last price was recorded less than
+    /// `min_interval` seconds ago, or whose price can't be queried, are skipped.
+    RecordPrices {
+        denoms: Vec<String>,
+    },
+    /// Record the current share prices of the Mars managed vaults used by the price sources of
+    /// coins, bounding the share prices of their `MarsManagedVaultShare` price sources (only
+    /// callable by owner or keeper). The first share price is recorded when the price source is
+    /// set. Vaults whose share price can't be queried are skipped.
+    RecordManagedVaultSharePrices {
+        denoms: Vec<String>,
+    },
     /// Custom messages defined by the contract
     Custom(C),
 }
@@ -103,6 +177,31 @@ pub enum QueryMsg {
     HasPriceSource {
         denom: String,
     },
+
+    /// Query the circuit breaker of a coin along with its last accepted price.
+    #[returns(CircuitBreakerResponse)]
+    CircuitBreaker {
+        denom: String,
+    },
+
+    /// Query the last price of a coin recorded at or before `timestamp` in its price history.
+    ///
+    /// Unlike `Price`, it can be used to check the price of a coin at a past block (e.g. to audit
+    /// a liquidation), as long as it hasn't been overwritten yet.
+    #[returns(PriceSample)]
+    PriceAt {
+        denom: String,
+        timestamp: u64,
+    },
+
+    /// Enumerate the recorded prices of a coin, from the oldest to the most recent.
+    #[returns(PriceHistoryResponse)]
+    PriceHistory {
+        denom: String,
+        /// Only return prices recorded after this timestamp
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -113,6 +212,8 @@ pub struct ConfigResponse {
     pub proposed_new_owner: Option<String>,
     /// The asset in which prices are denominated in
     pub base_denom: String,
+    /// The keeper allowed to record prices alongside the owner
+    pub keeper: Option<String>,
 }
 
 #[cw_serde]
@@ -127,10 +228,28 @@ pub struct HasPriceSourceResponse {
     pub has_price_source: bool,
 }
 
+#[cw_serde]
+pub struct CircuitBreakerResponse {
+    pub denom: String,
+    pub circuit_breaker: CircuitBreaker,
+    pub last_accepted_price: Option<AcceptedPrice>,
+}
+
+#[cw_serde]
+pub struct PriceHistoryResponse {
+    pub denom: String,
+    pub config: PriceHistoryConfig,
+    pub prices: Vec<PriceSample>,
+}
+
 #[cw_serde]
 pub struct PriceResponse {
     pub denom: String,
     pub price: Decimal,
+    /// Whether the price was provided by the fallback of the denom's price source, because the
+    /// primary source was unavailable
+    #[serde(default)]
+    pub fallback_used: bool,
 }
 
 #[cw_serde]
@@ -29,6 +29,49 @@ pub struct RedBankSettings {
     pub withdraw_enabled: bool,
 }
 
+/// Isolation mode of a collateral asset. An isolated asset can only back debts of the allow-listed
+/// denoms, and only up to the debt ceiling.
+#[cw_serde]
+pub struct IsolationParams {
+    /// Denoms that can be borrowed against the isolated asset
+    pub borrowable_denoms: Vec<String>,
+    /// Maximum total value of debt, in the oracle base denom, the isolated asset can back across
+    /// all positions in the Red Bank and the Credit Manager. Each position counts the portion of
+    /// its debts, interest included, that the isolated asset makes up of its max LTV adjusted
+    /// collateral. Enforced whenever a position's portion goes up.
+    pub debt_ceiling: Uint128,
+}
+
+impl IsolationParams {
+    /// Whether all the given debt denoms can be backed by the isolated asset
+    pub fn can_back(&self, debt_denoms: &[&str]) -> bool {
+        debt_denoms.iter().all(|denom| self.borrowable_denoms.iter().any(|d| d == denom))
+    }
+}
+
+/// Fixed-rate term borrowing of an asset in the Red Bank
+#[cw_serde]
+pub struct TermLoanParams {
+    /// Terms a loan can be borrowed for
+    pub terms: Vec<LoanTerm>,
+    /// Portion of the amount owed charged when a loan is repaid before maturity
+    pub early_repay_penalty: Decimal,
+}
+
+#[cw_serde]
+pub struct LoanTerm {
+    /// Duration (seconds) of the term
+    pub duration: u64,
+    /// Premium added to the variable borrow rate to get the fixed rate of the term
+    pub rate_premium: Decimal,
+}
+
+impl TermLoanParams {
+    pub fn term(&self, duration: u64) -> Option<&LoanTerm> {
+        self.terms.iter().find(|term| term.duration == duration)
+    }
+}
+
 /// The LB will depend on the Health Factor and a couple other parameters as follows:
 /// Liquidation Bonus = min(
 ///     b + (slope * (1 - HF)),
@@ -73,9 +116,30 @@ pub struct AssetParamsBase<T> {
     pub liquidation_bonus: LiquidationBonus,
     pub protocol_liquidation_fee: Decimal,
     pub deposit_cap: Uint128,
+    /// Maximum total amount of the asset that can be borrowed from the Red Bank, including the
+    /// debt of the Credit Manager. Not capped if missing from the stored params.
+    #[serde(default = "default_borrow_cap")]
+    pub borrow_cap: Uint128,
     pub close_factor: Decimal,
     pub reserve_factor: Decimal,
     pub interest_rate_model: InterestRateModel,
+    /// Haircut applied to the oracle value of the asset when it is deposited into the perps vault.
+    /// None if the asset can't be deposited into the perps vault.
+    #[serde(default)]
+    pub perps_vault_haircut: Option<Decimal>,
+    /// Fee charged on flash loans of the asset from the Red Bank, as a portion of the borrowed amount.
+    #[serde(default)]
+    pub flash_loan_fee_rate: Decimal,
+    /// Isolation mode of the asset as collateral. None if the asset is not isolated.
+    #[serde(default)]
+    pub isolation: Option<IsolationParams>,
+    /// Fixed-rate term borrowing of the asset from the Red Bank. None if not offered.
+    #[serde(default)]
+    pub term_loans: Option<TermLoanParams>,
+}
+
+fn default_borrow_cap() -> Uint128 {
+    Uint128::MAX
 }
 
 pub type AssetParams = AssetParamsBase<Addr>;
@@ -96,9 +160,14 @@ impl From<AssetParams> for AssetParamsUnchecked {
             liquidation_bonus: p.liquidation_bonus,
             protocol_liquidation_fee: p.protocol_liquidation_fee,
             deposit_cap: p.deposit_cap,
+            borrow_cap: p.borrow_cap,
             close_factor: p.close_factor,
             reserve_factor: p.reserve_factor,
             interest_rate_model: p.interest_rate_model,
+            perps_vault_haircut: p.perps_vault_haircut,
+            flash_loan_fee_rate: p.flash_loan_fee_rate,
+            isolation: p.isolation,
+            term_loans: p.term_loans,
         }
     }
 }
@@ -122,6 +191,34 @@ impl AssetParamsUnchecked {
             assert_hls_lqt_gt_max_ltv(hls.max_loan_to_value, hls.liquidation_threshold)?;
         }
 
+        decimal_param_lt_one(self.flash_loan_fee_rate, "flash_loan_fee_rate")?;
+
+        if let Some(haircut) = self.perps_vault_haircut {
+            decimal_param_lt_one(haircut, "perps_vault_haircut")?;
+        }
+
+        if let Some(isolation) = self.isolation.as_ref() {
+            for denom in &isolation.borrowable_denoms {
+                validate_native_denom(denom)?;
+            }
+        }
+
+        if let Some(term_loans) = self.term_loans.as_ref() {
+            decimal_param_lt_one(term_loans.early_repay_penalty, "early_repay_penalty")?;
+            for (i, term) in term_loans.terms.iter().enumerate() {
+                if term.duration == 0
+                    || term_loans.terms[..i].iter().any(|t| t.duration == term.duration)
+                {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "term_duration".to_string(),
+                        invalid_value: term.duration.to_string(),
+                        predicate: "> 0 and unique".to_string(),
+                    }
+                    .into());
+                }
+            }
+        }
+
         let hls = self.credit_manager.hls.as_ref().map(|hls| hls.check(api)).transpose()?;
 
         Ok(AssetParams {
@@ -137,9 +234,14 @@ impl AssetParamsUnchecked {
             liquidation_bonus: self.liquidation_bonus.clone(),
             protocol_liquidation_fee: self.protocol_liquidation_fee,
             deposit_cap: self.deposit_cap,
+            borrow_cap: self.borrow_cap,
             close_factor: self.close_factor,
             reserve_factor: self.reserve_factor,
             interest_rate_model: self.interest_rate_model.clone(),
+            perps_vault_haircut: self.perps_vault_haircut,
+            flash_loan_fee_rate: self.flash_loan_fee_rate,
+            isolation: self.isolation.clone(),
+            term_loans: self.term_loans.clone(),
         })
     }
 }
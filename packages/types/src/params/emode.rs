@@ -0,0 +1,54 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal;
+use mars_utils::{
+    error::ValidationError,
+    helpers::{decimal_param_le_one, decimal_param_lt_one, validate_native_denom},
+};
+
+use super::assertions::assert_lqt_gt_max_ltv;
+
+/// Efficiency mode (e-mode) category: a named set of correlated denoms with elevated risk params.
+///
+/// A Red Bank user who opted into the category gets its `max_loan_to_value` and
+/// `liquidation_threshold` for the collaterals belonging to it, as long as every debt of the
+/// position belongs to the category as well.
+#[cw_serde]
+pub struct EModeCategory {
+    pub name: String,
+    pub denoms: Vec<String>,
+    pub max_loan_to_value: Decimal,
+    pub liquidation_threshold: Decimal,
+}
+
+impl EModeCategory {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.name.is_empty() {
+            return Err(ValidationError::InvalidParam {
+                param_name: "name".to_string(),
+                invalid_value: self.name.clone(),
+                predicate: "not empty".to_string(),
+            });
+        }
+
+        for denom in &self.denoms {
+            validate_native_denom(denom)?;
+        }
+
+        decimal_param_lt_one(self.max_loan_to_value, "max_loan_to_value")?;
+        decimal_param_le_one(self.liquidation_threshold, "liquidation_threshold")?;
+        assert_lqt_gt_max_ltv(self.max_loan_to_value, self.liquidation_threshold)?;
+
+        Ok(())
+    }
+
+    pub fn contains(&self, denom: &str) -> bool {
+        self.denoms.iter().any(|d| d == denom)
+    }
+}
+
+#[cw_serde]
+pub enum EModeCategoryUpdate {
+    AddOrUpdate {
+        category: EModeCategory,
+    },
+}
@@ -1,11 +1,13 @@
 mod assertions;
 mod asset;
+mod emode;
 mod hls;
 mod msg;
 mod perp;
 mod vault;
 
 pub use asset::*;
+pub use emode::*;
 pub use hls::*;
 pub use msg::*;
 pub use perp::*;
@@ -1,8 +1,11 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Coin, Decimal, Uint128};
 use mars_owner::OwnerUpdate;
 
-use super::{asset::AssetParamsUnchecked, vault::VaultConfigUnchecked, PerpParams};
+use super::{
+    asset::AssetParamsUnchecked, emode::EModeCategoryUpdate, vault::VaultConfigUnchecked,
+    PerpParams,
+};
 use crate::red_bank::InterestRateModel;
 
 #[cw_serde]
@@ -29,6 +32,7 @@ pub enum ExecuteMsg {
     UpdateAssetParams(AssetParamsUpdate),
     UpdateVaultConfig(VaultConfigUpdate),
     UpdatePerpParams(PerpParamsUpdate),
+    UpdateEModeCategory(EModeCategoryUpdate),
     EmergencyUpdate(EmergencyUpdate),
     UpdateManagedVaultConfig(ManagedVaultConfigUpdate),
 }
@@ -100,6 +104,17 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    #[returns(Option<super::emode::EModeCategory>)]
+    EModeCategory {
+        name: String,
+    },
+
+    #[returns(Vec<super::emode::EModeCategory>)]
+    AllEModeCategories {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
     /// Compute the total amount deposited of the given asset across Red Bank
     /// and Credit Manager.
     #[returns(TotalDepositResponse)]
@@ -114,6 +129,20 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+
+    /// Compute the total amount borrowed of the given asset from Red Bank,
+    /// including the debt of Credit Manager.
+    #[returns(TotalBorrowResponse)]
+    TotalBorrow {
+        denom: String,
+    },
+
+    /// Compute the total debt backed by the given isolated collateral across Red Bank
+    /// and Credit Manager.
+    #[returns(TotalIsolatedDebtResponse)]
+    TotalIsolatedDebt {
+        denom: String,
+    },
 }
 
 #[cw_serde]
@@ -141,6 +170,28 @@ pub struct TotalDepositResponse {
     pub amount: Uint128,
 }
 
+#[cw_serde]
+pub struct TotalBorrowResponse {
+    pub denom: String,
+    pub cap: Uint128,
+    pub amount: Uint128,
+    /// Amount that can still be borrowed before reaching the cap
+    pub remaining: Uint128,
+}
+
+#[cw_serde]
+pub struct TotalIsolatedDebtResponse {
+    /// Denom of the isolated collateral
+    pub denom: String,
+    /// Debt ceiling of the isolated collateral, in the oracle base denom.
+    /// Zero if the asset is not isolated.
+    pub ceiling: Uint128,
+    /// Debts backed by the isolated collateral
+    pub debts: Vec<Coin>,
+    /// Value of the debts, in the oracle base denom
+    pub value: Uint128,
+}
+
 #[cw_serde]
 pub enum AssetParamsUpdate {
     AddOrUpdate {
@@ -174,6 +225,7 @@ pub enum CmEmergencyUpdate {
 pub enum RedBankEmergencyUpdate {
     DisableBorrowing(String),
     DisableWithdraw(String),
+    SetZeroBorrowCap(String),
 }
 
 #[cw_serde]
@@ -209,4 +261,5 @@ pub enum MigrateMsg {
         reserve_factor: Decimal,
         interest_rate_model: InterestRateModel,
     },
+    V2_4_0 {},
 }
@@ -10,7 +10,7 @@ use cosmwasm_std::{
 use mars_owner::OwnerUpdate;
 use thiserror::Error;
 
-use crate::{error::MarsError, oracle::ActionKind, params::PerpParams};
+use crate::{error::MarsError, oracle::ActionKind, params::PerpParams, swapper::SwapperRoute};
 
 // ------------------------------- message types -------------------------------
 
@@ -48,6 +48,11 @@ pub struct Config<T> {
     /// The percentage of fees that is directed to the protocol
     pub protocol_fee_rate: Decimal,
 
+    /// The percentage of fees that is directed to the insurance fund of the market the fee is
+    /// charged in. The insurance fund absorbs bad debt before the vault does.
+    #[serde(default)]
+    pub insurance_fund_fee_rate: Decimal,
+
     /// The target collateralization ratio of the vault
     pub target_vault_collateralization_ratio: Decimal,
 
@@ -79,6 +84,7 @@ impl Config<String> {
             cooldown_period: self.cooldown_period,
             max_positions: self.max_positions,
             protocol_fee_rate: self.protocol_fee_rate,
+            insurance_fund_fee_rate: self.insurance_fund_fee_rate,
             target_vault_collateralization_ratio: self.target_vault_collateralization_ratio,
             deleverage_enabled: self.deleverage_enabled,
             vault_withdraw_enabled: self.vault_withdraw_enabled,
@@ -95,6 +101,7 @@ impl From<Config<Addr>> for Config<String> {
             cooldown_period: cfg.cooldown_period,
             max_positions: cfg.max_positions,
             protocol_fee_rate: cfg.protocol_fee_rate,
+            insurance_fund_fee_rate: cfg.insurance_fund_fee_rate,
             target_vault_collateralization_ratio: cfg.target_vault_collateralization_ratio,
             deleverage_enabled: cfg.deleverage_enabled,
             vault_withdraw_enabled: cfg.vault_withdraw_enabled,
@@ -109,6 +116,7 @@ pub struct ConfigUpdates {
     pub cooldown_period: Option<u64>,
     pub max_positions: Option<u8>,
     pub protocol_fee_rate: Option<Decimal>,
+    pub insurance_fund_fee_rate: Option<Decimal>,
     pub target_vault_collateralization_ratio: Option<Decimal>,
     pub deleverage_enabled: Option<bool>,
     pub vault_withdraw_enabled: Option<bool>,
@@ -156,13 +164,21 @@ pub struct VaultResponse {
     /// See [`Accounting`] for more details regarding the calculation of `accounting.withdrawal_balance.total`.
     pub total_withdrawal_balance: Uint128,
 
-    /// Vault share price is calculated directly from the total withdrawal balance and the shares supply.
-    /// `share_price = total_withdrawal_balance / total_shares`
+    /// Non-base collateral deposited to the vault by liquidity providers.
+    pub collaterals: Vec<Coin>,
+
+    /// Value of the non-base collateral in the base denom, after applying the haircuts of the
+    /// collateral denoms.
+    pub total_collateral_value: Uint128,
+
+    /// Vault share price is calculated directly from the total vault value and the shares supply.
+    /// `share_price = (total_withdrawal_balance + total_collateral_value) / total_shares`
     /// None if `total_shares` is zero.
     pub share_price: Option<Decimal>,
 
-    /// Total liquidity in the base denom aggregated across all markets.
-    /// `total_liquidity = max(total_balance + accounting.cash_flow.total, 0)`
+    /// Total liquidity in the base denom aggregated across all markets, including the value of the
+    /// non-base collateral.
+    /// `total_liquidity = max(total_balance + accounting.cash_flow.total, 0) + total_collateral_value`
     /// See [`Accounting`] for more details regarding the calculation of `accounting.cash_flow.total`.
     pub total_liquidity: Uint128,
 
@@ -367,6 +383,11 @@ pub struct Position {
     pub entry_accrued_funding_per_unit_in_base_denom: SignedDecimal,
     pub initial_skew: Int128,
     pub realized_pnl: PnlAmounts,
+
+    /// Collateral dedicated to the position, denominated in the base denom.
+    /// `None` for cross-margin positions, which are backed by the whole credit account.
+    #[serde(default)]
+    pub isolated_margin: Option<Uint128>,
 }
 
 /// This is the position data to be returned in a query. It includes current
@@ -382,6 +403,57 @@ pub struct PerpPosition {
     pub current_exec_price: Decimal,
     pub unrealized_pnl: PnlAmounts,
     pub realized_pnl: PnlAmounts,
+    #[serde(default)]
+    pub isolated_margin: Option<Uint128>,
+}
+
+/// An order resting in the perps contract until the oracle price reaches its limit price.
+/// Buy orders (positive size) are filled when the price is at or below the limit price, sell
+/// orders (negative size) when it is at or above.
+#[cw_serde]
+pub struct LimitOrder {
+    pub order_id: u64,
+    pub account_id: String,
+    pub denom: String,
+    pub size: Int128,
+    pub limit_price: Decimal,
+    pub reduce_only: Option<bool>,
+    pub created_at: u64,
+    /// Fee escrowed by the credit manager, paid to whoever fills the order. It is refunded to the
+    /// account if the order is cancelled or dropped.
+    pub keeper_fee: Coin,
+}
+
+/// Funding state of a market recorded on every market update.
+#[cw_serde]
+pub struct FundingSnapshot {
+    /// Block time at which the market was updated
+    pub timestamp: u64,
+
+    /// Funding rate (24-hour rate) at the time of the update
+    pub funding_rate: SignedDecimal,
+
+    /// Funding accrued per unit at the time of the update
+    pub funding_accrued_per_unit_in_base_denom: SignedDecimal,
+
+    /// Time integral of the funding rate since the first snapshot of the market, in days.
+    /// The average funding rate between two points in time is the difference of their
+    /// cumulative funding rates divided by the number of days elapsed.
+    pub cumulative_funding_rate: SignedDecimal,
+}
+
+/// Bad debt of a position realized in a market, and the part of it covered by the market's
+/// insurance fund. The rest is absorbed by the vault.
+#[cw_serde]
+pub struct InsuranceFundDraw {
+    pub draw_id: u64,
+    pub account_id: String,
+    /// Loss exceeding the margin of an isolated-margin position, or the collateral of a credit
+    /// account for cross-margin positions
+    pub bad_debt: Uint128,
+    /// Amount drawn from the insurance fund
+    pub amount: Uint128,
+    pub timestamp: u64,
 }
 
 /// The profit-and-loss of a perp position, denominated in the base currency.
@@ -538,7 +610,8 @@ pub enum ExecuteMsg {
 
     /// Provide liquidity of the base token to the vault.
     ///
-    /// Must send exactly one coin of `base_denom`.
+    /// Must send exactly one coin of `base_denom`, or of a collateral denom whitelisted for the
+    /// vault with a haircut in the asset params.
     ///
     /// The deposited tokens will be used to settle perp trades. liquidity
     /// providers win if traders have negative PnLs, or loss if traders have
@@ -597,10 +670,24 @@ pub enum ExecuteMsg {
 
         // Discount percentage to apply to trading fees based on staking tier
         discount_pct: Option<Decimal>,
+
+        // Worst acceptable execution price (with market impact applied). Orders increasing the
+        // size (buy) revert if executed above it, orders decreasing the size (sell) revert if
+        // executed below it.
+        limit_price: Option<Decimal>,
+
+        // Opens the position in isolated-margin mode with this amount of base denom as its
+        // dedicated collateral, sent along with the opening fee. Only valid for new positions.
+        isolated_margin: Option<Uint128>,
+
+        // Pricing of the order, `ActionKind::Default` if not provided. `ActionKind::Liquidation`
+        // is used by the credit manager to partially liquidate positions and is only allowed for
+        // reduce-only orders.
+        action: Option<ActionKind>,
     },
 
-    /// Close all perp positions. Use this to liquidate a user's credit account.
-    ///
+    /// Close all cross-margin perp positions. Use this to liquidate a user's credit account.
+    /// Isolated-margin positions are not affected.
     ///
     /// Only callable by Rover credit manager.
     CloseAllPositions {
@@ -608,6 +695,10 @@ pub enum ExecuteMsg {
         action: Option<ActionKind>,
         // Discount percentage to apply to trading fees based on staking tier
         discount_pct: Option<Decimal>,
+        // Part of the loss the credit account can't back with its collateral. It is not paid by
+        // the credit manager, but absorbed by the insurance funds of the losing markets and then
+        // by the vault.
+        bad_debt: Option<Uint128>,
     },
 
     /// Deleveraging a vault by closing a position for an account.
@@ -621,6 +712,66 @@ pub enum ExecuteMsg {
         denom: String,
     },
 
+    /// Place a limit order resting in the market until the oracle price reaches `limit_price`.
+    /// The order is executed as a regular order through the credit manager once filled.
+    ///
+    /// Only callable by Rover credit manager.
+    PlaceLimitOrder {
+        account_id: String,
+        denom: String,
+
+        // The amount of size to execute against the position once the order is filled.
+        // Positive numbers are buy orders, negative numbers are sell orders.
+        size: Int128,
+
+        // Buy orders are filled at or below, sell orders at or above this price. It is also used
+        // as the worst acceptable execution price of the order.
+        limit_price: Decimal,
+
+        reduce_only: Option<bool>,
+
+        // Fee escrowed by the credit manager for the keeper filling the order
+        keeper_fee: Coin,
+    },
+
+    /// Cancel a resting limit order.
+    ///
+    /// Only callable by Rover credit manager.
+    CancelLimitOrder {
+        account_id: String,
+        order_id: u64,
+    },
+
+    /// Add collateral to an isolated-margin position.
+    ///
+    /// Must send exactly one coin of `base_denom`. Only callable by Rover credit manager.
+    AddIsolatedMargin {
+        account_id: String,
+        denom: String,
+    },
+
+    /// Close an isolated-margin position whose margin no longer covers its liquidation
+    /// requirement. Callable by anyone.
+    ///
+    /// The PnL is settled against the position's margin. The sender is paid a liquidation bonus
+    /// out of what is left of it, the rest is returned to the credit account. Losses exceeding
+    /// the margin are borne by the vault.
+    LiquidateIsolatedPosition {
+        account_id: String,
+        denom: String,
+    },
+
+    /// Fill resting limit orders of a market whose limit price is satisfied by the current
+    /// oracle price. Callable by anyone (keepers).
+    ///
+    /// Each matched order is removed from the book and executed through the credit manager,
+    /// which charges the opening fee, checks the account's health as for a market order and pays
+    /// the keeper fee of the order to the sender. Orders whose execution fails are dropped and
+    /// their keeper fee is refunded to the account.
+    FillLimitOrders {
+        denom: String,
+    },
+
     /// Receive updated parameters from the params contract
     UpdateMarket {
         params: PerpParams,
@@ -630,6 +781,17 @@ pub enum ExecuteMsg {
     UpdateConfig {
         updates: ConfigUpdates,
     },
+
+    /// Swap non-base collateral held by the vault to the base denom, so it can be used to pay
+    /// the PnL of traders. Only callable by the owner.
+    ///
+    /// The swap must return at least the haircut value of the swapped collateral, so the value
+    /// of the vault shares doesn't decrease.
+    SwapVaultCollateral {
+        denom: String,
+        amount: Uint128,
+        route: Option<SwapperRoute>,
+    },
 }
 
 #[cw_serde]
@@ -686,6 +848,7 @@ pub enum QueryMsg {
         denom: String,
         order_size: Option<Int128>,
         reduce_only: Option<bool>,
+        action: Option<ActionKind>,
     },
 
     /// List positions of all accounts and denoms.
@@ -737,6 +900,64 @@ pub enum QueryMsg {
         denom: String,
         new_size: Int128,
     },
+
+    /// Query a single resting limit order.
+    #[returns(Option<LimitOrder>)]
+    LimitOrder {
+        order_id: u64,
+    },
+
+    /// List resting limit orders of all accounts.
+    #[returns(Vec<LimitOrder>)]
+    LimitOrders {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// List resting limit orders that belong to a specific credit account.
+    #[returns(Vec<LimitOrder>)]
+    LimitOrdersByAccount {
+        account_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// List funding snapshots of a market, ordered by timestamp.
+    ///
+    /// Only the most recent snapshots are kept, older ones are dropped.
+    #[returns(Vec<FundingSnapshot>)]
+    FundingHistory {
+        denom: String,
+        /// Timestamp to start after
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Query the time-weighted average funding rate of a market over the window [start, end].
+    /// If `end` is not provided, the current block time is used.
+    ///
+    /// The window has to be covered by the funding history of the market.
+    #[returns(AverageFundingRateResponse)]
+    AverageFundingRate {
+        denom: String,
+        start: u64,
+        end: Option<u64>,
+    },
+
+    /// Query the insurance fund balance of a market.
+    #[returns(InsuranceFundResponse)]
+    InsuranceFund {
+        denom: String,
+    },
+
+    /// List the bad debt realized in a market and the amounts drawn from its insurance fund,
+    /// ordered by draw id.
+    #[returns(Vec<InsuranceFundDraw>)]
+    InsuranceFundDraws {
+        denom: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -748,6 +969,21 @@ pub struct MarketStateResponse {
     pub market_state: MarketState,
 }
 
+#[cw_serde]
+pub struct AverageFundingRateResponse {
+    pub denom: String,
+    pub start: u64,
+    pub end: u64,
+    /// Time-weighted average of the 24-hour funding rate over the window
+    pub average_funding_rate: SignedDecimal,
+}
+
+#[cw_serde]
+pub struct InsuranceFundResponse {
+    pub denom: String,
+    pub balance: Uint128,
+}
+
 #[cw_serde]
 pub struct VaultPositionResponse {
     pub denom: String,
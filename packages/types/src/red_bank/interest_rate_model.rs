@@ -1,59 +1,306 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Decimal;
-use mars_utils::{error::ValidationError, helpers::decimal_param_le_one};
+use mars_utils::{
+    error::ValidationError,
+    helpers::{decimal_param_le_one, decimal_param_lt_one},
+};
 
 use crate::error::MarsError;
 
+pub(crate) const SECONDS_PER_YEAR: u64 = 31536000u64;
+
+/// Model that defines the borrow rate of a market as a function of its utilization
+#[cw_serde]
+#[derive(Eq)]
+pub enum InterestRateModel {
+    /// Linear curve with a single kink at the optimal utilization rate
+    TwoSlope {
+        /// Optimal utilization rate
+        optimal_utilization_rate: Decimal,
+        /// Base rate
+        base: Decimal,
+        /// Slope parameter for interest rate model function when utilization_rate <= optimal_utilization_rate
+        slope_1: Decimal,
+        /// Slope parameter for interest rate model function when utilization_rate > optimal_utilization_rate
+        slope_2: Decimal,
+    },
+    /// Piecewise-linear curve going from the base rate at 0% utilization through every kink
+    MultiKink {
+        /// Borrow rate at 0% utilization
+        base: Decimal,
+        /// Kinks sorted by utilization rate, the last one being at 100% utilization
+        kinks: Vec<Kink>,
+    },
+    /// Curve which shifts over time toward keeping the utilization at the target.
+    ///
+    /// While the utilization is above the target, the rate at target increases exponentially,
+    /// and it decreases while the utilization is below the target. The borrow rate is the rate at
+    /// target scaled by a fixed curve: from `rate_at_target / curve_steepness` at 0% utilization
+    /// to `rate_at_target * curve_steepness` at 100% utilization.
+    AdaptiveCurve {
+        /// Utilization rate the model is steering toward
+        target_utilization_rate: Decimal,
+        /// Rate at target the market starts with
+        initial_rate_at_target: Decimal,
+        /// Lower bound of the rate at target
+        min_rate_at_target: Decimal,
+        /// Upper bound of the rate at target
+        max_rate_at_target: Decimal,
+        /// Yearly speed at which the rate at target adapts, when the utilization is at 100%
+        /// (or 0%). E.g. a speed of 50 multiplies the rate at target by e^50 per year.
+        adjustment_speed: Decimal,
+        /// Ratio between the borrow rate at 100% utilization and the rate at target
+        curve_steepness: Decimal,
+    },
+}
+
 #[cw_serde]
-#[derive(Eq, Default)]
-pub struct InterestRateModel {
-    /// Optimal utilization rate
-    pub optimal_utilization_rate: Decimal,
-    /// Base rate
-    pub base: Decimal,
-    /// Slope parameter for interest rate model function when utilization_rate <= optimal_utilization_rate
-    pub slope_1: Decimal,
-    /// Slope parameter for interest rate model function when utilization_rate > optimal_utilization_rate
-    pub slope_2: Decimal,
+#[derive(Eq)]
+pub struct Kink {
+    pub utilization_rate: Decimal,
+    pub borrow_rate: Decimal,
+}
+
+/// State of an adaptive curve model, persisted in the market
+#[cw_serde]
+#[derive(Eq)]
+pub struct AdaptiveRateState {
+    /// Current rate at target utilization
+    pub rate_at_target: Decimal,
+    /// Utilization rate of the market since the last update
+    pub utilization_rate: Decimal,
+    /// Timestamp (seconds) of the last update
+    pub last_updated: u64,
+}
+
+impl Default for InterestRateModel {
+    fn default() -> Self {
+        InterestRateModel::TwoSlope {
+            optimal_utilization_rate: Decimal::zero(),
+            base: Decimal::zero(),
+            slope_1: Decimal::zero(),
+            slope_2: Decimal::zero(),
+        }
+    }
 }
 
 impl InterestRateModel {
     pub fn validate(&self) -> Result<(), ValidationError> {
-        decimal_param_le_one(self.optimal_utilization_rate, "optimal_utilization_rate")?;
-
-        if self.slope_1 >= self.slope_2 {
-            return Err(ValidationError::InvalidParam {
-                param_name: "slope_1".to_string(),
-                invalid_value: self.slope_1.to_string(),
-                predicate: format!("< {}", self.slope_2),
-            });
+        match self {
+            InterestRateModel::TwoSlope {
+                optimal_utilization_rate,
+                slope_1,
+                slope_2,
+                ..
+            } => {
+                decimal_param_le_one(*optimal_utilization_rate, "optimal_utilization_rate")?;
+
+                if slope_1 >= slope_2 {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "slope_1".to_string(),
+                        invalid_value: slope_1.to_string(),
+                        predicate: format!("< {}", slope_2),
+                    });
+                }
+            }
+            InterestRateModel::MultiKink {
+                base,
+                kinks,
+            } => {
+                let mut previous = Kink {
+                    utilization_rate: Decimal::zero(),
+                    borrow_rate: *base,
+                };
+                for kink in kinks {
+                    decimal_param_le_one(kink.utilization_rate, "kink_utilization_rate")?;
+                    if kink.utilization_rate <= previous.utilization_rate {
+                        return Err(ValidationError::InvalidParam {
+                            param_name: "kink_utilization_rate".to_string(),
+                            invalid_value: kink.utilization_rate.to_string(),
+                            predicate: format!("> {}", previous.utilization_rate),
+                        });
+                    }
+                    if kink.borrow_rate < previous.borrow_rate {
+                        return Err(ValidationError::InvalidParam {
+                            param_name: "kink_borrow_rate".to_string(),
+                            invalid_value: kink.borrow_rate.to_string(),
+                            predicate: format!(">= {}", previous.borrow_rate),
+                        });
+                    }
+                    previous = kink.clone();
+                }
+
+                if previous.utilization_rate != Decimal::one() {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "kink_utilization_rate".to_string(),
+                        invalid_value: previous.utilization_rate.to_string(),
+                        predicate: "== 1 for the last kink".to_string(),
+                    });
+                }
+            }
+            InterestRateModel::AdaptiveCurve {
+                target_utilization_rate,
+                initial_rate_at_target,
+                min_rate_at_target,
+                max_rate_at_target,
+                curve_steepness,
+                ..
+            } => {
+                decimal_param_lt_one(*target_utilization_rate, "target_utilization_rate")?;
+                if target_utilization_rate.is_zero() {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "target_utilization_rate".to_string(),
+                        invalid_value: target_utilization_rate.to_string(),
+                        predicate: "> 0".to_string(),
+                    });
+                }
+
+                if min_rate_at_target.is_zero() {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "min_rate_at_target".to_string(),
+                        invalid_value: min_rate_at_target.to_string(),
+                        predicate: "> 0".to_string(),
+                    });
+                }
+
+                if initial_rate_at_target < min_rate_at_target
+                    || initial_rate_at_target > max_rate_at_target
+                {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "initial_rate_at_target".to_string(),
+                        invalid_value: initial_rate_at_target.to_string(),
+                        predicate: format!(
+                            "within [{}, {}]",
+                            min_rate_at_target, max_rate_at_target
+                        ),
+                    });
+                }
+
+                if *curve_steepness < Decimal::one() {
+                    return Err(ValidationError::InvalidParam {
+                        param_name: "curve_steepness".to_string(),
+                        invalid_value: curve_steepness.to_string(),
+                        predicate: ">= 1".to_string(),
+                    });
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Compute the borrow rate at the given utilization.
+    ///
+    /// The adaptive curve model is evaluated at its initial rate at target. Use
+    /// [`InterestRateModel::get_borrow_rate_and_state`] to take its state into account.
     pub fn get_borrow_rate(&self, current_utilization_rate: Decimal) -> Result<Decimal, MarsError> {
-        let new_borrow_rate = if current_utilization_rate <= self.optimal_utilization_rate {
-            if current_utilization_rate.is_zero() {
-                // prevent division by zero when current_utilization_rate is zero
-                self.base
-            } else {
-                // The borrow interest rates increase slowly with utilization
-                self.base
-                    + self.slope_1.checked_mul(
-                        current_utilization_rate.checked_div(self.optimal_utilization_rate)?,
-                    )?
+        match self {
+            InterestRateModel::TwoSlope {
+                optimal_utilization_rate,
+                base,
+                slope_1,
+                slope_2,
+            } => {
+                let new_borrow_rate = if current_utilization_rate <= *optimal_utilization_rate {
+                    if current_utilization_rate.is_zero() {
+                        // prevent division by zero when current_utilization_rate is zero
+                        *base
+                    } else {
+                        // The borrow interest rates increase slowly with utilization
+                        *base
+                            + slope_1.checked_mul(
+                                current_utilization_rate.checked_div(*optimal_utilization_rate)?,
+                            )?
+                    }
+                } else {
+                    // The borrow interest rates increase sharply with utilization
+                    *base
+                        + slope_1
+                        + slope_2
+                            .checked_mul(current_utilization_rate - optimal_utilization_rate)?
+                            .checked_div(Decimal::one() - optimal_utilization_rate)?
+                };
+                Ok(new_borrow_rate)
             }
-        } else {
-            // The borrow interest rates increase sharply with utilization
-            self.base
-                + self.slope_1
-                + self
-                    .slope_2
-                    .checked_mul(current_utilization_rate - self.optimal_utilization_rate)?
-                    .checked_div(Decimal::one() - self.optimal_utilization_rate)?
+            InterestRateModel::MultiKink {
+                base,
+                kinks,
+            } => {
+                let mut previous = Kink {
+                    utilization_rate: Decimal::zero(),
+                    borrow_rate: *base,
+                };
+                for kink in kinks {
+                    if current_utilization_rate <= kink.utilization_rate {
+                        // Interpolate between the previous kink and this one
+                        let segment_progress = (current_utilization_rate
+                            - previous.utilization_rate)
+                            .checked_div(kink.utilization_rate - previous.utilization_rate)?;
+                        return Ok(previous.borrow_rate
+                            + (kink.borrow_rate - previous.borrow_rate)
+                                .checked_mul(segment_progress)?);
+                    }
+                    previous = kink.clone();
+                }
+                Ok(previous.borrow_rate)
+            }
+            InterestRateModel::AdaptiveCurve {
+                initial_rate_at_target,
+                ..
+            } => self.adaptive_curve(*initial_rate_at_target, current_utilization_rate),
+        }
+    }
+
+    /// Compute the borrow rate of an adaptive curve model and its new state.
+    ///
+    /// The rate at target is first shifted according to the utilization recorded in the previous
+    /// state over the time elapsed since then, and the curve is then evaluated at the current
+    /// utilization. Returns `None` as state for the other models.
+    pub fn get_borrow_rate_and_state(
+        &self,
+        state: Option<&AdaptiveRateState>,
+        current_utilization_rate: Decimal,
+        current_timestamp: u64,
+    ) -> Result<(Decimal, Option<AdaptiveRateState>), MarsError> {
+        let InterestRateModel::AdaptiveCurve {
+            target_utilization_rate,
+            initial_rate_at_target,
+            min_rate_at_target,
+            max_rate_at_target,
+            adjustment_speed,
+            ..
+        } = self
+        else {
+            return Ok((self.get_borrow_rate(current_utilization_rate)?, None));
         };
-        Ok(new_borrow_rate)
+
+        let rate_at_target = match state {
+            Some(state) => {
+                let elapsed = current_timestamp.saturating_sub(state.last_updated);
+                let error = self.normalized_error(state.utilization_rate)?;
+                let adaptation = adjustment_speed
+                    .checked_mul(error)?
+                    .checked_mul(Decimal::from_ratio(elapsed, SECONDS_PER_YEAR))?;
+                let rate_at_target = if state.utilization_rate >= *target_utilization_rate {
+                    state.rate_at_target.checked_mul(exp(adaptation)?)?
+                } else {
+                    state.rate_at_target.checked_div(exp(adaptation)?)?
+                };
+                rate_at_target.clamp(*min_rate_at_target, *max_rate_at_target)
+            }
+            None => *initial_rate_at_target,
+        };
+
+        let borrow_rate = self.adaptive_curve(rate_at_target, current_utilization_rate)?;
+
+        Ok((
+            borrow_rate,
+            Some(AdaptiveRateState {
+                rate_at_target,
+                utilization_rate: current_utilization_rate,
+                last_updated: current_timestamp,
+            }),
+        ))
     }
 
     pub fn get_liquidity_rate(
@@ -67,10 +314,85 @@ impl InterestRateModel {
             // This operation should not underflow as reserve_factor is checked to be <= 1
             .checked_mul(Decimal::one() - reserve_factor)?)
     }
+
+    /// Distance of the utilization from the target of an adaptive curve model, normalized to
+    /// [0, 1] on both sides of the target
+    fn normalized_error(&self, utilization_rate: Decimal) -> Result<Decimal, MarsError> {
+        let InterestRateModel::AdaptiveCurve {
+            target_utilization_rate: target,
+            ..
+        } = self
+        else {
+            return Ok(Decimal::zero());
+        };
+
+        let error = if utilization_rate >= *target {
+            (utilization_rate - target).checked_div(Decimal::one() - target)?
+        } else {
+            (target - utilization_rate).checked_div(*target)?
+        };
+        Ok(error)
+    }
+
+    /// Borrow rate of an adaptive curve model with the given rate at target
+    fn adaptive_curve(
+        &self,
+        rate_at_target: Decimal,
+        utilization_rate: Decimal,
+    ) -> Result<Decimal, MarsError> {
+        let InterestRateModel::AdaptiveCurve {
+            target_utilization_rate,
+            curve_steepness,
+            ..
+        } = self
+        else {
+            return Ok(rate_at_target);
+        };
+
+        let error = self.normalized_error(utilization_rate)?;
+        let coefficient = if utilization_rate >= *target_utilization_rate {
+            // From 1 at target to curve_steepness at 100% utilization
+            Decimal::one() + (curve_steepness - Decimal::one()).checked_mul(error)?
+        } else {
+            // From 1 at target to 1 / curve_steepness at 0% utilization
+            let max_decrease = Decimal::one() - Decimal::one().checked_div(*curve_steepness)?;
+            Decimal::one() - max_decrease.checked_mul(error)?
+        };
+        Ok(rate_at_target.checked_mul(coefficient)?)
+    }
+}
+
+/// Exponent above which the rate at target is bound to be clamped anyway
+const MAX_EXP_EXPONENT: u64 = 20;
+
+/// Compute e^x by halving the exponent until a short Taylor expansion is accurate, and squaring
+/// the result back
+fn exp(x: Decimal) -> Result<Decimal, MarsError> {
+    let mut reduced = x.min(Decimal::from_ratio(MAX_EXP_EXPONENT, 1u64));
+    let mut squarings = 0;
+    while reduced > Decimal::percent(1) {
+        reduced = reduced.checked_div(Decimal::from_ratio(2u64, 1u64))?;
+        squarings += 1;
+    }
+
+    let mut result = Decimal::one();
+    let mut term = Decimal::one();
+    for n in 1..=4u64 {
+        term = term.checked_mul(reduced)?.checked_div(Decimal::from_ratio(n, 1u64))?;
+        result = result.checked_add(term)?;
+    }
+
+    for _ in 0..squarings {
+        result = result.checked_mul(result)?;
+    }
+
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use super::*;
     use crate::red_bank::Market;
 
@@ -78,11 +400,13 @@ mod tests {
     fn model_lifecycle() {
         let optimal_utilization_rate = Decimal::percent(80);
         let reserve_factor = Decimal::percent(20);
+        let base = Decimal::zero();
+        let slope_1 = Decimal::percent(7);
 
-        let model = InterestRateModel {
+        let model = InterestRateModel::TwoSlope {
             optimal_utilization_rate,
-            base: Decimal::zero(),
-            slope_1: Decimal::percent(7),
+            base,
+            slope_1,
             slope_2: Decimal::percent(45),
         };
 
@@ -90,32 +414,37 @@ mod tests {
             borrow_rate: Decimal::percent(10),
             liquidity_rate: Decimal::zero(),
             reserve_factor,
-            interest_rate_model: model.clone(),
+            interest_rate_model: model,
             ..Default::default()
         };
 
         let diff = Decimal::percent(10);
         let utilization_rate = optimal_utilization_rate - diff;
 
-        market.update_interest_rates(utilization_rate).unwrap();
+        market.update_interest_rates(utilization_rate, 0).unwrap();
 
-        let expected_borrow_rate =
-            model.base + model.slope_1 * utilization_rate / model.optimal_utilization_rate;
+        let expected_borrow_rate = base + slope_1 * utilization_rate / optimal_utilization_rate;
 
         assert_eq!(market.borrow_rate, expected_borrow_rate);
         assert_eq!(
             market.liquidity_rate,
             expected_borrow_rate * utilization_rate * (Decimal::one() - reserve_factor)
         );
+        assert_eq!(market.adaptive_rate_state, None);
     }
 
     #[test]
     fn interest_rates_calculation() {
-        let model = InterestRateModel {
-            optimal_utilization_rate: Decimal::percent(80),
-            base: Decimal::zero(),
-            slope_1: Decimal::percent(7),
-            slope_2: Decimal::percent(45),
+        let optimal_utilization_rate = Decimal::percent(80);
+        let base = Decimal::zero();
+        let slope_1 = Decimal::percent(7);
+        let slope_2 = Decimal::percent(45);
+
+        let model = InterestRateModel::TwoSlope {
+            optimal_utilization_rate,
+            base,
+            slope_1,
+            slope_2,
         };
 
         // current utilization rate < optimal utilization rate
@@ -123,8 +452,8 @@ mod tests {
             let current_utilization_rate = Decimal::percent(79);
             let new_borrow_rate = model.get_borrow_rate(current_utilization_rate).unwrap();
 
-            let expected_borrow_rate = model.base
-                + model.slope_1 * current_utilization_rate / model.optimal_utilization_rate;
+            let expected_borrow_rate =
+                base + slope_1 * current_utilization_rate / optimal_utilization_rate;
 
             assert_eq!(new_borrow_rate, expected_borrow_rate);
         }
@@ -134,8 +463,8 @@ mod tests {
             let current_utilization_rate = Decimal::percent(80);
             let new_borrow_rate = model.get_borrow_rate(current_utilization_rate).unwrap();
 
-            let expected_borrow_rate = model.base
-                + model.slope_1 * current_utilization_rate / model.optimal_utilization_rate;
+            let expected_borrow_rate =
+                base + slope_1 * current_utilization_rate / optimal_utilization_rate;
 
             assert_eq!(new_borrow_rate, expected_borrow_rate);
         }
@@ -145,17 +474,17 @@ mod tests {
             let current_utilization_rate = Decimal::percent(81);
             let new_borrow_rate = model.get_borrow_rate(current_utilization_rate).unwrap();
 
-            let expected_borrow_rate = model.base
-                + model.slope_1
-                + model.slope_2 * (current_utilization_rate - model.optimal_utilization_rate)
-                    / (Decimal::one() - model.optimal_utilization_rate);
+            let expected_borrow_rate = base
+                + slope_1
+                + slope_2 * (current_utilization_rate - optimal_utilization_rate)
+                    / (Decimal::one() - optimal_utilization_rate);
 
             assert_eq!(new_borrow_rate, expected_borrow_rate);
         }
 
         // current utilization rate == 100% and optimal utilization rate == 100%
         {
-            let model = InterestRateModel {
+            let model = InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(100),
                 base: Decimal::zero(),
                 slope_1: Decimal::percent(7),
@@ -170,7 +499,7 @@ mod tests {
 
         // current utilization rate == 0% and optimal utilization rate == 0%
         {
-            let model = InterestRateModel {
+            let model = InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(0),
                 base: Decimal::percent(2),
                 slope_1: Decimal::percent(7),
@@ -185,20 +514,217 @@ mod tests {
 
         // current utilization rate == 20% and optimal utilization rate == 0%
         {
-            let model = InterestRateModel {
+            let base = Decimal::percent(2);
+            let slope_1 = Decimal::percent(1);
+            let slope_2 = Decimal::percent(5);
+            let model = InterestRateModel::TwoSlope {
                 optimal_utilization_rate: Decimal::percent(0),
-                base: Decimal::percent(2),
-                slope_1: Decimal::percent(1),
-                slope_2: Decimal::percent(5),
+                base,
+                slope_1,
+                slope_2,
             };
 
             let current_utilization_rate = Decimal::percent(20);
             let new_borrow_rate = model.get_borrow_rate(current_utilization_rate).unwrap();
 
-            let expected_borrow_rate =
-                model.base + model.slope_1 + model.slope_2 * current_utilization_rate;
+            let expected_borrow_rate = base + slope_1 + slope_2 * current_utilization_rate;
 
             assert_eq!(new_borrow_rate, expected_borrow_rate);
         }
     }
+
+    #[test]
+    fn model_json_is_tagged_by_kind() {
+        let json = r#"{"two_slope":{"optimal_utilization_rate":"0.8","base":"0","slope_1":"0.07","slope_2":"0.45"}}"#;
+        let model: InterestRateModel = cosmwasm_std::from_json(json).unwrap();
+        assert_eq!(
+            model,
+            InterestRateModel::TwoSlope {
+                optimal_utilization_rate: Decimal::percent(80),
+                base: Decimal::zero(),
+                slope_1: Decimal::percent(7),
+                slope_2: Decimal::percent(45),
+            }
+        );
+        assert_eq!(cosmwasm_std::to_json_string(&model).unwrap(), json);
+
+        // Malformed models report the offending field of their kind
+        let json =
+            r#"{"two_slope":{"optimal_utilization_rate":"0.8","base":"0","slope_1":"0.07"}}"#;
+        let err = cosmwasm_std::from_json::<InterestRateModel>(json).unwrap_err();
+        assert!(err.to_string().contains("slope_2"));
+    }
+
+    fn multi_kink_model() -> InterestRateModel {
+        InterestRateModel::MultiKink {
+            base: Decimal::percent(1),
+            kinks: vec![
+                Kink {
+                    utilization_rate: Decimal::percent(50),
+                    borrow_rate: Decimal::percent(3),
+                },
+                Kink {
+                    utilization_rate: Decimal::percent(90),
+                    borrow_rate: Decimal::percent(7),
+                },
+                Kink {
+                    utilization_rate: Decimal::percent(100),
+                    borrow_rate: Decimal::percent(107),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn multi_kink_interest_rates_calculation() {
+        let model = multi_kink_model();
+        model.validate().unwrap();
+
+        let cases =
+            [(0, 100), (25, 200), (50, 300), (70, 500), (90, 700), (95, 5700), (100, 10700)];
+        for (utilization, expected_bps) in cases {
+            assert_eq!(
+                model.get_borrow_rate(Decimal::percent(utilization)).unwrap(),
+                Decimal::from_ratio(expected_bps, 10_000u128)
+            );
+        }
+    }
+
+    #[test]
+    fn multi_kink_validation() {
+        let InterestRateModel::MultiKink {
+            base,
+            mut kinks,
+        } = multi_kink_model()
+        else {
+            unreachable!()
+        };
+
+        // The last kink has to be at 100% utilization
+        let err = InterestRateModel::MultiKink {
+            base,
+            kinks: kinks[..2].to_vec(),
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::InvalidParam {
+                param_name: "kink_utilization_rate".to_string(),
+                invalid_value: "0.9".to_string(),
+                predicate: "== 1 for the last kink".to_string(),
+            }
+        );
+
+        // Rates can't decrease with utilization
+        kinks[1].borrow_rate = Decimal::percent(2);
+        let err = InterestRateModel::MultiKink {
+            base,
+            kinks,
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::InvalidParam {
+                param_name: "kink_borrow_rate".to_string(),
+                invalid_value: "0.02".to_string(),
+                predicate: ">= 0.03".to_string(),
+            }
+        );
+    }
+
+    fn adaptive_model() -> InterestRateModel {
+        InterestRateModel::AdaptiveCurve {
+            target_utilization_rate: Decimal::percent(90),
+            initial_rate_at_target: Decimal::percent(4),
+            min_rate_at_target: Decimal::permille(1),
+            max_rate_at_target: Decimal::percent(200),
+            adjustment_speed: Decimal::from_ratio(50u128, 1u128),
+            curve_steepness: Decimal::from_ratio(4u128, 1u128),
+        }
+    }
+
+    #[test]
+    fn adaptive_curve_interest_rates_calculation() {
+        let model = adaptive_model();
+        model.validate().unwrap();
+
+        // The curve goes from a quarter of the rate at target to four times the rate at target
+        assert_eq!(model.get_borrow_rate(Decimal::zero()).unwrap(), Decimal::percent(1));
+        assert_eq!(model.get_borrow_rate(Decimal::percent(90)).unwrap(), Decimal::percent(4));
+        assert_eq!(model.get_borrow_rate(Decimal::percent(95)).unwrap(), Decimal::percent(10));
+        assert_eq!(model.get_borrow_rate(Decimal::percent(100)).unwrap(), Decimal::percent(16));
+    }
+
+    #[test]
+    fn adaptive_curve_shifts_toward_target() {
+        let model = adaptive_model();
+
+        // Without state, the model starts at its initial rate at target
+        let (borrow_rate, state) =
+            model.get_borrow_rate_and_state(None, Decimal::percent(100), 1000).unwrap();
+        assert_eq!(borrow_rate, Decimal::percent(16));
+        let state = state.unwrap();
+        assert_eq!(state.rate_at_target, Decimal::percent(4));
+
+        // At 100% utilization, the rate at target is multiplied by e after 1 / 50 year
+        let elapsed = SECONDS_PER_YEAR / 50;
+        let (_, up_state) = model
+            .get_borrow_rate_and_state(Some(&state), Decimal::percent(90), 1000 + elapsed)
+            .unwrap();
+        let up_state = up_state.unwrap();
+        let expected = Decimal::percent(4) * Decimal::from_str("2.718281828").unwrap();
+        assert!(
+            up_state.rate_at_target.abs_diff(expected) < Decimal::from_str("0.000001").unwrap()
+        );
+        assert_eq!(up_state.utilization_rate, Decimal::percent(90));
+        assert_eq!(up_state.last_updated, 1000 + elapsed);
+
+        // At 0% utilization, it is divided by e
+        let state = AdaptiveRateState {
+            utilization_rate: Decimal::zero(),
+            ..state
+        };
+        let (_, down_state) =
+            model.get_borrow_rate_and_state(Some(&state), Decimal::zero(), 1000 + elapsed).unwrap();
+        let expected = Decimal::percent(4) / Decimal::from_str("2.718281828").unwrap();
+        assert!(
+            down_state.unwrap().rate_at_target.abs_diff(expected)
+                < Decimal::from_str("0.000001").unwrap()
+        );
+
+        // The rate at target is bounded
+        let state = AdaptiveRateState {
+            utilization_rate: Decimal::one(),
+            ..state
+        };
+        let (borrow_rate, bounded_state) = model
+            .get_borrow_rate_and_state(Some(&state), Decimal::percent(90), 1000 + SECONDS_PER_YEAR)
+            .unwrap();
+        assert_eq!(bounded_state.unwrap().rate_at_target, Decimal::percent(200));
+        assert_eq!(borrow_rate, Decimal::percent(200));
+    }
+
+    #[test]
+    fn adaptive_state_is_persisted_in_market() {
+        let mut market = Market {
+            interest_rate_model: adaptive_model(),
+            ..Default::default()
+        };
+
+        market.update_interest_rates(Decimal::one(), 1000).unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(16));
+        assert_eq!(market.adaptive_rate_state.clone().unwrap().rate_at_target, Decimal::percent(4));
+
+        market.update_interest_rates(Decimal::one(), 1000 + SECONDS_PER_YEAR / 50).unwrap();
+        assert!(market.adaptive_rate_state.clone().unwrap().rate_at_target > Decimal::percent(10));
+        assert!(market.borrow_rate > Decimal::percent(40));
+
+        // Switching to a stateless model clears the state
+        market.interest_rate_model = multi_kink_model();
+        market.update_interest_rates(Decimal::one(), 2000 + SECONDS_PER_YEAR / 50).unwrap();
+        assert_eq!(market.adaptive_rate_state, None);
+        assert_eq!(market.borrow_rate, Decimal::percent(107));
+    }
 }
@@ -2,7 +2,10 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Decimal, StdResult, Uint128};
 use mars_utils::{error::ValidationError, helpers::decimal_param_lt_one};
 
-use crate::red_bank::InterestRateModel;
+use crate::{
+    error::MarsError,
+    red_bank::{interest_rate_model::SECONDS_PER_YEAR, AdaptiveRateState, InterestRateModel},
+};
 
 #[cw_serde]
 pub struct Market {
@@ -29,6 +32,20 @@ pub struct Market {
     pub collateral_total_scaled: Uint128,
     /// Total debt scaled for the market's currency
     pub debt_total_scaled: Uint128,
+    /// Total principal and accrued interest of the outstanding fixed-rate term loans
+    #[serde(default)]
+    pub term_debt_total: Uint128,
+    /// Yearly interest owed by the outstanding term loans at their fixed rates
+    #[serde(default)]
+    pub term_interest_per_year: Uint128,
+
+    /// Fee charged on flash loans, as a portion of the borrowed amount
+    #[serde(default)]
+    pub flash_loan_fee_rate: Decimal,
+
+    /// State of the interest rate model, if it is an adaptive curve
+    #[serde(default)]
+    pub adaptive_rate_state: Option<AdaptiveRateState>,
 }
 
 impl Default for Market {
@@ -43,7 +60,11 @@ impl Default for Market {
             indexes_last_updated: 0,
             collateral_total_scaled: Uint128::zero(),
             debt_total_scaled: Uint128::zero(),
+            term_debt_total: Uint128::zero(),
+            term_interest_per_year: Uint128::zero(),
             interest_rate_model: InterestRateModel::default(),
+            flash_loan_fee_rate: Decimal::zero(),
+            adaptive_rate_state: None,
         }
     }
 }
@@ -51,14 +72,26 @@ impl Default for Market {
 impl Market {
     pub fn validate(&self) -> Result<(), ValidationError> {
         decimal_param_lt_one(self.reserve_factor, "reserve_factor")?;
+        decimal_param_lt_one(self.flash_loan_fee_rate, "flash_loan_fee_rate")?;
 
         self.interest_rate_model.validate()?;
 
         Ok(())
     }
 
-    pub fn update_interest_rates(&mut self, current_utilization_rate: Decimal) -> StdResult<()> {
-        self.borrow_rate = self.interest_rate_model.get_borrow_rate(current_utilization_rate)?;
+    pub fn update_interest_rates(
+        &mut self,
+        current_utilization_rate: Decimal,
+        current_timestamp: u64,
+    ) -> StdResult<()> {
+        let (borrow_rate, adaptive_rate_state) =
+            self.interest_rate_model.get_borrow_rate_and_state(
+                self.adaptive_rate_state.as_ref(),
+                current_utilization_rate,
+                current_timestamp,
+            )?;
+        self.borrow_rate = borrow_rate;
+        self.adaptive_rate_state = adaptive_rate_state;
 
         self.liquidity_rate = self.interest_rate_model.get_liquidity_rate(
             self.borrow_rate,
@@ -69,6 +102,16 @@ impl Market {
         Ok(())
     }
 
+    /// Add the interest accrued by the term loans over the elapsed time to the term debt, and
+    /// return it
+    pub fn accrue_term_interest(&mut self, time_elapsed: u64) -> Result<Uint128, MarsError> {
+        let interest = self
+            .term_interest_per_year
+            .checked_mul_floor(Decimal::from_ratio(time_elapsed, SECONDS_PER_YEAR))?;
+        self.term_debt_total = self.term_debt_total.checked_add(interest)?;
+        Ok(interest)
+    }
+
     pub fn increase_collateral(&mut self, amount_scaled: Uint128) -> StdResult<()> {
         self.collateral_total_scaled = self.collateral_total_scaled.checked_add(amount_scaled)?;
         Ok(())
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Uint128};
 use mars_owner::OwnerUpdate;
 
 use crate::{params::AssetParamsBase, red_bank::InterestRateModel};
@@ -59,6 +59,52 @@ pub enum ExecuteMsg {
         amount: Uint128,
         /// The address where the borrowed amount is sent
         recipient: Option<String>,
+        /// Borrow against the collateral of this address, using the credit delegation it
+        /// granted to the caller. The debt is charged to this address.
+        on_behalf_of: Option<String>,
+    },
+
+    /// Allow another address to borrow up to `amount` of an asset against the caller's
+    /// collateral, until `expires_at` (seconds) if set. Replaces any previous delegation of the
+    /// asset to the same address.
+    ApproveCreditDelegation {
+        delegatee: String,
+        denom: String,
+        amount: Uint128,
+        expires_at: Option<u64>,
+    },
+
+    /// Revoke a credit delegation granted by the caller
+    RevokeCreditDelegation {
+        delegatee: String,
+        denom: String,
+    },
+
+    /// Borrow native coins at a fixed rate for one of the terms offered by the market.
+    ///
+    /// The rate is the borrow rate of the market after the loan, plus the premium of the term.
+    BorrowTermLoan {
+        /// Asset to borrow
+        denom: String,
+        /// Amount to borrow
+        amount: Uint128,
+        /// Duration (seconds) of the term
+        duration: u64,
+        /// The address where the borrowed amount is sent
+        recipient: Option<String>,
+    },
+
+    /// Repay a term loan of the caller in full. Coins used to repay must be sent in the
+    /// transaction this call is made. Repaying before maturity is charged the early repay
+    /// penalty of the market. Any amount sent above what is owed is refunded.
+    RepayTermLoan {
+        loan_id: u64,
+    },
+
+    /// Convert a matured term loan of the user into variable debt. Callable by anyone.
+    ConvertTermLoan {
+        user: String,
+        loan_id: u64,
     },
 
     /// Repay native coins loan. Coins used to repay must be sent in the
@@ -82,6 +128,21 @@ pub enum ExecuteMsg {
         recipient: Option<String>,
     },
 
+    /// Write off the debt of a user left without any collateral. The loss is covered by the
+    /// reserves first, then socialized among the depositors by reducing the liquidity index.
+    /// Callable by anyone.
+    WriteOffBadDebt {
+        user: String,
+        denom: String,
+    },
+
+    /// Write off debt of the credit manager, after it removed the debt of one of its accounts left
+    /// without any collateral. Only callable by the credit manager.
+    WriteOffCreditManagerBadDebt {
+        denom: String,
+        amount: Uint128,
+    },
+
     /// Update (enable / disable) asset as collateral for the caller
     UpdateAssetCollateralStatus {
         /// Asset to update status for
@@ -89,6 +150,48 @@ pub enum ExecuteMsg {
         /// Option to enable (true) / disable (false) asset as collateral
         enable: bool,
     },
+
+    /// Opt the caller into an efficiency mode (e-mode) category defined in the params contract,
+    /// or out of it if `None`. While all of the caller's debts belong to the category, its
+    /// collaterals belonging to it use the category's max LTV and liquidation threshold.
+    SetEMode {
+        /// Name of the e-mode category
+        category: Option<String>,
+    },
+
+    /// Borrow native coins from the market liquidity for the duration of a single transaction.
+    ///
+    /// The coins are sent to the caller, which has to be a contract handling
+    /// [`FlashLoanReceiverMsg`]. Before the callback returns, the caller has to send back the
+    /// coins plus the flash loan fee of each market with `RepayFlashLoan`.
+    FlashLoan {
+        /// Coins to borrow
+        coins: Vec<Coin>,
+        /// Message passed back to the receiver
+        callback: Binary,
+    },
+
+    /// Repay the flash loan in progress. Coins used to repay must be sent in the transaction
+    /// this call is made. Any amount paid on top of the borrowed coins and the fees is treated
+    /// as an additional fee.
+    RepayFlashLoan {},
+
+    /// Assert that the flash loan in progress has been repaid and distribute the fees.
+    /// Only callable by the contract itself.
+    FinalizeFlashLoan {},
+}
+
+/// Message sent by the Red Bank to the receiver of a flash loan
+#[cw_serde]
+pub enum FlashLoanReceiverMsg {
+    FlashLoanCallback {
+        /// Borrowed coins, sent along with the message
+        coins: Vec<Coin>,
+        /// Fees to be paid on top of the borrowed coins
+        fees: Vec<Coin>,
+        /// Message passed to `FlashLoan` by the receiver
+        callback: Binary,
+    },
 }
 
 #[cw_serde]
@@ -110,6 +213,9 @@ pub struct MarketParams {
     pub reserve_factor: Option<Decimal>,
     /// Interest rate strategy to calculate borrow_rate and liquidity_rate
     pub interest_rate_model: Option<InterestRateModel>,
+    /// Fee charged on flash loans, as a portion of the borrowed amount
+    #[serde(default)]
+    pub flash_loan_fee_rate: Option<Decimal>,
 }
 
 impl From<&AssetParamsBase<Addr>> for MarketParams {
@@ -118,6 +224,7 @@ impl From<&AssetParamsBase<Addr>> for MarketParams {
             denom: p.denom.clone(),
             reserve_factor: Some(p.reserve_factor),
             interest_rate_model: Some(p.interest_rate_model.clone()),
+            flash_loan_fee_rate: Some(p.flash_loan_fee_rate),
         }
     }
 }
@@ -181,6 +288,32 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Get all credit delegations granted by a user
+    #[returns(Vec<crate::red_bank::CreditDelegationResponse>)]
+    CreditDelegationsGranted {
+        delegator: String,
+        /// Start after the (delegatee, denom) pair
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    /// Get all credit delegations received by a user
+    #[returns(Vec<crate::red_bank::CreditDelegationResponse>)]
+    CreditDelegationsReceived {
+        delegatee: String,
+        /// Start after the (delegator, denom) pair
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    /// Get all term loans of a user
+    #[returns(Vec<crate::red_bank::TermLoanResponse>)]
+    UserTermLoans {
+        user: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
     /// Get user collateral position for a specific asset
     #[returns(crate::red_bank::UserCollateralResponse)]
     UserCollateral {
@@ -207,6 +340,12 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Get the e-mode category the user opted into
+    #[returns(Option<String>)]
+    UserEMode {
+        user: String,
+    },
+
     /// Get user position
     #[returns(crate::red_bank::UserPositionResponse)]
     UserPosition {
@@ -254,6 +393,12 @@ pub enum QueryMsg {
         denom: String,
         amount_scaled: Uint128,
     },
+
+    /// Get the debts backed by the given isolated collateral, interest included
+    #[returns(Vec<Coin>)]
+    IsolatedDebts {
+        denom: String,
+    },
 }
 
 #[cw_serde]
@@ -265,4 +410,5 @@ pub enum MigrateMsg {
         haircut: Decimal,
         market: String,
     },
+    V2_3_3ToV2_4_0 {},
 }
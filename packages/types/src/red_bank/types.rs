@@ -2,7 +2,11 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Decimal, Uint128};
 use cw_paginate::PaginationResponse;
 
-use crate::red_bank::Market;
+use crate::{
+    error::MarsError,
+    params::IsolationParams,
+    red_bank::{interest_rate_model::SECONDS_PER_YEAR, Market},
+};
 
 /// Global configuration
 #[cw_serde]
@@ -36,6 +40,59 @@ pub struct Debt {
     pub uncollateralized: bool,
 }
 
+/// Loan borrowed at a fixed rate for a given term.
+///
+/// Term loans are tracked separately from the variable debt scaled by the borrow index. Interest
+/// accrues linearly at the fixed rate until the loan is repaid or converted to variable debt.
+#[cw_serde]
+pub struct TermLoan {
+    pub id: u64,
+    pub denom: String,
+    /// Borrowed amount
+    pub principal: Uint128,
+    /// Fixed yearly borrow rate
+    pub rate: Decimal,
+    /// Timestamp (seconds) the loan was borrowed at
+    pub opened_at: u64,
+    /// Timestamp (seconds) from which the loan can be repaid without penalty, or converted to
+    /// variable debt
+    pub maturity: u64,
+}
+
+impl TermLoan {
+    /// Interest accrued at the fixed rate since the loan was borrowed
+    pub fn accrued_interest(&self, current_timestamp: u64) -> Result<Uint128, MarsError> {
+        let elapsed = current_timestamp.saturating_sub(self.opened_at);
+        let rate = self.rate.checked_mul(Decimal::from_ratio(elapsed, SECONDS_PER_YEAR))?;
+        Ok(self.principal.checked_mul_ceil(rate)?)
+    }
+
+    /// Interest owed per year at the fixed rate, rounded down
+    pub fn yearly_interest(&self) -> Result<Uint128, MarsError> {
+        Ok(self.principal.checked_mul_floor(self.rate)?)
+    }
+
+    /// Principal and accrued interest owed by the borrower
+    pub fn amount_owed(&self, current_timestamp: u64) -> Result<Uint128, MarsError> {
+        Ok(self.principal.checked_add(self.accrued_interest(current_timestamp)?)?)
+    }
+}
+
+/// Allowance granted by a depositor to another address to borrow against their collateral
+#[cw_serde]
+pub struct CreditDelegation {
+    /// Amount that can still be borrowed
+    pub amount: Uint128,
+    /// Timestamp (seconds) after which the allowance can't be used anymore
+    pub expires_at: Option<u64>,
+}
+
+impl CreditDelegation {
+    pub fn is_expired(&self, current_timestamp: u64) -> bool {
+        self.expires_at.map_or(false, |expires_at| current_timestamp >= expires_at)
+    }
+}
+
 #[cw_serde]
 pub enum UserHealthStatus {
     NotBorrowing,
@@ -55,6 +112,7 @@ pub struct Position {
     pub max_ltv: Decimal,
     pub liquidation_threshold: Decimal,
     pub asset_price: Decimal,
+    pub isolation: Option<IsolationParams>,
 }
 
 #[cw_serde]
@@ -67,6 +125,26 @@ pub struct ConfigResponse {
     pub address_provider: String,
 }
 
+#[cw_serde]
+pub struct TermLoanResponse {
+    pub loan: TermLoan,
+    /// Principal and accrued interest owed at the current block
+    pub amount_owed: Uint128,
+    /// Whether the loan can be repaid without penalty, or converted to variable debt
+    pub matured: bool,
+}
+
+#[cw_serde]
+pub struct CreditDelegationResponse {
+    /// Address whose collateral is borrowed against and who is charged the debt
+    pub delegator: String,
+    /// Address allowed to borrow
+    pub delegatee: String,
+    pub denom: String,
+    pub amount: Uint128,
+    pub expires_at: Option<u64>,
+}
+
 #[cw_serde]
 pub struct UserDebtResponse {
     /// Asset denom